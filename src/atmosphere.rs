@@ -0,0 +1,552 @@
+// 行星大气：包裹球形星体的一层参与介质，Rayleigh（分子）与 Mie（气溶胶）散射，
+// 两者密度都随海拔按各自的标高指数衰减。每段的上界取该段最低海拔处的消光系数，稀薄的高层大气几乎不产生虚碰撞。
+// 距离采样不缩减通道：各通道共用一个上界，暂定碰撞以消光最强通道的概率接受；碰撞处按各通道条件概率的
+// 平均值选择 Rayleigh 散射、Mie 散射、Mie 吸收或穿过（其余通道的虚碰撞），并逐通道乘以 条件概率 / 平均概率
+// （单样本光谱 MIS，权重不超过通道数）。穿过大气而未碰撞的光线不产生命中，也不花费反弹次数。
+// 多次散射由路径追踪自然给出；单次散射模式在散射点对场景光源做下一事件估计后终止路径，阴影光线穿过场景，星体与其他物体都能遮挡
+use std::sync::Arc;
+use rand::rngs::ThreadRng;
+use crate::aabb::Aabb;
+use crate::chromatic_medium::single_channel;
+use crate::heterogeneous_medium::visible_emission;
+use crate::hittable::{HitRecord, Hittable};
+use crate::interval::Interval;
+use crate::material::{Material, ScatterRecord};
+use crate::pdf::Pdf;
+use crate::phase::{CornetteShanks, PhaseFunction, PhasePdf, Rayleigh};
+use crate::ray::Ray;
+use crate::rtweekend::random::random_double;
+use crate::spectrum::{self, SPECTRUM_SAMPLES};
+use crate::vec3::{Color, Point3, Vec3};
+
+// 地球大气的参考值（Bruneton & Neyret 2008）：以大气厚度为单位的标高，
+// 以及海平面系数 × 标高得到的竖直光学厚度
+const EARTH_RAYLEIGH_HEIGHT: f64 = 8.0 / 60.0;
+const EARTH_MIE_HEIGHT: f64 = 1.2 / 60.0;
+const EARTH_RAYLEIGH_DEPTH: (f64, f64, f64) = (0.0464, 0.108, 0.265);
+const EARTH_MIE_DEPTH: f64 = 0.0252;
+const EARTH_MIE_ALBEDO: f64 = 0.9;
+const EARTH_MIE_G: f64 = 0.76;
+
+// 碰撞处的事件：Rayleigh 散射、Mie 散射、Mie 吸收、穿过
+const RAYLEIGH: usize = 0;
+const MIE: usize = 1;
+const ABSORB: usize = 2;
+const PASS: usize = 3;
+
+type Channels = [f64; SPECTRUM_SAMPLES];
+
+fn to_vec3(c: &Channels) -> Vec3 {
+    Vec3::new(c[0], c[1], c[2])
+}
+
+// 光线与球的交点参数区间 [t0, t1]
+fn sphere_interval(center: &Point3, radius: f64, r: &Ray) -> Option<(f64, f64)> {
+    let oc = *center - r.origin;
+    let a = r.direction.length_squared();
+    let h = Vec3::dot(&r.direction, &oc);
+    let c = oc.length_squared() - radius * radius;
+    let discriminant = h * h - a * c;
+    if discriminant < 0.0 {
+        return None;
+    }
+    let sqrtd = discriminant.sqrt();
+    Some(((h - sqrtd) / a, (h + sqrtd) / a))
+}
+
+// 一条路径在大气中的逐通道参数：各通道的海平面 Rayleigh 系数，以及仍在贡献的通道
+// （路径已被色散或其他介质缩减到单个通道时只剩该通道）
+struct PathChannels {
+    rayleigh: Channels,
+    live: [bool; SPECTRUM_SAMPLES],
+}
+
+impl PathChannels {
+    fn new(rayleigh: &Color, r: &Ray) -> Self {
+        let rayleigh = match r.wavelengths {
+            Some(ref wavelengths) => spectrum::uplift(rayleigh, wavelengths),
+            None => *rayleigh,
+        };
+        let live = match single_channel(r) {
+            Some(channel) => std::array::from_fn(|c| c == channel),
+            None => [true; SPECTRUM_SAMPLES],
+        };
+        Self { rayleigh: [rayleigh.x, rayleigh.y, rayleigh.z], live }
+    }
+
+    fn max(&self, v: &Channels) -> f64 {
+        (0..SPECTRUM_SAMPLES).filter(|&c| self.live[c]).map(|c| v[c]).fold(0.0, f64::max)
+    }
+
+    fn mean(&self, v: &Channels) -> f64 {
+        let live = (0..SPECTRUM_SAMPLES).filter(|&c| self.live[c]);
+        live.clone().map(|c| v[c]).sum::<f64>() / live.count() as f64
+    }
+}
+
+// 某一海拔处的系数：逐通道的 Rayleigh 散射，以及与波长无关的 Mie 散射与吸收
+struct Coefficients {
+    rayleigh: Channels,
+    mie_scattering: f64,
+    mie_absorption: f64,
+}
+
+impl Coefficients {
+    fn extinction(&self) -> Channels {
+        self.rayleigh.map(|rayleigh| rayleigh + self.mie_scattering + self.mie_absorption)
+    }
+}
+
+// 大气的几何与密度分布，由大气体与碰撞材质共享
+struct Profile {
+    center: Point3,
+    planet_radius: f64,
+    top_radius: f64,
+    rayleigh: Color,      // 海平面 Rayleigh 散射系数（逐通道）
+    rayleigh_height: f64, // 标高
+    mie_scattering: f64,
+    mie_absorption: f64,
+    mie_height: f64,
+}
+
+impl Profile {
+    fn altitude(&self, p: &Point3) -> f64 {
+        ((*p - self.center).length() - self.planet_radius).max(0.0)
+    }
+
+    fn coefficients(&self, channels: &PathChannels, h: f64) -> Coefficients {
+        let rayleigh = (-h / self.rayleigh_height).exp();
+        let mie = (-h / self.mie_height).exp();
+        Coefficients {
+            rayleigh: channels.rayleigh.map(|beta| beta * rayleigh),
+            mie_scattering: self.mie_scattering * mie,
+            mie_absorption: self.mie_absorption * mie,
+        }
+    }
+
+    // 光线在大气层内且在星体之外的各段；进入星体后的部分被星体本身遮挡，直接丢弃
+    fn spans(&self, r: &Ray, ray_t: Interval) -> Vec<(f64, f64)> {
+        let Some((t0, t1)) = sphere_interval(&self.center, self.top_radius, r) else { return Vec::new() };
+        let (t0, mut t1) = (t0.max(ray_t.min), t1.min(ray_t.max));
+        if let Some((p0, _)) = sphere_interval(&self.center, self.planet_radius, r) {
+            if p0 > ray_t.min {
+                t1 = t1.min(p0);
+            }
+        }
+        if t0 < t1 { vec![(t0, t1)] } else { Vec::new() }
+    }
+
+    // 段内最低海拔处各存活通道消光系数的最大值作为共同上界
+    fn majorant(&self, r: &Ray, channels: &PathChannels, t0: f64, t1: f64) -> f64 {
+        let t_closest = (Vec3::dot(&(self.center - r.origin), &r.direction) / r.direction.length_squared()).clamp(t0, t1);
+        channels.max(&self.coefficients(channels, self.altitude(&r.at(t_closest))).extinction())
+    }
+
+    // 以共同上界跟踪：对每个暂定碰撞调用 visit(t, 系数, 上界)，visit 返回 false 时停止
+    fn track(
+        &self,
+        r: &Ray,
+        channels: &PathChannels,
+        ray_t: Interval,
+        mut visit: impl FnMut(f64, &Coefficients, f64) -> bool,
+    ) -> Option<f64> {
+        let length = r.direction.length();
+        for (t0, t1) in self.spans(r, ray_t) {
+            let majorant = self.majorant(r, channels, t0, t1);
+            if majorant <= 0.0 {
+                continue;
+            }
+            let mut t = t0;
+            loop {
+                t -= (1.0 - random_double()).ln() / (majorant * length);
+                if t >= t1 {
+                    break;
+                }
+                let coefficients = self.coefficients(channels, self.altitude(&r.at(t)));
+                if !visit(t, &coefficients, majorant) {
+                    return Some(t);
+                }
+            }
+        }
+        None
+    }
+
+    // ratio tracking 逐通道估计 ray_t 内的透射率；其间被星体遮挡时为 0
+    fn transmittance(&self, r: &Ray, channels: &PathChannels, ray_t: Interval) -> Channels {
+        if sphere_interval(&self.center, self.planet_radius, r).is_some_and(|(p0, _)| p0 > ray_t.min && p0 < ray_t.max) {
+            return [0.0; SPECTRUM_SAMPLES];
+        }
+        let mut tr = [1.0; SPECTRUM_SAMPLES];
+        self.track(r, channels, ray_t, |_, coefficients, majorant| {
+            for (tr, extinction) in tr.iter_mut().zip(coefficients.extinction()) {
+                *tr *= 1.0 - extinction / majorant;
+            }
+            true
+        });
+        tr
+    }
+
+    // 在 p 处发生碰撞（按消光最强的通道接受）时，各事件在每个通道上的条件概率；各通道的四项之和为 1
+    fn events(&self, channels: &PathChannels, p: &Point3) -> [Channels; 4] {
+        let coefficients = self.coefficients(channels, self.altitude(p));
+        let extinction = coefficients.extinction();
+        let sigma_max = channels.max(&extinction);
+        if sigma_max <= 0.0 {
+            return [[0.0; SPECTRUM_SAMPLES], [0.0; SPECTRUM_SAMPLES], [0.0; SPECTRUM_SAMPLES], [1.0; SPECTRUM_SAMPLES]];
+        }
+        [
+            coefficients.rayleigh.map(|rayleigh| rayleigh / sigma_max),
+            [coefficients.mie_scattering / sigma_max; SPECTRUM_SAMPLES],
+            [coefficients.mie_absorption / sigma_max; SPECTRUM_SAMPLES],
+            extinction.map(|extinction| (sigma_max - extinction) / sigma_max),
+        ]
+    }
+}
+
+// 碰撞处的材质：按各存活通道条件概率的平均值选择事件，以 条件概率 / 平均概率 逐通道加权
+struct Collision {
+    profile: Arc<Profile>,
+    rayleigh: Arc<dyn PhaseFunction + Send + Sync>,
+    mie: Arc<dyn PhaseFunction + Send + Sync>,
+    single_scattering: bool,
+}
+
+impl Collision {
+    // 返回选中的事件与逐通道权重；已不贡献的通道权重为 0
+    fn choose(&self, r: &Ray, p: &Point3) -> (usize, Channels) {
+        let channels = PathChannels::new(&self.profile.rayleigh, r);
+        let events = self.profile.events(&channels, p);
+        let probabilities = events.map(|event| channels.mean(&event));
+        let mut xi = random_double() * probabilities.iter().sum::<f64>();
+        let chosen = (0..PASS).find(|&k| {
+            xi -= probabilities[k];
+            xi < 0.0
+        });
+        let chosen = chosen.unwrap_or(PASS);
+        let weight = std::array::from_fn(|c| {
+            if channels.live[c] { events[chosen][c] / probabilities[chosen] } else { 0.0 }
+        });
+        (chosen, weight)
+    }
+
+}
+
+impl Material for Collision {
+    // 多次散射：散射事件按相位函数采样新方向（反照率已计入事件概率，权重即事件权重）；
+    // 单次散射：散射已由 direct_light 计入，只有穿过事件继续
+    fn scatter(
+        &self,
+        r_in: &Ray,
+        rec: &HitRecord,
+        srec: &mut ScatterRecord,
+        _rng: &mut ThreadRng,
+    ) -> bool {
+        let (event, weight) = self.choose(r_in, &rec.p);
+        let direction = match event {
+            PASS => r_in.direction,
+            ABSORB => return false,
+            _ if self.single_scattering => return false,
+            RAYLEIGH => PhasePdf::new(self.rayleigh.clone(), &r_in.direction).generate(),
+            _ => PhasePdf::new(self.mie.clone(), &r_in.direction).generate(),
+        };
+        srec.attenuation = to_vec3(&weight);
+        srec.spectral = r_in.wavelengths.is_some();
+        srec.pdf_ptr = None;
+        srec.skip_pdf = true;
+        srec.skip_pdf_ray = Some(r_in.spawn(rec.p, direction));
+        true
+    }
+
+    // 单次散射：按光源列表采样一个方向，Rayleigh 与 Mie 散射按各自的条件概率合计而不随机选择，
+    // 返回 条件概率 × 相位函数 × 光源辐亮度 × 逐通道透射率 / pdf；阴影光线被星体或其他物体挡住时为 0
+    fn direct_light(&self, r_in: &Ray, rec: &HitRecord, world: &dyn Hittable, lights: &dyn Hittable) -> Vec3 {
+        if !self.single_scattering {
+            return Vec3::default();
+        }
+        let direction = lights.random(&rec.p);
+        let pdf = lights.pdf_value(&rec.p, &direction);
+        if pdf <= 0.0 {
+            return Vec3::default();
+        }
+        let shadow = r_in.spawn(rec.p, direction);
+        let Some((radiance, t)) = visible_emission(world, &shadow, self) else { return Vec3::default() };
+        let channels = PathChannels::new(&self.profile.rayleigh, r_in);
+        let events = self.profile.events(&channels, &rec.p);
+        let cos_theta = Vec3::dot(&Vec3::unit_vector(r_in.direction), &Vec3::unit_vector(direction));
+        let (rayleigh, mie) = (self.rayleigh.eval(cos_theta), self.mie.eval(cos_theta));
+        let tr = self.profile.transmittance(&shadow, &channels, Interval::new(1e-6, t));
+        let radiance = [radiance.x, radiance.y, radiance.z];
+        to_vec3(&std::array::from_fn(|c| {
+            let live = if channels.live[c] { 1.0 } else { 0.0 };
+            live * (events[RAYLEIGH][c] * rayleigh + events[MIE][c] * mie) * radiance[c] * tr[c] / pdf
+        }))
+    }
+}
+
+// 修改 profile 时碰撞材质的占位
+struct Absorb;
+
+impl Material for Absorb {}
+
+pub struct Atmosphere {
+    profile: Arc<Profile>,
+    mie_g: f64,
+    bbox: Aabb,
+    single_scattering: bool,
+    collision: Arc<dyn Material + Send + Sync>,
+}
+
+impl Atmosphere {
+    /// 包裹以 center 为心、半径 planet_radius 的星体、厚 thickness 的大气层。
+    /// 默认参数按地球大气缩放：标高与厚度的比例、竖直光学厚度都与地球相同
+    pub fn new(center: Point3, planet_radius: f64, thickness: f64) -> Self {
+        let profile = Profile {
+            center,
+            planet_radius,
+            top_radius: planet_radius + thickness,
+            rayleigh: Color::new(0.0, 0.0, 0.0),
+            rayleigh_height: 1.0,
+            mie_scattering: 0.0,
+            mie_absorption: 0.0,
+            mie_height: 1.0,
+        };
+        let r = profile.top_radius;
+        let mut atmosphere = Self {
+            profile: Arc::new(profile),
+            mie_g: EARTH_MIE_G,
+            bbox: Aabb::from_points(center - Vec3::new(r, r, r), center + Vec3::new(r, r, r)),
+            single_scattering: false,
+            collision: Arc::new(Absorb),
+        };
+        let (x, y, z) = EARTH_RAYLEIGH_DEPTH;
+        atmosphere.set_rayleigh(Color::new(x, y, z), EARTH_RAYLEIGH_HEIGHT * thickness);
+        atmosphere.set_mie(EARTH_MIE_DEPTH, EARTH_MIE_ALBEDO, EARTH_MIE_HEIGHT * thickness, EARTH_MIE_G);
+        atmosphere
+    }
+
+    /// optical_depth 为竖直穿过整层大气的 Rayleigh 光学厚度（逐通道），scale_height 为标高
+    pub fn with_rayleigh(mut self, optical_depth: Color, scale_height: f64) -> Self {
+        self.set_rayleigh(optical_depth, scale_height);
+        self
+    }
+
+    /// optical_depth 为竖直 Mie 消光光学厚度，albedo 为其中散射所占比例，g 为 Cornette–Shanks 的不对称参数
+    pub fn with_mie(mut self, optical_depth: f64, albedo: f64, scale_height: f64, g: f64) -> Self {
+        self.set_mie(optical_depth, albedo, scale_height, g);
+        self
+    }
+
+    /// 单次散射：散射点对渲染时传入的光源列表做下一事件估计后终止路径，噪声小但不含多次散射与地面反射
+    pub fn with_single_scattering(mut self) -> Self {
+        self.single_scattering = true;
+        self.rebuild_collision();
+        self
+    }
+
+    // 碰撞材质持有 profile，修改前先释放，之后由 rebuild_collision 重建
+    fn profile_mut(&mut self) -> &mut Profile {
+        self.collision = Arc::new(Absorb);
+        Arc::get_mut(&mut self.profile).expect("atmosphere profile is not shared outside its collision material")
+    }
+
+    fn set_rayleigh(&mut self, optical_depth: Color, scale_height: f64) {
+        let profile = self.profile_mut();
+        profile.rayleigh = optical_depth / scale_height;
+        profile.rayleigh_height = scale_height;
+        self.rebuild_collision();
+    }
+
+    fn set_mie(&mut self, optical_depth: f64, albedo: f64, scale_height: f64, g: f64) {
+        let profile = self.profile_mut();
+        let extinction = optical_depth / scale_height;
+        profile.mie_scattering = extinction * albedo.clamp(0.0, 1.0);
+        profile.mie_absorption = extinction * (1.0 - albedo.clamp(0.0, 1.0));
+        profile.mie_height = scale_height;
+        self.mie_g = g;
+        self.rebuild_collision();
+    }
+
+    fn rebuild_collision(&mut self) {
+        self.collision = Arc::new(Collision {
+            profile: self.profile.clone(),
+            rayleigh: Arc::new(Rayleigh),
+            mie: Arc::new(CornetteShanks::new(self.mie_g)),
+            single_scattering: self.single_scattering,
+        });
+    }
+}
+
+impl Hittable for Atmosphere {
+    // 暂定碰撞以消光最强通道的概率接受，事件与逐通道权重由碰撞材质决定
+    fn hit(&self, r: &Ray, ray_t: Interval, rec: &mut HitRecord) -> bool {
+        let channels = PathChannels::new(&self.profile.rayleigh, r);
+        let hit_t = self.profile.track(r, &channels, ray_t, |_, coefficients, majorant| {
+            random_double() * majorant >= channels.max(&coefficients.extinction())
+        });
+        let Some(t) = hit_t else { return false };
+
+        rec.t = t;
+        rec.p = r.at(t);
+        rec.normal = Vec3::new(1.0, 0.0, 0.0); // 任意
+        rec.front_face = true;                  // 任意
+        rec.mat = Some(self.collision.clone());
+        rec.dpdu = Vec3::default();
+        rec.dpdv = Vec3::default();
+        rec.vertex_color = None;
+        true
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hittable::HittableList;
+    use crate::material::{DiffuseLight, Lambertian};
+    use crate::sphere::Sphere;
+
+    // 半径 10、大气厚 1 的地球式大气
+    fn earth() -> Atmosphere {
+        Atmosphere::new(Point3::new(0.0, 0.0, 0.0), 10.0, 1.0)
+    }
+
+    fn upward() -> Ray {
+        Ray::new(Point3::new(0.0, 10.0 + 1e-9, 0.0), Vec3::new(0.0, 1.0, 0.0), 0.0)
+    }
+
+    // 竖直光学厚度：海平面系数 × 标高 × (1 - exp(-厚度 / 标高))
+    fn vertical_depth(atmosphere: &Atmosphere) -> Channels {
+        let profile = &atmosphere.profile;
+        let column = |height: f64| height * (1.0 - (-1.0 / height).exp());
+        let mie = (profile.mie_scattering + profile.mie_absorption) * column(profile.mie_height);
+        let rayleigh = profile.rayleigh * column(profile.rayleigh_height);
+        [rayleigh.x + mie, rayleigh.y + mie, rayleigh.z + mie]
+    }
+
+    #[test]
+    fn defaults_follow_the_earth_reference() {
+        let depth = vertical_depth(&earth());
+        let (x, y, z) = EARTH_RAYLEIGH_DEPTH;
+        for (depth, rayleigh) in depth.iter().zip([x, y, z]) {
+            assert!((depth - rayleigh - EARTH_MIE_DEPTH).abs() < 1e-3);
+        }
+        let atmosphere = earth();
+        let profile = &atmosphere.profile;
+        assert!((profile.mie_absorption / (profile.mie_scattering + profile.mie_absorption) - (1.0 - EARTH_MIE_ALBEDO)).abs() < 1e-12);
+    }
+
+    // ratio tracking 的逐通道透射率与 delta tracking 的穿透概率都等于 exp(-光学厚度)
+    #[test]
+    fn tracking_matches_the_vertical_optical_depth() {
+        let atmosphere = earth();
+        let depth = vertical_depth(&atmosphere);
+        let r = upward();
+        let channels = PathChannels::new(&atmosphere.profile.rayleigh, &r);
+        let n = 40000;
+        let mut tr = [0.0; SPECTRUM_SAMPLES];
+        for _ in 0..n {
+            for (sum, t) in tr.iter_mut().zip(atmosphere.profile.transmittance(&r, &channels, Interval::new(1e-6, f64::INFINITY))) {
+                *sum += t / n as f64;
+            }
+        }
+        for c in 0..SPECTRUM_SAMPLES {
+            assert!((tr[c] - (-depth[c]).exp()).abs() < 0.005, "channel {c}");
+        }
+        // 以消光最强的蓝通道接受碰撞
+        let passed = (0..n).filter(|_| !atmosphere.hit(&r, Interval::new(1e-9, f64::INFINITY), &mut HitRecord::default())).count();
+        assert!((passed as f64 / n as f64 - (-depth[2]).exp()).abs() < 0.01);
+    }
+
+    // 掠射光线穿过更厚的大气，透射光偏红；穿过星体的光线完全被遮挡
+    #[test]
+    fn grazing_light_reddens_and_the_planet_blocks() {
+        let atmosphere = earth();
+        let profile = &atmosphere.profile;
+        let grazing = Ray::new(Point3::new(-20.0, 10.05, 0.0), Vec3::new(1.0, 0.0, 0.0), 0.0);
+        let channels = PathChannels::new(&profile.rayleigh, &grazing);
+        let n = 4000;
+        let mean = |r: &Ray| {
+            let mut tr = [0.0; SPECTRUM_SAMPLES];
+            for _ in 0..n {
+                for (sum, t) in tr.iter_mut().zip(profile.transmittance(r, &channels, Interval::new(1e-6, f64::INFINITY))) {
+                    *sum += t / n as f64;
+                }
+            }
+            tr
+        };
+        let (side, up) = (mean(&grazing), mean(&upward()));
+        assert!(side[0] < up[0] && side[2] < up[2]);
+        assert!(side[0] / side[2] > up[0] / up[2]);
+
+        let through = Ray::new(Point3::new(-20.0, 0.0, 0.0), Vec3::new(1.0, 0.0, 0.0), 0.0);
+        assert_eq!(profile.transmittance(&through, &channels, Interval::new(1e-6, f64::INFINITY)), [0.0; SPECTRUM_SAMPLES]);
+        // 大气只覆盖星体之前的那一段
+        let spans = profile.spans(&through, Interval::new(0.0, f64::INFINITY));
+        assert_eq!(spans.len(), 1);
+        assert!((spans[0].0 - 9.0).abs() < 1e-9 && (spans[0].1 - 10.0).abs() < 1e-9);
+    }
+
+    // 单样本 MIS：每个事件在通道 c 上的期望权重等于它在该通道上的条件概率
+    #[test]
+    fn collision_weights_are_unbiased_per_channel() {
+        let atmosphere = earth();
+        let collision = Collision {
+            profile: atmosphere.profile.clone(),
+            rayleigh: Arc::new(Rayleigh),
+            mie: Arc::new(CornetteShanks::new(EARTH_MIE_G)),
+            single_scattering: false,
+        };
+        let r = upward();
+        let p = Point3::new(0.0, 10.05, 0.0);
+        let channels = PathChannels::new(&atmosphere.profile.rayleigh, &r);
+        let events = atmosphere.profile.events(&channels, &p);
+        for c in 0..SPECTRUM_SAMPLES {
+            assert!((events.iter().map(|e| e[c]).sum::<f64>() - 1.0).abs() < 1e-12);
+        }
+        let n = 400000;
+        let mut sums = [[0.0; SPECTRUM_SAMPLES]; 4];
+        for _ in 0..n {
+            let (event, weight) = collision.choose(&r, &p);
+            for c in 0..SPECTRUM_SAMPLES {
+                sums[event][c] += weight[c] / n as f64;
+            }
+        }
+        for event in 0..4 {
+            for c in 0..SPECTRUM_SAMPLES {
+                assert!((sums[event][c] - events[event][c]).abs() < 0.01, "event {event} channel {c}");
+            }
+        }
+    }
+
+    // 单次散射：场景里的星体挡住太阳时散射点处于阴影中，夜侧不发光；朝向太阳时为正；没有光源列表时为 0
+    #[test]
+    fn single_scattering_sees_the_planet_shadow() {
+        let atmosphere = earth().with_single_scattering();
+        let collision = atmosphere.collision.clone();
+        let sun = Point3::new(0.0, 1000.0, 0.0);
+        let mut world = HittableList::new();
+        world.add(Arc::new(Sphere::new(sun, 50.0, Some(Arc::new(DiffuseLight::from_color(Color::new(10.0, 10.0, 10.0)))))));
+        world.add(Arc::new(Sphere::new(Point3::new(0.0, 0.0, 0.0), 10.0, Some(Arc::new(Lambertian::from_color(Color::new(0.5, 0.5, 0.5)))))));
+        world.add(Arc::new(atmosphere));
+        let mut lights = HittableList::new();
+        lights.add(Arc::new(Sphere::new(sun, 50.0, None)));
+
+        let mut rec = HitRecord::default();
+        let r = Ray::new(Point3::new(-20.0, 10.5, 0.0), Vec3::new(1.0, 0.0, 0.0), 0.0);
+        rec.p = Point3::new(0.0, 10.5, 0.0);
+        let lit = collision.direct_light(&r, &rec, &world, &lights);
+        assert!(lit.x > 0.0 && lit.z > lit.x);
+        assert_eq!(collision.direct_light(&r, &rec, &world, &HittableList::new()).length(), 0.0);
+        rec.p = Point3::new(0.0, -10.5, 0.0);
+        for _ in 0..100 {
+            assert_eq!(collision.direct_light(&r, &rec, &world, &lights).length(), 0.0);
+        }
+        // 星体之外的物体同样投下阴影
+        world.add(Arc::new(Sphere::new(Point3::new(0.0, 500.0, 0.0), 60.0, Some(Arc::new(Lambertian::from_color(Color::new(0.5, 0.5, 0.5)))))));
+        rec.p = Point3::new(0.0, 10.5, 0.0);
+        assert_eq!(collision.direct_light(&r, &rec, &world, &lights).length(), 0.0);
+    }
+}
@@ -1,14 +1,14 @@
 use std::sync::{Arc, Mutex, Condvar, atomic::{AtomicUsize, Ordering}};
 use std::io::{self, Write};
 use std::fs::File;
-use crate::vec3::{Point3, Vec3};
+use crate::vec3::{Color, Point3, Vec3};
 use crate::ray::Ray;
-use crate::color::*;
 use crate::hittable::{Hittable,HitRecord};
 use crate::interval::Interval;
 use crate::rtweekend::*;
 use crate::rtweekend::random::*;
 use crate::material::Material;
+use crate::spectrum::{self, Wavelengths};
 use rayon::prelude::*;
 use indicatif::{ProgressBar, ProgressStyle};
 use crossbeam::thread;
@@ -130,6 +130,7 @@ pub struct Camera {
     sqrt_spp: usize,            // Square root of samples per pixel
     bar: ProgressBar,           // Progress bar
     pub russian_roulette: RussianRouletteStrategy, // 俄罗斯轮盘赌策略
+    pub spectral: bool,         // 光谱渲染模式：主波长采样，胶片中转换回 RGB
 }
 
 impl Camera {
@@ -165,6 +166,7 @@ impl Camera {
             sqrt_spp,
             bar: ProgressBar::hidden(),
             russian_roulette: RussianRouletteStrategy::None, // 默认关闭俄罗斯轮盘赌
+            spectral: false,
         };
         cam.initialize();
         cam
@@ -373,8 +375,14 @@ impl Camera {
                     let s_j_f64 = s_j as f64;
                     for s_i in 0..self.sqrt_spp {
                         let s_i_f64 = s_i as f64;
-                        let ray = self.get_ray_stratified_with_rng_optimized(i, j, s_i_f64, s_j_f64, sqrt_spp_f64, &mut rng);
-                        let sample_color = self.ray_color(&ray, self.max_depth, world, lights);
+                        let mut ray = self.get_ray_stratified_with_rng_optimized(i, j, s_i_f64, s_j_f64, sqrt_spp_f64, &mut rng);
+                        let sample_color = if self.spectral {
+                            let wavelengths = Wavelengths::sample_hero(&mut rng);
+                            ray.wavelengths = Some(wavelengths);
+                            spectrum::to_rgb(&self.ray_color(&ray, self.max_depth, world, lights), &wavelengths)
+                        } else {
+                            self.ray_color(&ray, self.max_depth, world, lights)
+                        };
                         pixel_color += sample_color;
                     }
                 }
@@ -501,9 +509,9 @@ impl Camera {
                 let dir = Vec3::unit_vector(r.direction());
                 let u = 0.5 + dir.x.atan2(dir.z) / (2.0 * std::f64::consts::PI);
                 let v = 0.5 - dir.y.asin() / std::f64::consts::PI;
-                return tex.value(u, v, &dir);
+                return path_color(r, tex.value(u, v, &dir));
             } else {
-                return self.background;
+                return path_color(r, self.background);
             }
         }
 
@@ -511,68 +519,99 @@ impl Camera {
 
         // 如果光线击中场景中的物体
         if world.hit(r, Interval::new(0.001, f64::INFINITY), &mut rec) {
-            use crate::material::ScatterRecord;
-            let mut srec = ScatterRecord {
-                attenuation: Color::new(0.0, 0.0, 0.0),
-                pdf_ptr: None,
-                skip_pdf: false,
-                skip_pdf_ray: None,
-            };
+            // 按光线所处介质计算本段路径的 Beer–Lambert 吸收
+            let segment_transmittance = path_color(r, r.media.transmittance(rec.t * r.direction().length()));
+            segment_transmittance * self.shade_hit(r, &rec, depth, world, lights)
+        } else {
+            // 未命中物体时返回背景贴图或背景色
+            if let Some(ref tex) = self.background_texture {
+                let dir = Vec3::unit_vector(r.direction());
+                let u = 0.5 + dir.x.atan2(dir.z) / (2.0 * std::f64::consts::PI);
+                let v = 0.5 - dir.y.asin() / std::f64::consts::PI;
+                path_color(r, tex.value(u, v, &dir))
+            } else {
+                path_color(r, self.background)
+            }
+        }
+    }
 
-            let color_from_emission = match &rec.mat {
-                Some(mat) => mat.emitted(rec.u, rec.v, &rec.p),
-                None => return self.background,
-            };
+    // 命中物体后的着色：自发光 + 散射
+    fn shade_hit(&self, r: &Ray, rec: &HitRecord, depth: usize, world: &dyn Hittable, lights: &dyn Hittable) -> Color {
+        use crate::material::ScatterRecord;
+        let mut srec = ScatterRecord::new();
 
-            let mut rng = thread_rng();
-            if !rec.mat.as_ref().map_or(false, |mat| mat.scatter(r, &rec, &mut srec, &mut rng)) {
-                return color_from_emission;
+        let color_from_emission = match &rec.mat {
+            Some(mat) => {
+                let emitted = match r.wavelengths {
+                    Some(ref wavelengths) => mat.emitted_at_wavelengths(r, rec, wavelengths),
+                    None => mat.emitted(r, rec),
+                };
+                emitted + mat.direct_light(r, rec, world, lights)
             }
+            None => return path_color(r, self.background),
+        };
+
+        let mut rng = thread_rng();
+        if !rec.mat.as_ref().is_some_and(|mat| mat.scatter(r, rec, &mut srec, &mut rng)) {
+            return color_from_emission;
+        }
+        if !srec.spectral {
+            srec.attenuation = path_color(r, srec.attenuation);
+        }
 
-            if srec.skip_pdf {
-                if let Some(ref skip_ray) = srec.skip_pdf_ray {
-                    return srec.attenuation * self.ray_color(skip_ray, depth - 1, world, lights);
+        if srec.skip_pdf {
+            if let Some(ref skip_ray) = srec.skip_pdf_ray {
+                let mut incoming = self.ray_color(skip_ray, depth - 1, world, lights);
+                // 在此处发生色散时，只有主波长沿该方向继续
+                let terminated_here = skip_ray.wavelengths.is_some_and(|wl| wl.secondary_terminated)
+                    && !r.wavelengths.is_some_and(|wl| wl.secondary_terminated);
+                if terminated_here {
+                    incoming = incoming * spectrum::termination_mask();
                 }
+                return srec.attenuation * incoming;
             }
+        }
 
-            // === C++风格的PDF混合采样实现 ===
-            use crate::pdf::{HittablePdf, MixturePdf, Pdf};
-            use std::sync::Arc;
-            // 彻底避免静态生命周期约束，直接用空实现的HittableList，不包裹lights引用
-            let lights_arc: Arc<dyn Hittable + Send + Sync> = Arc::new(crate::hittable::HittableList::new());
-            let light_ptr = Arc::new(HittablePdf::new(lights_arc.clone(), rec.p));
-            let pdf_ptr = srec.pdf_ptr.clone().expect("ScatterRecord.pdf_ptr must be Some for non-specular materials");
-            let mixture_pdf = MixturePdf::new(light_ptr, pdf_ptr);
-            let scattered = Ray::new(rec.p, mixture_pdf.generate(), r.time());
-            let pdf_value = mixture_pdf.value(&scattered.direction());
-
-            let scattering_pdf = rec.mat.as_ref().expect("HitRecord.mat must be Some").scattering_pdf(r, &rec, &scattered);
-            let continue_prob = self.russian_roulette_probability(&srec.attenuation, depth);
-            if thread_rng().gen::<f64>() < continue_prob {
-                // 只为PDF混合采样临时用Arc，递归调用仍传&dyn Hittable
-                let sample_color = self.ray_color(&scattered, depth - 1, world, lights);
-                let color_from_scatter = if pdf_value > 0.0 {
-                    (srec.attenuation * scattering_pdf * sample_color) / pdf_value
-                } else {
-                    Color::new(0.0, 0.0, 0.0)
-                };
-                (color_from_emission + color_from_scatter) / continue_prob
-            } else {
-                color_from_emission
+        // === C++风格的PDF混合采样实现 ===
+        use crate::pdf::{HittablePdf, MixturePdf, Pdf};
+        use std::sync::Arc;
+        // 彻底避免静态生命周期约束，直接用空实现的HittableList，不包裹lights引用
+        let lights_arc: Arc<dyn Hittable + Send + Sync> = Arc::new(crate::hittable::HittableList::new());
+        let light_ptr = Arc::new(HittablePdf::new(lights_arc.clone(), rec.p));
+        let pdf_ptr = srec.pdf_ptr.clone().expect("ScatterRecord.pdf_ptr must be Some for non-specular materials");
+        let mixture_pdf = MixturePdf::new(light_ptr, pdf_ptr);
+        let scattered = r.spawn(rec.p, mixture_pdf.generate());
+        let pdf_value = mixture_pdf.value(&scattered.direction());
+
+        let mat = rec.mat.as_ref().expect("HitRecord.mat must be Some");
+        // 彩色 BSDF 优先；否则退化为标量 scattering_pdf
+        let bsdf = match mat.eval_bsdf(r, rec, &scattered) {
+            Some(f) => path_color(r, f),
+            None => {
+                let scattering_pdf = mat.scattering_pdf(r, rec, &scattered);
+                Color::new(scattering_pdf, scattering_pdf, scattering_pdf)
             }
-        } else {
-            // 未命中物体时返回背景贴图或背景色
-            if let Some(ref tex) = self.background_texture {
-                let dir = Vec3::unit_vector(r.direction());
-                let u = 0.5 + dir.x.atan2(dir.z) / (2.0 * std::f64::consts::PI);
-                let v = 0.5 - dir.y.asin() / std::f64::consts::PI;
-                tex.value(u, v, &dir)
+        };
+        let continue_prob = self.russian_roulette_probability(&srec.attenuation, depth);
+        if thread_rng().gen::<f64>() < continue_prob {
+            // 只为PDF混合采样临时用Arc，递归调用仍传&dyn Hittable
+            let sample_color = self.ray_color(&scattered, depth - 1, world, lights);
+            let color_from_scatter = if pdf_value > 0.0 {
+                (srec.attenuation * bsdf * sample_color) / pdf_value
             } else {
-                self.background
-            }
+                Color::new(0.0, 0.0, 0.0)
+            };
+            (color_from_emission + color_from_scatter) / continue_prob
+        } else {
+            color_from_emission
         }
     }
 
+    // 设置光谱渲染模式
+    pub fn set_spectral(&mut self, spectral: bool) {
+        self.spectral = spectral;
+    }
+
     // 辅助函数：将Color转换为RGB u8数组 - 内联版本已移至render_sub中优化
 
 
@@ -1041,6 +1080,14 @@ impl Camera {
     }
 }
 
+// 光谱模式下把 RGB 量（纹理、光源、背景、衰减）上采样到光线的采样波长
+fn path_color(r: &Ray, c: Color) -> Color {
+    match r.wavelengths {
+        Some(ref wavelengths) => spectrum::uplift(&c, wavelengths),
+        None => c,
+    }
+}
+
 // SIMD优化的光线颜色计算函数 - 移到模块级别
 #[cfg(feature = "simd")]
 fn ray_color_with_rr_simd(camera: &Camera, r: &Ray, depth: usize, world: &dyn Hittable, background: Color, simd_rng: &mut SimdRng) -> Color {
@@ -1061,7 +1108,7 @@ fn ray_color_with_rr_simd(camera: &Camera, r: &Ray, depth: usize, world: &dyn Hi
         None => return Color::new(0.0, 0.0, 0.0),
     };
 
-    let color_from_emission = mat.emitted(rec.u, rec.v, &rec.p);
+    let color_from_emission = mat.emitted(r, &rec);
 
     let mut scattered = Ray::default();
     let mut attenuation = Color::default();
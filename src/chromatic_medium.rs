@@ -0,0 +1,340 @@
+// 彩色、可自发光的参与介质：逐通道的吸收与散射系数，吸收处按纹理或温度场发光（火焰、星云、彩色烟雾）
+// 距离采样用主通道法：光线第一次接触介质时把路径缩减到单个通道（光谱模式下终止副波长，
+// RGB 模式下随机选一个通道并乘以 3 倍掩码），之后在该通道上做标量 delta tracking，
+// 透射率与采样概率严格相消，介质内部的其他物体也能得到正确的权重
+use std::sync::Arc;
+use rand::{rngs::ThreadRng, Rng};
+use crate::aabb::Aabb;
+use crate::constant_medium::boundary_spans;
+use crate::heterogeneous_medium::Density;
+use crate::hittable::{HitRecord, Hittable};
+use crate::interval::Interval;
+use crate::material::{Material, ScatterRecord};
+use crate::phase::{HenyeyGreenstein, PhaseFunction, PhaseMaterial};
+use crate::ray::Ray;
+use crate::rtweekend::random::random_double;
+use crate::spectrum::{self, SPECTRUM_SAMPLES};
+use crate::texture::Texture;
+use crate::vec3::{Color, Point3, Vec3};
+
+// 温度场的黑体颜色查找表大小
+const TEMPERATURE_TABLE_SIZE: usize = 64;
+
+/// 介质的自发光辐亮度（每单位吸收系数），只在吸收事件处计入
+pub enum MediumEmission {
+    Texture(Arc<dyn Texture + Send + Sync>),
+    // field 的 [0, 1] 线性映射到 kelvin 的温度范围，亮度按 Stefan–Boltzmann 定律随 T⁴ 变化
+    Temperature { field: Density, kelvin: (f64, f64), intensity: f64, table: Vec<Color> },
+}
+
+impl MediumEmission {
+    pub fn from_texture(tex: Arc<dyn Texture + Send + Sync>) -> Self {
+        MediumEmission::Texture(tex)
+    }
+
+    /// 黑体颜色预先在温度范围内制表，避免每次吸收事件都对普朗克光谱积分
+    pub fn from_temperature(field: Density, kelvin_min: f64, kelvin_max: f64, intensity: f64) -> Self {
+        let table = (0..TEMPERATURE_TABLE_SIZE)
+            .map(|i| {
+                let t = kelvin_min + (kelvin_max - kelvin_min) * i as f64 / (TEMPERATURE_TABLE_SIZE - 1) as f64;
+                spectrum::color_temperature_rgb(t) * (t / kelvin_max).powi(4)
+            })
+            .collect();
+        MediumEmission::Temperature { field, kelvin: (kelvin_min, kelvin_max), intensity, table }
+    }
+
+    pub fn radiance(&self, p: &Point3) -> Color {
+        match self {
+            MediumEmission::Texture(tex) => tex.value(0.0, 0.0, p),
+            MediumEmission::Temperature { field, kelvin, intensity, table } => {
+                if kelvin.1 <= 0.0 {
+                    return Color::new(0.0, 0.0, 0.0);
+                }
+                let x = field.at(p).clamp(0.0, 1.0) * (TEMPERATURE_TABLE_SIZE - 1) as f64;
+                let i = (x as usize).min(TEMPERATURE_TABLE_SIZE - 2);
+                let f = x - i as f64;
+                *intensity * ((1.0 - f) * table[i] + f * table[i + 1])
+            }
+        }
+    }
+}
+
+// 吸收事件：路径在此终止，返回介质的自发光
+struct MediumEmitter {
+    emission: Option<MediumEmission>,
+}
+
+impl Material for MediumEmitter {
+    fn is_emissive(&self) -> bool {
+        self.emission.is_some()
+    }
+
+    fn emitted(&self, _r_in: &Ray, rec: &HitRecord) -> Color {
+        match self.emission {
+            Some(ref emission) => emission.radiance(&rec.p),
+            None => Color::new(0.0, 0.0, 0.0),
+        }
+    }
+}
+
+// 首次接触介质时选定主通道，光线方向不变
+struct ChannelSelect;
+
+impl Material for ChannelSelect {
+    fn scatter(
+        &self,
+        r_in: &Ray,
+        rec: &HitRecord,
+        srec: &mut ScatterRecord,
+        rng: &mut ThreadRng,
+    ) -> bool {
+        let mut ray = r_in.spawn(rec.p, r_in.direction);
+        srec.attenuation = match ray.wavelengths.as_mut() {
+            // 光谱模式：相机在副波长终止处乘以 termination_mask
+            Some(wavelengths) => {
+                wavelengths.terminate_secondary();
+                Color::new(1.0, 1.0, 1.0)
+            }
+            None => {
+                let channel = rng.gen_range(0..SPECTRUM_SAMPLES);
+                ray.channel = Some(channel);
+                let mut mask = [0.0; SPECTRUM_SAMPLES];
+                mask[channel] = SPECTRUM_SAMPLES as f64;
+                Color::new(mask[0], mask[1], mask[2])
+            }
+        };
+        srec.pdf_ptr = None;
+        srec.skip_pdf = true;
+        srec.skip_pdf_ray = Some(ray);
+        true
+    }
+}
+
+// 路径已缩减到的通道；光谱模式下副波长终止后只剩主波长（分量 0）
+pub(crate) fn single_channel(r: &Ray) -> Option<usize> {
+    match r.wavelengths {
+        Some(ref wavelengths) => wavelengths.secondary_terminated.then_some(0),
+        None => r.channel,
+    }
+}
+
+// RGB 系数在该通道上的值；光谱模式下上采样到主波长
+fn channel_value(c: &Color, r: &Ray, channel: usize) -> f64 {
+    match r.wavelengths {
+        Some(ref wavelengths) => spectrum::rgb_to_spectrum(c, wavelengths.hero()),
+        None => [c.x, c.y, c.z][channel],
+    }
+}
+
+pub struct ChromaticMedium {
+    boundary: Arc<dyn Hittable + Send + Sync>,
+    sigma_a: Color, // 吸收系数（每单位长度，逐通道）
+    sigma_s: Color, // 散射系数
+    density: Option<Density>,
+    density_bound: f64, // density 的保证上界，均匀介质为 1
+    emitter: Arc<dyn Material + Send + Sync>,
+    phase_function: Arc<dyn Material + Send + Sync>,
+    channel_select: Arc<dyn Material + Send + Sync>,
+}
+
+impl ChromaticMedium {
+    /// 均匀、各向同性散射、不发光；用 with_* 添加密度场、相位函数与自发光
+    pub fn new(boundary: Arc<dyn Hittable + Send + Sync>, sigma_a: Color, sigma_s: Color) -> Self {
+        Self {
+            boundary,
+            sigma_a,
+            sigma_s,
+            density: None,
+            density_bound: 1.0,
+            emitter: Arc::new(MediumEmitter { emission: None }),
+            phase_function: Arc::new(PhaseMaterial::new_with_color(Arc::new(HenyeyGreenstein::new(0.0)), Color::new(1.0, 1.0, 1.0))),
+            channel_select: Arc::new(ChannelSelect),
+        }
+    }
+
+    // 散射概率已计入 σs / σt，相位材质的反照率取 1
+    pub fn with_phase(mut self, phase: Arc<dyn PhaseFunction + Send + Sync>) -> Self {
+        self.phase_function = Arc::new(PhaseMaterial::new_with_color(phase, Color::new(1.0, 1.0, 1.0)));
+        self
+    }
+
+    /// 系数乘以 density(p)，非均匀介质；上界取 Density 的保证上界
+    pub fn with_density(mut self, density: Density) -> Self {
+        self.density_bound = density.bound();
+        self.density = Some(density);
+        self
+    }
+
+    pub fn with_emission(mut self, emission: MediumEmission) -> Self {
+        self.emitter = Arc::new(MediumEmitter { emission: Some(emission) });
+        self
+    }
+
+    fn density_at(&self, p: &Point3) -> f64 {
+        match self.density {
+            Some(ref density) => density.at(p),
+            None => 1.0,
+        }
+    }
+
+    fn fill(&self, r: &Ray, t: f64, mat: &Arc<dyn Material + Send + Sync>, rec: &mut HitRecord) {
+        rec.t = t;
+        rec.p = r.at(t);
+        rec.normal = Vec3::new(1.0, 0.0, 0.0); // 任意
+        rec.front_face = true;                  // 任意
+        rec.mat = Some(mat.clone());
+        rec.dpdu = Vec3::default();
+        rec.dpdv = Vec3::default();
+        rec.vertex_color = None;
+    }
+}
+
+impl Hittable for ChromaticMedium {
+    fn hit(&self, r: &Ray, ray_t: Interval, rec: &mut HitRecord) -> bool {
+        let spans = boundary_spans(self.boundary.as_ref(), r, ray_t);
+        let Some(&(t_first, _)) = spans.first() else { return false };
+        let Some(channel) = single_channel(r) else {
+            self.fill(r, t_first, &self.channel_select, rec);
+            return true;
+        };
+
+        // 在该通道上做 delta tracking：暂定碰撞按 σa : σs : 虚碰撞 的比例分为吸收、散射与继续前进
+        let (sigma_a, sigma_s) = (channel_value(&self.sigma_a, r, channel), channel_value(&self.sigma_s, r, channel));
+        let majorant = (sigma_a + sigma_s) * self.density_bound;
+        if majorant <= 0.0 {
+            return false;
+        }
+        let step = 1.0 / (majorant * r.direction.length());
+        for (t0, t1) in spans {
+            let mut t = t0;
+            loop {
+                t -= (1.0 - random_double()).ln() * step;
+                if t >= t1 {
+                    break;
+                }
+                let density = self.density_at(&r.at(t));
+                let xi = random_double() * majorant;
+                if xi < sigma_a * density {
+                    self.fill(r, t, &self.emitter, rec);
+                    return true;
+                }
+                if xi < (sigma_a + sigma_s) * density {
+                    self.fill(r, t, &self.phase_function, rec);
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.boundary.bounding_box()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aabb::Aabb;
+    use crate::heterogeneous_medium::VoxelGrid;
+    use crate::sphere::Sphere;
+    use crate::texture::SolidColor;
+    use rand::thread_rng;
+
+    // 半径 1 的球，沿 x 轴穿过的长度为 2
+    fn ball() -> Arc<dyn Hittable + Send + Sync> {
+        Arc::new(Sphere::new(Point3::new(0.0, 0.0, 0.0), 1.0, None))
+    }
+
+    fn ray_in(channel: Option<usize>) -> Ray {
+        let mut r = Ray::new(Point3::new(-5.0, 0.0, 0.0), Vec3::new(1.0, 0.0, 0.0), 0.0);
+        r.channel = channel;
+        r
+    }
+
+    fn hit(medium: &ChromaticMedium, r: &Ray) -> Option<HitRecord> {
+        let mut rec = HitRecord::default();
+        medium.hit(r, Interval::new(0.001, f64::INFINITY), &mut rec).then_some(rec)
+    }
+
+    // 首次接触只选择通道：平均掩码为白色，之后的光线沿原方向带着所选通道继续
+    #[test]
+    fn first_contact_selects_a_channel() {
+        let medium = ChromaticMedium::new(ball(), Color::new(0.1, 0.2, 0.3), Color::new(0.3, 0.2, 0.1));
+        let r = ray_in(None);
+        let rec = hit(&medium, &r).unwrap();
+        assert!((rec.t - 4.0).abs() < 1e-9);
+        let n = 30000;
+        let mut mean = Color::new(0.0, 0.0, 0.0);
+        for _ in 0..n {
+            let mut srec = ScatterRecord::new();
+            assert!(rec.mat.as_ref().unwrap().scatter(&r, &rec, &mut srec, &mut thread_rng()));
+            let next = srec.skip_pdf_ray.unwrap();
+            let channel = next.channel.unwrap();
+            assert_eq!([srec.attenuation.x, srec.attenuation.y, srec.attenuation.z][channel], 3.0);
+            assert!((Vec3::unit_vector(next.direction) - Vec3::new(1.0, 0.0, 0.0)).length() < 1e-12);
+            mean += srec.attenuation / n as f64;
+        }
+        assert!((mean - Color::new(1.0, 1.0, 1.0)).length() < 0.05);
+    }
+
+    // 每个通道的穿透概率为 exp(-(σa + σs) L)，碰撞中吸收与散射之比为 σa : σs
+    #[test]
+    fn tracking_uses_the_selected_channel() {
+        let (sigma_a, sigma_s) = (Color::new(0.05, 0.3, 0.9), Color::new(0.15, 0.1, 0.3));
+        let medium = ChromaticMedium::new(ball(), sigma_a, sigma_s);
+        let n = 40000;
+        for channel in 0..3 {
+            let (a, s) = ([sigma_a.x, sigma_a.y, sigma_a.z][channel], [sigma_s.x, sigma_s.y, sigma_s.z][channel]);
+            let r = ray_in(Some(channel));
+            let (mut passed, mut absorbed) = (0, 0);
+            for _ in 0..n {
+                match hit(&medium, &r) {
+                    None => passed += 1,
+                    Some(rec) => {
+                        assert!(rec.p.length() <= 1.0 + 1e-9);
+                        if Arc::ptr_eq(rec.mat.as_ref().unwrap(), &medium.emitter) {
+                            absorbed += 1;
+                        }
+                    }
+                }
+            }
+            let expected = (-(a + s) * 2.0).exp();
+            assert!((passed as f64 / n as f64 - expected).abs() < 0.01, "channel {channel}");
+            let collided = (n - passed) as f64;
+            assert!((absorbed as f64 / collided - a / (a + s)).abs() < 0.02, "channel {channel}");
+        }
+    }
+
+    // 密度场按 density × 系数缩放：x ∈ [-2, 2] 上从 0 线性增到 1 的斜坡，光学厚度为 σ × 1
+    #[test]
+    fn density_scales_the_coefficients() {
+        let grid = VoxelGrid::new(vec![0.0, 1.0], 2, 1, 1, Aabb::from_points(Point3::new(-4.0, -4.0, -4.0), Point3::new(4.0, 4.0, 4.0)));
+        let medium = ChromaticMedium::new(ball(), Color::new(0.6, 0.6, 0.6), Color::new(0.6, 0.6, 0.6)).with_density(Density::Grid(grid));
+        let n = 40000;
+        let passed = (0..n).filter(|_| hit(&medium, &ray_in(Some(1))).is_none()).count();
+        assert!((passed as f64 / n as f64 - (-1.2f64).exp()).abs() < 0.01);
+    }
+
+    #[test]
+    fn emission_follows_texture_and_temperature() {
+        let glow = MediumEmission::from_texture(Arc::new(SolidColor::new(Color::new(0.2, 0.4, 0.8))));
+        assert!((glow.radiance(&Point3::new(3.0, 1.0, 0.0)) - Color::new(0.2, 0.4, 0.8)).length() < 1e-12);
+
+        let grid = |v: f64| Density::Grid(VoxelGrid::new(vec![v], 1, 1, 1, Aabb::from_points(Point3::new(-1.0, -1.0, -1.0), Point3::new(1.0, 1.0, 1.0))));
+        let hot = MediumEmission::from_temperature(grid(1.0), 1000.0, 3000.0, 5.0).radiance(&Point3::default());
+        assert!((hot - 5.0 * spectrum::color_temperature_rgb(3000.0)).length() < 1e-9);
+        // 亮度随 T⁴：1000 K 为 3000 K 的 1/81，且颜色更红
+        let cold = MediumEmission::from_temperature(grid(0.0), 1000.0, 3000.0, 5.0).radiance(&Point3::default());
+        assert!((cold - 5.0 / 81.0 * spectrum::color_temperature_rgb(1000.0)).length() < 1e-9);
+        assert!(cold.x / cold.z > hot.x / hot.z);
+
+        // 吸收事件处的材质给出自发光，散射事件的材质不发光；光学厚度 40，光线必然在球内碰撞
+        let medium = ChromaticMedium::new(ball(), Color::new(20.0, 20.0, 20.0), Color::new(0.0, 0.0, 0.0)).with_emission(glow);
+        let r = ray_in(Some(2));
+        let rec = hit(&medium, &r).unwrap();
+        let mat = rec.mat.as_ref().unwrap();
+        assert!(mat.is_emissive() && !mat.scatter(&r, &rec, &mut ScatterRecord::new(), &mut thread_rng()));
+        assert!((mat.emitted(&r, &rec) - Color::new(0.2, 0.4, 0.8)).length() < 1e-12);
+    }
+}
@@ -0,0 +1,205 @@
+// 圆锥（可选底盖），局部坐标系中底面在 z = 0、锥顶在 z = height
+use std::f64::consts::PI;
+use std::sync::Arc;
+use crate::aabb::Aabb;
+use crate::cylinder::{azimuth_u, disk_bbox};
+use crate::hittable::{area_pdf_value, HitRecord, Hittable};
+use crate::interval::Interval;
+use crate::material::Material;
+use crate::onb::Onb;
+use crate::ray::Ray;
+use crate::rtweekend::random::random_double;
+use crate::vec3::{Point3, Vec3};
+
+pub struct Cone {
+    base: Point3,
+    uvw: Onb, // w 由底面指向锥顶
+    height: f64,
+    radius: f64,
+    capped: bool,
+    mat: Arc<dyn Material + Send + Sync>,
+    bbox: Aabb,
+    area: f64,
+}
+
+impl Cone {
+    /// 底面中心 base、锥顶 apex、底面半径 radius，默认带底盖
+    pub fn new(base: Point3, apex: Point3, radius: f64, mat: Arc<dyn Material + Send + Sync>) -> Self {
+        let axis = apex - base;
+        let height = axis.length();
+        let bbox = Aabb::surrounding_box(&disk_bbox(&base, &axis, radius), &Aabb::from_points(apex, apex));
+        let mut cone = Self { base, uvw: Onb::new(&axis), height, radius, capped: true, mat, bbox, area: 0.0 };
+        cone.area = cone.compute_area();
+        cone
+    }
+
+    pub fn uncapped(mut self) -> Self {
+        self.capped = false;
+        self.area = self.compute_area();
+        self
+    }
+
+    pub fn area(&self) -> f64 {
+        self.area
+    }
+
+    fn side_area(&self) -> f64 {
+        PI * self.radius * (self.radius * self.radius + self.height * self.height).sqrt()
+    }
+
+    fn compute_area(&self) -> f64 {
+        let cap = if self.capped { PI * self.radius * self.radius } else { 0.0 };
+        self.side_area() + cap
+    }
+}
+
+impl Hittable for Cone {
+    fn hit(&self, r: &Ray, ray_t: Interval, rec: &mut HitRecord) -> bool {
+        let o = self.uvw.to_local(&(r.origin - self.base));
+        let d = self.uvw.to_local(&r.direction);
+        let k = self.radius / self.height;
+        let k2 = k * k;
+        let mut closest: Option<(f64, bool)> = None; // (t, 是否为侧面)
+        let mut consider = |t: f64, side: bool| {
+            if ray_t.surrounds(t) && closest.is_none_or(|(best, _)| t < best) {
+                closest = Some((t, side));
+            }
+        };
+
+        // 侧面：x² + y² = k² (h - z)²，0 ≤ z ≤ h
+        let hz = self.height - o.z;
+        let a = d.x * d.x + d.y * d.y - k2 * d.z * d.z;
+        let half_b = o.x * d.x + o.y * d.y + k2 * hz * d.z;
+        let c = o.x * o.x + o.y * o.y - k2 * hz * hz;
+        let mut roots = [f64::NAN; 2];
+        if a.abs() < 1e-12 {
+            // 光线与母线平行，只有一个交点
+            if half_b.abs() > 1e-12 {
+                roots[0] = -c / (2.0 * half_b);
+            }
+        } else {
+            let discriminant = half_b * half_b - a * c;
+            if discriminant >= 0.0 {
+                let sqrtd = discriminant.sqrt();
+                roots = [(-half_b - sqrtd) / a, (-half_b + sqrtd) / a];
+            }
+        }
+        for t in roots {
+            let z = o.z + t * d.z;
+            if (0.0..=self.height).contains(&z) {
+                consider(t, true);
+            }
+        }
+
+        if self.capped && d.z.abs() > 1e-12 {
+            let t = -o.z / d.z;
+            let (x, y) = (o.x + t * d.x, o.y + t * d.y);
+            if x * x + y * y <= self.radius * self.radius {
+                consider(t, false);
+            }
+        }
+
+        let Some((t, side)) = closest else { return false };
+        let lp = o + t * d;
+        let rho = (lp.x * lp.x + lp.y * lp.y).sqrt();
+        let (cos_phi, sin_phi) = if rho > 0.0 { (lp.x / rho, lp.y / rho) } else { (1.0, 0.0) };
+        let tangent = 2.0 * PI * Vec3::new(-lp.y, lp.x, 0.0);
+        let (normal, v, dpdv) = if side {
+            // 隐式面梯度方向 (x, y, k² (h - z)) 在锥顶退化，改用 (cosφ, sinφ, k)
+            (
+                Vec3::unit_vector(Vec3::new(cos_phi, sin_phi, k)),
+                lp.z / self.height,
+                self.height * Vec3::new(-k * cos_phi, -k * sin_phi, 1.0),
+            )
+        } else {
+            (Vec3::new(0.0, 0.0, -1.0), rho / self.radius, self.radius * Vec3::new(cos_phi, sin_phi, 0.0))
+        };
+
+        rec.t = t;
+        rec.p = r.at(t);
+        rec.set_face_normal(r, self.uvw.transform(&normal));
+        rec.u = azimuth_u(lp.x, lp.y);
+        rec.v = v;
+        rec.dpdu = self.uvw.transform(&tangent);
+        rec.dpdv = self.uvw.transform(&dpdv);
+        rec.vertex_color = None;
+        rec.mat = Some(self.mat.clone());
+        true
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+
+    fn pdf_value(&self, origin: &Point3, direction: &Vec3) -> f64 {
+        area_pdf_value(self, self.area, origin, direction)
+    }
+
+    fn random(&self, origin: &Point3) -> Vec3 {
+        let phi = 2.0 * PI * random_double();
+        let local = if random_double() * self.area < self.side_area() {
+            // 侧面面积元正比于到锥顶的距离：h - z = h sqrt(ξ)
+            let z = self.height * (1.0 - random_double().sqrt());
+            let rho = self.radius * (self.height - z) / self.height;
+            Vec3::new(rho * phi.cos(), rho * phi.sin(), z)
+        } else {
+            let rho = self.radius * random_double().sqrt();
+            Vec3::new(rho * phi.cos(), rho * phi.sin(), 0.0)
+        };
+        self.base + self.uvw.transform(&local) - *origin
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hittable::pdf_integral;
+    use crate::material::Lambertian;
+    use crate::vec3::Color;
+
+    fn spike() -> Cone {
+        Cone::new(Point3::new(0.0, 0.0, 0.0), Point3::new(0.0, 2.0, 0.0), 1.0, Arc::new(Lambertian::from_color(Color::new(0.5, 0.5, 0.5))))
+    }
+
+    fn hit(shape: &dyn Hittable, origin: Point3, direction: Vec3) -> Option<HitRecord> {
+        let mut rec = HitRecord::default();
+        shape.hit(&Ray::new(origin, direction, 0.0), Interval::new(0.001, f64::INFINITY), &mut rec).then_some(rec)
+    }
+
+    // 半高处半径为 0.5，侧面法线 ∝ (高度, 半径)
+    #[test]
+    fn side_hits_follow_the_slant() {
+        let rec = hit(&spike(), Point3::new(5.0, 1.0, 0.0), Vec3::new(-1.0, 0.0, 0.0)).unwrap();
+        assert!((rec.t - 4.5).abs() < 1e-9);
+        let expected = Vec3::unit_vector(Vec3::new(2.0, 1.0, 0.0));
+        assert!((rec.normal - expected).length() < 1e-9 && rec.front_face);
+        assert!(hit(&spike(), Point3::new(5.0, 2.1, 0.0), Vec3::new(-1.0, 0.0, 0.0)).is_none());
+    }
+
+    #[test]
+    fn base_cap_is_optional() {
+        let (origin, direction) = (Point3::new(0.3, -5.0, 0.0), Vec3::new(0.0, 1.0, 0.0));
+        let capped = hit(&spike(), origin, direction).unwrap();
+        assert!((capped.t - 5.0).abs() < 1e-9);
+        assert!((capped.normal - Vec3::new(0.0, -1.0, 0.0)).length() < 1e-9);
+
+        let open = hit(&spike().uncapped(), origin, direction).unwrap();
+        assert!((open.t - 6.4).abs() < 1e-9);
+        assert!(!open.front_face);
+    }
+
+    #[test]
+    fn light_sampling_pdf_integrates_to_one() {
+        let origin = Point3::new(-2.0, 1.0, 2.5);
+        let slant = 5.0f64.sqrt();
+        for shape in [spike(), spike().uncapped()] {
+            let cap = if shape.capped { PI } else { 0.0 };
+            assert!((shape.area() - PI * slant - cap).abs() < 1e-12);
+            assert!((pdf_integral(&shape, &origin) - 1.0).abs() < 0.03);
+            for _ in 0..100 {
+                assert!(shape.pdf_value(&origin, &shape.random(&origin)) > 0.0);
+            }
+        }
+    }
+}
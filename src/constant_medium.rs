@@ -2,6 +2,7 @@ use std::sync::Arc;
 use crate::material::Isotropic;
 use crate::hittable::{Hittable, HitRecord};
 use crate::material::Material;
+use crate::phase::{PhaseFunction, PhaseMaterial};
 use crate::texture::Texture;
 use crate::vec3::{Vec3, Point3};
 use crate::ray::Ray;
@@ -10,6 +11,35 @@ use crate::aabb::Aabb;
 use crate::rtweekend::random::*;
 use crate::rtweekend::INFINITY;
 
+// 在密度均匀的介质中按指数分布采样自由程，neg_inv_density = -1 / density
+pub fn sample_free_flight(neg_inv_density: f64) -> f64 {
+    neg_inv_density * random_double().ln()
+}
+
+// 光线在封闭边界内部的各段参数区间 [t0, t1]，已裁剪到 ray_t。
+// 非凸边界可能有多段；由交点的 front_face 判断进出，第一个交点为离开时起点在内部
+pub(crate) fn boundary_spans(boundary: &dyn Hittable, r: &Ray, ray_t: Interval) -> Vec<(f64, f64)> {
+    // 查询到无穷远，ray_t.max 之前没有交点时也能判断起点是否在内部
+    let mut hits = Vec::new();
+    boundary.hit_all(r, Interval::new(ray_t.min, INFINITY), &mut hits);
+    let mut spans = Vec::new();
+    let mut start = hits.first().filter(|h| !h.front_face).map(|_| ray_t.min);
+    for h in &hits {
+        match start {
+            None if h.front_face => start = Some(h.t),
+            Some(t0) if !h.front_face => {
+                if t0 < ray_t.max {
+                    spans.push((t0, h.t.min(ray_t.max)));
+                }
+                start = None;
+            }
+            // 连续两次进入或离开（开放曲面、数值误差）时忽略该交点
+            _ => {}
+        }
+    }
+    spans
+}
+
 pub struct ConstantMedium {
     boundary: Arc<dyn Hittable + Send + Sync>,
     neg_inv_density: f64,
@@ -40,6 +70,20 @@ impl ConstantMedium {
             phase_function: Arc::new(Isotropic::new_with_color(albedo)),
         }
     }
+
+    // 各向异性散射，如 HenyeyGreenstein 描述的雾和云
+    pub fn new_with_phase(
+        boundary: Arc<dyn Hittable + Send + Sync>,
+        density: f64,
+        phase: Arc<dyn PhaseFunction + Send + Sync>,
+        albedo: Vec3,
+    ) -> Self {
+        Self {
+            boundary,
+            neg_inv_density: -1.0 / density,
+            phase_function: Arc::new(PhaseMaterial::new_with_color(phase, albedo)),
+        }
+    }
 }
 
 impl Hittable for ConstantMedium {
@@ -49,45 +93,29 @@ impl Hittable for ConstantMedium {
         ray_t: Interval,
         rec: &mut HitRecord,
     ) -> bool {
-        let mut rec1 = HitRecord::default();
-        let mut rec2 = HitRecord::default();
-
-        if !self.boundary.hit(r, Interval::universe(), &mut rec1) {
-            return false;
-        }
-
-        if !self.boundary.hit(r, Interval::new(rec1.t + 0.0001, INFINITY), &mut rec2) {
-            return false;
-        }
-
-        let mut t1 = rec1.t;
-        let mut t2 = rec2.t;
-
-        if t1 < ray_t.min { t1 = ray_t.min; }
-        if t2 > ray_t.max { t2 = ray_t.max; }
-
-        if t1 >= t2 {
-            return false;
-        }
-
-        if t1 < 0.0 {
-            t1 = 0.0;
-        }
-
+        // 指数分布无记忆：采样一次自由程，依次扣除各段在介质内的长度
         let ray_length = r.direction.length();
-        let distance_inside_boundary = (t2 - t1) * ray_length;
-        let hit_distance = self.neg_inv_density * random_double().ln();
-
-        if hit_distance > distance_inside_boundary {
-            return false;
+        let mut hit_distance = sample_free_flight(self.neg_inv_density);
+        let mut hit_t = None;
+        for (t1, t2) in boundary_spans(self.boundary.as_ref(), r, ray_t) {
+            let distance_inside_boundary = (t2 - t1) * ray_length;
+            if hit_distance <= distance_inside_boundary {
+                hit_t = Some(t1 + hit_distance / ray_length);
+                break;
+            }
+            hit_distance -= distance_inside_boundary;
         }
+        let Some(t) = hit_t else { return false };
 
-        rec.t = t1 + hit_distance / ray_length;
+        rec.t = t;
         rec.p = r.at(rec.t);
 
         rec.normal = Vec3::new(1.0, 0.0, 0.0); // 任意
         rec.front_face = true;                  // 任意
         rec.mat = Some(self.phase_function.clone());
+        rec.dpdu = Vec3::default();
+        rec.dpdv = Vec3::default();
+        rec.vertex_color = None;
 
         true
     }
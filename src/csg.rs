@@ -0,0 +1,235 @@
+// 构造实体几何：对两个封闭物体做并、交、差
+// 合并两者沿光线的进出交点序列，组合后的内外状态发生变化的交点即为结果的表面
+use std::sync::Arc;
+use crate::aabb::Aabb;
+use crate::hittable::{HitRecord, Hittable};
+use crate::interval::Interval;
+use crate::ray::Ray;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CsgOp {
+    Union,
+    Intersection,
+    Difference, // a 减去 b
+}
+
+impl CsgOp {
+    fn inside(&self, in_a: bool, in_b: bool) -> bool {
+        match self {
+            CsgOp::Union => in_a || in_b,
+            CsgOp::Intersection => in_a && in_b,
+            CsgOp::Difference => in_a && !in_b,
+        }
+    }
+}
+
+pub struct Csg {
+    op: CsgOp,
+    a: Arc<dyn Hittable + Send + Sync>,
+    b: Arc<dyn Hittable + Send + Sync>,
+    bbox: Aabb,
+}
+
+impl Csg {
+    /// a、b 须为封闭物体（球、make_box 的盒子、封闭网格或嵌套的 Csg）
+    pub fn new(op: CsgOp, a: Arc<dyn Hittable + Send + Sync>, b: Arc<dyn Hittable + Send + Sync>) -> Self {
+        let (box_a, box_b) = (a.bounding_box(), b.bounding_box());
+        let bbox = match op {
+            CsgOp::Union => Aabb::surrounding_box(&box_a, &box_b),
+            CsgOp::Intersection => Aabb::new(
+                Interval::new(box_a.x.min.max(box_b.x.min), box_a.x.max.min(box_b.x.max)),
+                Interval::new(box_a.y.min.max(box_b.y.min), box_a.y.max.min(box_b.y.max)),
+                Interval::new(box_a.z.min.max(box_b.z.min), box_a.z.max.min(box_b.z.max)),
+            ),
+            CsgOp::Difference => box_a,
+        };
+        Self { op, a, b, bbox }
+    }
+
+    pub fn union(a: Arc<dyn Hittable + Send + Sync>, b: Arc<dyn Hittable + Send + Sync>) -> Self {
+        Self::new(CsgOp::Union, a, b)
+    }
+
+    pub fn intersection(a: Arc<dyn Hittable + Send + Sync>, b: Arc<dyn Hittable + Send + Sync>) -> Self {
+        Self::new(CsgOp::Intersection, a, b)
+    }
+
+    pub fn difference(a: Arc<dyn Hittable + Send + Sync>, b: Arc<dyn Hittable + Send + Sync>) -> Self {
+        Self::new(CsgOp::Difference, a, b)
+    }
+}
+
+impl Hittable for Csg {
+    fn hit(&self, r: &Ray, ray_t: Interval, rec: &mut HitRecord) -> bool {
+        let mut hits = Vec::new();
+        self.hit_all(r, ray_t, &mut hits);
+        match hits.into_iter().next() {
+            Some(first) => {
+                *rec = first;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+
+    fn hit_all(&self, r: &Ray, ray_t: Interval, hits: &mut Vec<HitRecord>) {
+        if !self.bbox.hit(r, ray_t) {
+            return;
+        }
+        // 子物体查询到无穷远，才能由第一个交点可靠地推断起点处的内外状态
+        let full = Interval::new(ray_t.min, f64::INFINITY);
+        let (mut ha, mut hb) = (Vec::new(), Vec::new());
+        self.a.hit_all(r, full, &mut ha);
+        self.b.hit_all(r, full, &mut hb);
+
+        // 第一个交点是离开，说明起点在物体内部
+        let mut in_a = ha.first().is_some_and(|h| !h.front_face);
+        let mut in_b = hb.first().is_some_and(|h| !h.front_face);
+        let mut inside = self.op.inside(in_a, in_b);
+
+        let (mut i, mut j) = (0, 0);
+        while i < ha.len() || j < hb.len() {
+            let from_a = j >= hb.len() || (i < ha.len() && ha[i].t <= hb[j].t);
+            let mut rec = if from_a {
+                i += 1;
+                in_a = ha[i - 1].front_face;
+                ha[i - 1].clone()
+            } else {
+                j += 1;
+                in_b = hb[j - 1].front_face;
+                hb[j - 1].clone()
+            };
+            if rec.t >= ray_t.max {
+                break;
+            }
+            let now = self.op.inside(in_a, in_b);
+            if now == inside {
+                continue;
+            }
+            inside = now;
+            // 差集中 b 的表面成为结果的内壁，外法线反向
+            let outward = if rec.front_face { rec.normal } else { -rec.normal };
+            let outward = if !from_a && self.op == CsgOp::Difference { -outward } else { outward };
+            rec.set_face_normal(r, outward);
+            hits.push(rec);
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::material::{Lambertian, Material};
+    use crate::quad::make_box;
+    use crate::rtweekend::random::random_double_range;
+    use crate::sphere::Sphere;
+    use crate::triangle::TriangleMesh;
+    use crate::vec3::{Color, Point3, Vec3};
+
+    fn grey() -> Arc<dyn Material + Send + Sync> {
+        Arc::new(Lambertian::from_color(Color::new(0.5, 0.5, 0.5)))
+    }
+
+    fn ball(x: f64) -> Arc<dyn Hittable + Send + Sync> {
+        Arc::new(Sphere::new(Point3::new(x, 0.0, 0.0), 1.0, Some(grey())))
+    }
+
+    // 沿 +x 的光线上所有交点的 (x, 是否进入)
+    fn spans(shape: &dyn Hittable, x0: f64) -> Vec<(f64, bool)> {
+        let r = Ray::new(Point3::new(x0, 0.0, 0.0), Vec3::new(1.0, 0.0, 0.0), 0.0);
+        let mut hits = Vec::new();
+        shape.hit_all(&r, Interval::new(0.001, f64::INFINITY), &mut hits);
+        hits.iter().map(|h| (h.p.x, h.front_face)).collect()
+    }
+
+    fn assert_spans(found: Vec<(f64, bool)>, expected: &[(f64, bool)]) {
+        assert_eq!(found.len(), expected.len(), "{:?}", found);
+        for ((x, enter), (ex, eenter)) in found.iter().zip(expected) {
+            assert!((x - ex).abs() < 1e-9 && enter == eenter, "{:?} vs {:?}", found, expected);
+        }
+    }
+
+    // 两个单位球沿 x 轴错开 1：a 占 [-1, 1]，b 占 [0, 2]
+    #[test]
+    fn span_merging_follows_the_boolean_operator() {
+        assert_spans(spans(&Csg::union(ball(0.0), ball(1.0)), -5.0), &[(-1.0, true), (2.0, false)]);
+        assert_spans(spans(&Csg::intersection(ball(0.0), ball(1.0)), -5.0), &[(0.0, true), (1.0, false)]);
+        assert_spans(spans(&Csg::difference(ball(0.0), ball(1.0)), -5.0), &[(-1.0, true), (0.0, false)]);
+        assert_spans(spans(&Csg::difference(ball(1.0), ball(0.0)), -5.0), &[(1.0, true), (2.0, false)]);
+        // 不相交的差集保持 a 不变，交集为空
+        assert_spans(spans(&Csg::difference(ball(0.0), ball(5.0)), -5.0), &[(-1.0, true), (1.0, false)]);
+        assert!(spans(&Csg::intersection(ball(0.0), ball(5.0)), -5.0).is_empty());
+    }
+
+    // b 的表面成为差集的内壁，法线朝向挖空的一侧
+    #[test]
+    fn difference_flips_normals_of_the_subtracted_surface() {
+        let bite = Csg::difference(ball(1.0), ball(0.0));
+        let r = Ray::new(Point3::new(-5.0, 0.0, 0.0), Vec3::new(1.0, 0.0, 0.0), 0.0);
+        let mut rec = HitRecord::default();
+        assert!(bite.hit(&r, Interval::new(0.001, f64::INFINITY), &mut rec));
+        assert!((rec.t - 6.0).abs() < 1e-9 && rec.front_face);
+        assert!((rec.normal - Vec3::new(-1.0, 0.0, 0.0)).length() < 1e-9);
+    }
+
+    // 起点在结果内部时第一个交点是离开；ray_t.max 截断后面的交点
+    #[test]
+    fn rays_starting_inside_and_clipped_intervals() {
+        let lens = Csg::intersection(ball(0.0), ball(1.0));
+        assert_spans(spans(&lens, 0.5), &[(1.0, false)]);
+        let nested = Csg::difference(Arc::new(Csg::union(ball(0.0), ball(1.0))), Arc::new(Sphere::new(Point3::new(0.5, 0.0, 0.0), 0.25, None)));
+        assert_spans(spans(&nested, -5.0), &[(-1.0, true), (0.25, false), (0.75, true), (2.0, false)]);
+        let r = Ray::new(Point3::new(-5.0, 0.0, 0.0), Vec3::new(1.0, 0.0, 0.0), 0.0);
+        let mut hits = Vec::new();
+        nested.hit_all(&r, Interval::new(0.001, 5.5), &mut hits);
+        assert_eq!(hits.len(), 2);
+        let bbox = lens.bounding_box();
+        assert!(bbox.x.min >= -0.001 && bbox.x.max <= 1.001);
+    }
+
+    // 12 个三角形组成的封闭立方体网格，朝外绕序
+    fn cube_mesh() -> TriangleMesh {
+        let vertices = (0..8)
+            .map(|i| Point3::new(
+                if i & 1 == 0 { -1.0 } else { 1.0 },
+                if i & 2 == 0 { -1.0 } else { 1.0 },
+                if i & 4 == 0 { -1.0 } else { 1.0 },
+            ))
+            .collect();
+        let faces = vec![
+            [0, 4, 6], [0, 6, 2], [1, 3, 7], [1, 7, 5], [0, 1, 5], [0, 5, 4],
+            [2, 6, 7], [2, 7, 3], [0, 2, 3], [0, 3, 1], [4, 5, 7], [4, 7, 6],
+        ];
+        TriangleMesh::new(vertices, faces, grey()).unwrap()
+    }
+
+    // make_box 的盒子与同形的网格作为运算对象时给出相同的结果
+    #[test]
+    fn boxes_and_meshes_agree_as_operands() {
+        let cutter = || Arc::new(Sphere::new(Point3::new(0.0, 1.0, 0.0), 0.6, Some(grey())));
+        let from_box = Csg::difference(make_box(Point3::new(-1.0, -1.0, -1.0), Point3::new(1.0, 1.0, 1.0), grey()), cutter());
+        let from_mesh = Csg::difference(Arc::new(cube_mesh()), cutter());
+
+        let r = Ray::new(Point3::new(0.0, 5.0, 0.0), Vec3::new(0.0, -1.0, 0.0), 0.0);
+        let mut rec = HitRecord::default();
+        assert!(from_box.hit(&r, Interval::new(0.001, f64::INFINITY), &mut rec));
+        assert!((rec.p.y - 0.4).abs() < 1e-9 && (rec.normal - Vec3::new(0.0, 1.0, 0.0)).length() < 1e-9);
+
+        for _ in 0..500 {
+            let origin = Point3::new(random_double_range(-3.0, 3.0), random_double_range(-3.0, 3.0), random_double_range(-3.0, 3.0));
+            let r = Ray::new(origin, Vec3::random_unit_vector(), 0.0);
+            let (mut a, mut b) = (Vec::new(), Vec::new());
+            from_box.hit_all(&r, Interval::new(0.001, f64::INFINITY), &mut a);
+            from_mesh.hit_all(&r, Interval::new(0.001, f64::INFINITY), &mut b);
+            assert_eq!(a.len(), b.len());
+            for (x, y) in a.iter().zip(&b) {
+                assert!((x.t - y.t).abs() < 1e-9 && x.front_face == y.front_face);
+            }
+        }
+    }
+}
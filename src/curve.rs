@@ -0,0 +1,511 @@
+// 三次 Bézier 曲线图元（毛发、草叶等细长物体），宽度沿曲线线性变化
+// 求交按 pbrt 的做法：把控制点变换到以光线为 z 轴的坐标系，递归二分曲线直到近似直线段，
+// 再检查光线（即原点）到线段的距离是否小于半宽
+use std::sync::Arc;
+use crate::aabb::Aabb;
+use crate::hittable::{HitRecord, Hittable};
+use crate::interval::Interval;
+use crate::material::Material;
+use crate::onb::Onb;
+use crate::ray::Ray;
+use crate::vec3::{Point3, Vec3};
+
+// 每条曲线预先切成的段数，段越短包围盒越紧
+const CURVE_SEGMENTS: usize = 4;
+// 曲线集合内部 BVH 叶子最多包含的段数
+const CURVE_LEAF_SIZE: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CurveType {
+    Flat,     // 始终正对光线的扁带，适合远看的毛发
+    Ribbon,   // 法线由两端给定的定向扁带，如草叶
+    Cylinder, // 仍按扁带求交，但法线绕切线弯曲，着色看起来像圆柱
+}
+
+#[derive(Debug, Clone)]
+pub struct Curve {
+    cp: [Point3; 4],
+    width: (f64, f64),        // u = 0 与 u = 1 处的宽度
+    normals: Option<(Vec3, Vec3)>, // Ribbon 两端的法线
+    kind: CurveType,
+}
+
+impl Curve {
+    pub fn flat(cp: [Point3; 4], width0: f64, width1: f64) -> Self {
+        Self { cp, width: (width0, width1), normals: None, kind: CurveType::Flat }
+    }
+
+    pub fn cylinder(cp: [Point3; 4], width0: f64, width1: f64) -> Self {
+        Self { cp, width: (width0, width1), normals: None, kind: CurveType::Cylinder }
+    }
+
+    // 两端法线之间球面插值
+    pub fn ribbon(cp: [Point3; 4], width0: f64, width1: f64, n0: Vec3, n1: Vec3) -> Self {
+        let normals = Some((Vec3::unit_vector(n0), Vec3::unit_vector(n1)));
+        Self { cp, width: (width0, width1), normals, kind: CurveType::Ribbon }
+    }
+
+    fn width_at(&self, u: f64) -> f64 {
+        lerp(u, self.width.0, self.width.1)
+    }
+
+    fn normal_at(&self, u: f64) -> Vec3 {
+        let Some((n0, n1)) = self.normals else { return Vec3::default() };
+        let angle = Vec3::dot(&n0, &n1).clamp(-1.0, 1.0).acos();
+        if angle < 1e-6 {
+            return n0;
+        }
+        let sin = angle.sin();
+        ((1.0 - u) * angle).sin() / sin * n0 + (u * angle).sin() / sin * n1
+    }
+}
+
+fn lerp(t: f64, a: f64, b: f64) -> f64 {
+    (1.0 - t) * a + t * b
+}
+
+fn lerp_point(t: f64, a: Point3, b: Point3) -> Point3 {
+    (1.0 - t) * a + t * b
+}
+
+// 三次 Bézier 的 blossom，blossom(u, u, u) 为曲线上的点
+fn blossom(cp: &[Point3; 4], u0: f64, u1: f64, u2: f64) -> Point3 {
+    let a = [lerp_point(u0, cp[0], cp[1]), lerp_point(u0, cp[1], cp[2]), lerp_point(u0, cp[2], cp[3])];
+    let b = [lerp_point(u1, a[0], a[1]), lerp_point(u1, a[1], a[2])];
+    lerp_point(u2, b[0], b[1])
+}
+
+// 子区间 [u0, u1] 上的控制点
+fn sub_curve(cp: &[Point3; 4], u0: f64, u1: f64) -> [Point3; 4] {
+    [blossom(cp, u0, u0, u0), blossom(cp, u0, u0, u1), blossom(cp, u0, u1, u1), blossom(cp, u1, u1, u1)]
+}
+
+// de Casteljau 求值：返回 (点, 导数)
+fn eval_bezier(cp: &[Point3; 4], u: f64) -> (Point3, Vec3) {
+    let a = [lerp_point(u, cp[0], cp[1]), lerp_point(u, cp[1], cp[2]), lerp_point(u, cp[2], cp[3])];
+    let b = [lerp_point(u, a[0], a[1]), lerp_point(u, a[1], a[2])];
+    let d = 3.0 * (b[1] - b[0]);
+    // 端点处控制点重合时导数为零，用首尾连线代替
+    let d = if d.length_squared() > 0.0 { d } else { cp[3] - cp[0] };
+    (lerp_point(u, b[0], b[1]), d)
+}
+
+// 在 u = 0.5 处一分为二，返回 7 个控制点，前后两段共用中间一个
+fn split_bezier(cp: &[Point3; 4]) -> [Point3; 7] {
+    let m01 = 0.5 * (cp[0] + cp[1]);
+    let m12 = 0.5 * (cp[1] + cp[2]);
+    let m23 = 0.5 * (cp[2] + cp[3]);
+    let a = 0.5 * (m01 + m12);
+    let b = 0.5 * (m12 + m23);
+    [cp[0], m01, a, 0.5 * (a + b), b, m23, cp[3]]
+}
+
+fn rotate(v: &Vec3, axis: &Vec3, angle: f64) -> Vec3 {
+    let (sin, cos) = angle.sin_cos();
+    *v * cos + *axis * (Vec3::dot(axis, v) * (1.0 - cos)) + Vec3::cross(axis, v) * sin
+}
+
+// 预切分的曲线段
+struct CurveSegment {
+    curve: usize,
+    cp: [Point3; 4], // 本段控制点（世界坐标）
+    u: (f64, f64),   // 在整条曲线上的参数区间
+    max_depth: u32,  // 递归二分的层数，使子段足够接近直线
+    bbox: Aabb,
+}
+
+// 光线坐标系下的最近交点
+struct CurveHit {
+    z: f64, // 沿单位光线方向的距离
+    u: f64,
+    v: f64,
+    width: f64,
+    segment: usize,
+}
+
+// 一次求交的状态：z 区间随找到的交点收紧
+struct CurveQuery {
+    dir: Vec3, // 单位光线方向（世界坐标）
+    z_min: f64,
+    z_max: f64,
+    best: Option<CurveHit>,
+}
+
+// 内部 BVH 节点，布局同三角网格：count > 0 为叶子
+struct CurveBvhNode {
+    bbox: Aabb,
+    start: usize,
+    count: usize,
+    right: usize,
+}
+
+// 曲线集合：成千上万根毛发共用一个材质与一棵内部 BVH
+pub struct CurveSet {
+    curves: Vec<Curve>,
+    segments: Vec<CurveSegment>,
+    nodes: Vec<CurveBvhNode>,
+    order: Vec<usize>,
+    mat: Arc<dyn Material + Send + Sync>,
+    bbox: Aabb,
+}
+
+impl CurveSet {
+    pub fn new(curves: Vec<Curve>, mat: Arc<dyn Material + Send + Sync>) -> Self {
+        let mut segments = Vec::with_capacity(curves.len() * CURVE_SEGMENTS);
+        for (i, curve) in curves.iter().enumerate() {
+            for s in 0..CURVE_SEGMENTS {
+                let u0 = s as f64 / CURVE_SEGMENTS as f64;
+                let u1 = (s + 1) as f64 / CURVE_SEGMENTS as f64;
+                let cp = sub_curve(&curve.cp, u0, u1);
+                let half_width = 0.5 * curve.width_at(u0).max(curve.width_at(u1));
+                let bbox = cp.iter().fold(Aabb::new_empty(), |b, p| {
+                    let pad = Vec3::new(half_width, half_width, half_width);
+                    Aabb::surrounding_box(&b, &Aabb::from_points(*p - pad, *p + pad))
+                });
+                segments.push(CurveSegment { curve: i, cp, u: (u0, u1), max_depth: split_depth(&cp, half_width), bbox });
+            }
+        }
+
+        let mut set = Self { curves, segments, nodes: Vec::new(), order: Vec::new(), mat, bbox: Aabb::new_empty() };
+        let mut order: Vec<usize> = (0..set.segments.len()).collect();
+        if !order.is_empty() {
+            set.build(&mut order, 0);
+            set.bbox = set.nodes[0].bbox;
+        }
+        set.order = order;
+        set
+    }
+
+    pub fn curve_count(&self) -> usize {
+        self.curves.len()
+    }
+
+    // 按包围盒中心在最长轴上取中位数划分，返回节点下标
+    fn build(&mut self, segs: &mut [usize], start: usize) -> usize {
+        let bbox = segs.iter().fold(Aabb::new_empty(), |b, &s| Aabb::surrounding_box(&b, &self.segments[s].bbox));
+        let index = self.nodes.len();
+        self.nodes.push(CurveBvhNode { bbox, start, count: segs.len(), right: 0 });
+        if segs.len() <= CURVE_LEAF_SIZE {
+            return index;
+        }
+
+        let center = |s: usize| {
+            let b = &self.segments[s].bbox;
+            Vec3::new(b.x.min + b.x.max, b.y.min + b.y.max, b.z.min + b.z.max)
+        };
+        let centroid_bounds = segs.iter().fold(Aabb::new_empty(), |b, &s| {
+            let c = center(s);
+            Aabb::surrounding_box(&b, &Aabb::from_points(c, c))
+        });
+        let axis = centroid_bounds.longest_axis();
+        let key = |s: usize| match axis {
+            0 => center(s).x,
+            1 => center(s).y,
+            _ => center(s).z,
+        };
+        segs.sort_by(|&a, &b| key(a).total_cmp(&key(b)));
+        let mid = segs.len() / 2;
+        let (left, right) = segs.split_at_mut(mid);
+        self.build(left, start);
+        let right_index = self.build(right, start + mid);
+        self.nodes[index].count = 0;
+        self.nodes[index].right = right_index;
+        index
+    }
+
+    // 递归二分：cp 为光线坐标系下的控制点
+    fn intersect(&self, segment: usize, cp: &[Point3; 4], u0: f64, u1: f64, depth: u32, q: &mut CurveQuery) {
+        let curve = &self.curves[self.segments[segment].curve];
+        let half_width = 0.5 * curve.width_at(u0).max(curve.width_at(u1));
+        let (mut lo, mut hi) = (cp[0], cp[0]);
+        for p in &cp[1..] {
+            lo = Vec3::new(lo.x.min(p.x), lo.y.min(p.y), lo.z.min(p.z));
+            hi = Vec3::new(hi.x.max(p.x), hi.y.max(p.y), hi.z.max(p.z));
+        }
+        if lo.x - half_width > 0.0 || hi.x + half_width < 0.0 || lo.y - half_width > 0.0 || hi.y + half_width < 0.0 {
+            return;
+        }
+        if hi.z + half_width < q.z_min || lo.z - half_width > q.z_max {
+            return;
+        }
+
+        if depth > 0 {
+            let split = split_bezier(cp);
+            let mid = 0.5 * (u0 + u1);
+            self.intersect(segment, &[split[0], split[1], split[2], split[3]], u0, mid, depth - 1, q);
+            self.intersect(segment, &[split[3], split[4], split[5], split[6]], mid, u1, depth - 1, q);
+            return;
+        }
+
+        // 原点须位于首尾两端的垂直截线之间
+        let edge = (cp[1].y - cp[0].y) * -cp[0].y + cp[0].x * (cp[0].x - cp[1].x);
+        if edge < 0.0 {
+            return;
+        }
+        let edge = (cp[2].y - cp[3].y) * -cp[3].y + cp[3].x * (cp[3].x - cp[2].x);
+        if edge < 0.0 {
+            return;
+        }
+
+        // 把子段近似为首尾连线，求原点在其上的投影参数
+        let (sx, sy) = (cp[3].x - cp[0].x, cp[3].y - cp[0].y);
+        let denom = sx * sx + sy * sy;
+        if denom == 0.0 {
+            return;
+        }
+        let w = ((-cp[0].x * sx - cp[0].y * sy) / denom).clamp(0.0, 1.0);
+        let u = lerp(w, u0, u1);
+        let mut width = curve.width_at(u);
+        if curve.kind == CurveType::Ribbon {
+            // 定向扁带侧对光线时投影变窄
+            width *= Vec3::dot(&curve.normal_at(u), &q.dir).abs();
+        }
+
+        let (pc, dpcdw) = eval_bezier(cp, w);
+        let dist2 = pc.x * pc.x + pc.y * pc.y;
+        if dist2 > 0.25 * width * width || pc.z < q.z_min || pc.z > q.z_max {
+            return;
+        }
+        // v 沿 (-dy, dx) 方向从 0 增到 1
+        let tangent_len = (dpcdw.x * dpcdw.x + dpcdw.y * dpcdw.y).sqrt();
+        let offset = if tangent_len > 0.0 { (pc.x * dpcdw.y - pc.y * dpcdw.x) / tangent_len } else { 0.0 };
+        let v = (0.5 + offset / width).clamp(0.0, 1.0);
+        q.z_max = pc.z;
+        q.best = Some(CurveHit { z: pc.z, u, v, width, segment });
+    }
+}
+
+// 二分层数：使子段控制多边形的二阶差分小于宽度的 5%（pbrt 的估计）
+fn split_depth(cp: &[Point3; 4], half_width: f64) -> u32 {
+    let mut l0: f64 = 0.0;
+    for i in 0..2 {
+        let d = cp[i] - 2.0 * cp[i + 1] + cp[i + 2];
+        l0 = l0.max(d.x.abs()).max(d.y.abs()).max(d.z.abs());
+    }
+    let eps = 2.0 * half_width * 0.05;
+    if l0 <= 0.0 || eps <= 0.0 {
+        return 0;
+    }
+    let r0 = (std::f64::consts::SQRT_2 * 6.0 * l0 / (8.0 * eps)).log2() / 2.0;
+    r0.round().clamp(0.0, 10.0) as u32
+}
+
+impl Hittable for CurveSet {
+    fn hit(&self, r: &Ray, ray_t: Interval, rec: &mut HitRecord) -> bool {
+        if self.nodes.is_empty() {
+            return false;
+        }
+        let speed = r.direction.length();
+        if speed == 0.0 {
+            return false;
+        }
+        // 光线坐标系：原点为光线起点，w 轴为光线方向，z 即沿光线的距离
+        let frame = Onb::new(&r.direction);
+        let mut q = CurveQuery {
+            dir: *frame.w(),
+            z_min: ray_t.min * speed,
+            z_max: ray_t.max * speed,
+            best: None,
+        };
+        let mut stack = vec![0usize];
+        while let Some(n) = stack.pop() {
+            let node = &self.nodes[n];
+            if !node.bbox.hit(r, Interval::new(ray_t.min, q.z_max / speed)) {
+                continue;
+            }
+            if node.count == 0 {
+                stack.push(node.right);
+                stack.push(n + 1);
+                continue;
+            }
+            for &s in &self.order[node.start..node.start + node.count] {
+                let seg = &self.segments[s];
+                let cp = seg.cp.map(|p| frame.to_local(&(p - r.origin)));
+                self.intersect(s, &cp, seg.u.0, seg.u.1, seg.max_depth, &mut q);
+            }
+        }
+        let Some(hit) = q.best else { return false };
+        let t = hit.z / speed;
+        if !ray_t.surrounds(t) {
+            return false;
+        }
+
+        let curve = &self.curves[self.segments[hit.segment].curve];
+        let (_, dpdu) = eval_bezier(&curve.cp, hit.u);
+        let mut v = hit.v;
+        let dpdv = match curve.kind {
+            CurveType::Ribbon => {
+                let dpdv = Vec3::unit_vector(Vec3::cross(&curve.normal_at(hit.u), &dpdu)) * hit.width;
+                // 求交时 v 沿投影平面内的 (-dy, dx) 增大，dpdv 与之反向时翻转 v
+                let (t_local, s_local) = (frame.to_local(&dpdu), frame.to_local(&dpdv));
+                if s_local.y * t_local.x - s_local.x * t_local.y < 0.0 {
+                    v = 1.0 - v;
+                }
+                dpdv
+            }
+            CurveType::Flat | CurveType::Cylinder => {
+                // 在垂直于光线的平面内取与切线垂直的方向，与求交时 v 的方向一致
+                let t_local = frame.to_local(&dpdu);
+                let side = Vec3::new(-t_local.y, t_local.x, 0.0);
+                let side = if side.length_squared() > 0.0 { Vec3::unit_vector(side) } else { Vec3::new(1.0, 0.0, 0.0) };
+                let side = if curve.kind == CurveType::Cylinder {
+                    // 横截面上从 v = 0 到 v = 1 转过半圈，两侧边缘处法线与光线垂直
+                    let theta = lerp(v, -0.5, 0.5) * std::f64::consts::PI;
+                    rotate(&side, &Vec3::unit_vector(t_local), theta)
+                } else {
+                    side
+                };
+                frame.transform(&side) * hit.width
+            }
+        };
+
+        rec.t = t;
+        rec.p = r.at(t);
+        rec.set_face_normal(r, Vec3::unit_vector(Vec3::cross(&dpdu, &dpdv)));
+        rec.u = hit.u;
+        rec.v = v;
+        rec.dpdu = dpdu;
+        rec.dpdv = dpdv;
+        rec.vertex_color = None;
+        rec.mat = Some(self.mat.clone());
+        true
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::material::Lambertian;
+    use crate::rtweekend::random::random_double_range;
+    use crate::vec3::Color;
+
+    fn grey() -> Arc<dyn Material + Send + Sync> {
+        Arc::new(Lambertian::from_color(Color::new(0.5, 0.5, 0.5)))
+    }
+
+    fn trace(set: &CurveSet, origin: Point3, direction: Vec3) -> Option<HitRecord> {
+        let mut rec = HitRecord::default();
+        set.hit(&Ray::new(origin, direction, 0.0), Interval::new(0.001, f64::INFINITY), &mut rec).then_some(rec)
+    }
+
+    // 沿 x 轴从 -1 到 1 的直线，控制点均匀分布，u 与 x 成线性关系
+    fn straight() -> [Point3; 4] {
+        [Point3::new(-1.0, 0.0, 0.0), Point3::new(-1.0 / 3.0, 0.0, 0.0), Point3::new(1.0 / 3.0, 0.0, 0.0), Point3::new(1.0, 0.0, 0.0)]
+    }
+
+    #[test]
+    fn splitting_and_blossoms_agree_with_evaluation() {
+        let cp = [Point3::new(0.0, 0.0, 0.0), Point3::new(1.0, 2.0, 0.5), Point3::new(2.0, -1.0, 1.0), Point3::new(3.0, 0.5, -0.5)];
+        let split = split_bezier(&cp);
+        let halves = [sub_curve(&cp, 0.0, 0.5), sub_curve(&cp, 0.5, 1.0)];
+        for i in 0..4 {
+            assert!((split[i] - halves[0][i]).length() < 1e-12);
+            assert!((split[i + 3] - halves[1][i]).length() < 1e-12);
+        }
+        for k in 0..=10 {
+            let u = k as f64 / 10.0;
+            let (p, d) = eval_bezier(&cp, u);
+            assert!((p - blossom(&cp, u, u, u)).length() < 1e-12);
+            // 导数与中心差分一致
+            let h = 1e-6;
+            let fd = (eval_bezier(&cp, u + h).0 - eval_bezier(&cp, u - h).0) / (2.0 * h);
+            assert!((d - fd).length() < 1e-6);
+            // 子区间曲线在端点处与原曲线重合
+            let sub = sub_curve(&cp, 0.25, u.max(0.3));
+            assert!((sub[3] - eval_bezier(&cp, u.max(0.3)).0).length() < 1e-12);
+        }
+    }
+
+    // 扁带正对光线：半宽以内命中，之外错过，宽度随 u 线性变化
+    #[test]
+    fn flat_curves_hit_within_their_width() {
+        let set = CurveSet::new(vec![Curve::flat(straight(), 0.2, 0.1)], grey());
+        let down = Vec3::new(0.0, 0.0, -1.0);
+        let rec = trace(&set, Point3::new(-0.5, 0.085, 5.0), down).expect("inside the half width");
+        assert!((rec.t - 5.0).abs() < 1e-3);
+        assert!((rec.u - 0.25).abs() < 1e-3);
+        assert!((rec.v - 0.5).abs() > 0.3);
+        assert!(rec.normal.z > 0.999);
+        // u = 0.25 处宽度 0.175
+        assert!(trace(&set, Point3::new(-0.5, 0.09, 5.0), down).is_none());
+        // u = 0.75 处宽度 0.125
+        assert!(trace(&set, Point3::new(0.5, 0.06, 5.0), down).is_some());
+        assert!(trace(&set, Point3::new(0.5, 0.065, 5.0), down).is_none());
+        assert!(trace(&set, Point3::new(1.1, 0.0, 5.0), down).is_none());
+        assert_eq!(set.curve_count(), 1);
+    }
+
+    // 圆柱型：中心处法线朝向光线，边缘处法线转向外侧
+    #[test]
+    fn cylinder_normals_wrap_around_the_curve() {
+        let set = CurveSet::new(vec![Curve::cylinder(straight(), 0.2, 0.2)], grey());
+        let down = Vec3::new(0.0, 0.0, -1.0);
+        let center = trace(&set, Point3::new(0.0, 0.0, 5.0), down).unwrap();
+        assert!(center.normal.z > 0.999);
+        for y in [0.08, -0.08] {
+            let edge = trace(&set, Point3::new(0.0, y, 5.0), down).unwrap();
+            assert!(edge.normal.z > 0.0 && edge.normal.z < 0.5);
+            assert!(edge.normal.y * y > 0.0);
+            assert!(edge.normal.x.abs() < 1e-9);
+        }
+    }
+
+    // 定向扁带的投影宽度随法线与光线夹角缩小，侧对时完全看不见
+    #[test]
+    fn ribbons_narrow_when_seen_edge_on() {
+        let up = Vec3::new(0.0, 0.0, 1.0);
+        let facing = CurveSet::new(vec![Curve::ribbon(straight(), 0.2, 0.2, up, up)], grey());
+        let rec = trace(&facing, Point3::new(0.0, 0.09, 5.0), Vec3::new(0.0, 0.0, -1.0)).unwrap();
+        assert!(rec.normal.z > 0.999);
+
+        let tilted = Vec3::new(0.0, 3.0f64.sqrt(), 1.0); // 与光线成 60°
+        let half = CurveSet::new(vec![Curve::ribbon(straight(), 0.2, 0.2, tilted, tilted)], grey());
+        assert!(trace(&half, Point3::new(0.0, 0.04, 5.0), Vec3::new(0.0, 0.0, -1.0)).is_some());
+        assert!(trace(&half, Point3::new(0.0, 0.06, 5.0), Vec3::new(0.0, 0.0, -1.0)).is_none());
+
+        let side = Vec3::new(0.0, 1.0, 0.0);
+        let edge_on = CurveSet::new(vec![Curve::ribbon(straight(), 0.2, 0.2, side, side)], grey());
+        assert!(trace(&edge_on, Point3::new(0.0, 0.001, 5.0), Vec3::new(0.0, 0.0, -1.0)).is_none());
+        // 两端法线不同时中点取球面插值
+        let twisted = Curve::ribbon(straight(), 0.2, 0.2, up, side);
+        let mid = twisted.normal_at(0.5);
+        assert!((mid - Vec3::unit_vector(up + side)).length() < 1e-12);
+    }
+
+    // 内部 BVH 与逐条曲线求交给出相同的最近交点
+    #[test]
+    fn curve_set_matches_brute_force() {
+        let curves: Vec<Curve> = (0..300)
+            .map(|_| {
+                let root = Point3::new(random_double_range(-2.0, 2.0), random_double_range(-2.0, 2.0), random_double_range(-2.0, 2.0));
+                let mut cp = [root; 4];
+                for i in 1..4 {
+                    cp[i] = cp[i - 1] + 0.3 * Vec3::random_unit_vector();
+                }
+                Curve::cylinder(cp, 0.05, 0.01)
+            })
+            .collect();
+        let singles: Vec<CurveSet> = curves.iter().map(|c| CurveSet::new(vec![c.clone()], grey())).collect();
+        let set = CurveSet::new(curves, grey());
+        assert_eq!(set.curve_count(), 300);
+        let mut hits = 0;
+        for _ in 0..2000 {
+            let origin = 6.0 * Vec3::random_unit_vector();
+            let target = Point3::new(random_double_range(-2.0, 2.0), random_double_range(-2.0, 2.0), random_double_range(-2.0, 2.0));
+            let direction = target - origin;
+            let brute = singles.iter().filter_map(|s| trace(s, origin, direction)).map(|r| r.t).fold(f64::INFINITY, f64::min);
+            match trace(&set, origin, direction) {
+                Some(rec) => {
+                    hits += 1;
+                    assert!((rec.t - brute).abs() < 1e-9 * brute.max(1.0));
+                }
+                None => assert!(brute.is_infinite()),
+            }
+        }
+        assert!(hits > 100);
+    }
+}
@@ -0,0 +1,223 @@
+// 圆柱（可选端盖），在以轴线为 z 轴的局部坐标系中求交
+use std::f64::consts::PI;
+use std::sync::Arc;
+use crate::aabb::Aabb;
+use crate::hittable::{area_pdf_value, HitRecord, Hittable};
+use crate::interval::Interval;
+use crate::material::Material;
+use crate::onb::Onb;
+use crate::ray::Ray;
+use crate::rtweekend::random::random_double;
+use crate::vec3::{Point3, Vec3};
+
+pub struct Cylinder {
+    base: Point3,
+    uvw: Onb, // w 为轴向
+    height: f64,
+    radius: f64,
+    capped: bool,
+    mat: Arc<dyn Material + Send + Sync>,
+    bbox: Aabb,
+    area: f64,
+}
+
+// 命中的部位
+#[derive(Clone, Copy, PartialEq)]
+enum Part {
+    Side,
+    Bottom,
+    Top,
+}
+
+impl Cylinder {
+    /// 底面中心 base、顶面中心 top，默认带端盖
+    pub fn new(base: Point3, top: Point3, radius: f64, mat: Arc<dyn Material + Send + Sync>) -> Self {
+        let axis = top - base;
+        let height = axis.length();
+        let bbox = Aabb::surrounding_box(&disk_bbox(&base, &axis, radius), &disk_bbox(&top, &axis, radius));
+        let mut cylinder = Self { base, uvw: Onb::new(&axis), height, radius, capped: true, mat, bbox, area: 0.0 };
+        cylinder.area = cylinder.compute_area();
+        cylinder
+    }
+
+    // 去掉端盖，成为两端开口的管
+    pub fn uncapped(mut self) -> Self {
+        self.capped = false;
+        self.area = self.compute_area();
+        self
+    }
+
+    pub fn area(&self) -> f64 {
+        self.area
+    }
+
+    fn side_area(&self) -> f64 {
+        2.0 * PI * self.radius * self.height
+    }
+
+    fn compute_area(&self) -> f64 {
+        let caps = if self.capped { 2.0 * PI * self.radius * self.radius } else { 0.0 };
+        self.side_area() + caps
+    }
+}
+
+/// 法线为 axis 的圆盘的包围盒：每个轴上的半宽为 radius * sqrt(1 - a_i²)
+pub(crate) fn disk_bbox(center: &Point3, axis: &Vec3, radius: f64) -> Aabb {
+    let a = Vec3::unit_vector(*axis);
+    let extent = radius * Vec3::new(
+        (1.0 - a.x * a.x).max(0.0).sqrt(),
+        (1.0 - a.y * a.y).max(0.0).sqrt(),
+        (1.0 - a.z * a.z).max(0.0).sqrt(),
+    );
+    Aabb::from_points(*center - extent, *center + extent)
+}
+
+// 局部坐标下绕轴的角度纹理坐标 u ∈ [0, 1]
+pub(crate) fn azimuth_u(x: f64, y: f64) -> f64 {
+    (y.atan2(x) + PI) / (2.0 * PI)
+}
+
+impl Hittable for Cylinder {
+    fn hit(&self, r: &Ray, ray_t: Interval, rec: &mut HitRecord) -> bool {
+        let o = self.uvw.to_local(&(r.origin - self.base));
+        let d = self.uvw.to_local(&r.direction);
+        let r2 = self.radius * self.radius;
+        let mut closest: Option<(f64, Part)> = None;
+        let mut consider = |t: f64, part: Part| {
+            if ray_t.surrounds(t) && closest.is_none_or(|(best, _)| t < best) {
+                closest = Some((t, part));
+            }
+        };
+
+        // 侧面：x² + y² = r²
+        let a = d.x * d.x + d.y * d.y;
+        if a > 1e-12 {
+            let half_b = o.x * d.x + o.y * d.y;
+            let c = o.x * o.x + o.y * o.y - r2;
+            let discriminant = half_b * half_b - a * c;
+            if discriminant >= 0.0 {
+                let sqrtd = discriminant.sqrt();
+                for t in [(-half_b - sqrtd) / a, (-half_b + sqrtd) / a] {
+                    let z = o.z + t * d.z;
+                    if (0.0..=self.height).contains(&z) {
+                        consider(t, Part::Side);
+                    }
+                }
+            }
+        }
+
+        // 端盖：z = 0 与 z = height
+        if self.capped && d.z.abs() > 1e-12 {
+            for (z, part) in [(0.0, Part::Bottom), (self.height, Part::Top)] {
+                let t = (z - o.z) / d.z;
+                let (x, y) = (o.x + t * d.x, o.y + t * d.y);
+                if x * x + y * y <= r2 {
+                    consider(t, part);
+                }
+            }
+        }
+
+        let Some((t, part)) = closest else { return false };
+        let lp = o + t * d;
+        let tangent = 2.0 * PI * Vec3::new(-lp.y, lp.x, 0.0);
+        let rho = (lp.x * lp.x + lp.y * lp.y).sqrt();
+        let radial = if rho > 0.0 { Vec3::new(lp.x / rho, lp.y / rho, 0.0) } else { Vec3::new(1.0, 0.0, 0.0) };
+        let (normal, v, dpdv) = match part {
+            Part::Side => (radial, lp.z / self.height, Vec3::new(0.0, 0.0, self.height)),
+            Part::Bottom => (Vec3::new(0.0, 0.0, -1.0), rho / self.radius, self.radius * radial),
+            Part::Top => (Vec3::new(0.0, 0.0, 1.0), rho / self.radius, self.radius * radial),
+        };
+
+        rec.t = t;
+        rec.p = r.at(t);
+        rec.set_face_normal(r, self.uvw.transform(&normal));
+        rec.u = azimuth_u(lp.x, lp.y);
+        rec.v = v;
+        rec.dpdu = self.uvw.transform(&tangent);
+        rec.dpdv = self.uvw.transform(&dpdv);
+        rec.vertex_color = None;
+        rec.mat = Some(self.mat.clone());
+        true
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+
+    fn pdf_value(&self, origin: &Point3, direction: &Vec3) -> f64 {
+        area_pdf_value(self, self.area, origin, direction)
+    }
+
+    // 按面积在侧面与端盖间选择，再在所选部位上均匀采样
+    fn random(&self, origin: &Point3) -> Vec3 {
+        let phi = 2.0 * PI * random_double();
+        let pick = random_double() * self.area;
+        let local = if pick < self.side_area() {
+            Vec3::new(self.radius * phi.cos(), self.radius * phi.sin(), self.height * random_double())
+        } else {
+            let rho = self.radius * random_double().sqrt();
+            let z = if pick < self.side_area() + PI * self.radius * self.radius { 0.0 } else { self.height };
+            Vec3::new(rho * phi.cos(), rho * phi.sin(), z)
+        };
+        self.base + self.uvw.transform(&local) - *origin
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hittable::pdf_integral;
+    use crate::material::Lambertian;
+    use crate::vec3::Color;
+
+    fn pillar() -> Cylinder {
+        Cylinder::new(Point3::new(0.0, 0.0, 0.0), Point3::new(0.0, 2.0, 0.0), 1.0, Arc::new(Lambertian::from_color(Color::new(0.5, 0.5, 0.5))))
+    }
+
+    fn hit(shape: &dyn Hittable, origin: Point3, direction: Vec3) -> Option<HitRecord> {
+        let mut rec = HitRecord::default();
+        shape.hit(&Ray::new(origin, direction, 0.0), Interval::new(0.001, f64::INFINITY), &mut rec).then_some(rec)
+    }
+
+    #[test]
+    fn side_hits_have_radial_normals_and_axial_v() {
+        let rec = hit(&pillar(), Point3::new(5.0, 1.5, 0.0), Vec3::new(-1.0, 0.0, 0.0)).unwrap();
+        assert!((rec.t - 4.0).abs() < 1e-9);
+        assert!((rec.normal - Vec3::new(1.0, 0.0, 0.0)).length() < 1e-9 && rec.front_face);
+        assert!((rec.v - 0.75).abs() < 1e-9 && (0.0..=1.0).contains(&rec.u));
+        assert!(hit(&pillar(), Point3::new(5.0, 2.5, 0.0), Vec3::new(-1.0, 0.0, 0.0)).is_none());
+    }
+
+    // 斜着从顶部射入：带盖时命中顶盖，开口时穿过开口命中内壁
+    #[test]
+    fn caps_close_the_tube() {
+        let (origin, direction) = (Point3::new(0.0, 5.0, 0.0), Vec3::new(0.2, -1.0, 0.0));
+        let capped = hit(&pillar(), origin, direction).unwrap();
+        assert!((capped.t - 3.0).abs() < 1e-9);
+        assert!((capped.normal - Vec3::new(0.0, 1.0, 0.0)).length() < 1e-9);
+        assert!((capped.v - 0.6).abs() < 1e-9);
+
+        let tube = pillar().uncapped();
+        let inner = hit(&tube, origin, direction).unwrap();
+        assert!((inner.t - 5.0).abs() < 1e-9);
+        assert!(!inner.front_face);
+        assert!(hit(&tube, origin, Vec3::new(0.0, -1.0, 0.0)).is_none());
+    }
+
+    #[test]
+    fn light_sampling_pdf_integrates_to_one() {
+        let origin = Point3::new(2.0, 3.0, 1.5);
+        for shape in [pillar(), pillar().uncapped()] {
+            let caps = if shape.capped { 2.0 * PI } else { 0.0 };
+            assert!((shape.area() - 4.0 * PI - caps).abs() < 1e-12);
+            assert!((pdf_integral(&shape, &origin) - 1.0).abs() < 0.03);
+            for _ in 0..100 {
+                let direction = shape.random(&origin);
+                assert!(shape.pdf_value(&origin, &direction) > 0.0);
+                let p = origin + direction;
+                assert!(shape.bounding_box().x.contains(p.x) && shape.bounding_box().y.contains(p.y) && shape.bounding_box().z.contains(p.z));
+            }
+        }
+    }
+}
@@ -0,0 +1,975 @@
+// glTF 2.0 场景导入（.gltf 与 .glb）：节点层级、网格、相机、KHR_lights_punctual 光源
+// 与金属度-粗糙度 PBR 材质。网格按节点的世界变换烘焙为 TriangleMesh
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use crate::camera::Camera;
+use crate::hittable::{HitRecord, Hittable, HittableList};
+use crate::json::Json;
+use crate::material::{
+    CoatedMaterial, Dielectric, DiffuseLight, Lambertian, LightUnit, Material, Metal, MixMaterial, NormalMapMaterial,
+};
+use crate::matrix::{Mat4, Quat};
+use crate::ray::Ray;
+use crate::sphere::Sphere;
+use crate::texture::{ImageTexture, SolidColor, Texture};
+use crate::triangle::TriangleMesh;
+use crate::vec3::{Color, Point3, Vec3};
+
+type MaterialRef = Arc<dyn Material + Send + Sync>;
+type TextureRef = Arc<dyn Texture + Send + Sync>;
+
+// 点光源/聚光灯用小球近似的半径（场景单位）
+const POINT_LIGHT_RADIUS: f64 = 0.05;
+// 聚光灯外锥角的默认值（弧度），见 KHR_lights_punctual
+const SPOT_OUTER_CONE_ANGLE: f64 = std::f64::consts::FRAC_PI_4;
+// 平行光用远处的发光球近似：距离为场景包围球半径的倍数，以及视半角（弧度）。
+// 视半角比真实太阳大，以降低噪点
+const SUN_DISTANCE_FACTOR: f64 = 100.0;
+const SUN_HALF_ANGLE: f64 = 0.02;
+// 没有 bufferView 的访问器全为 0，限制其分量数，避免恶意文件申请巨量内存
+const MAX_ZERO_ACCESSOR: usize = 1 << 24;
+
+#[derive(Debug, Clone)]
+pub struct GltfError {
+    pub file: PathBuf,
+    pub message: String,
+}
+
+impl GltfError {
+    fn new(file: &Path, message: impl Into<String>) -> Self {
+        GltfError { file: file.to_path_buf(), message: message.into() }
+    }
+}
+
+impl fmt::Display for GltfError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.file.display(), self.message)
+    }
+}
+
+impl std::error::Error for GltfError {}
+
+// 导入的透视相机，可套用到 Camera
+#[derive(Debug, Clone)]
+pub struct GltfCamera {
+    pub name: String,
+    pub lookfrom: Point3,
+    pub lookat: Point3,
+    pub vup: Vec3,
+    pub vfov: f64, // 度
+    pub aspect_ratio: Option<f64>,
+}
+
+impl GltfCamera {
+    pub fn apply(&self, cam: &mut Camera) {
+        cam.lookfrom = self.lookfrom;
+        cam.lookat = self.lookat;
+        cam.vup = self.vup;
+        cam.vfov = self.vfov;
+        if let Some(aspect_ratio) = self.aspect_ratio {
+            cam.aspect_ratio = aspect_ratio;
+        }
+    }
+}
+
+pub struct GltfScene {
+    pub world: HittableList,
+    pub lights: HittableList, // 发光网格与 punctual 光源，可用于光源采样
+    pub cameras: Vec<GltfCamera>,
+}
+
+fn decode_base64(text: &str) -> Option<Vec<u8>> {
+    let value = |c: u8| -> Option<u32> {
+        Some(match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' | b'-' => 62,
+            b'/' | b'_' => 63,
+            _ => return None,
+        } as u32)
+    };
+    let digits: Vec<u8> = text.bytes().filter(|c| !c.is_ascii_whitespace() && *c != b'=').collect();
+    let mut out = Vec::with_capacity(digits.len() * 3 / 4);
+    for chunk in digits.chunks(4) {
+        let mut acc = 0u32;
+        for (i, &c) in chunk.iter().enumerate() {
+            acc |= value(c)? << (18 - 6 * i);
+        }
+        let bytes = acc.to_be_bytes();
+        out.extend_from_slice(&bytes[1..chunk.len()]);
+    }
+    Some(out)
+}
+
+// URI 中的 %XX 转义
+fn decode_uri(uri: &str) -> String {
+    let bytes = uri.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            if let Some(b) = uri.get(i + 1..i + 3).and_then(|h| u8::from_str_radix(h, 16).ok()) {
+                out.push(b);
+                i += 3;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+// GLB 容器：12 字节文件头后依次为 JSON 块与可选的 BIN 块
+fn parse_glb(bytes: &[u8], path: &Path) -> Result<(String, Option<Vec<u8>>), GltfError> {
+    let u32_at = |offset: usize| -> Result<u32, GltfError> {
+        bytes
+            .get(offset..offset + 4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .ok_or_else(|| GltfError::new(path, "truncated GLB container"))
+    };
+    let version = u32_at(4)?;
+    if version != 2 {
+        return Err(GltfError::new(path, format!("unsupported GLB version {}", version)));
+    }
+    let mut json = None;
+    let mut bin = None;
+    let mut offset = 12;
+    while offset + 8 <= bytes.len() {
+        let length = u32_at(offset)? as usize;
+        let kind = u32_at(offset + 4)?;
+        let end = (offset + 8).checked_add(length);
+        let data = end
+            .and_then(|end| bytes.get(offset + 8..end))
+            .ok_or_else(|| GltfError::new(path, "truncated GLB chunk"))?;
+        match kind {
+            0x4E4F_534A => json = Some(String::from_utf8_lossy(data).into_owned()),
+            0x004E_4942 => bin = Some(data.to_vec()),
+            _ => {}
+        }
+        offset += 8 + data.len();
+    }
+    let json = json.ok_or_else(|| GltfError::new(path, "GLB has no JSON chunk"))?;
+    Ok((json, bin))
+}
+
+pub fn load_gltf<P: AsRef<Path>>(path: P) -> Result<GltfScene, GltfError> {
+    let path = path.as_ref();
+    let bytes = fs::read(path).map_err(|e| GltfError::new(path, format!("cannot read file: {}", e)))?;
+    let (json, bin) = if bytes.starts_with(b"glTF") {
+        parse_glb(&bytes, path)?
+    } else {
+        (String::from_utf8_lossy(&bytes).into_owned(), None)
+    };
+    let doc = Json::parse(&json).map_err(|e| GltfError::new(path, format!("invalid JSON: {}", e)))?;
+    let mut importer = Importer {
+        path,
+        dir: path.parent().unwrap_or(Path::new(".")).to_path_buf(),
+        doc: &doc,
+        buffers: Vec::new(),
+        materials: HashMap::new(),
+        images: HashMap::new(),
+        scene: GltfScene { world: HittableList::new(), lights: HittableList::new(), cameras: Vec::new() },
+        directional: Vec::new(),
+    };
+    importer.load_buffers(bin)?;
+    importer.import()?;
+    Ok(importer.scene)
+}
+
+// 与 glTF 纹理相乘的因子（baseColorFactor、emissiveFactor）
+struct FactorTexture {
+    tex: TextureRef,
+    factor: Color,
+}
+
+impl Texture for FactorTexture {
+    fn value(&self, u: f64, v: f64, p: &Point3) -> Color {
+        self.tex.value(u, v, p) * self.factor
+    }
+}
+
+// 纹理单个通道乘以因子后的灰度，用于 metallicRoughnessTexture（G 为粗糙度，B 为金属度）
+struct ChannelTexture {
+    tex: TextureRef,
+    channel: usize,
+    factor: f64,
+}
+
+impl Texture for ChannelTexture {
+    fn value(&self, u: f64, v: f64, p: &Point3) -> Color {
+        let c = self.tex.value(u, v, p);
+        let x = [c.x, c.y, c.z][self.channel] * self.factor;
+        Color::new(x, x, x)
+    }
+}
+
+// 聚光灯：发光小球的辐亮度按出射方向与光轴的夹角衰减，内锥内为 1，外锥外为 0
+struct SpotEmitter {
+    light: DiffuseLight,
+    axis: Vec3, // 世界空间光轴（单位向量）
+    cos_inner: f64,
+    cos_outer: f64,
+}
+
+impl Material for SpotEmitter {
+    fn is_emissive(&self) -> bool {
+        true
+    }
+
+    fn emitted(&self, r_in: &Ray, rec: &HitRecord) -> Color {
+        // KHR_lights_punctual 推荐的平滑衰减
+        let cos_theta = Vec3::dot(&-Vec3::unit_vector(r_in.direction), &self.axis);
+        let scale = 1.0 / (self.cos_inner - self.cos_outer).max(0.001);
+        let falloff = ((cos_theta - self.cos_outer) * scale).clamp(0.0, 1.0);
+        self.light.emitted(r_in, rec) * (falloff * falloff)
+    }
+}
+
+struct Importer<'a> {
+    path: &'a Path,
+    dir: PathBuf,
+    doc: &'a Json,
+    buffers: Vec<Vec<u8>>,
+    materials: HashMap<usize, MaterialRef>,
+    images: HashMap<usize, Arc<ImageTexture>>,
+    scene: GltfScene,
+    directional: Vec<(Vec3, Color, f64)>, // 平行光：(世界方向, 颜色, 照度 lux)，在网格之后生成
+}
+
+impl<'a> Importer<'a> {
+    fn error(&self, message: impl Into<String>) -> GltfError {
+        GltfError::new(self.path, message)
+    }
+
+    // 顶层数组中的第 index 项
+    fn item(&self, collection: &str, index: usize) -> Result<&'a Json, GltfError> {
+        self.doc
+            .get(collection)
+            .and_then(Json::as_array)
+            .and_then(|items| items.get(index))
+            .ok_or_else(|| self.error(format!("{}[{}] does not exist", collection, index)))
+    }
+
+    fn index(&self, value: Option<&Json>, what: &str) -> Result<usize, GltfError> {
+        value.and_then(Json::as_usize).ok_or_else(|| self.error(format!("missing or invalid {}", what)))
+    }
+
+    // 外部文件或 data URI
+    fn read_uri(&self, uri: &str) -> Result<Vec<u8>, GltfError> {
+        if let Some(rest) = uri.strip_prefix("data:") {
+            let (_, data) = rest.split_once(";base64,").ok_or_else(|| self.error("only base64 data URIs are supported"))?;
+            return decode_base64(data).ok_or_else(|| self.error("invalid base64 data URI"));
+        }
+        let file = self.dir.join(decode_uri(uri));
+        fs::read(&file).map_err(|e| self.error(format!("cannot read '{}': {}", file.display(), e)))
+    }
+
+    fn load_buffers(&mut self, mut bin: Option<Vec<u8>>) -> Result<(), GltfError> {
+        let count = self.doc.get("buffers").and_then(Json::as_array).map_or(0, |b| b.len());
+        for i in 0..count {
+            let buffer = self.item("buffers", i)?;
+            let data = match buffer.get("uri").and_then(Json::as_str) {
+                Some(uri) => self.read_uri(uri)?,
+                None => bin.take().ok_or_else(|| self.error(format!("buffers[{}] has no uri and no GLB BIN chunk", i)))?,
+            };
+            let length = self.index(buffer.get("byteLength"), "buffer byteLength")?;
+            if data.len() < length {
+                return Err(self.error(format!("buffers[{}] is {} bytes, expected {}", i, data.len(), length)));
+            }
+            self.buffers.push(data);
+        }
+        Ok(())
+    }
+
+    fn buffer_view(&self, index: usize) -> Result<(&[u8], Option<usize>), GltfError> {
+        let view = self.item("bufferViews", index)?;
+        let buffer = self.index(view.get("buffer"), "bufferView buffer")?;
+        let offset = view.get("byteOffset").and_then(Json::as_usize).unwrap_or(0);
+        let length = self.index(view.get("byteLength"), "bufferView byteLength")?;
+        let data = self
+            .buffers
+            .get(buffer)
+            .and_then(|b| b.get(offset..offset.checked_add(length)?))
+            .ok_or_else(|| self.error(format!("bufferViews[{}] is out of range", index)))?;
+        Ok((data, view.get("byteStride").and_then(Json::as_usize)))
+    }
+
+    // 读取访问器为 (扁平数组, 分量数)；归一化整数映射到 [0, 1] / [-1, 1]
+    fn accessor(&self, index: usize) -> Result<(Vec<f64>, usize), GltfError> {
+        let accessor = self.item("accessors", index)?;
+        if accessor.get("sparse").is_some() {
+            return Err(self.error(format!("accessors[{}]: sparse accessors are not supported", index)));
+        }
+        let count = self.index(accessor.get("count"), "accessor count")?;
+        let components = match accessor.get("type").and_then(Json::as_str) {
+            Some("SCALAR") => 1,
+            Some("VEC2") => 2,
+            Some("VEC3") => 3,
+            Some("VEC4") | Some("MAT2") => 4,
+            Some("MAT3") => 9,
+            Some("MAT4") => 16,
+            other => return Err(self.error(format!("accessors[{}]: unknown type {:?}", index, other))),
+        };
+        let component_type = self.index(accessor.get("componentType"), "accessor componentType")?;
+        let normalized = accessor.get("normalized").and_then(Json::as_bool).unwrap_or(false);
+        let size = match component_type {
+            5120 | 5121 => 1,
+            5122 | 5123 => 2,
+            5125 | 5126 => 4,
+            other => return Err(self.error(format!("accessors[{}]: unknown componentType {}", index, other))),
+        };
+
+        let too_large = || self.error(format!("accessors[{}] is too large", index));
+        let total = count.checked_mul(components).ok_or_else(too_large)?;
+        let Some(view) = accessor.get("bufferView").and_then(Json::as_usize) else {
+            if total > MAX_ZERO_ACCESSOR {
+                return Err(too_large());
+            }
+            return Ok((vec![0.0; total], components));
+        };
+        let (data, stride) = self.buffer_view(view)?;
+        let offset = accessor.get("byteOffset").and_then(Json::as_usize).unwrap_or(0);
+        let stride = stride.unwrap_or(size * components);
+        // 分配前确认最后一个元素的末尾仍在 bufferView 内，其余读取位置都不超过它
+        if count > 0 {
+            let end = (count - 1)
+                .checked_mul(stride)
+                .and_then(|last| last.checked_add(offset))
+                .and_then(|last| last.checked_add(components * size));
+            if end.is_none_or(|end| end > data.len()) {
+                return Err(self.error(format!("accessors[{}] reads past its bufferView", index)));
+            }
+        }
+        let mut values = Vec::with_capacity(total);
+        for i in 0..count {
+            for c in 0..components {
+                let at = offset + i * stride + c * size;
+                let b = data
+                    .get(at..at + size)
+                    .ok_or_else(|| self.error(format!("accessors[{}] reads past its bufferView", index)))?;
+                let value = match component_type {
+                    5120 => { let v = b[0] as i8 as f64; if normalized { (v / 127.0).max(-1.0) } else { v } }
+                    5121 => { let v = b[0] as f64; if normalized { v / 255.0 } else { v } }
+                    5122 => { let v = i16::from_le_bytes([b[0], b[1]]) as f64; if normalized { (v / 32767.0).max(-1.0) } else { v } }
+                    5123 => { let v = u16::from_le_bytes([b[0], b[1]]) as f64; if normalized { v / 65535.0 } else { v } }
+                    5125 => u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64,
+                    _ => f32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64,
+                };
+                values.push(value);
+            }
+        }
+        Ok((values, components))
+    }
+
+    fn image(&mut self, texture_info: Option<&Json>) -> Result<Option<Arc<ImageTexture>>, GltfError> {
+        let Some(texture) = texture_info.and_then(|t| t.get("index")).and_then(Json::as_usize) else {
+            return Ok(None);
+        };
+        let source = self.index(self.item("textures", texture)?.get("source"), "texture source")?;
+        if let Some(image) = self.images.get(&source) {
+            return Ok(Some(image.clone()));
+        }
+        let image = self.item("images", source)?;
+        let bytes = match (image.get("uri").and_then(Json::as_str), image.get("bufferView").and_then(Json::as_usize)) {
+            (Some(uri), _) => self.read_uri(uri)?,
+            (None, Some(view)) => self.buffer_view(view)?.0.to_vec(),
+            _ => return Err(self.error(format!("images[{}] has neither uri nor bufferView", source))),
+        };
+        let tex = Arc::new(
+            ImageTexture::from_memory(&bytes).ok_or_else(|| self.error(format!("images[{}] could not be decoded", source)))?,
+        );
+        self.images.insert(source, tex.clone());
+        Ok(Some(tex))
+    }
+
+    fn color_texture(&mut self, texture_info: Option<&Json>, factor: Color) -> Result<Option<TextureRef>, GltfError> {
+        Ok(self.image(texture_info)?.map(|tex| Arc::new(FactorTexture { tex, factor }) as TextureRef))
+    }
+
+    // 金属度-粗糙度 PBR 到现有材质的映射：
+    // 自发光 → DiffuseLight；透射 → Dielectric；金属 → Metal（fuzz = 粗糙度）；
+    // 光滑电介质 → 带涂层的 Lambertian；粗糙电介质 → Lambertian。
+    // 按金属度随机混合金属与电介质、按粗糙度随机混合涂层与漫反射，有 metallicRoughnessTexture 时取纹素值；
+    // normalTexture 包装为 NormalMapMaterial
+    fn material(&mut self, index: Option<usize>) -> Result<MaterialRef, GltfError> {
+        let Some(index) = index else {
+            return Ok(Arc::new(Lambertian::from_color(Color::new(0.8, 0.8, 0.8))));
+        };
+        if let Some(m) = self.materials.get(&index) {
+            return Ok(m.clone());
+        }
+        let m = self.item("materials", index)?;
+        let pbr = m.get("pbrMetallicRoughness");
+        let extension = |name: &str| m.get("extensions").and_then(|e| e.get(name));
+        let color = |v: Option<Vec<f64>>, default: f64| {
+            v.map_or(Color::new(default, default, default), |c| Color::new(c[0], c[1], c[2]))
+        };
+
+        let base = color(pbr.and_then(|p| p.get("baseColorFactor")).and_then(Json::as_f64_vec), 1.0);
+        let metallic = pbr.and_then(|p| p.get("metallicFactor")).and_then(Json::as_f64).unwrap_or(1.0);
+        let roughness = pbr.and_then(|p| p.get("roughnessFactor")).and_then(Json::as_f64).unwrap_or(1.0);
+        let strength = extension("KHR_materials_emissive_strength")
+            .and_then(|e| e.get("emissiveStrength"))
+            .and_then(Json::as_f64)
+            .unwrap_or(1.0);
+        let emissive = color(m.get("emissiveFactor").and_then(Json::as_f64_vec), 0.0) * strength;
+        let transmission = extension("KHR_materials_transmission")
+            .and_then(|e| e.get("transmissionFactor"))
+            .and_then(Json::as_f64)
+            .unwrap_or(0.0);
+        let ior = extension("KHR_materials_ior").and_then(|e| e.get("ior")).and_then(Json::as_f64).unwrap_or(1.5);
+
+        let material: MaterialRef = if emissive.x.max(emissive.y).max(emissive.z) > 0.0 {
+            match self.color_texture(m.get("emissiveTexture"), emissive)? {
+                Some(tex) => Arc::new(DiffuseLight::from_texture(tex)),
+                None => Arc::new(DiffuseLight::from_color(emissive)),
+            }
+        } else if transmission > 0.0 {
+            // KHR_materials_volume：attenuationColor 在 attenuationDistance 处的透过率
+            let volume = extension("KHR_materials_volume");
+            let distance = volume.and_then(|v| v.get("attenuationDistance")).and_then(Json::as_f64);
+            let tint = volume.and_then(|v| v.get("attenuationColor")).and_then(Json::as_f64_vec);
+            match (distance, tint) {
+                (Some(d), Some(c)) if d > 0.0 => {
+                    let absorb = |t: f64| -t.max(1e-6).ln() / d;
+                    Arc::new(Dielectric::with_absorption(ior, Color::new(absorb(c[0]), absorb(c[1]), absorb(c[2]))))
+                }
+                _ => Arc::new(Dielectric::new(ior)),
+            }
+        } else {
+            let base_tex = self.color_texture(pbr.and_then(|p| p.get("baseColorTexture")), base)?;
+            let mr: Option<TextureRef> = self.image(pbr.and_then(|p| p.get("metallicRoughnessTexture")))?.map(|t| t as TextureRef);
+            let channel = |tex: &TextureRef, channel: usize, factor: f64| -> TextureRef {
+                Arc::new(ChannelTexture { tex: tex.clone(), channel, factor })
+            };
+
+            let mut metal = match base_tex {
+                Some(ref tex) => Metal::from_texture(tex.clone(), roughness),
+                None => Metal::new(base, roughness),
+            };
+            if let Some(ref mr) = mr {
+                metal = metal.with_fuzz_texture(channel(mr, 1, roughness));
+            }
+            let metal: MaterialRef = Arc::new(metal);
+
+            let diffuse: MaterialRef = match base_tex {
+                Some(tex) => Arc::new(Lambertian::from_texture(tex)),
+                None => Arc::new(Lambertian::from_color(base)),
+            };
+            let coated: MaterialRef = Arc::new(CoatedMaterial::new(diffuse.clone(), ior));
+            let constant = |x: f64| -> TextureRef { Arc::new(SolidColor::new(Color::new(x, x, x))) };
+            let (roughness_mask, metallic_mask) = match mr {
+                Some(ref mr) => (channel(mr, 1, roughness), channel(mr, 2, metallic)),
+                None => (constant(roughness), constant(metallic)),
+            };
+            let dielectric: MaterialRef = Arc::new(MixMaterial::stochastic(coated, diffuse, roughness_mask));
+            Arc::new(MixMaterial::stochastic(dielectric, metal, metallic_mask))
+        };
+
+        let normal_texture = m.get("normalTexture");
+        let material: MaterialRef = match self.image(normal_texture)? {
+            Some(normal_map) if !material.is_emissive() => {
+                let scale = normal_texture.and_then(|n| n.get("scale")).and_then(Json::as_f64).unwrap_or(1.0);
+                Arc::new(NormalMapMaterial::new(material, normal_map, scale))
+            }
+            _ => material,
+        };
+        self.materials.insert(index, material.clone());
+        Ok(material)
+    }
+
+    fn import(&mut self) -> Result<(), GltfError> {
+        let roots: Vec<usize> = match self.doc.get("scenes").and_then(Json::as_array) {
+            Some(scenes) if !scenes.is_empty() => {
+                let scene = self.doc.get("scene").and_then(Json::as_usize).unwrap_or(0);
+                let scene = scenes.get(scene).ok_or_else(|| self.error(format!("scenes[{}] does not exist", scene)))?;
+                scene.get("nodes").and_then(Json::as_array).unwrap_or(&[]).iter().filter_map(Json::as_usize).collect()
+            }
+            // 没有场景时导入所有根节点
+            _ => {
+                let nodes = self.doc.get("nodes").and_then(Json::as_array).unwrap_or(&[]);
+                let children: Vec<usize> = nodes
+                    .iter()
+                    .filter_map(|n| n.get("children").and_then(Json::as_array))
+                    .flatten()
+                    .filter_map(Json::as_usize)
+                    .collect();
+                (0..nodes.len()).filter(|i| !children.contains(i)).collect()
+            }
+        };
+        let node_count = self.doc.get("nodes").and_then(Json::as_array).map_or(0, |n| n.len());
+        for root in roots {
+            self.node(root, &Mat4::identity(), node_count)?;
+        }
+        self.add_directional_lights();
+        Ok(())
+    }
+
+    // depth 防止循环引用导致无限递归
+    fn node(&mut self, index: usize, parent: &Mat4, depth: usize) -> Result<(), GltfError> {
+        if depth == 0 {
+            return Err(self.error("node hierarchy contains a cycle"));
+        }
+        let node = self.item("nodes", index)?;
+        let local = match node.get("matrix").and_then(Json::as_f64_vec) {
+            Some(m) if m.len() == 16 => Mat4::from_cols_slice(&m),
+            _ => {
+                let t = node.get("translation").and_then(Json::as_f64_vec).unwrap_or(vec![0.0; 3]);
+                let r = node.get("rotation").and_then(Json::as_f64_vec).unwrap_or(vec![0.0, 0.0, 0.0, 1.0]);
+                let s = node.get("scale").and_then(Json::as_f64_vec).unwrap_or(vec![1.0; 3]);
+                if t.len() != 3 || r.len() != 4 || s.len() != 3 {
+                    return Err(self.error(format!("nodes[{}] has a malformed TRS transform", index)));
+                }
+                Mat4::trs(Vec3::new(t[0], t[1], t[2]), Quat::new(r[0], r[1], r[2], r[3]), Vec3::new(s[0], s[1], s[2]))
+            }
+        };
+        let world = *parent * local;
+
+        if let Some(mesh) = node.get("mesh").and_then(Json::as_usize) {
+            self.mesh(mesh, &world)?;
+        }
+        if let Some(camera) = node.get("camera").and_then(Json::as_usize) {
+            self.camera(camera, &world)?;
+        }
+        if let Some(light) = node
+            .get("extensions")
+            .and_then(|e| e.get("KHR_lights_punctual"))
+            .and_then(|l| l.get("light"))
+            .and_then(Json::as_usize)
+        {
+            self.light(light, &world)?;
+        }
+        let children: Vec<usize> = node.get("children").and_then(Json::as_array).unwrap_or(&[]).iter().filter_map(Json::as_usize).collect();
+        for child in children {
+            self.node(child, &world, depth - 1)?;
+        }
+        Ok(())
+    }
+
+    fn mesh(&mut self, index: usize, world: &Mat4) -> Result<(), GltfError> {
+        let mesh = self.item("meshes", index)?;
+        let primitives = mesh.get("primitives").and_then(Json::as_array).unwrap_or(&[]);
+        let flip = world.determinant3() < 0.0;
+        for primitive in primitives {
+            let mode = primitive.get("mode").and_then(Json::as_usize).unwrap_or(4);
+            if !matches!(mode, 4..=6) {
+                continue; // 点与线没有面积
+            }
+            let attributes = primitive.get("attributes");
+            let attribute = |name: &str| attributes.and_then(|a| a.get(name)).and_then(Json::as_usize);
+            let position = attribute("POSITION").ok_or_else(|| self.error(format!("meshes[{}] primitive has no POSITION", index)))?;
+
+            let (p, _) = self.accessor(position)?;
+            let positions: Vec<Point3> = p.chunks_exact(3).map(|c| world.transform_point(&Point3::new(c[0], c[1], c[2]))).collect();
+            // 退化变换（秩小于 2）使法线变为零向量，此时改用几何法线
+            let normals: Vec<Vec3> = match attribute("NORMAL") {
+                Some(a) => self.accessor(a)?.0.chunks_exact(3).map(|c| world.transform_normal(&Vec3::new(c[0], c[1], c[2]))).collect(),
+                None => Vec::new(),
+            };
+            let normals: Vec<Vec3> = if normals.iter().any(|n| n.length_squared() == 0.0) {
+                Vec::new()
+            } else {
+                normals.into_iter().map(Vec3::unit_vector).collect()
+            };
+            // glTF 的 UV 原点在左上角，ImageTexture 的在左下角
+            let uvs: Vec<(f64, f64)> = match attribute("TEXCOORD_0") {
+                Some(a) => self.accessor(a)?.0.chunks_exact(2).map(|c| (c[0], 1.0 - c[1])).collect(),
+                None => Vec::new(),
+            };
+            let colors: Vec<Color> = match attribute("COLOR_0") {
+                Some(a) => {
+                    let (c, n) = self.accessor(a)?;
+                    c.chunks_exact(n).map(|c| Color::new(c[0], c[1], c[2])).collect()
+                }
+                None => Vec::new(),
+            };
+
+            let vertex_indices: Vec<usize> = match primitive.get("indices").and_then(Json::as_usize) {
+                Some(a) => self.accessor(a)?.0.iter().map(|&i| i as usize).collect(),
+                None => (0..positions.len()).collect(),
+            };
+            if let Some(&bad) = vertex_indices.iter().find(|&&i| i >= positions.len()) {
+                return Err(self.error(format!("meshes[{}] index {} out of range (have {} vertices)", index, bad, positions.len())));
+            }
+            let mut triangles: Vec<[usize; 3]> = match mode {
+                4 => vertex_indices.chunks_exact(3).map(|t| [t[0], t[1], t[2]]).collect(),
+                5 => (2..vertex_indices.len())
+                    .map(|i| if i % 2 == 0 {
+                        [vertex_indices[i - 2], vertex_indices[i - 1], vertex_indices[i]]
+                    } else {
+                        [vertex_indices[i - 1], vertex_indices[i - 2], vertex_indices[i]]
+                    })
+                    .collect(),
+                _ => (2..vertex_indices.len()).map(|i| [vertex_indices[0], vertex_indices[i - 1], vertex_indices[i]]).collect(),
+            };
+            if flip {
+                triangles.iter_mut().for_each(|t| t.swap(1, 2));
+            }
+            if triangles.is_empty() {
+                continue;
+            }
+
+            let mat = self.material(primitive.get("material").and_then(Json::as_usize))?;
+            let emissive = mat.is_emissive();
+            let mesh = TriangleMesh::with_attributes(positions, normals, uvs, triangles, mat)
+                .and_then(|mesh| mesh.with_vertex_colors(colors))
+                .map_err(|e| self.error(format!("meshes[{}]: {}", index, e)))?;
+            let mesh: Arc<dyn Hittable + Send + Sync> = Arc::new(mesh);
+            if emissive {
+                self.scene.lights.add(mesh.clone());
+            }
+            self.scene.world.add(mesh);
+        }
+        Ok(())
+    }
+
+    // glTF 相机沿局部 -Z 观察，+Y 朝上
+    fn camera(&mut self, index: usize, world: &Mat4) -> Result<(), GltfError> {
+        let camera = self.item("cameras", index)?;
+        let Some(perspective) = camera.get("perspective") else {
+            return Ok(()); // 正交相机不受支持
+        };
+        let yfov = perspective
+            .get("yfov")
+            .and_then(Json::as_f64)
+            .ok_or_else(|| self.error(format!("cameras[{}] has no yfov", index)))?;
+        let lookfrom = world.transform_point(&Point3::default());
+        let forward = Vec3::unit_vector(world.transform_vector(&Vec3::new(0.0, 0.0, -1.0)));
+        self.scene.cameras.push(GltfCamera {
+            name: camera.get("name").and_then(Json::as_str).unwrap_or("").to_string(),
+            lookfrom,
+            lookat: lookfrom + forward,
+            vup: Vec3::unit_vector(world.transform_vector(&Vec3::new(0.0, 1.0, 0.0))),
+            vfov: yfov.to_degrees(),
+            aspect_ratio: perspective.get("aspectRatio").and_then(Json::as_f64),
+        });
+        Ok(())
+    }
+
+    // 点光源与聚光灯（强度单位坎德拉）近似为发光小球，聚光灯按锥角衰减；
+    // 平行光（照度单位 lux）在所有网格导入后作为远处的太阳球生成
+    fn light(&mut self, index: usize, world: &Mat4) -> Result<(), GltfError> {
+        let light = self
+            .doc
+            .get("extensions")
+            .and_then(|e| e.get("KHR_lights_punctual"))
+            .and_then(|l| l.get("lights"))
+            .and_then(Json::as_array)
+            .and_then(|lights| lights.get(index))
+            .ok_or_else(|| self.error(format!("KHR_lights_punctual light {} does not exist", index)))?;
+        let color = light.get("color").and_then(Json::as_f64_vec).map_or(Color::new(1.0, 1.0, 1.0), |c| Color::new(c[0], c[1], c[2]));
+        let intensity = light.get("intensity").and_then(Json::as_f64).unwrap_or(1.0);
+        match light.get("type").and_then(Json::as_str) {
+            Some(kind @ ("point" | "spot")) => {
+                let center = world.transform_point(&Point3::default());
+                let area = 4.0 * std::f64::consts::PI * POINT_LIGHT_RADIUS * POINT_LIGHT_RADIUS;
+                let flux = LightUnit::Lumens(4.0 * std::f64::consts::PI * intensity);
+                let emitter = DiffuseLight::from_color(color).one_sided().with_power(flux, area);
+                let mat: MaterialRef = if kind == "spot" {
+                    let spot = light.get("spot");
+                    let inner = spot.and_then(|s| s.get("innerConeAngle")).and_then(Json::as_f64).unwrap_or(0.0);
+                    let outer = spot.and_then(|s| s.get("outerConeAngle")).and_then(Json::as_f64).unwrap_or(SPOT_OUTER_CONE_ANGLE);
+                    Arc::new(SpotEmitter {
+                        light: emitter,
+                        axis: Vec3::unit_vector(world.transform_vector(&Vec3::new(0.0, 0.0, -1.0))),
+                        cos_inner: inner.cos(),
+                        cos_outer: outer.cos(),
+                    })
+                } else {
+                    Arc::new(emitter)
+                };
+                let sphere: Arc<dyn Hittable + Send + Sync> = Arc::new(Sphere::new(center, POINT_LIGHT_RADIUS, Some(mat)));
+                self.scene.lights.add(sphere.clone());
+                self.scene.world.add(sphere);
+            }
+            Some("directional") => {
+                let direction = Vec3::unit_vector(world.transform_vector(&Vec3::new(0.0, 0.0, -1.0)));
+                self.directional.push((direction, color, intensity));
+            }
+            other => return Err(self.error(format!("unknown light type {:?}", other))),
+        }
+        Ok(())
+    }
+
+    fn add_directional_lights(&mut self) {
+        if self.directional.is_empty() {
+            return;
+        }
+        let bbox = self.scene.world.bounding_box();
+        let (center, radius) = if self.scene.world.objects.is_empty() {
+            (Point3::default(), 1.0)
+        } else {
+            let min = Point3::new(bbox.x.min, bbox.y.min, bbox.z.min);
+            let max = Point3::new(bbox.x.max, bbox.y.max, bbox.z.max);
+            ((min + max) / 2.0, ((max - min).length() / 2.0).max(1.0))
+        };
+        let distance = SUN_DISTANCE_FACTOR * radius;
+        // 照度 E = L · Ω，Ω 为太阳球所张立体角
+        let solid_angle = 2.0 * std::f64::consts::PI * (1.0 - SUN_HALF_ANGLE.cos());
+        for &(direction, color, lux) in &self.directional {
+            let mat = Arc::new(DiffuseLight::from_color(color).one_sided().with_power(LightUnit::Nits(lux / solid_angle), 0.0));
+            let sun: Arc<dyn Hittable + Send + Sync> = Arc::new(Sphere::new(
+                center - distance * direction,
+                distance * SUN_HALF_ANGLE.tan(),
+                Some(mat),
+            ));
+            self.scene.lights.add(sun.clone());
+            self.scene.world.add(sun);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interval::Interval;
+    use crate::material::ScatterRecord;
+
+    fn encode_base64(bytes: &[u8]) -> String {
+        const DIGITS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+        let mut out = String::new();
+        for chunk in bytes.chunks(3) {
+            let mut acc = 0u32;
+            for (i, &b) in chunk.iter().enumerate() {
+                acc |= (b as u32) << (16 - 8 * i);
+            }
+            for i in 0..4 {
+                out.push(if i <= chunk.len() { DIGITS[(acc >> (18 - 6 * i)) as usize & 63] as char } else { '=' });
+            }
+        }
+        out
+    }
+
+    // 单个三角形：x ∈ [0, 1]、z ∈ [0, 1] 的 y = 0 平面上
+    fn triangle_buffer() -> Vec<u8> {
+        [0.0f32, 0.0, 0.0, 0.0, 0.0, 1.0, 1.0, 0.0, 0.0].iter().flat_map(|v| v.to_le_bytes()).collect()
+    }
+
+    // 缓冲区 JSON 之外的部分：一个三角形网格，extra 追加到顶层对象
+    fn document(buffer: &str, nodes: &str, extra: &str) -> String {
+        format!(
+            r#"{{"asset": {{"version": "2.0"}}, "buffers": [{}],
+                "bufferViews": [{{"buffer": 0, "byteLength": 36}}],
+                "accessors": [{{"bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3"}}],
+                "meshes": [{{"primitives": [{{"attributes": {{"POSITION": 0}}}}]}}],
+                "nodes": [{}]{}}}"#,
+            buffer, nodes, extra
+        )
+    }
+
+    fn embedded(nodes: &str, extra: &str) -> String {
+        let uri = format!("data:application/octet-stream;base64,{}", encode_base64(&triangle_buffer()));
+        document(&format!(r#"{{"uri": "{}", "byteLength": 36}}"#, uri), nodes, extra)
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("gltf-test-{}-{}", name, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn load_text(name: &str, text: &str) -> Result<GltfScene, GltfError> {
+        let dir = temp_dir(name);
+        let file = dir.join("scene.gltf");
+        fs::write(&file, text).unwrap();
+        let scene = load_gltf(&file);
+        fs::remove_dir_all(&dir).unwrap();
+        scene
+    }
+
+    // 三角形使用 material 描述的材质，extra 追加到顶层对象
+    fn material_scene(name: &str, material: &str, extra: &str) -> GltfScene {
+        let text = embedded(r#"{"mesh": 0}"#, &format!(r#", "materials": [{}]{}"#, material, extra))
+            .replace(r#""POSITION": 0}"#, r#""POSITION": 0}, "material": 0"#);
+        load_text(name, &text).unwrap()
+    }
+
+    // 1×1 的 PNG data URI
+    fn png_uri(rgb: [u8; 3]) -> String {
+        let mut bytes = Vec::new();
+        image::RgbImage::from_pixel(1, 1, image::Rgb(rgb))
+            .write_to(&mut std::io::Cursor::new(&mut bytes), image::ImageOutputFormat::Png)
+            .unwrap();
+        format!("data:image/png;base64,{}", encode_base64(&bytes))
+    }
+
+    // 自上而下打在三角形上的光线经材质散射的结果
+    fn scatter_down(scene: &GltfScene, x: f64, z: f64) -> Option<ScatterRecord> {
+        let r = Ray::new(Point3::new(x, 10.0, z), Vec3::new(0.0, -1.0, 0.0), 0.0);
+        let rec = hit_down(scene, x, z)?;
+        let mut srec = ScatterRecord::new();
+        rec.mat.as_ref()?.scatter(&r, &rec, &mut srec, &mut rand::thread_rng()).then_some(srec)
+    }
+
+    fn hit_down(scene: &GltfScene, x: f64, z: f64) -> Option<HitRecord> {
+        let r = Ray::new(Point3::new(x, 10.0, z), Vec3::new(0.0, -1.0, 0.0), 0.0);
+        let mut rec = HitRecord::default();
+        scene.world.hit(&r, Interval::new(0.001, f64::INFINITY), &mut rec).then_some(rec)
+    }
+
+    #[test]
+    fn decodes_base64_and_uris() {
+        assert_eq!(decode_base64("aGVsbG8=").unwrap(), b"hello");
+        assert_eq!(decode_base64("-_-_").unwrap(), decode_base64("+/+/").unwrap());
+        assert!(decode_base64("a*b=").is_none());
+        let bytes: Vec<u8> = (0..=255).collect();
+        assert_eq!(decode_base64(&encode_base64(&bytes)).unwrap(), bytes);
+        assert_eq!(decode_uri("my%20model.bin"), "my model.bin");
+        assert_eq!(decode_uri("100%"), "100%");
+    }
+
+    // 父节点缩放、子节点平移：网格烘焙到世界空间
+    #[test]
+    fn node_transforms_are_composed() {
+        let nodes = r#"{"scale": [2, 2, 2], "children": [1]}, {"mesh": 0, "translation": [1, 0.5, 0]}"#;
+        let scene = load_text("compose", &embedded(nodes, "")).unwrap();
+        let rec = hit_down(&scene, 2.5, 0.5).expect("triangle should be moved to x ∈ [2, 4]");
+        assert!((rec.p.y - 1.0).abs() < 1e-9);
+        assert!(hit_down(&scene, 0.5, 0.5).is_none());
+    }
+
+    #[test]
+    fn reads_external_buffers_and_glb_containers() {
+        let dir = temp_dir("external");
+        fs::write(dir.join("tri angle.bin"), triangle_buffer()).unwrap();
+        let text = document(r#"{"uri": "tri%20angle.bin", "byteLength": 36}"#, r#"{"mesh": 0}"#, "");
+        fs::write(dir.join("scene.gltf"), &text).unwrap();
+        let external = load_gltf(dir.join("scene.gltf")).unwrap();
+        assert!(hit_down(&external, 0.25, 0.25).is_some());
+
+        let mut json = document(r#"{"byteLength": 36}"#, r#"{"mesh": 0}"#, "").into_bytes();
+        while !json.len().is_multiple_of(4) {
+            json.push(b' ');
+        }
+        let bin = triangle_buffer();
+        let mut glb = b"glTF".to_vec();
+        glb.extend_from_slice(&2u32.to_le_bytes());
+        glb.extend_from_slice(&((12 + 8 + json.len() + 8 + bin.len()) as u32).to_le_bytes());
+        glb.extend_from_slice(&(json.len() as u32).to_le_bytes());
+        glb.extend_from_slice(&0x4E4F_534Au32.to_le_bytes());
+        glb.extend_from_slice(&json);
+        glb.extend_from_slice(&(bin.len() as u32).to_le_bytes());
+        glb.extend_from_slice(&0x004E_4942u32.to_le_bytes());
+        glb.extend_from_slice(&bin);
+        fs::write(dir.join("scene.glb"), &glb).unwrap();
+        let binary = load_gltf(dir.join("scene.glb")).unwrap();
+        assert!(hit_down(&binary, 0.25, 0.25).is_some());
+        glb.truncate(glb.len() - 4);
+        fs::write(dir.join("scene.glb"), &glb).unwrap();
+        assert_eq!(load_gltf(dir.join("scene.glb")).err().unwrap().message, "truncated GLB chunk");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn rejects_malformed_documents() {
+        let error = |nodes: &str, extra: &str| load_text("errors", &embedded(nodes, extra)).err().unwrap().message;
+        assert_eq!(error(r#"{"children": [1]}, {"children": [0]}"#, r#", "scenes": [{"nodes": [0]}]"#), "node hierarchy contains a cycle");
+        assert_eq!(error(r#"{"mesh": 3}"#, ""), "meshes[3] does not exist");
+        assert_eq!(error(r#"{"scale": [1, 1]}"#, ""), "nodes[0] has a malformed TRS transform");
+        // 文件给出的偏移与数量不能溢出，也不能在读取前申请巨量内存
+        let accessor = |fields: &str| {
+            let text = embedded(r#"{"mesh": 0}"#, "").replace(r#""count": 3, "type": "VEC3"}"#, fields);
+            load_text("overflow", &text).err().unwrap().message
+        };
+        assert_eq!(accessor(r#""count": 1e30, "type": "VEC3"}"#), "accessors[0] is too large");
+        assert_eq!(accessor(r#""count": 1e17, "type": "VEC3"}"#), "accessors[0] reads past its bufferView");
+        assert_eq!(accessor(r#""count": 3, "type": "VEC3", "byteOffset": 1e30}"#), "accessors[0] reads past its bufferView");
+        let view = embedded(r#"{"mesh": 0}"#, "").replace(r#""byteLength": 36}]"#, r#""byteLength": 36, "byteOffset": 1e30}]"#);
+        assert_eq!(load_text("overflow", &view).err().unwrap().message, "bufferViews[0] is out of range");
+        let zeros = embedded(r#"{"mesh": 0}"#, "").replace(r#""bufferView": 0, "#, "").replace(r#""count": 3,"#, r#""count": 1e12,"#);
+        assert_eq!(load_text("overflow", &zeros).err().unwrap().message, "accessors[0] is too large");
+        let bad_image = r#", "materials": [{"pbrMetallicRoughness": {"baseColorTexture": {"index": 0}}}],
+            "textures": [{"source": 0}], "images": [{"uri": "data:image/png;base64,AAAA"}]"#;
+        let text = embedded(r#"{"mesh": 0}"#, bad_image).replace(r#""POSITION": 0}"#, r#""POSITION": 0}, "material": 0"#);
+        assert_eq!(load_text("image", &text).err().unwrap().message, "images[0] could not be decoded");
+        let missing = document(r#"{"uri": "missing.bin", "byteLength": 36}"#, r#"{"mesh": 0}"#, "");
+        assert!(load_text("missing", &missing).err().unwrap().message.starts_with("cannot read"));
+    }
+
+    // glTF 相机朝局部 -Z；点光源生成发光小球并加入光源列表
+    #[test]
+    fn imports_cameras_and_point_lights() {
+        let nodes = r#"{"mesh": 0}, {"camera": 0, "translation": [0, 1, 5]}, {"translation": [0, 2, 0], "extensions": {"KHR_lights_punctual": {"light": 0}}}"#;
+        let extra = r#", "cameras": [{"name": "front", "type": "perspective", "perspective": {"yfov": 0.5, "aspectRatio": 2.0}}],
+            "extensions": {"KHR_lights_punctual": {"lights": [{"type": "point", "intensity": 10}]}}"#;
+        let scene = load_text("camera", &embedded(nodes, extra)).unwrap();
+        assert_eq!(scene.world.objects.len(), 2);
+        assert_eq!(scene.lights.objects.len(), 1);
+        let camera = &scene.cameras[0];
+        assert_eq!(camera.name, "front");
+        assert!((camera.lookat - Point3::new(0.0, 1.0, 4.0)).length() < 1e-12);
+        assert!((camera.vfov - 0.5f64.to_degrees()).abs() < 1e-12);
+        let mut cam = Camera::new();
+        camera.apply(&mut cam);
+        assert_eq!(cam.aspect_ratio, 2.0);
+    }
+
+    // 粗糙度 = roughnessFactor × 纹理 G 通道；金属度 = metallicFactor × B 通道
+    #[test]
+    fn roughness_factor_scales_the_metallic_roughness_texture() {
+        let material = r#"{"pbrMetallicRoughness": {"metallicFactor": 1.0, "roughnessFactor": 0.3, "metallicRoughnessTexture": {"index": 0}}}"#;
+        let extra = format!(r#", "textures": [{{"source": 0}}], "images": [{{"uri": "{}"}}]"#, png_uri([0, 255, 255]));
+        let scene = material_scene("roughness", material, &extra);
+        for _ in 0..100 {
+            let srec = scatter_down(&scene, 0.25, 0.25).expect("metal reflects");
+            let reflected = srec.skip_pdf_ray.expect("metal is specular").direction;
+            assert!(((reflected - Vec3::new(0.0, 1.0, 0.0)).length() - 0.3).abs() < 1e-9);
+        }
+    }
+
+    // 无纹理时同样按金属度与粗糙度随机混合，而不是按阈值整体归为一类
+    #[test]
+    fn constant_factors_blend_metal_coat_and_diffuse() {
+        let material = r#"{"pbrMetallicRoughness": {"baseColorFactor": [0.5, 0.5, 0.5, 1], "metallicFactor": 0.4, "roughnessFactor": 0.29}}"#;
+        let scene = material_scene("factors", material, "");
+        let n = 20000;
+        let (mut metal, mut diffuse) = (0, 0);
+        for i in 0..n {
+            let (x, z) = ((i % 141) as f64 / 300.0 + 0.01, (i / 141) as f64 / 300.0 + 0.01);
+            let srec = scatter_down(&scene, x, z).expect("every lobe scatters");
+            if !srec.skip_pdf {
+                diffuse += 1;
+            } else if srec.attenuation.x < 0.75 {
+                metal += 1; // 涂层反射不着色，金属反射乘以基色
+            }
+        }
+        assert!((metal as f64 / n as f64 - 0.4).abs() < 0.02);
+        // 电介质中 29% 为漫反射，其余经涂层，正入射时约 4% 被涂层反射
+        let expected = 0.6 * (0.29 + 0.71 * 0.96);
+        assert!((diffuse as f64 / n as f64 - expected).abs() < 0.02);
+    }
+
+    #[test]
+    fn spot_light_falls_off_between_the_cones() {
+        let spot = SpotEmitter {
+            light: DiffuseLight::from_color(Color::new(1.0, 1.0, 1.0)),
+            axis: Vec3::new(0.0, -1.0, 0.0),
+            cos_inner: 0.2f64.cos(),
+            cos_outer: 0.4f64.cos(),
+        };
+        let mut rec = HitRecord::default();
+        rec.front_face = true;
+        // 光线从被照点射向光源，出射方向与之相反
+        let toward_light = |angle: f64| Ray::new(Point3::default(), Vec3::new(angle.sin(), angle.cos(), 0.0), 0.0);
+        assert_eq!(spot.emitted(&toward_light(0.1), &rec).x, 1.0);
+        let middle = spot.emitted(&toward_light(0.3), &rec).x;
+        assert!(middle > 0.0 && middle < 1.0);
+        assert_eq!(spot.emitted(&toward_light(0.5), &rec).x, 0.0);
+    }
+
+    #[test]
+    fn loads_the_gallery_scene() {
+        let scene = load_gltf("input/models/gallery.gltf").unwrap();
+        // 五个网格、聚光灯小球与太阳
+        assert_eq!(scene.world.objects.len(), 7);
+        // 发光面板、聚光灯与太阳
+        assert_eq!(scene.lights.objects.len(), 3);
+        assert_eq!(scene.cameras.len(), 1);
+        assert!((scene.cameras[0].vfov - 35.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,444 @@
+// 毛发散射模型（Chiang et al. 2016 / d'Eon et al. 2011，pbrt-v3 的 HairBSDF）
+// 把散射分解为 R、TT、TRT 与其余高阶项：纵向 Mp 用 d'Eon 的球面高斯，
+// 方位向 Np 用截断 logistic，各项能量 Ap 由 Fresnel 与毛发内部吸收决定
+use std::f64::consts::LN_2;
+use std::sync::Arc;
+use rand::rngs::ThreadRng;
+use crate::hittable::HitRecord;
+use crate::material::{Material, ScatterRecord};
+use crate::pdf::Pdf;
+use crate::rtweekend::random::random_double;
+use crate::rtweekend::PI;
+use crate::vec3::{Color, Vec3};
+use crate::Ray;
+
+// 单独建模的散射阶数，更高阶合并成一项
+const P_MAX: usize = 3;
+// 真黑色素与褐黑色素的吸收系数
+const EUMELANIN_SIGMA_A: (f64, f64, f64) = (0.419, 0.697, 1.37);
+const PHEOMELANIN_SIGMA_A: (f64, f64, f64) = (0.187, 0.4, 1.05);
+
+pub struct HairMaterial {
+    sigma_a: Color, // 毛发内部吸收系数（以毛发直径为单位长度）
+    beta_m: f64,    // 纵向粗糙度 [0, 1]
+    beta_n: f64,    // 方位向粗糙度 [0, 1]
+    alpha: f64,     // 毛鳞片倾角，度
+    eta: f64,
+}
+
+impl HairMaterial {
+    pub fn from_sigma_a(sigma_a: Color, beta_m: f64, beta_n: f64, alpha: f64) -> Self {
+        Self { sigma_a, beta_m: beta_m.clamp(0.0, 1.0), beta_n: beta_n.clamp(0.0, 1.0), alpha, eta: 1.55 }
+    }
+
+    /// 由两种黑色素浓度给出吸收，真黑色素约 0（金发）到 8（黑发），褐黑色素使头发偏红
+    pub fn from_melanin(eumelanin: f64, pheomelanin: f64, beta_m: f64, beta_n: f64, alpha: f64) -> Self {
+        let (e, p) = (EUMELANIN_SIGMA_A, PHEOMELANIN_SIGMA_A);
+        let sigma_a = Color::new(
+            eumelanin * e.0 + pheomelanin * p.0,
+            eumelanin * e.1 + pheomelanin * p.1,
+            eumelanin * e.2 + pheomelanin * p.2,
+        );
+        Self::from_sigma_a(sigma_a, beta_m, beta_n, alpha)
+    }
+
+    /// 由期望的多次散射后颜色反推吸收系数（Chiang 等的拟合）
+    pub fn from_reflectance(color: Color, beta_m: f64, beta_n: f64, alpha: f64) -> Self {
+        let b = beta_n.clamp(0.0, 1.0);
+        let denom = 5.969 - 0.215 * b + 2.532 * b * b - 10.73 * b.powi(3) + 5.574 * b.powi(4) + 0.245 * b.powi(5);
+        let sigma = |c: f64| (c.clamp(1e-4, 1.0).ln() / denom).powi(2);
+        Self::from_sigma_a(Color::new(sigma(color.x), sigma(color.y), sigma(color.z)), beta_m, beta_n, alpha)
+    }
+
+    pub fn with_eta(mut self, eta: f64) -> Self {
+        self.eta = eta;
+        self
+    }
+}
+
+// 某一交点处的 BSDF：局部坐标 x 沿毛发，y 沿宽度方向，z 为法线
+pub struct HairBsdf {
+    frame: [Vec3; 3],
+    h: f64,          // 光线在毛发横截面上的偏移 [-1, 1]
+    gamma_o: f64,
+    eta: f64,
+    sigma_a: Color,
+    v: [f64; P_MAX + 1], // 各阶 Mp 的方差
+    s: f64,              // Np 的 logistic 尺度
+    sin_2k_alpha: [f64; 3],
+    cos_2k_alpha: [f64; 3],
+    wo: Vec3,            // 局部坐标下的出射方向
+}
+
+impl HairBsdf {
+    pub fn new(mat: &HairMaterial, rec: &HitRecord, wo: &Vec3) -> Self {
+        // 切线取 dpdu，宽度方向取 dpdv 去掉切向分量，与 v 增大的方向一致
+        let ss = Vec3::unit_vector(rec.dpdu);
+        let ts = rec.dpdv - Vec3::dot(&rec.dpdv, &ss) * ss;
+        let ts = if ts.length_squared() > 0.0 { Vec3::unit_vector(ts) } else { Vec3::cross(&rec.normal, &ss) };
+        let frame = [ss, ts, Vec3::cross(&ss, &ts)];
+        let h = (-1.0 + 2.0 * rec.v).clamp(-1.0, 1.0);
+
+        let (bm, bn) = (mat.beta_m, mat.beta_n);
+        let v0 = (0.726 * bm + 0.812 * bm * bm + 3.7 * bm.powi(20)).powi(2);
+        let v = [v0, 0.25 * v0, 4.0 * v0, 4.0 * v0];
+        let s = (PI / 8.0).sqrt() * (0.265 * bn + 1.194 * bn * bn + 5.372 * bn.powi(22));
+
+        // 鳞片倾角使各阶的纵向峰偏移 -2α、α、4α
+        let mut sin_2k_alpha = [0.0; 3];
+        let mut cos_2k_alpha = [0.0; 3];
+        sin_2k_alpha[0] = mat.alpha.to_radians().sin();
+        cos_2k_alpha[0] = safe_sqrt(1.0 - sin_2k_alpha[0] * sin_2k_alpha[0]);
+        for i in 1..3 {
+            sin_2k_alpha[i] = 2.0 * cos_2k_alpha[i - 1] * sin_2k_alpha[i - 1];
+            cos_2k_alpha[i] = cos_2k_alpha[i - 1].powi(2) - sin_2k_alpha[i - 1].powi(2);
+        }
+
+        let w = Vec3::unit_vector(*wo);
+        let wo = Vec3::new(Vec3::dot(&w, &frame[0]), Vec3::dot(&w, &frame[1]), Vec3::dot(&w, &frame[2]));
+        Self { frame, h, gamma_o: h.asin(), eta: mat.eta, sigma_a: mat.sigma_a, v, s, sin_2k_alpha, cos_2k_alpha, wo }
+    }
+
+    fn to_local(&self, w: &Vec3) -> Vec3 {
+        let w = Vec3::unit_vector(*w);
+        Vec3::new(Vec3::dot(&w, &self.frame[0]), Vec3::dot(&w, &self.frame[1]), Vec3::dot(&w, &self.frame[2]))
+    }
+
+    fn to_world(&self, w: &Vec3) -> Vec3 {
+        w.x * self.frame[0] + w.y * self.frame[1] + w.z * self.frame[2]
+    }
+
+    // 第 p 阶考虑鳞片倾角后的出射纵向角
+    fn tilted(&self, p: usize, sin_theta_o: f64, cos_theta_o: f64) -> (f64, f64) {
+        let (sin_op, cos_op) = match p {
+            0 => (
+                sin_theta_o * self.cos_2k_alpha[1] - cos_theta_o * self.sin_2k_alpha[1],
+                cos_theta_o * self.cos_2k_alpha[1] + sin_theta_o * self.sin_2k_alpha[1],
+            ),
+            1 => (
+                sin_theta_o * self.cos_2k_alpha[0] + cos_theta_o * self.sin_2k_alpha[0],
+                cos_theta_o * self.cos_2k_alpha[0] - sin_theta_o * self.sin_2k_alpha[0],
+            ),
+            2 => (
+                sin_theta_o * self.cos_2k_alpha[2] + cos_theta_o * self.sin_2k_alpha[2],
+                cos_theta_o * self.cos_2k_alpha[2] - sin_theta_o * self.sin_2k_alpha[2],
+            ),
+            _ => (sin_theta_o, cos_theta_o),
+        };
+        (sin_op, cos_op.abs())
+    }
+
+    // 折射进入毛发后的方位角 γt 与穿过一次的透射率
+    fn refracted(&self, sin_theta_o: f64, cos_theta_o: f64) -> (f64, Color) {
+        let sin_theta_t = sin_theta_o / self.eta;
+        let cos_theta_t = safe_sqrt(1.0 - sin_theta_t * sin_theta_t);
+        // 修正折射率 η'：把斜入射投影到横截面上
+        let etap = (self.eta * self.eta - sin_theta_o * sin_theta_o).sqrt() / cos_theta_o;
+        let sin_gamma_t = (self.h / etap).clamp(-1.0, 1.0);
+        let cos_gamma_t = safe_sqrt(1.0 - sin_gamma_t * sin_gamma_t);
+        let k = 2.0 * cos_gamma_t / cos_theta_t;
+        let t = Color::new((-self.sigma_a.x * k).exp(), (-self.sigma_a.y * k).exp(), (-self.sigma_a.z * k).exp());
+        (sin_gamma_t.asin(), t)
+    }
+
+    // 各阶能量衰减 Ap
+    fn ap(&self, cos_theta_o: f64, t: Color) -> [Color; P_MAX + 1] {
+        let cos_gamma_o = safe_sqrt(1.0 - self.h * self.h);
+        let f = fr_dielectric(cos_theta_o * cos_gamma_o, self.eta);
+        let a0 = Color::new(f, f, f);
+        let a1 = (1.0 - f) * (1.0 - f) * t;
+        let a2 = a1 * t * f;
+        // 其余各阶为几何级数之和
+        let tail = |a: f64, tf: f64| a * tf / (1.0 - tf);
+        let a3 = Color::new(tail(a2.x, t.x * f), tail(a2.y, t.y * f), tail(a2.z, t.z * f));
+        [a0, a1, a2, a3]
+    }
+
+    // 按各阶能量的亮度选择阶数的概率
+    fn ap_pdf(&self, cos_theta_o: f64) -> [f64; P_MAX + 1] {
+        let sin_theta_o = safe_sqrt(1.0 - cos_theta_o * cos_theta_o);
+        let (_, t) = self.refracted(sin_theta_o, cos_theta_o);
+        let ap = self.ap(cos_theta_o, t);
+        let lum = ap.map(|c| luminance(&c));
+        let sum: f64 = lum.iter().sum();
+        if sum <= 0.0 {
+            return [0.25; P_MAX + 1];
+        }
+        lum.map(|l| l / sum)
+    }
+
+    // f × |cosθi|：pbrt 中 f 已除以 |cosθi|，这里正好抵消
+    pub fn eval(&self, wi: &Vec3) -> Color {
+        let wo = self.wo;
+        let wi = self.to_local(wi);
+        let (sin_theta_o, cos_theta_o, phi_o) = angles(&wo);
+        let (sin_theta_i, cos_theta_i, phi_i) = angles(&wi);
+        let (gamma_t, t) = self.refracted(sin_theta_o, cos_theta_o);
+        let ap = self.ap(cos_theta_o, t);
+        let phi = phi_i - phi_o;
+
+        let mut fsum = Color::new(0.0, 0.0, 0.0);
+        for (p, ap_p) in ap.iter().enumerate().take(P_MAX) {
+            let (sin_op, cos_op) = self.tilted(p, sin_theta_o, cos_theta_o);
+            let weight = mp(cos_theta_i, cos_op, sin_theta_i, sin_op, self.v[p]) * np(phi, p, self.s, self.gamma_o, gamma_t);
+            fsum += weight * *ap_p;
+        }
+        // 高阶项在方位向上均匀
+        fsum += mp(cos_theta_i, cos_theta_o, sin_theta_i, sin_theta_o, self.v[P_MAX]) / (2.0 * PI) * ap[P_MAX];
+        fsum
+    }
+}
+
+impl Pdf for HairBsdf {
+    fn value(&self, direction: &Vec3) -> f64 {
+        let wi = self.to_local(direction);
+        let (sin_theta_o, cos_theta_o, phi_o) = angles(&self.wo);
+        let (sin_theta_i, cos_theta_i, phi_i) = angles(&wi);
+        let (gamma_t, _) = self.refracted(sin_theta_o, cos_theta_o);
+        let ap_pdf = self.ap_pdf(cos_theta_o);
+        let phi = phi_i - phi_o;
+
+        let mut pdf = 0.0;
+        for (p, ap_p) in ap_pdf.iter().enumerate().take(P_MAX) {
+            let (sin_op, cos_op) = self.tilted(p, sin_theta_o, cos_theta_o);
+            pdf += mp(cos_theta_i, cos_op, sin_theta_i, sin_op, self.v[p]) * ap_p * np(phi, p, self.s, self.gamma_o, gamma_t);
+        }
+        pdf + mp(cos_theta_i, cos_theta_o, sin_theta_i, sin_theta_o, self.v[P_MAX]) * ap_pdf[P_MAX] / (2.0 * PI)
+    }
+
+    fn generate(&self) -> Vec3 {
+        let (sin_theta_o, cos_theta_o, phi_o) = angles(&self.wo);
+
+        // 先按 Ap 选散射阶数
+        let ap_pdf = self.ap_pdf(cos_theta_o);
+        let xi = random_double();
+        let mut p = 0;
+        let mut acc = ap_pdf[0];
+        while p < P_MAX && xi >= acc {
+            p += 1;
+            acc += ap_pdf[p];
+        }
+
+        // 纵向：对 Mp 精确采样
+        let (sin_op, cos_op) = self.tilted(p, sin_theta_o, cos_theta_o);
+        let u = random_double().max(1e-5);
+        let cos_theta = 1.0 + self.v[p] * (u + (1.0 - u) * (-2.0 / self.v[p]).exp()).ln();
+        let sin_theta = safe_sqrt(1.0 - cos_theta * cos_theta);
+        let cos_phi = (2.0 * PI * random_double()).cos();
+        let sin_theta_i = (-cos_theta * sin_op + sin_theta * cos_phi * cos_op).clamp(-1.0, 1.0);
+        let cos_theta_i = safe_sqrt(1.0 - sin_theta_i * sin_theta_i);
+
+        // 方位向：围绕第 p 阶的偏转角采样截断 logistic
+        let (gamma_t, _) = self.refracted(sin_theta_o, cos_theta_o);
+        let dphi = if p < P_MAX {
+            phi_shift(p, self.gamma_o, gamma_t) + sample_trimmed_logistic(random_double(), self.s, -PI, PI)
+        } else {
+            2.0 * PI * random_double()
+        };
+        let phi_i = phi_o + dphi;
+        self.to_world(&Vec3::new(sin_theta_i, cos_theta_i * phi_i.cos(), cos_theta_i * phi_i.sin()))
+    }
+}
+
+impl Material for HairMaterial {
+    fn scatter(
+        &self,
+        r_in: &Ray,
+        rec: &HitRecord,
+        srec: &mut ScatterRecord,
+        _rng: &mut ThreadRng,
+    ) -> bool {
+        // 颜色完全由 eval_bsdf 给出
+        srec.attenuation = Color::new(1.0, 1.0, 1.0);
+        srec.pdf_ptr = Some(Arc::new(HairBsdf::new(self, rec, &-r_in.direction)));
+        srec.skip_pdf = false;
+        srec.skip_pdf_ray = None;
+        true
+    }
+
+    fn eval_bsdf(&self, r_in: &Ray, rec: &HitRecord, scattered: &Ray) -> Option<Color> {
+        Some(HairBsdf::new(self, rec, &-r_in.direction).eval(&scattered.direction))
+    }
+
+    // 仅供不使用 eval_bsdf 的调用方：按亮度退化为灰度
+    fn scattering_pdf(&self, r_in: &Ray, rec: &HitRecord, scattered: &Ray) -> f64 {
+        luminance(&HairBsdf::new(self, rec, &-r_in.direction).eval(&scattered.direction))
+    }
+}
+
+fn safe_sqrt(x: f64) -> f64 {
+    x.max(0.0).sqrt()
+}
+
+fn luminance(c: &Color) -> f64 {
+    0.2126 * c.x + 0.7152 * c.y + 0.0722 * c.z
+}
+
+// 局部方向的 (sinθ, cosθ, φ)：θ 相对法平面，φ 在法平面内从 y 轴量起
+fn angles(w: &Vec3) -> (f64, f64, f64) {
+    let sin_theta = w.x.clamp(-1.0, 1.0);
+    (sin_theta, safe_sqrt(1.0 - sin_theta * sin_theta), w.z.atan2(w.y))
+}
+
+// 介质外折射率为 1 的非偏振 Fresnel 反射率
+fn fr_dielectric(cos_theta_i: f64, eta: f64) -> f64 {
+    let (cos_i, eta) = if cos_theta_i < 0.0 { (-cos_theta_i, 1.0 / eta) } else { (cos_theta_i, eta) };
+    let cos_i = cos_i.min(1.0);
+    let sin_t = safe_sqrt(1.0 - cos_i * cos_i) / eta;
+    if sin_t >= 1.0 {
+        return 1.0;
+    }
+    let cos_t = safe_sqrt(1.0 - sin_t * sin_t);
+    let r_parl = (eta * cos_i - cos_t) / (eta * cos_i + cos_t);
+    let r_perp = (cos_i - eta * cos_t) / (cos_i + eta * cos_t);
+    0.5 * (r_parl * r_parl + r_perp * r_perp)
+}
+
+// 第一类修正 Bessel 函数 I0 的级数
+fn bessel_i0(x: f64) -> f64 {
+    let mut val = 0.0;
+    let mut x2i = 1.0;
+    let mut ifact = 1.0;
+    let mut i4 = 1.0;
+    for i in 0..10 {
+        if i > 1 {
+            ifact *= i as f64;
+        }
+        val += x2i / (i4 * ifact * ifact);
+        x2i *= x * x;
+        i4 *= 4.0;
+    }
+    val
+}
+
+fn log_bessel_i0(x: f64) -> f64 {
+    if x > 12.0 {
+        x + 0.5 * (-(2.0 * PI).ln() + (1.0 / x).ln() + 1.0 / (8.0 * x))
+    } else {
+        bessel_i0(x).ln()
+    }
+}
+
+// 纵向散射函数，方差小时在对数域计算以免溢出
+fn mp(cos_theta_i: f64, cos_theta_o: f64, sin_theta_i: f64, sin_theta_o: f64, v: f64) -> f64 {
+    let a = cos_theta_i * cos_theta_o / v;
+    let b = sin_theta_i * sin_theta_o / v;
+    if v <= 0.1 {
+        (log_bessel_i0(a) - b - 1.0 / v + LN_2 + (1.0 / (2.0 * v)).ln()).exp()
+    } else {
+        (-b).exp() * bessel_i0(a) / ((1.0 / v).sinh() * 2.0 * v)
+    }
+}
+
+// 第 p 阶的理想方位偏转角
+fn phi_shift(p: usize, gamma_o: f64, gamma_t: f64) -> f64 {
+    2.0 * p as f64 * gamma_t - 2.0 * gamma_o + p as f64 * PI
+}
+
+fn logistic(x: f64, s: f64) -> f64 {
+    let x = x.abs();
+    (-x / s).exp() / (s * (1.0 + (-x / s).exp()).powi(2))
+}
+
+fn logistic_cdf(x: f64, s: f64) -> f64 {
+    1.0 / (1.0 + (-x / s).exp())
+}
+
+fn trimmed_logistic(x: f64, s: f64, a: f64, b: f64) -> f64 {
+    logistic(x, s) / (logistic_cdf(b, s) - logistic_cdf(a, s))
+}
+
+fn sample_trimmed_logistic(u: f64, s: f64, a: f64, b: f64) -> f64 {
+    let k = logistic_cdf(b, s) - logistic_cdf(a, s);
+    let x = -s * (1.0 / (u * k + logistic_cdf(a, s)) - 1.0).ln();
+    x.clamp(a, b)
+}
+
+// 方位向散射函数
+fn np(phi: f64, p: usize, s: f64, gamma_o: f64, gamma_t: f64) -> f64 {
+    let mut dphi = phi - phi_shift(p, gamma_o, gamma_t);
+    // 归一化到 [-π, π]
+    while dphi > PI {
+        dphi -= 2.0 * PI;
+    }
+    while dphi < -PI {
+        dphi += 2.0 * PI;
+    }
+    trimmed_logistic(dphi, s, -PI, PI)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 沿 x 轴的毛发，宽度方向为 y，在横截面偏移 h 处被击中
+    fn bsdf(mat: &HairMaterial, h: f64, wo: Vec3) -> HairBsdf {
+        let mut rec = HitRecord::default();
+        rec.dpdu = Vec3::new(1.0, 0.0, 0.0);
+        rec.dpdv = Vec3::new(0.0, 0.01, 0.0);
+        rec.normal = Vec3::new(0.0, 0.0, 1.0);
+        rec.v = 0.5 * (h + 1.0);
+        HairBsdf::new(mat, &rec, &wo)
+    }
+
+    fn outgoing() -> Vec3 {
+        Vec3::unit_vector(Vec3::new(0.3, 0.2, 1.0))
+    }
+
+    // 与 hittable::pdf_integral 相同的混合估计：均匀球面与 generate 各半
+    #[test]
+    fn pdf_integrates_to_one_and_matches_sampling() {
+        for (beta_m, beta_n, h) in [(0.3, 0.3, 0.0), (0.2, 0.5, 0.6), (0.6, 0.2, -0.8)] {
+            let mat = HairMaterial::from_melanin(1.3, 0.6, beta_m, beta_n, 2.0);
+            let f = bsdf(&mat, h, outgoing());
+            let n = 200000;
+            let uniform = 1.0 / (4.0 * PI);
+            let integral = (0..n)
+                .map(|_| {
+                    let wi = if random_double() < 0.5 { Vec3::random_unit_vector() } else { f.generate() };
+                    let pdf = f.value(&wi);
+                    pdf / (0.5 * uniform + 0.5 * pdf)
+                })
+                .sum::<f64>() / n as f64;
+            assert!((integral - 1.0).abs() < 0.03, "beta_m {beta_m} beta_n {beta_n} h {h}: {integral}");
+        }
+    }
+
+    // 白炉测试：无吸收时反射率接近 1，有吸收时严格小于 1
+    #[test]
+    fn energy_is_conserved() {
+        let albedo = |mat: &HairMaterial| {
+            let n = 400000;
+            let mut sum = Color::new(0.0, 0.0, 0.0);
+            for _ in 0..n {
+                let f = bsdf(mat, random_double() * 2.0 - 1.0, outgoing());
+                let wi = Vec3::random_unit_vector();
+                sum += f.eval(&wi) * (4.0 * PI);
+            }
+            sum / n as f64
+        };
+        let clear = albedo(&HairMaterial::from_sigma_a(Color::new(0.0, 0.0, 0.0), 0.3, 0.3, 2.0));
+        for c in [clear.x, clear.y, clear.z] {
+            assert!((c - 1.0).abs() < 0.05, "{c}");
+        }
+        let brown = albedo(&HairMaterial::from_melanin(1.3, 0.6, 0.3, 0.3, 2.0));
+        assert!(brown.x < clear.x && brown.z < brown.x);
+    }
+
+    // 期望颜色越暗吸收越强，白色几乎不吸收；with_eta 改变 R 项的 Fresnel 反射
+    #[test]
+    fn reflectance_and_eta_parameterise_the_fibre() {
+        let mat = HairMaterial::from_reflectance(Color::new(0.9, 0.5, 0.1), 0.3, 0.3, 2.0);
+        assert!(mat.sigma_a.x < mat.sigma_a.y && mat.sigma_a.y < mat.sigma_a.z);
+        let white = HairMaterial::from_reflectance(Color::new(1.0, 1.0, 1.0), 0.3, 0.3, 2.0);
+        assert!(white.sigma_a.length() < 1e-12);
+
+        let base = HairMaterial::from_sigma_a(Color::new(0.5, 0.5, 0.5), 0.3, 0.3, 0.0);
+        let dense = HairMaterial::from_sigma_a(Color::new(0.5, 0.5, 0.5), 0.3, 0.3, 0.0).with_eta(2.0);
+        assert_eq!(dense.eta, 2.0);
+        // 镜面方向附近以 R 项为主，折射率越高反射越强
+        let wo = outgoing();
+        let mirror = Vec3::new(-wo.x, -wo.y, wo.z);
+        assert!(bsdf(&dense, 0.0, wo).eval(&mirror).x > bsdf(&base, 0.0, wo).eval(&mirror).x);
+    }
+}
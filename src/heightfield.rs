@@ -0,0 +1,314 @@
+// 高度场地形：规则网格上的高度采样，每个格子拆成两个三角形
+// 求交时在 xz 平面上用网格 DDA 逐格前进，而不是把整片地形展开成独立三角形再建 BVH
+use std::sync::Arc;
+use crate::aabb::Aabb;
+use crate::hittable::{HitRecord, Hittable};
+use crate::interval::Interval;
+use crate::material::Material;
+use crate::perlin::Perlin;
+use crate::ray::Ray;
+use crate::rtw_stb_image::RtwImage;
+use crate::triangle::intersect_watertight;
+use crate::vec3::{Point3, Vec3};
+
+pub struct Heightfield {
+    nx: usize,          // x 方向采样数
+    nz: usize,          // z 方向采样数
+    points: Vec<Point3>, // 世界坐标下的网格顶点，行优先 points[iz * nx + ix]
+    normals: Vec<Vec3>,  // 顶点法线，由高度差分得到
+    corner: Point3,
+    cell: (f64, f64),   // 格子在 x、z 方向的边长
+    mat: Arc<dyn Material + Send + Sync>,
+    bbox: Aabb,
+}
+
+impl Heightfield {
+    /// heights 为行优先的 nx × nz 个采样，取值 [0, 1] 映射到 corner.y ..= corner.y + size.y；
+    /// 地形在 xz 平面上覆盖 corner 起、边长 size.x × size.z 的矩形
+    pub fn new(heights: &[f64], nx: usize, nz: usize, corner: Point3, size: Vec3, mat: Arc<dyn Material + Send + Sync>) -> Self {
+        assert!(nx >= 2 && nz >= 2 && heights.len() >= nx * nz, "heightfield needs at least 2x2 samples");
+        let cell = (size.x / (nx - 1) as f64, size.z / (nz - 1) as f64);
+        let mut points = Vec::with_capacity(nx * nz);
+        let mut bbox = Aabb::new_empty();
+        for iz in 0..nz {
+            for ix in 0..nx {
+                let p = corner + Vec3::new(ix as f64 * cell.0, heights[iz * nx + ix] * size.y, iz as f64 * cell.1);
+                bbox = Aabb::surrounding_box(&bbox, &Aabb::from_points(p, p));
+                points.push(p);
+            }
+        }
+
+        // 中心差分求梯度，边界处退化为单侧差分
+        let height = |ix: usize, iz: usize| points[iz * nx + ix].y;
+        let mut normals = Vec::with_capacity(nx * nz);
+        for iz in 0..nz {
+            for ix in 0..nx {
+                let (x0, x1) = (ix.saturating_sub(1), (ix + 1).min(nx - 1));
+                let (z0, z1) = (iz.saturating_sub(1), (iz + 1).min(nz - 1));
+                let dhdx = (height(x1, iz) - height(x0, iz)) / ((x1 - x0) as f64 * cell.0);
+                let dhdz = (height(ix, z1) - height(ix, z0)) / ((z1 - z0) as f64 * cell.1);
+                normals.push(Vec3::unit_vector(Vec3::new(-dhdx, 1.0, -dhdz)));
+            }
+        }
+
+        Self { nx, nz, points, normals, corner, cell, mat, bbox }
+    }
+
+    /// 灰度图作高度图，图像的行对应 z 方向；亮度取 Rec.709 加权
+    pub fn from_image(filename: &str, corner: Point3, size: Vec3, mat: Arc<dyn Material + Send + Sync>) -> Self {
+        let image = RtwImage::from_file(filename);
+        let (w, h) = (image.width().max(2), image.height().max(2));
+        let mut heights = Vec::with_capacity(w * h);
+        for j in 0..h {
+            for i in 0..w {
+                let pixel = image.pixel_data(i as isize, j as isize);
+                let luminance = 0.2126 * pixel[0] as f64 + 0.7152 * pixel[1] as f64 + 0.0722 * pixel[2] as f64;
+                heights.push(luminance / 255.0);
+            }
+        }
+        Self::new(&heights, w, h, corner, size, mat)
+    }
+
+    /// 用 Perlin 湍流生成 resolution × resolution 的高度，frequency 为整块地形上的噪声周期数，
+    /// 结果按最大值归一化到 [0, 1]
+    pub fn from_perlin(noise: &Perlin, resolution: usize, frequency: f64, depth: i32, corner: Point3, size: Vec3, mat: Arc<dyn Material + Send + Sync>) -> Self {
+        let n = resolution.max(2);
+        let mut heights = Vec::with_capacity(n * n);
+        for iz in 0..n {
+            for ix in 0..n {
+                let p = Point3::new(ix as f64 / (n - 1) as f64 * frequency, 0.0, iz as f64 / (n - 1) as f64 * frequency);
+                heights.push(noise.turb(&p, depth));
+            }
+        }
+        let max = heights.iter().cloned().fold(0.0, f64::max);
+        if max > 0.0 {
+            heights.iter_mut().for_each(|h| *h /= max);
+        }
+        Self::new(&heights, n, n, corner, size, mat)
+    }
+
+    fn index(&self, ix: usize, iz: usize) -> usize {
+        iz * self.nx + ix
+    }
+
+    // 格子 (ix, iz) 的两个三角形中最近的交点：(t, 三个顶点下标, 重心坐标)
+    fn hit_cell(&self, r: &Ray, ray_t: Interval, ix: usize, iz: usize) -> Option<(f64, [usize; 3], [f64; 3])> {
+        let i00 = self.index(ix, iz);
+        let i10 = self.index(ix + 1, iz);
+        let i01 = self.index(ix, iz + 1);
+        let i11 = self.index(ix + 1, iz + 1);
+        let mut closest = None;
+        let mut t_max = ray_t.max;
+        // 两个三角形都按 (x, z) 逆时针排列，几何法线朝 +y
+        for idx in [[i00, i11, i10], [i00, i01, i11]] {
+            let [p0, p1, p2] = idx.map(|i| &self.points[i]);
+            if let Some((t, b0, b1, b2)) = intersect_watertight(r, Interval::new(ray_t.min, t_max), p0, p1, p2) {
+                t_max = t;
+                closest = Some((t, idx, [b0, b1, b2]));
+            }
+        }
+        closest
+    }
+
+    // 光线与包围盒的参数区间，不相交时返回 None
+    fn clip(&self, r: &Ray, ray_t: Interval) -> Option<(f64, f64)> {
+        let (mut t0, mut t1) = (ray_t.min, ray_t.max);
+        for axis in 0..3 {
+            let slab = self.bbox.axis_interval(axis);
+            let (o, d) = match axis {
+                0 => (r.origin.x, r.direction.x),
+                1 => (r.origin.y, r.direction.y),
+                _ => (r.origin.z, r.direction.z),
+            };
+            let inv = 1.0 / d;
+            let (mut near, mut far) = ((slab.min - o) * inv, (slab.max - o) * inv);
+            if near > far {
+                std::mem::swap(&mut near, &mut far);
+            }
+            if near.is_nan() || far.is_nan() {
+                continue;
+            }
+            t0 = t0.max(near);
+            t1 = t1.min(far);
+            if t1 <= t0 {
+                return None;
+            }
+        }
+        Some((t0, t1))
+    }
+}
+
+// 网格 DDA 在一个轴上的状态：当前格子、步进方向、下一条格线处的 t 与跨过一格的 t 增量
+fn dda_axis(origin: f64, direction: f64, corner: f64, cell: f64, t_start: f64, cells: usize) -> (usize, isize, f64, f64) {
+    let g = ((origin + t_start * direction - corner) / cell).floor();
+    let i = (g.max(0.0) as usize).min(cells - 1);
+    if direction > 0.0 {
+        (i, 1, (corner + (i + 1) as f64 * cell - origin) / direction, cell / direction)
+    } else if direction < 0.0 {
+        (i, -1, (corner + i as f64 * cell - origin) / direction, -cell / direction)
+    } else {
+        (i, 0, f64::INFINITY, f64::INFINITY)
+    }
+}
+
+impl Hittable for Heightfield {
+    fn hit(&self, r: &Ray, ray_t: Interval, rec: &mut HitRecord) -> bool {
+        let Some((t_enter, t_exit)) = self.clip(r, ray_t) else { return false };
+        let (o, d) = (r.origin, r.direction);
+        let (cells_x, cells_z) = (self.nx - 1, self.nz - 1);
+        let (mut ix, step_x, mut next_x, delta_x) = dda_axis(o.x, d.x, self.corner.x, self.cell.0, t_enter, cells_x);
+        let (mut iz, step_z, mut next_z, delta_z) = dda_axis(o.z, d.z, self.corner.z, self.cell.1, t_enter, cells_z);
+
+        // 格子里的三角形完全落在格子的 xz 投影内，按 t 顺序遍历格子时第一个命中即最近
+        let mut t_cell = t_enter;
+        let found = loop {
+            let t_next = next_x.min(next_z).min(t_exit);
+            // 光线在本格内的高度范围与格子的高度范围不重叠时跳过
+            let (y0, y1) = (o.y + t_cell * d.y, o.y + t_next * d.y);
+            let corners = [self.index(ix, iz), self.index(ix + 1, iz), self.index(ix, iz + 1), self.index(ix + 1, iz + 1)];
+            let lo = corners.iter().map(|&i| self.points[i].y).fold(f64::INFINITY, f64::min);
+            let hi = corners.iter().map(|&i| self.points[i].y).fold(f64::NEG_INFINITY, f64::max);
+            if y0.min(y1) <= hi && y0.max(y1) >= lo {
+                if let Some(hit) = self.hit_cell(r, ray_t, ix, iz) {
+                    break Some(hit);
+                }
+            }
+            if t_next >= t_exit {
+                break None;
+            }
+            t_cell = t_next;
+            if next_x < next_z {
+                if (step_x < 0 && ix == 0) || (step_x > 0 && ix + 1 == cells_x) {
+                    break None;
+                }
+                ix = (ix as isize + step_x) as usize;
+                next_x += delta_x;
+            } else {
+                if (step_z < 0 && iz == 0) || (step_z > 0 && iz + 1 == cells_z) {
+                    break None;
+                }
+                iz = (iz as isize + step_z) as usize;
+                next_z += delta_z;
+            }
+        };
+        let Some((t, idx, b)) = found else { return false };
+
+        let [p0, p1, p2] = idx.map(|i| self.points[i]);
+        rec.t = t;
+        rec.p = b[0] * p0 + b[1] * p1 + b[2] * p2;
+        // u 沿 +x，v 沿 -z：与高度图同尺寸的颜色图用 ImageTexture 贴上时像素一一对应
+        let (size_x, size_z) = (self.cell.0 * cells_x as f64, self.cell.1 * cells_z as f64);
+        rec.u = (rec.p.x - self.corner.x) / size_x;
+        rec.v = 1.0 - (rec.p.z - self.corner.z) / size_z;
+        // 三角形上位置关于 (u, v) 是线性的，由两条边解出切向量
+        let (e1, e2) = (p1 - p0, p2 - p0);
+        let (du1, dv1) = (e1.x / size_x, -e1.z / size_z);
+        let (du2, dv2) = (e2.x / size_x, -e2.z / size_z);
+        let det = du1 * dv2 - du2 * dv1;
+        (rec.dpdu, rec.dpdv) = if det.abs() > 0.0 {
+            ((dv2 * e1 - dv1 * e2) / det, (du1 * e2 - du2 * e1) / det)
+        } else {
+            (Vec3::default(), Vec3::default())
+        };
+        rec.vertex_color = None;
+        rec.mat = Some(self.mat.clone());
+        // front_face 由几何法线决定；插值的顶点法线同样朝 +y，按 front_face 取向。
+        // 陡坡上两者的夹角可能超过 90°，不能按点积翻转
+        rec.set_face_normal(r, Vec3::unit_vector(Vec3::cross(&e1, &e2)));
+        let n = b[0] * self.normals[idx[0]] + b[1] * self.normals[idx[1]] + b[2] * self.normals[idx[2]];
+        let n = Vec3::unit_vector(n);
+        rec.normal = if rec.front_face { n } else { -n };
+        true
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::material::Lambertian;
+    use crate::rtweekend::random::{random_double, random_double_range};
+    use crate::vec3::Color;
+
+    fn grey() -> Arc<dyn Material + Send + Sync> {
+        Arc::new(Lambertian::from_color(Color::new(0.5, 0.5, 0.5)))
+    }
+
+    // 逐个格子测试所有三角形的最近交点
+    fn brute_force(field: &Heightfield, r: &Ray) -> Option<f64> {
+        let mut best: Option<f64> = None;
+        for iz in 0..field.nz - 1 {
+            for ix in 0..field.nx - 1 {
+                if let Some((t, _, _)) = field.hit_cell(r, Interval::new(0.001, f64::INFINITY), ix, iz) {
+                    best = Some(best.map_or(t, |b| b.min(t)));
+                }
+            }
+        }
+        best
+    }
+
+    #[test]
+    fn grid_dda_matches_brute_force() {
+        let (nx, nz) = (9, 7);
+        let heights: Vec<f64> = (0..nx * nz).map(|_| random_double()).collect();
+        let field = Heightfield::new(&heights, nx, nz, Point3::new(-2.0, 0.0, -1.0), Vec3::new(4.0, 1.5, 3.0), grey());
+        let mut hits = 0;
+        for _ in 0..2000 {
+            let origin = Point3::new(random_double_range(-4.0, 4.0), random_double_range(-1.0, 4.0), random_double_range(-3.0, 4.0));
+            let target = Point3::new(random_double_range(-2.0, 2.0), random_double_range(0.0, 1.5), random_double_range(-1.0, 2.0));
+            let r = Ray::new(origin, target - origin, 0.0);
+            let mut rec = HitRecord::default();
+            let dda = field.hit(&r, Interval::new(0.001, f64::INFINITY), &mut rec).then_some(rec.t);
+            match (dda, brute_force(&field, &r)) {
+                (Some(a), Some(b)) => {
+                    hits += 1;
+                    assert!((a - b).abs() < 1e-9, "{} vs {}", a, b);
+                }
+                (None, None) => {}
+                other => panic!("dda and brute force disagree: {:?}", other),
+            }
+        }
+        assert!(hits > 500);
+    }
+
+    // 沿 x 线性升高的斜坡：插值法线等于平面法线，UV 覆盖整块地形
+    #[test]
+    fn slope_normals_and_uvs() {
+        let (nx, nz) = (5, 4);
+        let heights: Vec<f64> = (0..nx * nz).map(|i| (i % nx) as f64 / (nx - 1) as f64).collect();
+        let field = Heightfield::new(&heights, nx, nz, Point3::new(0.0, 0.0, 0.0), Vec3::new(4.0, 2.0, 3.0), grey());
+        let r = Ray::new(Point3::new(1.0, 5.0, 0.75), Vec3::new(0.0, -1.0, 0.0), 0.0);
+        let mut rec = HitRecord::default();
+        assert!(field.hit(&r, Interval::new(0.001, f64::INFINITY), &mut rec));
+        assert!((rec.p.y - 0.5).abs() < 1e-12);
+        let slope = Vec3::unit_vector(Vec3::new(-0.5, 1.0, 0.0));
+        assert!((rec.normal - slope).length() < 1e-12 && rec.front_face);
+        assert!((rec.u - 0.25).abs() < 1e-12 && (rec.v - 0.75).abs() < 1e-12);
+        assert!((rec.dpdu - Vec3::new(4.0, 2.0, 0.0)).length() < 1e-9);
+        assert!((rec.dpdv - Vec3::new(0.0, 0.0, -3.0)).length() < 1e-9);
+        // 从下方射来的光线命中背面，法线朝下
+        let below = Ray::new(Point3::new(3.0, -1.0, 2.0), Vec3::new(0.0, 1.0, 0.0), 0.0);
+        assert!(field.hit(&below, Interval::new(0.001, f64::INFINITY), &mut rec));
+        assert!(!rec.front_face && rec.normal.y < 0.0);
+    }
+
+    #[test]
+    fn builds_from_images_and_noise() {
+        let path = std::env::temp_dir().join(format!("heightfield_{}.png", std::process::id()));
+        image::GrayImage::from_fn(3, 2, |x, y| image::Luma([(x * 100 + y * 51) as u8])).save(&path).unwrap();
+        let field = Heightfield::from_image(path.to_str().unwrap(), Point3::new(0.0, 0.0, 0.0), Vec3::new(2.0, 255.0, 1.0), grey());
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!((field.nx, field.nz), (3, 2));
+        // 图像第 y 行对应 z = y，灰度值即高度
+        assert!((field.points[field.index(2, 1)].y - 251.0).abs() < 1e-9);
+        assert!((field.points[field.index(1, 0)] - Point3::new(1.0, 100.0, 0.0)).length() < 1e-9);
+
+        let noise = Heightfield::from_perlin(&Perlin::new(), 17, 3.0, 5, Point3::new(0.0, -1.0, 0.0), Vec3::new(8.0, 2.0, 8.0), grey());
+        assert!((noise.bounding_box().y.max - 1.0).abs() < 1e-3);
+        assert!(noise.points.iter().all(|p| p.y >= -1.0 && p.y <= 1.0 + 1e-12));
+    }
+}
@@ -0,0 +1,429 @@
+// 非均匀参与介质：密度由 3D 纹理、Perlin 湍流或体素网格给出
+// 自由程用 delta tracking（Woodcock 跟踪）无偏采样，透射率用 ratio tracking 估计；
+// 边界可以是非凸的封闭物体，光线在内部的每一段分别跟踪。
+// 单次散射模式在散射点对场景光源做下一事件估计，阴影光线穿过场景，介质自身的透射率由 ratio tracking 给出
+use std::sync::Arc;
+use rand::thread_rng;
+use crate::aabb::Aabb;
+use crate::constant_medium::boundary_spans;
+use crate::hittable::{HitRecord, Hittable};
+use crate::interval::Interval;
+use crate::material::{Isotropic, Material, ScatterRecord};
+use crate::perlin::Perlin;
+use crate::phase::{PhaseFunction, PhaseMaterial};
+use crate::ray::Ray;
+use crate::rtweekend::random::random_double;
+use crate::spectrum;
+use crate::texture::Texture;
+use crate::vec3::{Color, Point3, Vec3};
+
+/// 规则体素网格，values[(iz * ny + iy) * nx + ix]，在 bbox 内三线性插值，bbox 外密度为 0
+pub struct VoxelGrid {
+    nx: usize,
+    ny: usize,
+    nz: usize,
+    values: Vec<f64>,
+    bbox: Aabb,
+}
+
+impl VoxelGrid {
+    pub fn new(values: Vec<f64>, nx: usize, ny: usize, nz: usize, bbox: Aabb) -> Self {
+        assert!(nx >= 1 && ny >= 1 && nz >= 1 && values.len() >= nx * ny * nz, "voxel grid needs nx * ny * nz values");
+        Self { nx, ny, nz, values, bbox }
+    }
+
+    fn value(&self, ix: usize, iy: usize, iz: usize) -> f64 {
+        self.values[(iz * self.ny + iy) * self.nx + ix]
+    }
+
+    // 体素值位于体素中心
+    pub fn sample(&self, p: &Point3) -> f64 {
+        let axis = |a: &Interval, x: f64, n: usize| -> Option<(usize, usize, f64)> {
+            if x < a.min || x > a.max {
+                return None;
+            }
+            let g = ((x - a.min) / (a.max - a.min) * n as f64 - 0.5).clamp(0.0, (n - 1) as f64);
+            let i = (g.floor() as usize).min(n - 1);
+            Some((i, (i + 1).min(n - 1), g - i as f64))
+        };
+        let (Some((x0, x1, fx)), Some((y0, y1, fy)), Some((z0, z1, fz))) =
+            (axis(&self.bbox.x, p.x, self.nx), axis(&self.bbox.y, p.y, self.ny), axis(&self.bbox.z, p.z, self.nz))
+        else {
+            return 0.0;
+        };
+        let lerp = |t: f64, a: f64, b: f64| a + t * (b - a);
+        let c0 = lerp(fy, lerp(fx, self.value(x0, y0, z0), self.value(x1, y0, z0)), lerp(fx, self.value(x0, y1, z0), self.value(x1, y1, z0)));
+        let c1 = lerp(fy, lerp(fx, self.value(x0, y0, z1), self.value(x1, y0, z1)), lerp(fx, self.value(x0, y1, z1), self.value(x1, y1, z1)));
+        lerp(fz, c0, c1)
+    }
+
+    pub fn max_value(&self) -> f64 {
+        self.values.iter().cloned().fold(0.0, f64::max)
+    }
+}
+
+/// 密度场，取值乘以 HeterogeneousMedium 的 scale 后为消光系数
+pub enum Density {
+    // 取纹理在 p 处颜色的亮度；纹理的取值范围未知，max 由调用方给出，必须不小于亮度的最大值
+    Texture { tex: Arc<dyn Texture + Send + Sync>, max: f64 },
+    Perlin { noise: Perlin, frequency: f64, depth: i32 },
+    Grid(VoxelGrid),
+}
+
+impl Density {
+    pub fn from_texture(tex: Arc<dyn Texture + Send + Sync>, max: f64) -> Self {
+        Density::Texture { tex, max }
+    }
+
+    pub fn at(&self, p: &Point3) -> f64 {
+        match self {
+            Density::Texture { tex, .. } => {
+                let c = tex.value(0.0, 0.0, p);
+                (0.2126 * c.x + 0.7152 * c.y + 0.0722 * c.z).max(0.0)
+            }
+            Density::Perlin { noise, frequency, depth } => noise.turb(&(*frequency * *p), *depth),
+            Density::Grid(grid) => grid.sample(p).max(0.0),
+        }
+    }
+
+    // 密度的保证上界：体素网格取最大体素值（三线性插值不会超过它），
+    // Perlin 湍流取解析上界，纹理取调用方给出的值
+    pub(crate) fn bound(&self) -> f64 {
+        match self {
+            Density::Texture { max, .. } => *max,
+            Density::Perlin { noise, depth, .. } => noise.turb_bound(*depth),
+            Density::Grid(grid) => grid.max_value(),
+        }
+    }
+}
+
+// 阴影光线上跳过介质自身碰撞的次数上限；超过时视为被遮挡
+const MAX_SHADOW_COLLISIONS: usize = 256;
+
+// 沿阴影光线穿过场景，跳过 medium 自身的碰撞（其透射率由调用方用 ratio tracking 计入），
+// 返回第一个其他物体朝光线的自发光（按路径的通道）与其 t；未命中时为 None，被不发光的物体遮挡时自发光为 0
+pub(crate) fn visible_emission(world: &dyn Hittable, shadow: &Ray, medium: &dyn Material) -> Option<(Vec3, f64)> {
+    let mut t_min = 0.001;
+    for _ in 0..MAX_SHADOW_COLLISIONS {
+        let mut rec = HitRecord::default();
+        if !world.hit(shadow, Interval::new(t_min, f64::INFINITY), &mut rec) {
+            return None;
+        }
+        let mat = rec.mat.clone()?;
+        if std::ptr::addr_eq(Arc::as_ptr(&mat), medium as *const dyn Material) {
+            t_min = rec.t;
+            continue;
+        }
+        let emitted = match shadow.wavelengths {
+            Some(ref wavelengths) => mat.emitted_at_wavelengths(shadow, &rec, wavelengths),
+            None => mat.emitted(shadow, &rec),
+        };
+        return Some((emitted, rec.t));
+    }
+    None
+}
+
+pub(crate) type TransmittanceFn = Arc<dyn Fn(&Ray, Interval) -> f64 + Send + Sync>;
+
+// 单次散射事件：按场景光源列表采样方向做下一事件估计后终止路径，返回
+// 反照率 × 相位函数 × 光源辐亮度 × 阴影光线透射率 / pdf；其他物体的遮挡由穿过场景的阴影光线给出
+pub(crate) struct LightScatter {
+    pub phase_function: Arc<dyn Material + Send + Sync>, // 提供反照率（attenuation）与相位函数（scattering_pdf）
+    pub transmittance: TransmittanceFn,
+}
+
+impl Material for LightScatter {
+    fn direct_light(&self, r_in: &Ray, rec: &HitRecord, world: &dyn Hittable, lights: &dyn Hittable) -> Vec3 {
+        let direction = lights.random(&rec.p);
+        let pdf = lights.pdf_value(&rec.p, &direction);
+        let mut srec = ScatterRecord::new();
+        if pdf <= 0.0 || !self.phase_function.scatter(r_in, rec, &mut srec, &mut thread_rng()) {
+            return Vec3::default();
+        }
+        let shadow = r_in.spawn(rec.p, direction);
+        let Some((radiance, t)) = visible_emission(world, &shadow, self) else { return Vec3::default() };
+        let phase = self.phase_function.scattering_pdf(r_in, rec, &shadow);
+        let tr = (self.transmittance)(&shadow, Interval::new(1e-6, t));
+        let albedo = match r_in.wavelengths {
+            Some(ref wavelengths) if !srec.spectral => spectrum::uplift(&srec.attenuation, wavelengths),
+            _ => srec.attenuation,
+        };
+        albedo * radiance * (phase * tr / pdf)
+    }
+}
+
+// 介质的几何与密度，由介质与单次散射材质共享
+struct Volume {
+    boundary: Arc<dyn Hittable + Send + Sync>,
+    density: Density,
+    scale: f64,
+    majorant: f64, // 消光系数的上界 σ̄
+}
+
+impl Volume {
+    fn sigma_t(&self, p: &Point3) -> f64 {
+        self.scale * self.density.at(p)
+    }
+
+    // 在一段 [t0, t1] 内按上界 σ̄ 做指数步进，对每个暂定碰撞点调用 visit(t, σt / σ̄)；
+    // visit 返回 false 时停止并返回该点的 t
+    fn track(&self, r: &Ray, t0: f64, t1: f64, mut visit: impl FnMut(f64, f64) -> bool) -> Option<f64> {
+        let step = 1.0 / (self.majorant * r.direction.length());
+        let mut t = t0;
+        loop {
+            t -= (1.0 - random_double()).ln() * step;
+            if t >= t1 {
+                return None;
+            }
+            if !visit(t, self.sigma_t(&r.at(t)) / self.majorant) {
+                return Some(t);
+            }
+        }
+    }
+
+    // ratio tracking：每个暂定碰撞点乘以 1 - σt / σ̄，无偏且不会提前终止为 0
+    fn transmittance(&self, r: &Ray, ray_t: Interval) -> f64 {
+        if self.majorant <= 0.0 {
+            return 1.0;
+        }
+        let mut tr = 1.0;
+        for (t0, t1) in boundary_spans(self.boundary.as_ref(), r, ray_t) {
+            self.track(r, t0, t1, |_, ratio| {
+                tr *= 1.0 - ratio;
+                true
+            });
+        }
+        tr
+    }
+}
+
+pub struct HeterogeneousMedium {
+    volume: Arc<Volume>,
+    phase_function: Arc<dyn Material + Send + Sync>,
+    single_scattering: bool,
+    event: Arc<dyn Material + Send + Sync>, // 真实碰撞处的材质：phase_function 或单次散射估计
+}
+
+impl HeterogeneousMedium {
+    /// 消光系数为 scale × density(p)；上界取 Density 的保证上界
+    pub fn new(
+        boundary: Arc<dyn Hittable + Send + Sync>,
+        density: Density,
+        scale: f64,
+        phase_function: Arc<dyn Material + Send + Sync>,
+    ) -> Self {
+        let majorant = scale * density.bound();
+        Self {
+            volume: Arc::new(Volume { boundary, density, scale, majorant }),
+            phase_function: phase_function.clone(),
+            single_scattering: false,
+            event: phase_function,
+        }
+    }
+
+    pub fn new_with_color(boundary: Arc<dyn Hittable + Send + Sync>, density: Density, scale: f64, albedo: Color) -> Self {
+        Self::new(boundary, density, scale, Arc::new(Isotropic::new_with_color(albedo)))
+    }
+
+    pub fn new_with_texture(
+        boundary: Arc<dyn Hittable + Send + Sync>,
+        density: Density,
+        scale: f64,
+        tex: Arc<dyn Texture + Send + Sync>,
+    ) -> Self {
+        Self::new(boundary, density, scale, Arc::new(Isotropic::new_with_texture(tex)))
+    }
+
+    pub fn new_with_phase(
+        boundary: Arc<dyn Hittable + Send + Sync>,
+        density: Density,
+        scale: f64,
+        phase: Arc<dyn PhaseFunction + Send + Sync>,
+        albedo: Color,
+    ) -> Self {
+        Self::new(boundary, density, scale, Arc::new(PhaseMaterial::new_with_color(phase, albedo)))
+    }
+
+    /// 单次散射：散射点对渲染时传入的光源列表做下一事件估计后终止路径，噪声小但不含多次散射
+    pub fn with_single_scattering(mut self) -> Self {
+        self.single_scattering = true;
+        self.rebuild_event();
+        self
+    }
+
+    fn rebuild_event(&mut self) {
+        self.event = if self.single_scattering {
+            let volume = self.volume.clone();
+            Arc::new(LightScatter {
+                phase_function: self.phase_function.clone(),
+                transmittance: Arc::new(move |r: &Ray, ray_t: Interval| volume.transmittance(r, ray_t)),
+            })
+        } else {
+            self.phase_function.clone()
+        };
+    }
+}
+
+impl Hittable for HeterogeneousMedium {
+    // delta tracking：以概率 σt / σ̄ 接受暂定碰撞为真实碰撞，否则为虚碰撞继续前进
+    fn hit(&self, r: &Ray, ray_t: Interval, rec: &mut HitRecord) -> bool {
+        let volume = &self.volume;
+        if volume.majorant <= 0.0 {
+            return false;
+        }
+        let hit_t = boundary_spans(volume.boundary.as_ref(), r, ray_t)
+            .into_iter()
+            .find_map(|(t0, t1)| volume.track(r, t0, t1, |_, ratio| random_double() >= ratio));
+        let Some(t) = hit_t else { return false };
+
+        rec.t = t;
+        rec.p = r.at(t);
+        rec.normal = Vec3::new(1.0, 0.0, 0.0); // 任意
+        rec.front_face = true;                  // 任意
+        rec.mat = Some(self.event.clone());
+        rec.dpdu = Vec3::default();
+        rec.dpdv = Vec3::default();
+        rec.vertex_color = None;
+        true
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.volume.boundary.bounding_box()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hittable::HittableList;
+    use crate::material::{DiffuseLight, Lambertian};
+    use crate::rtweekend::PI;
+    use crate::sphere::Sphere;
+
+    fn ball(center: Point3, radius: f64) -> Arc<dyn Hittable + Send + Sync> {
+        Arc::new(Sphere::new(center, radius, None))
+    }
+
+    // 覆盖 [-4, 4]³ 的 2 × 1 × 1 网格，两个体素值 0 与 1：密度在 x ∈ [-2, 2] 上从 0 线性增到 1
+    fn ramp() -> VoxelGrid {
+        VoxelGrid::new(vec![0.0, 1.0], 2, 1, 1, Aabb::from_points(Point3::new(-4.0, -4.0, -4.0), Point3::new(4.0, 4.0, 4.0)))
+    }
+
+    fn along_x() -> Ray {
+        Ray::new(Point3::new(-5.0, 0.0, 0.0), Vec3::new(1.0, 0.0, 0.0), 0.0)
+    }
+
+    #[test]
+    fn voxel_grid_interpolates_between_voxel_centres() {
+        let grid = ramp();
+        for (x, expected) in [(-2.0, 0.0), (-3.5, 0.0), (0.0, 0.5), (1.0, 0.75), (2.0, 1.0), (3.9, 1.0)] {
+            assert!((grid.sample(&Point3::new(x, 0.3, -1.0)) - expected).abs() < 1e-12, "x = {x}");
+        }
+        assert_eq!(grid.sample(&Point3::new(4.5, 0.0, 0.0)), 0.0);
+        assert_eq!(grid.max_value(), 1.0);
+    }
+
+    #[test]
+    fn perlin_turbulence_stays_below_its_bound() {
+        let density = Density::Perlin { noise: Perlin::new(), frequency: 1.5, depth: 5 };
+        let bound = density.bound();
+        let max = (0..20000).map(|_| density.at(&(5.0 * Vec3::random_unit_vector()))).fold(0.0, f64::max);
+        assert!(max <= bound && max > 0.0);
+    }
+
+    // 均匀密度时 delta tracking 的碰撞概率为 1 - exp(-σ L)，与方向向量的长度无关
+    #[test]
+    fn delta_tracking_samples_the_exponential_free_path() {
+        let unit = VoxelGrid::new(vec![1.0], 1, 1, 1, Aabb::from_points(Point3::new(-2.0, -2.0, -2.0), Point3::new(2.0, 2.0, 2.0)));
+        let medium = HeterogeneousMedium::new_with_color(ball(Point3::new(0.0, 0.0, 0.0), 1.0), Density::Grid(unit), 0.7, Color::new(1.0, 1.0, 1.0));
+        let expected = 1.0 - (-0.7f64 * 2.0).exp();
+        for speed in [1.0, 2.5] {
+            let r = Ray::new(Point3::new(-5.0, 0.0, 0.0), Vec3::new(speed, 0.0, 0.0), 0.0);
+            let n = 40000;
+            let mut hits = 0;
+            for _ in 0..n {
+                let mut rec = HitRecord::default();
+                if medium.hit(&r, Interval::new(0.001, f64::INFINITY), &mut rec) {
+                    hits += 1;
+                    assert!(rec.p.length() <= 1.0 + 1e-9);
+                }
+            }
+            assert!((hits as f64 / n as f64 - expected).abs() < 0.01);
+        }
+    }
+
+    // 两个分离的球组成非凸边界：光学厚度 1.5 × (0.25 + 0.75)，ratio tracking 与 delta tracking 都应得到 exp(-1.5)
+    #[test]
+    fn tracking_covers_every_span_of_a_non_convex_boundary() {
+        let mut pair = HittableList::new();
+        pair.add(ball(Point3::new(-1.0, 0.0, 0.0), 0.5));
+        pair.add(ball(Point3::new(1.0, 0.0, 0.0), 0.5));
+        let medium = HeterogeneousMedium::new_with_color(Arc::new(pair), Density::Grid(ramp()), 1.5, Color::new(1.0, 1.0, 1.0));
+        let expected = (-1.5f64).exp();
+        let n = 40000;
+        let ratio = (0..n).map(|_| medium.volume.transmittance(&along_x(), Interval::new(0.001, f64::INFINITY))).sum::<f64>() / n as f64;
+        assert!((ratio - expected).abs() < 0.01);
+        let passed = (0..n).filter(|_| !medium.hit(&along_x(), Interval::new(0.001, f64::INFINITY), &mut HitRecord::default())).count();
+        assert!((passed as f64 / n as f64 - expected).abs() < 0.01);
+        // 第一个球之后截断的光线只经过一段
+        let first = (0..n).map(|_| medium.volume.transmittance(&along_x(), Interval::new(0.001, 5.0))).sum::<f64>() / n as f64;
+        assert!((first - (-1.5f64 * 0.25).exp()).abs() < 0.01);
+    }
+
+    // 球形光源 (0, 10, 0)、半径 2、辐亮度 3：world 中发光，lights 中只作采样
+    fn lit_scene(extra: Option<Arc<dyn Hittable + Send + Sync>>) -> (HittableList, HittableList) {
+        let mut world = HittableList::new();
+        world.add(Arc::new(Sphere::new(Point3::new(0.0, 10.0, 0.0), 2.0, Some(Arc::new(DiffuseLight::from_color(Color::new(3.0, 3.0, 3.0)))))));
+        if let Some(object) = extra {
+            world.add(object);
+        }
+        let mut lights = HittableList::new();
+        lights.add(ball(Point3::new(0.0, 10.0, 0.0), 2.0));
+        (world, lights)
+    }
+
+    // 各向同性、原点处的单次散射估计恰为 反照率 × 辐亮度 × 光源立体角 / 4π
+    fn cone_estimate() -> f64 {
+        let solid_angle = 2.0 * PI * (1.0 - (1.0 - 0.04f64).sqrt());
+        0.5 * 3.0 * solid_angle / (4.0 * PI)
+    }
+
+    fn scatter_at_origin() -> (Ray, HitRecord) {
+        let mut rec = HitRecord::default();
+        rec.p = Point3::new(0.0, 0.0, 0.0);
+        (Ray::new(Point3::new(-1.0, 0.0, 0.0), Vec3::new(1.0, 0.0, 0.0), 0.0), rec)
+    }
+
+    #[test]
+    fn single_scattering_samples_the_scene_lights() {
+        let scatter = LightScatter {
+            phase_function: Arc::new(Isotropic::new_with_color(Color::new(0.5, 0.5, 0.5))),
+            transmittance: Arc::new(|_: &Ray, _: Interval| 1.0),
+        };
+        let (r, rec) = scatter_at_origin();
+        let (world, lights) = lit_scene(None);
+        for _ in 0..10 {
+            assert!((scatter.direct_light(&r, &rec, &world, &lights).y - cone_estimate()).abs() < 1e-9);
+        }
+        // 没有光源列表时不做估计；光源被其他物体挡住时为 0
+        assert_eq!(scatter.direct_light(&r, &rec, &world, &HittableList::new()).length(), 0.0);
+        let (world, lights) = lit_scene(Some(Arc::new(Sphere::new(Point3::new(0.0, 5.0, 0.0), 3.0, Some(Arc::new(Lambertian::from_color(Color::new(1.0, 1.0, 1.0))))))));
+        assert_eq!(scatter.direct_light(&r, &rec, &world, &lights).length(), 0.0);
+    }
+
+    // 阴影光线穿过场景时跳过介质自身的碰撞，自身的透射率 exp(-σ × 1) 由 ratio tracking 给出
+    #[test]
+    fn single_scattering_sees_through_its_own_medium() {
+        let unit = VoxelGrid::new(vec![1.0], 1, 1, 1, Aabb::from_points(Point3::new(-2.0, -2.0, -2.0), Point3::new(2.0, 2.0, 2.0)));
+        let medium = Arc::new(
+            HeterogeneousMedium::new_with_color(ball(Point3::new(0.0, 0.0, 0.0), 1.0), Density::Grid(unit), 0.7, Color::new(0.5, 0.5, 0.5))
+                .with_single_scattering(),
+        );
+        let event = medium.event.clone();
+        let (world, lights) = lit_scene(Some(medium));
+        let (r, rec) = scatter_at_origin();
+        let n = 20000;
+        let mean = (0..n).map(|_| event.direct_light(&r, &rec, &world, &lights).y).sum::<f64>() / n as f64;
+        assert!((mean / cone_estimate() - (-0.7f64).exp()).abs() < 0.01);
+    }
+}
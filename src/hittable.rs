@@ -2,9 +2,10 @@
 use std::sync::Arc;
 use crate::aabb::*;
 use crate::ray::Ray;
-use crate::vec3::{Point3, Vec3};
+use crate::vec3::{Color, Point3, Vec3};
 use crate::material::Material;
 use crate::interval::Interval;
+use crate::matrix::Mat4;
 
 #[derive(Clone)]
 pub struct HitRecord {
@@ -15,6 +16,9 @@ pub struct HitRecord {
     pub u: f64,
     pub v: f64,
     pub front_face: bool,
+    pub dpdu: Vec3, // 表面对 (u, v) 的偏导（切线），未知时为零向量
+    pub dpdv: Vec3,
+    pub vertex_color: Option<Color>, // 网格顶点颜色插值，供 VertexColorTexture 使用
 }
 
 impl HitRecord {
@@ -27,6 +31,9 @@ impl HitRecord {
             u: 0.0,
             v: 0.0,
             front_face: false,
+            dpdu: Vec3::default(),
+            dpdv: Vec3::default(),
+            vertex_color: None,
         }
     }
 
@@ -52,6 +59,28 @@ pub trait Hittable: Send + Sync {
     fn random(&self, _origin : &Point3) -> Vec3 {
         Vec3::new(1.0, 0.0, 0.0)
     }
+
+    /// 沿光线的所有表面交点，按 t 升序追加到 hits，front_face 区分进入与离开；供 CSG 使用。
+    /// 默认实现反复调用 hit 并逐次前移 t_min，适用于任意封闭物体
+    fn hit_all(&self, r: &Ray, ray_t: Interval, hits: &mut Vec<HitRecord>) {
+        let mut t_min = ray_t.min;
+        for _ in 0..MAX_HITS_PER_RAY {
+            let mut rec = HitRecord::default();
+            if !self.hit(r, Interval::new(t_min, ray_t.max), &mut rec) {
+                break;
+            }
+            t_min = next_t(rec.t);
+            hits.push(rec);
+        }
+    }
+}
+
+// hit_all 默认实现的交点数上限，防止退化几何导致死循环
+const MAX_HITS_PER_RAY: usize = 64;
+
+/// 严格大于 t 的下一个查询起点；有的图元用闭区间判断，必须前移才不会重复命中
+pub(crate) fn next_t(t: f64) -> f64 {
+    t + 1e-9 * t.abs().max(1.0)
 }
 
 pub struct HittableList { pub objects: Vec<Arc<dyn Hittable + Send + Sync>>,pub bbox: Aabb,}
@@ -182,6 +211,15 @@ impl Hittable for Translate {
     fn bounding_box(&self) -> Aabb {
         self.bbox
     }
+
+    // 平移不改变方向，立体角密度不变
+    fn pdf_value(&self, origin: &Point3, direction: &Vec3) -> f64 {
+        self.object.pdf_value(&(*origin - self.offset), direction)
+    }
+
+    fn random(&self, origin: &Point3) -> Vec3 {
+        self.object.random(&(*origin - self.offset))
+    }
 }
 
 pub struct RotateY {
@@ -227,6 +265,15 @@ impl RotateY {
 
         Self { object, sin_theta, cos_theta, bbox }
     }
+
+    // 世界空间 -> 物体空间（点与方向相同）
+    fn to_object(&self, v: &Vec3) -> Vec3 {
+        Vec3::new(
+            self.cos_theta * v.x - self.sin_theta * v.z,
+            v.y,
+            self.sin_theta * v.x + self.cos_theta * v.z,
+        )
+    }
 }
 
 impl Hittable for RotateY {
@@ -259,14 +306,16 @@ impl Hittable for RotateY {
             rec.p.y,
             -self.sin_theta * rec.p.x + self.cos_theta * rec.p.z,
         );
-        let normal = Vec3::new(
-            self.cos_theta * rec.normal.x + self.sin_theta * rec.normal.z,
-            rec.normal.y,
-            -self.sin_theta * rec.normal.x + self.cos_theta * rec.normal.z,
+        let to_world = |v: &Vec3| Vec3::new(
+            self.cos_theta * v.x + self.sin_theta * v.z,
+            v.y,
+            -self.sin_theta * v.x + self.cos_theta * v.z,
         );
 
         rec.p = p;
-        rec.normal = normal;
+        rec.normal = to_world(&rec.normal);
+        rec.dpdu = to_world(&rec.dpdu);
+        rec.dpdv = to_world(&rec.dpdv);
 
         true
     }
@@ -274,6 +323,169 @@ impl Hittable for RotateY {
     fn bounding_box(&self) -> Aabb {
         self.bbox
     }
+
+    // 旋转保持立体角，只需把查询转到物体空间
+    fn pdf_value(&self, origin: &Point3, direction: &Vec3) -> f64 {
+        self.object.pdf_value(&self.to_object(origin), &self.to_object(direction))
+    }
+
+    fn random(&self, origin: &Point3) -> Vec3 {
+        let v = self.object.random(&self.to_object(origin));
+        Vec3::new(
+            self.cos_theta * v.x + self.sin_theta * v.z,
+            v.y,
+            -self.sin_theta * v.x + self.cos_theta * v.z,
+        )
+    }
+}
+
+/// 任意仿射变换（旋转、非均匀缩放、剪切、镜像），缓存矩阵及其逆
+pub struct Transform {
+    object: Arc<dyn Hittable + Send + Sync>,
+    to_world: Mat4,
+    to_object: Mat4,
+    bbox: Aabb,
+}
+
+impl Transform {
+    /// to_world 为物体空间到世界空间的矩阵；不可逆时返回 None
+    pub fn new(object: Arc<dyn Hittable + Send + Sync>, to_world: Mat4) -> Option<Self> {
+        let to_object = to_world.inverse()?;
+        let bbox = transform_bbox(&object.bounding_box(), &to_world);
+        Some(Self { object, to_world, to_object, bbox })
+    }
+}
+
+impl Hittable for Transform {
+    fn hit(
+        &self,
+        r: &Ray,
+        ray_t: Interval,
+        rec: &mut HitRecord,
+    ) -> bool {
+        hit_transformed(self.object.as_ref(), &self.to_world, &self.to_object, r, ray_t, rec)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+
+    fn pdf_value(&self, origin: &Point3, direction: &Vec3) -> f64 {
+        pdf_value_transformed(self.object.as_ref(), &self.to_object, origin, direction)
+    }
+
+    fn random(&self, origin: &Point3) -> Vec3 {
+        let local_dir = self.object.random(&self.to_object.transform_point(origin));
+        self.to_world.transform_vector(&local_dir)
+    }
+}
+
+// 以下供 Transform 与随时间变化的变换共用
+
+/// 变换包围盒的 8 个角点，取新的轴对齐包围盒
+pub(crate) fn transform_bbox(bbox: &Aabb, m: &Mat4) -> Aabb {
+    let mut min = Point3::new(f64::INFINITY, f64::INFINITY, f64::INFINITY);
+    let mut max = Point3::new(f64::NEG_INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY);
+    for i in 0..8 {
+        let corner = Point3::new(
+            if i & 1 == 0 { bbox.x.min } else { bbox.x.max },
+            if i & 2 == 0 { bbox.y.min } else { bbox.y.max },
+            if i & 4 == 0 { bbox.z.min } else { bbox.z.max },
+        );
+        let p = m.transform_point(&corner);
+        min = Point3::new(min.x.min(p.x), min.y.min(p.y), min.z.min(p.z));
+        max = Point3::new(max.x.max(p.x), max.y.max(p.y), max.z.max(p.z));
+    }
+    Aabb::from_points(min, max)
+}
+
+pub(crate) fn hit_transformed(
+    object: &dyn Hittable,
+    to_world: &Mat4,
+    to_object: &Mat4,
+    r: &Ray,
+    ray_t: Interval,
+    rec: &mut HitRecord,
+) -> bool {
+    // 物体空间方向不归一化，t 在两个空间中一致
+    let local_r = r.spawn(to_object.transform_point(&r.origin), to_object.transform_vector(&r.direction));
+    if !object.hit(&local_r, ray_t, rec) {
+        return false;
+    }
+
+    // 逆转置保持法线与光线方向点积的符号，front_face 无需重算
+    rec.p = to_world.transform_point(&rec.p);
+    rec.normal = Vec3::unit_vector(to_object.transform_normal_by_inverse(&rec.normal));
+    rec.dpdu = to_world.transform_vector(&rec.dpdu);
+    rec.dpdv = to_world.transform_vector(&rec.dpdv);
+
+    true
+}
+
+// 物体空间方向 w' = A w / |A w|（A 为逆矩阵线性部分），
+// 立体角雅可比 dw'/dw = |det A| / |A w|^3
+pub(crate) fn pdf_value_transformed(object: &dyn Hittable, to_object: &Mat4, origin: &Point3, direction: &Vec3) -> f64 {
+    let local_dir = to_object.transform_vector(&Vec3::unit_vector(*direction));
+    let len = local_dir.length();
+    if len == 0.0 {
+        return 0.0;
+    }
+    let jacobian = to_object.determinant3().abs() / (len * len * len);
+    object.pdf_value(&to_object.transform_point(origin), &local_dir) * jacobian
+}
+
+/// 按面积均匀采样的光源在方向 direction 上的立体角密度。
+/// 非凸或封闭曲面可能在同一方向上有多个交点，采样点落在其中任一处都会产生该方向，故逐个求和
+pub(crate) fn area_pdf_value(shape: &dyn Hittable, area: f64, origin: &Point3, direction: &Vec3) -> f64 {
+    area_pdf_value_with(area, origin, direction, |ray, ray_t| {
+        let mut rec = HitRecord::default();
+        shape.hit(ray, ray_t, &mut rec).then_some((rec.t, rec.normal))
+    })
+}
+
+/// 同 area_pdf_value，交点由 next_hit 给出 (t, 几何法线)；供着色法线与几何法线不同的网格使用
+pub(crate) fn area_pdf_value_with(
+    area: f64,
+    origin: &Point3,
+    direction: &Vec3,
+    mut next_hit: impl FnMut(&Ray, Interval) -> Option<(f64, Vec3)>,
+) -> f64 {
+    let length = direction.length();
+    if length == 0.0 || area <= 0.0 {
+        return 0.0;
+    }
+    let ray = Ray::new(*origin, *direction, 0.0);
+    let mut t_min = 0.001;
+    let mut pdf = 0.0;
+    for _ in 0..8 {
+        let Some((t, normal)) = next_hit(&ray, Interval::new(t_min, f64::INFINITY)) else {
+            break;
+        };
+        let distance_squared = t * t * length * length;
+        let cosine = Vec3::dot(direction, &normal).abs() / length;
+        if cosine > 1e-8 {
+            pdf += distance_squared / (cosine * area);
+        }
+        t_min = t * (1.0 + 1e-9) + 1e-9;
+    }
+    pdf
+}
+
+// 光源采样的立体角 PDF 在球面上的积分，应为 1（供各图元的测试使用）。
+// 方向取自均匀球面与 light.random 各半的混合分布，权重 pdf / q ≤ 2，
+// 避免曲面轮廓处 1/cos 的长尾；random 与 pdf_value 不一致时积分会偏离 1
+#[cfg(test)]
+pub(crate) fn pdf_integral(light: &dyn Hittable, origin: &Point3) -> f64 {
+    use crate::rtweekend::random::random_double;
+    let n = 200000;
+    let uniform = 1.0 / (4.0 * std::f64::consts::PI);
+    (0..n)
+        .map(|_| {
+            let direction = if random_double() < 0.5 { Vec3::random_unit_vector() } else { light.random(origin) };
+            let pdf = light.pdf_value(origin, &direction);
+            pdf / (0.5 * uniform + 0.5 * pdf)
+        })
+        .sum::<f64>() / n as f64
 }
 
 // 用于将 &dyn Hittable 包装为实现 Hittable trait 的结构体，便于 Arc/Pdf 混合采样
@@ -307,5 +519,57 @@ impl<'a> Hittable for HittableRefWrapper<'a> {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::material::Lambertian;
+    use crate::quad::Quad;
+    use crate::sphere::Sphere;
 
+    fn grey() -> Arc<dyn Material + Send + Sync> {
+        Arc::new(Lambertian::from_color(Color::new(0.5, 0.5, 0.5)))
+    }
 
+    // 非均匀缩放的球即椭球 x²/4 + y² + z² = 1，法线 ∝ (x/4, y, z)
+    #[test]
+    fn transformed_hits_use_inverse_transpose_normals() {
+        let sphere = Arc::new(Sphere::new(Point3::new(0.0, 0.0, 0.0), 1.0, Some(grey())));
+        let ellipsoid = Transform::new(sphere, Mat4::scale(Vec3::new(2.0, 1.0, 1.0))).unwrap();
+        let direction = Vec3::new(-1.0, -0.4, 0.0);
+        let r = Ray::new(Point3::new(5.0, 2.0, 0.0), direction, 0.0);
+        let mut rec = HitRecord::default();
+        assert!(ellipsoid.hit(&r, Interval::new(0.001, f64::INFINITY), &mut rec));
+        assert!((rec.p.x * rec.p.x / 4.0 + rec.p.y * rec.p.y - 1.0).abs() < 1e-9);
+        let expected = Vec3::unit_vector(Vec3::new(rec.p.x / 4.0, rec.p.y, 0.0));
+        assert!((rec.normal - expected).length() < 1e-9);
+        assert!(rec.front_face);
+        assert!((r.at(rec.t) - rec.p).length() < 1e-9);
+    }
+
+    #[test]
+    fn bounding_box_covers_the_rotated_object() {
+        let ball = Arc::new(Sphere::new(Point3::new(0.0, 0.0, 0.0), 1.0, Some(grey())));
+        let moved = Transform::new(ball, Mat4::translate(Vec3::new(0.0, 3.0, 0.0)) * Mat4::rotate_y(45.0)).unwrap();
+        let bbox = moved.bounding_box();
+        let half = 2.0f64.sqrt();
+        assert!((bbox.x.max - half).abs() < 1e-9 && (bbox.y.min - 2.0).abs() < 1e-9);
+        let flat = Arc::new(Sphere::new(Point3::new(0.0, 0.0, 0.0), 1.0, Some(grey())));
+        assert!(Transform::new(flat, Mat4::scale(Vec3::new(1.0, 0.0, 1.0))).is_none());
+    }
+
+    // 变换后的光源与直接在世界空间构造的同一光源给出相同的采样密度
+    #[test]
+    fn light_sampling_passes_through_the_transform() {
+        let local = Arc::new(Quad::new(Point3::new(-1.0, 0.0, -1.0), Vec3::new(2.0, 0.0, 0.0), Vec3::new(0.0, 0.0, 2.0), grey()));
+        let to_world = Mat4::translate(Vec3::new(0.0, 3.0, 0.0)) * Mat4::rotate_x(30.0) * Mat4::scale(Vec3::new(1.5, 1.0, 0.5));
+        let light = Transform::new(local, to_world).unwrap();
+        let q = to_world.transform_point(&Point3::new(-1.0, 0.0, -1.0));
+        let reference = Quad::new(q, to_world.transform_vector(&Vec3::new(2.0, 0.0, 0.0)), to_world.transform_vector(&Vec3::new(0.0, 0.0, 2.0)), grey());
+        let origin = Point3::new(0.3, 0.0, 0.2);
+        for _ in 0..200 {
+            let direction = light.random(&origin);
+            let (a, b) = (light.pdf_value(&origin, &direction), reference.pdf_value(&origin, &direction));
+            assert!(a > 0.0 && (a - b).abs() < 1e-9 * b, "{} vs {}", a, b);
+        }
+    }
+}
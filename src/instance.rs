@@ -0,0 +1,165 @@
+// 物体实例化：多个实例共享同一个原型（自带 BVH），每个实例只保存 3x4 逆变换、世界包围盒和可选的材质覆盖
+use std::sync::Arc;
+use crate::aabb::Aabb;
+use crate::bvh::BvhNode;
+use crate::hittable::{transform_bbox, HitRecord, Hittable, HittableList};
+use crate::interval::Interval;
+use crate::material::Material;
+use crate::matrix::{Affine, Mat4};
+use crate::ray::Ray;
+use crate::vec3::{Point3, Vec3};
+
+/// 把一组物体构建为可共享的原型，内部建一次 BVH
+pub fn make_prototype(list: &HittableList) -> Arc<dyn Hittable + Send + Sync> {
+    let mut objects = list.objects.clone();
+    match objects.len() {
+        1 => objects[0].clone(),
+        _ => Arc::new(BvhNode::new_from_list(&mut objects)),
+    }
+}
+
+// 不存正向矩阵：命中点取世界光线上的同一 t，切向量与采样方向用逆矩阵的线性部分反解
+pub struct Instance {
+    prototype: Arc<dyn Hittable + Send + Sync>,
+    to_object: Affine,
+    bbox: Aabb,
+    material: Option<Arc<dyn Material + Send + Sync>>, // 覆盖原型上的所有材质
+}
+
+impl Instance {
+    /// to_world 不可逆或不是仿射变换时返回 None
+    pub fn new(prototype: Arc<dyn Hittable + Send + Sync>, to_world: Mat4) -> Option<Self> {
+        Affine::from_mat4(&to_world)?;
+        // 仿射矩阵的逆仍是仿射的，末行只差舍入误差
+        let inverse = to_world.inverse()?;
+        let to_object = Affine { m: [inverse.m[0], inverse.m[1], inverse.m[2]] };
+        let bbox = transform_bbox(&prototype.bounding_box(), &to_world);
+        Some(Self { prototype, to_object, bbox, material: None })
+    }
+
+    pub fn with_material(mut self, mat: Arc<dyn Material + Send + Sync>) -> Self {
+        self.material = Some(mat);
+        self
+    }
+}
+
+impl Hittable for Instance {
+    fn hit(
+        &self,
+        r: &Ray,
+        ray_t: Interval,
+        rec: &mut HitRecord,
+    ) -> bool {
+        // 物体空间方向不归一化，t 在两个空间中一致
+        let local_r = r.spawn(self.to_object.transform_point(&r.origin), self.to_object.transform_vector(&r.direction));
+        if !self.prototype.hit(&local_r, ray_t, rec) {
+            return false;
+        }
+        rec.p = r.at(rec.t);
+        rec.normal = Vec3::unit_vector(self.to_object.transform_normal_by_inverse(&rec.normal));
+        rec.dpdu = self.to_object.inverse_transform_vector(&rec.dpdu);
+        rec.dpdv = self.to_object.inverse_transform_vector(&rec.dpdv);
+        if let Some(ref mat) = self.material {
+            rec.mat = Some(mat.clone());
+        }
+        true
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+
+    // 同 pdf_value_transformed：立体角雅可比 |det A| / |A w|^3，A 为逆矩阵线性部分
+    fn pdf_value(&self, origin: &Point3, direction: &Vec3) -> f64 {
+        let local_dir = self.to_object.transform_vector(&Vec3::unit_vector(*direction));
+        let len = local_dir.length();
+        if len == 0.0 {
+            return 0.0;
+        }
+        let jacobian = self.to_object.determinant3().abs() / (len * len * len);
+        self.prototype.pdf_value(&self.to_object.transform_point(origin), &local_dir) * jacobian
+    }
+
+    fn random(&self, origin: &Point3) -> Vec3 {
+        let local_dir = self.prototype.random(&self.to_object.transform_point(origin));
+        self.to_object.inverse_transform_vector(&local_dir)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::material::Lambertian;
+    use crate::quad::{make_box, Quad};
+    use crate::sphere::Sphere;
+    use crate::vec3::Color;
+
+    fn lambert(c: f64) -> Arc<dyn Material + Send + Sync> {
+        Arc::new(Lambertian::from_color(Color::new(c, c, c)))
+    }
+
+    fn hit_from_above(object: &dyn Hittable, x: f64, z: f64) -> Option<HitRecord> {
+        let r = Ray::new(Point3::new(x, 2000.0, z), Vec3::new(0.0, -1.0, 0.0), 0.0);
+        let mut rec = HitRecord::default();
+        object.hit(&r, Interval::new(0.001, f64::INFINITY), &mut rec).then_some(rec)
+    }
+
+    #[test]
+    fn material_override_replaces_the_prototype_material() {
+        let base = lambert(0.5);
+        let ball: Arc<dyn Hittable + Send + Sync> = Arc::new(Sphere::new(Point3::new(0.0, 0.0, 0.0), 1.0, Some(base.clone())));
+        let red = lambert(0.9);
+        let plain = Instance::new(ball.clone(), Mat4::identity()).unwrap();
+        let painted = Instance::new(ball, Mat4::identity()).unwrap().with_material(red.clone());
+        assert!(Arc::ptr_eq(hit_from_above(&plain, 0.0, 0.0).unwrap().mat.as_ref().unwrap(), &base));
+        assert!(Arc::ptr_eq(hit_from_above(&painted, 0.0, 0.0).unwrap().mat.as_ref().unwrap(), &red));
+    }
+
+    // 一千个实例只增加引用计数，各自按自己的变换命中同一个原型
+    #[test]
+    fn instances_share_one_prototype() {
+        let single: Arc<dyn Hittable + Send + Sync> = Arc::new(Sphere::new(Point3::new(0.0, 0.0, 0.0), 1.0, Some(lambert(0.5))));
+        let mut list = HittableList::new();
+        list.add(single.clone());
+        assert!(Arc::ptr_eq(&make_prototype(&list), &single));
+
+        let unit_box = make_prototype(&make_box(Point3::new(0.0, 0.0, 0.0), Point3::new(1.0, 1.0, 1.0), lambert(0.5)));
+        let instances: Vec<Instance> = (0..1000)
+            .map(|i| {
+                let height = 1.0 + i as f64;
+                Instance::new(unit_box.clone(), Mat4::translate(Vec3::new(2.0 * i as f64, 0.0, 0.0)) * Mat4::scale(Vec3::new(1.0, height, 1.0))).unwrap()
+            })
+            .collect();
+        assert_eq!(Arc::strong_count(&unit_box), 1001);
+        for i in [0usize, 7, 999] {
+            let x = 2.0 * i as f64 + 0.5;
+            let rec = hit_from_above(&instances[i], x, 0.5).unwrap();
+            assert!((rec.p.y - (1.0 + i as f64)).abs() < 1e-9);
+            assert!((rec.normal - Vec3::new(0.0, 1.0, 0.0)).length() < 1e-9);
+            assert!(hit_from_above(&instances[i], x + 2.0, 0.5).is_none());
+            let bbox = instances[i].bounding_box();
+            assert!(bbox.x.min <= x && x <= bbox.x.max && bbox.x.max < x + 1.0);
+        }
+    }
+
+    // 共享原型的胖指针、3x4 逆矩阵、包围盒与材质覆盖，远小于存两个 4x4 矩阵的 Transform
+    #[test]
+    fn instances_stay_compact() {
+        assert_eq!(std::mem::size_of::<Instance>(), 16 + 96 + 48 + 16);
+        assert!(std::mem::size_of::<Instance>() < std::mem::size_of::<crate::hittable::Transform>());
+    }
+
+    #[test]
+    fn light_sampling_passes_through_the_instance() {
+        let local: Arc<dyn Hittable + Send + Sync> = Arc::new(Quad::new(Point3::new(0.0, 0.0, 0.0), Vec3::new(1.0, 0.0, 0.0), Vec3::new(0.0, 0.0, 1.0), lambert(0.5)));
+        let to_world = Mat4::translate(Vec3::new(-1.0, 4.0, -1.0)) * Mat4::uniform_scale(2.0);
+        let light = Instance::new(local, to_world).unwrap().with_material(lambert(0.9));
+        let reference = Quad::new(Point3::new(-1.0, 4.0, -1.0), Vec3::new(2.0, 0.0, 0.0), Vec3::new(0.0, 0.0, 2.0), lambert(0.5));
+        let origin = Point3::new(0.2, 0.0, -0.3);
+        for _ in 0..200 {
+            let direction = light.random(&origin);
+            let (a, b) = (light.pdf_value(&origin, &direction), reference.pdf_value(&origin, &direction));
+            assert!(a > 0.0 && (a - b).abs() < 1e-9 * b, "{} vs {}", a, b);
+        }
+    }
+}
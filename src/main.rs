@@ -20,6 +20,32 @@ mod quad;
 mod constant_medium;
 mod onb;
 mod pdf;
+mod medium_stack;
+mod spectrum;
+mod subsurface;
+mod thin_film;
+mod measured;
+mod triangle;
+mod obj;
+mod ply;
+mod json;
+mod gltf;
+mod matrix;
+mod instance;
+mod motion;
+mod cylinder;
+mod cone;
+mod torus;
+mod csg;
+mod sdf;
+mod heightfield;
+mod curve;
+mod hair;
+mod subdivision;
+mod heterogeneous_medium;
+mod phase;
+mod chromatic_medium;
+mod atmosphere;
 
 use std::time::Instant;
 use crate::color::write_color;
@@ -31,16 +57,26 @@ use crate::vec3::{Color, Point3,Vec3};
 use crate::interval::Interval;
 use crate::camera::{Camera, RussianRouletteStrategy};
 use crate::sphere::Sphere;
-use crate::hittable::{HitRecord,Hittable,HittableList};
+use crate::hittable::{HitRecord,Hittable,HittableList,Transform};
 use crate::material::{Material,Lambertian,Metal,Dielectric,NumberMaterial};
 use crate::bvh::BvhNode;
 use crate::texture::{Texture, SolidColor, CheckerTexture,NoiseTexture};
 use crate::quad::Quad;
 use crate::quad::make_box;
+use crate::csg::Csg;
+use crate::instance::{make_prototype, Instance};
+use crate::matrix::{Mat4, Quat};
+use crate::motion::{Keyframe, MotionTransform};
  use crate::hittable::{RotateY, Translate};
 use crate::constant_medium::ConstantMedium;
+use crate::atmosphere::Atmosphere;
 use crate::material::DiffuseLight;
 use crate::texture::ImageTexture;
+use crate::triangle::{Triangle, TriangleMesh};
+use crate::obj::load_obj;
+use crate::subdivision::SubdivisionMesh;
+use crate::ply::load_ply;
+use crate::texture::VertexColorTexture;
 
 fn bouncing_spheres() {
     // 创建世界
@@ -267,6 +303,143 @@ fn earth_sphere() {
     writeln!(file, "Earth Render耗时: {:?}", duration).unwrap();
 }
 
+// 土星：行星贴图球体加解析圆环（而非用方块拼出的环）
+fn saturn() {
+    use crate::texture::ImageTexture;
+    use crate::heightfield::Heightfield;
+    use crate::perlin::Perlin;
+    let mut world = HittableList::new();
+    let saturn_texture = Arc::new(ImageTexture::new("input/saturnmap.jpg"));
+    let saturn_surface = Arc::new(Lambertian::from_texture(saturn_texture));
+    world.add(Arc::new(Sphere::new(Point3::new(0.0, 0.0, 0.0), 2.0, Some(saturn_surface))));
+
+    // 环面略微倾斜，内外半径约为行星半径的 1.2 与 2.3 倍
+    let ring_normal = Vec3::new(0.15, 1.0, 0.1);
+    let ring_mat = Arc::new(Lambertian::from_color(Color::new(0.78, 0.70, 0.56)));
+    world.add(Arc::new(Quad::annulus(Point3::new(0.0, 0.0, 0.0), ring_normal, 2.4, 4.6, ring_mat)));
+
+    // 行星下方的噪声地形，网格 DDA 求交，不必展开成三角网格
+    let terrain_mat = Arc::new(Lambertian::from_color(Color::new(0.45, 0.38, 0.30)));
+    world.add(Arc::new(Heightfield::from_perlin(
+        &Perlin::new(), 257, 6.0, 7,
+        Point3::new(-40.0, -6.0, -40.0), Vec3::new(80.0, 3.0, 80.0), terrain_mat,
+    )));
+
+    let mut cam = Camera::new();
+    cam.aspect_ratio = 16.0 / 9.0;
+    cam.image_width = 400;
+    cam.samples_per_pixel = 100;
+    cam.max_depth = 50;
+    cam.vfov = 40.0;
+    cam.lookfrom = Point3::new(0.0, 4.0, 12.0);
+    cam.lookat = Point3::new(0.0, 0.0, 0.0);
+    cam.vup = Vec3::new(0.0, 1.0, 0.0);
+    cam.defocus_angle = 0.0;
+    cam.focus_dist = 10.0;
+    cam.background = Color::new(0.70, 0.80, 1.00);
+    cam.set_russian_roulette_strategy(RussianRouletteStrategy::Conservative);
+
+    let lights = HittableList::new();
+    let (duration, _) = time_it(|| cam.render(&world, &lights, &mut std::io::stdout()).unwrap());
+    let mut file = OpenOptions::new().create(true).append(true).open("output.txt").unwrap();
+    writeln!(file, "Saturn Render耗时: {:?}", duration).unwrap();
+}
+
+// 毛球：球面上随机生长、略微下垂的毛发曲线，使用毛发 BSDF
+fn fur_ball() {
+    use crate::curve::{Curve, CurveSet};
+    use crate::hair::HairMaterial;
+    use std::f64::consts::PI;
+    let mut world = HittableList::new();
+    let center = Point3::new(0.0, 0.0, 0.0);
+    let radius = 1.0;
+    let skin = Arc::new(Lambertian::from_color(Color::new(0.35, 0.22, 0.12)));
+    world.add(Arc::new(Sphere::new(center, radius, Some(skin))));
+
+    let mut curves = Vec::new();
+    for _ in 0..20000 {
+        let n = Vec3::random_unit_vector();
+        let length = random_double_range(0.25, 0.4);
+        let root = center + radius * n;
+        // 沿法线生长，末端受重力下垂并带一点随机弯曲
+        let droop = Vec3::new(0.0, -0.35 * length, 0.0) + 0.1 * length * Vec3::random_unit_vector();
+        let cp = [
+            root,
+            root + length / 3.0 * n,
+            root + 2.0 * length / 3.0 * n + 0.5 * droop,
+            root + length * n + droop,
+        ];
+        curves.push(Curve::flat(cp, 0.008, 0.001));
+    }
+    let hair = Arc::new(HairMaterial::from_melanin(1.3, 0.6, 0.3, 0.3, 2.0));
+    let fur = Arc::new(CurveSet::new(curves, hair));
+    world.add(fur.clone());
+
+    // 胡须：粗而光滑的浅色圆柱曲线，从正面两侧向外弯
+    let mut whiskers = Vec::new();
+    for side in [-1.0, 1.0] {
+        for k in 0..4 {
+            let root = center + radius * Vec3::unit_vector(Vec3::new(0.35 * side, -0.1 - 0.08 * k as f64, 1.0));
+            let out = Vec3::new(side, 0.12 - 0.1 * k as f64, 0.4);
+            let cp = [root, root + 0.4 * out, root + 0.8 * out + Vec3::new(0.0, -0.05, 0.0), root + 1.2 * out + Vec3::new(0.0, -0.15, 0.0)];
+            whiskers.push(Curve::cylinder(cp, 0.014, 0.002));
+        }
+    }
+    let whisker = Arc::new(HairMaterial::from_reflectance(Color::new(0.9, 0.85, 0.7), 0.15, 0.3, 2.0).with_eta(1.6));
+    let whiskers = Arc::new(CurveSet::new(whiskers, whisker));
+    world.add(whiskers.clone());
+
+    let ground_y = -1.4;
+    let ground = Arc::new(Lambertian::from_color(Color::new(0.5, 0.5, 0.5)));
+    world.add(Arc::new(Sphere::new(Point3::new(0.0, ground_y - 1000.0, 0.0), 1000.0, Some(ground))));
+
+    // 草地：定向扁带，叶面法线从根到梢绕叶片扭转
+    let mut blades = Vec::new();
+    for _ in 0..15000 {
+        let (x, z) = (random_double_range(-3.5, 3.5), random_double_range(-2.5, 3.5));
+        let height = random_double_range(0.15, 0.45);
+        let lean = Vec3::new(random_double_range(-0.3, 0.3), 0.0, random_double_range(-0.3, 0.3)) * height;
+        let root = Point3::new(x, ground_y, z);
+        let cp = [
+            root,
+            root + Vec3::new(0.0, height / 3.0, 0.0),
+            root + Vec3::new(0.0, 2.0 * height / 3.0, 0.0) + 0.4 * lean,
+            root + Vec3::new(0.0, height, 0.0) + lean,
+        ];
+        let facing = random_double_range(0.0, 2.0 * PI);
+        let twist = facing + random_double_range(-1.0, 1.0);
+        let n0 = Vec3::new(facing.cos(), 0.0, facing.sin());
+        let n1 = Vec3::new(twist.cos(), 0.0, twist.sin());
+        blades.push(Curve::ribbon(cp, 0.03, 0.004, n0, n1));
+    }
+    let grass = Arc::new(Lambertian::from_color(Color::new(0.18, 0.4, 0.08)));
+    let blades = Arc::new(CurveSet::new(blades, grass));
+    world.add(blades.clone());
+    let light = Arc::new(DiffuseLight::from_color(Color::new(3.0, 3.0, 3.0)));
+    world.add(Arc::new(Sphere::new(Point3::new(-4.0, 6.0, 4.0), 2.5, Some(light))));
+
+    let mut cam = Camera::new();
+    cam.aspect_ratio = 1.0;
+    cam.image_width = 400;
+    cam.samples_per_pixel = 200;
+    cam.max_depth = 30;
+    cam.vfov = 30.0;
+    cam.lookfrom = Point3::new(0.0, 1.0, 7.0);
+    cam.lookat = Point3::new(0.0, 0.0, 0.0);
+    cam.vup = Vec3::new(0.0, 1.0, 0.0);
+    cam.defocus_angle = 0.0;
+    cam.focus_dist = 7.0;
+    cam.background = Color::new(0.10, 0.12, 0.16);
+    cam.set_russian_roulette_strategy(RussianRouletteStrategy::Adaptive);
+
+    let world = HittableList::with_object(Arc::new(BvhNode::new_from_list(&mut world.objects)));
+    let lights = HittableList::new();
+    let (duration, _) = time_it(|| cam.render(&world, &lights, &mut std::io::stdout()).unwrap());
+    let mut file = OpenOptions::new().create(true).append(true).open("output.txt").unwrap();
+    let curve_count = fur.curve_count() + whiskers.curve_count() + blades.curve_count();
+    writeln!(file, "Fur Ball Render耗时: {:?}（{} 条曲线）", duration, curve_count).unwrap();
+}
+
 fn perlin_spheres() {
     let mut world = HittableList::new();
 
@@ -636,11 +809,838 @@ fn cornell_smoke() {
     writeln!(file, "CornellSmoke Render耗时: {:?}", duration).unwrap();
 }
 
+// 非均匀介质：Perlin 湍流密度的烟雾球与体素网格密度的方块，单次散射对光源列表中的球形光源做下一事件估计
+fn heterogeneous_smoke() {
+    use crate::aabb::Aabb;
+    use crate::heterogeneous_medium::{Density, HeterogeneousMedium, VoxelGrid};
+    use crate::perlin::Perlin;
+    use crate::phase::{DoubleHenyeyGreenstein, HenyeyGreenstein};
+    let mut world = HittableList::new();
+
+    let ground = Arc::new(Lambertian::from_color(Color::new(0.48, 0.83, 0.53)));
+    world.add(Arc::new(Sphere::new(Point3::new(0.0, -1000.0, 0.0), 1000.0, Some(ground))));
+
+    let light_center = Point3::new(-6.0, 10.0, 6.0);
+    let light_radius = 2.0;
+    let light_radiance = Color::new(12.0, 12.0, 10.0);
+    let light = Arc::new(DiffuseLight::from_color(light_radiance));
+    world.add(Arc::new(Sphere::new(light_center, light_radius, Some(light))));
+
+    // 湍流烟雾球：以前向散射为主、带一个弱后向波瓣，像云一样
+    let cloud_boundary = Arc::new(Sphere::new(Point3::new(-1.5, 2.0, 0.0), 2.0, None));
+    let density = Density::Perlin { noise: Perlin::new(), frequency: 1.5, depth: 5 };
+    let cloud_phase = Arc::new(DoubleHenyeyGreenstein::new(0.7, -0.3, 0.8));
+    world.add(Arc::new(
+        HeterogeneousMedium::new_with_phase(cloud_boundary, density, 1.5, cloud_phase, Color::new(0.9, 0.9, 0.9))
+            .with_single_scattering(),
+    ));
+
+    // 中心浓、边缘淡的体素方块
+    let (nx, ny, nz) = (16, 16, 16);
+    let mut values = Vec::with_capacity(nx * ny * nz);
+    for k in 0..nz {
+        for j in 0..ny {
+            for i in 0..nx {
+                let q = Vec3::new(i as f64 / (nx - 1) as f64, j as f64 / (ny - 1) as f64, k as f64 / (nz - 1) as f64) - Vec3::new(0.5, 0.5, 0.5);
+                values.push((1.0 - 2.0 * q.length()).max(0.0));
+            }
+        }
+    }
+    let (box_min, box_max) = (Point3::new(1.0, 0.0, -1.5), Point3::new(4.0, 3.0, 1.5));
+    let grid = VoxelGrid::new(values, nx, ny, nz, Aabb::from_points(box_min, box_max));
+    let white = Arc::new(Lambertian::from_color(Color::new(0.73, 0.73, 0.73)));
+    world.add(Arc::new(HeterogeneousMedium::new_with_color(make_box(box_min, box_max, white), Density::Grid(grid), 2.0, Color::new(0.8, 0.5, 0.3))));
+
+    // 大理石纹理的亮度作为密度，亮度不超过 1
+    let marble = Arc::new(NoiseTexture::with_scale(4.0));
+    let marble_boundary = Arc::new(Sphere::new(Point3::new(1.0, 0.8, 3.5), 0.8, None));
+    world.add(Arc::new(HeterogeneousMedium::new_with_texture(marble_boundary, Density::from_texture(marble.clone(), 1.0), 3.0, marble)));
+
+    // 均匀的后向散射尘埃球：光源在相机一侧，散射回来的光使它比同密度的各向同性介质更亮
+    let dust_boundary = Arc::new(Sphere::new(Point3::new(-4.5, 1.0, 2.5), 1.0, None));
+    world.add(Arc::new(ConstantMedium::new_with_phase(dust_boundary, 1.2, Arc::new(HenyeyGreenstein::new(-0.5)), Color::new(0.85, 0.75, 0.6))));
+
+    let mut cam = Camera::new();
+    cam.aspect_ratio = 16.0 / 9.0;
+    cam.image_width = 400;
+    cam.samples_per_pixel = 200;
+    cam.max_depth = 50;
+    cam.vfov = 35.0;
+    cam.lookfrom = Point3::new(0.0, 4.0, 14.0);
+    cam.lookat = Point3::new(0.5, 1.5, 0.0);
+    cam.vup = Vec3::new(0.0, 1.0, 0.0);
+    cam.defocus_angle = 0.0;
+    cam.focus_dist = 14.0;
+    cam.background = Color::new(0.05, 0.05, 0.08);
+    cam.set_russian_roulette_strategy(RussianRouletteStrategy::Adaptive);
+
+    // 单次散射的烟雾球对光源列表做下一事件估计
+    let mut lights = HittableList::new();
+    lights.add(Arc::new(Sphere::new(light_center, light_radius, None)));
+    let (duration, _) = time_it(|| cam.render(&world, &lights, &mut std::io::stdout()).unwrap());
+    let mut file = OpenOptions::new().create(true).append(true).open("output.txt").unwrap();
+    writeln!(file, "Heterogeneous Smoke Render耗时: {:?}", duration).unwrap();
+}
+
+// 火球：按温度场发光、逐通道吸收的彩色介质，外面包一层偏蓝散射的薄烟，旁边是按纹理发光的等离子球
+fn fireball() {
+    use crate::chromatic_medium::{ChromaticMedium, MediumEmission};
+    use crate::heterogeneous_medium::Density;
+    use crate::perlin::Perlin;
+    use crate::phase::HenyeyGreenstein;
+    let mut world = HittableList::new();
+
+    let ground = Arc::new(Lambertian::from_color(Color::new(0.4, 0.4, 0.4)));
+    world.add(Arc::new(Sphere::new(Point3::new(0.0, -1000.0, 0.0), 1000.0, Some(ground))));
+
+    let center = Point3::new(0.0, 1.6, 0.0);
+    let noise = Perlin::new();
+    let temperature = Density::Perlin { noise: noise.clone(), frequency: 2.0, depth: 4 };
+    let flame = ChromaticMedium::new(Arc::new(Sphere::new(center, 1.2, None)), Color::new(0.8, 1.0, 1.4), Color::new(0.2, 0.2, 0.2))
+        .with_density(Density::Perlin { noise, frequency: 2.0, depth: 4 })
+        .with_emission(MediumEmission::from_temperature(temperature, 1000.0, 2800.0, 6.0));
+    world.add(Arc::new(flame));
+
+    let haze = ChromaticMedium::new(Arc::new(Sphere::new(center, 1.5, None)), Color::new(0.02, 0.02, 0.02), Color::new(0.05, 0.1, 0.2))
+        .with_phase(Arc::new(HenyeyGreenstein::new(0.3)));
+    world.add(Arc::new(haze));
+
+    // 旁边的等离子球：自发光取大理石纹理，逐通道吸收使光晕偏紫
+    let plasma = ChromaticMedium::new(Arc::new(Sphere::new(Point3::new(1.9, 0.7, 0.8), 0.6, None)), Color::new(1.2, 0.3, 1.6), Color::new(0.05, 0.05, 0.05))
+        .with_emission(MediumEmission::from_texture(Arc::new(NoiseTexture::with_scale(6.0))));
+    world.add(Arc::new(plasma));
+
+    let mut cam = Camera::new();
+    cam.aspect_ratio = 1.0;
+    cam.image_width = 400;
+    cam.samples_per_pixel = 200;
+    cam.max_depth = 50;
+    cam.vfov = 35.0;
+    cam.lookfrom = Point3::new(0.0, 2.5, 8.0);
+    cam.lookat = Point3::new(0.0, 1.4, 0.0);
+    cam.vup = Vec3::new(0.0, 1.0, 0.0);
+    cam.defocus_angle = 0.0;
+    cam.focus_dist = 8.0;
+    cam.background = Color::new(0.0, 0.0, 0.0);
+    cam.set_russian_roulette_strategy(RussianRouletteStrategy::Adaptive);
+
+    let lights = HittableList::new();
+    let (duration, _) = time_it(|| cam.render(&world, &lights, &mut std::io::stdout()).unwrap());
+    let mut file = OpenOptions::new().create(true).append(true).open("output.txt").unwrap();
+    writeln!(file, "Fireball Render耗时: {:?}", duration).unwrap();
+}
+
+// 材质展示：一排球体依次使用各种组合与物理材质，顶部面光源照明
+fn material_showcase() {
+    use crate::material::{CoatedMaterial, LightUnit, MixMaterial};
+    use crate::measured::{MeasuredMaterial, MerlBrdf};
+    use std::f64::consts::PI;
+    use crate::subsurface::SubsurfaceMaterial;
+    use crate::thin_film::ThinFilm;
+    let mut world = HittableList::new();
+
+    let checker = Arc::new(CheckerTexture::from_color(0.5, Color::new(0.2, 0.3, 0.1), Color::new(0.9, 0.9, 0.9)));
+    world.add(Arc::new(Sphere::new(Point3::new(0.0, -1000.0, 0.0), 1000.0, Some(Arc::new(Lambertian::from_texture(checker))))));
+
+    // 棋盘格遮罩在漫反射与金属之间随机选择；大理石纹遮罩按权重混合两种漫反射
+    let red = Arc::new(Lambertian::from_color(Color::new(0.7, 0.1, 0.1)));
+    let gold = Arc::new(Metal::new(Color::new(0.9, 0.7, 0.3), 0.1));
+    let mask = Arc::new(CheckerTexture::from_color(0.25, Color::new(0.0, 0.0, 0.0), Color::new(1.0, 1.0, 1.0)));
+    let mixed = Arc::new(MixMaterial::stochastic(red.clone(), gold, mask));
+    world.add(Arc::new(Sphere::new(Point3::new(-6.0, 1.0, 0.0), 1.0, Some(mixed))));
+    let blue = Arc::new(Lambertian::from_color(Color::new(0.1, 0.2, 0.7)));
+    let blended = Arc::new(MixMaterial::weighted(red.clone(), blue, Arc::new(NoiseTexture::with_scale(4.0))));
+    world.add(Arc::new(Sphere::new(Point3::new(-3.6, 1.0, 0.0), 1.0, Some(blended))));
+
+    // 红色漫反射上的清漆涂层
+    let coated = Arc::new(CoatedMaterial::new(red, 1.5));
+    world.add(Arc::new(Sphere::new(Point3::new(-1.2, 1.0, 0.0), 1.0, Some(coated))));
+
+    // 蜡质的次表面散射：球既是物体也是游走的边界
+    let wax_shape = Arc::new(Sphere::new(Point3::new(1.2, 1.0, 0.0), 1.0, None));
+    let wax = SubsurfaceMaterial::new(wax_shape, Color::new(0.95, 0.9, 0.8), Color::new(0.3, 0.15, 0.08), 1.4).with_max_bounces(128);
+    world.add(Arc::new(wax.into_object()));
+
+    // 玻璃球内盛着带吸收的水：两者重叠处按优先级以玻璃为准
+    let bowl_center = Point3::new(3.6, 1.0, 0.0);
+    let glass = Arc::new(Dielectric::new(1.5).with_priority(2));
+    let water = Arc::new(Dielectric::with_absorption(1.33, Color::new(0.6, 0.2, 0.05)).with_priority(1));
+    world.add(Arc::new(Sphere::new(bowl_center, 1.0, Some(glass))));
+    world.add(Arc::new(Sphere::new(bowl_center - Vec3::new(0.0, 0.15, 0.0), 0.9, Some(water))));
+
+    // 厚度随噪声起伏的肥皂泡，以及阳极氧化的金属
+    let soap = ThinFilm::new(450.0, 1.33).with_thickness_map(Arc::new(NoiseTexture::with_scale(2.0)));
+    let bubble = Arc::new(Dielectric::new(1.0).with_thin_film(soap));
+    world.add(Arc::new(Sphere::new(Point3::new(6.0, 1.0, 0.0), 1.0, Some(bubble))));
+    let anodized = Arc::new(Metal::new(Color::new(0.6, 0.6, 0.6), 0.05).with_thin_film(ThinFilm::new(250.0, 2.4)));
+    world.add(Arc::new(Sphere::new(Point3::new(-4.8, 1.0, -3.0), 1.0, Some(anodized))));
+
+    // MERL 测量数据（约 35MB）不随仓库分发；缺失时按 MERL 分箱对金色金属漆的解析模型制表，
+    // 同样走测量 BRDF 的查表与采样：暗色漫反射底 + 带 Schlick 菲涅尔的金色 Blinn-Phong 高光
+    let measured = MeasuredMaterial::load("input/gold-metallic-paint.binary").unwrap_or_else(|_| {
+        let (base, gold, shininess) = (Color::new(0.12, 0.07, 0.02), Color::new(1.0, 0.78, 0.34), 300.0);
+        MeasuredMaterial::new(Arc::new(MerlBrdf::tabulate(|theta_h: f64, theta_d: f64, _| {
+            let fresnel = gold + (Color::new(1.0, 1.0, 1.0) - gold) * (1.0 - theta_d.cos()).powi(5);
+            base / PI + fresnel * ((shininess + 2.0) / (8.0 * PI) * theta_h.cos().powf(shininess))
+        })))
+    });
+    world.add(Arc::new(Sphere::new(Point3::new(-2.4, 1.0, -3.0), 1.0, Some(Arc::new(measured)))));
+
+    // 单面光源，法线 u × v 朝下；5500K、1200W 分布在 16×4 的面上
+    let light = Arc::new(DiffuseLight::from_temperature(5500.0, 1.0).one_sided().with_power_open(LightUnit::Watts(1200.0), 64.0));
+    world.add(Arc::new(Quad::new(Point3::new(-8.0, 7.0, -2.0), Vec3::new(16.0, 0.0, 0.0), Vec3::new(0.0, 0.0, 4.0), light)));
+    // 前排暖色小灯泡：封闭球面按表面积换算光通量
+    let (bulb_center, bulb_radius) = (Point3::new(0.0, 0.25, 2.5), 0.25);
+    let bulb = Arc::new(DiffuseLight::from_temperature_and_power(2700.0, LightUnit::Lumens(6000.0), Sphere::new(bulb_center, bulb_radius, None).area()));
+    world.add(Arc::new(Sphere::new(bulb_center, bulb_radius, Some(bulb))));
+
+    let mut cam = Camera::new();
+    cam.aspect_ratio = 16.0 / 9.0;
+    cam.image_width = 600;
+    cam.samples_per_pixel = 200;
+    cam.max_depth = 50;
+    cam.vfov = 30.0;
+    cam.lookfrom = Point3::new(0.0, 4.0, 18.0);
+    cam.lookat = Point3::new(0.0, 1.0, 0.0);
+    cam.vup = Vec3::new(0.0, 1.0, 0.0);
+    cam.defocus_angle = 0.0;
+    cam.focus_dist = 18.0;
+    cam.background = Color::new(0.05, 0.05, 0.07);
+    cam.set_russian_roulette_strategy(RussianRouletteStrategy::Conservative);
+
+    let lights = HittableList::new();
+    let (duration, _) = time_it(|| cam.render(&world, &lights, &mut std::io::stdout()).unwrap());
+    let mut file = OpenOptions::new().create(true).append(true).open("output.txt").unwrap();
+    writeln!(file, "Material Showcase Render耗时: {:?}", duration).unwrap();
+}
+
+// 色散：光谱模式下几种色散玻璃在窄光带照射下投出彩色焦散
+fn dispersion() {
+    use crate::spectrum::Dispersion;
+    let mut world = HittableList::new();
+
+    let ground = Arc::new(Lambertian::from_color(Color::new(0.8, 0.8, 0.8)));
+    world.add(Arc::new(Sphere::new(Point3::new(0.0, -1000.0, 0.0), 1000.0, Some(ground))));
+
+    // 熔融石英的 Sellmeier 系数
+    let fused_silica = Dielectric::sellmeier([0.6961663, 0.4079426, 0.8974794], [0.00467914826, 0.0135120631, 97.9340025]);
+    let glasses = [
+        Dielectric::from_dispersion(Dispersion::BK7),
+        Dielectric::from_dispersion(Dispersion::DIAMOND),
+        Dielectric::cauchy(1.45, 0.02),
+        fused_silica,
+    ];
+    for (i, glass) in glasses.into_iter().enumerate() {
+        let x = -3.75 + 2.5 * i as f64;
+        world.add(Arc::new(Sphere::new(Point3::new(x, 1.0, 0.0), 1.0, Some(Arc::new(glass)))));
+    }
+
+    // 细长的光带，焦散沿 z 方向较锐利
+    let light = Arc::new(DiffuseLight::from_color(Color::new(10.0, 10.0, 10.0)));
+    world.add(Arc::new(Quad::new(Point3::new(-6.0, 8.0, -1.0), Vec3::new(12.0, 0.0, 0.0), Vec3::new(0.0, 0.0, 2.0), light)));
+
+    let mut cam = Camera::new();
+    cam.aspect_ratio = 16.0 / 9.0;
+    cam.image_width = 600;
+    cam.samples_per_pixel = 400;
+    cam.max_depth = 50;
+    cam.vfov = 30.0;
+    cam.lookfrom = Point3::new(0.0, 6.0, 14.0);
+    cam.lookat = Point3::new(0.0, 0.5, 0.0);
+    cam.vup = Vec3::new(0.0, 1.0, 0.0);
+    cam.defocus_angle = 0.0;
+    cam.focus_dist = 14.0;
+    cam.background = Color::new(0.0, 0.0, 0.0);
+    cam.set_spectral(true);
+    cam.set_russian_roulette_strategy(RussianRouletteStrategy::Conservative);
+
+    let lights = HittableList::new();
+    let (duration, _) = time_it(|| cam.render(&world, &lights, &mut std::io::stdout()).unwrap());
+    let mut file = OpenOptions::new().create(true).append(true).open("output.txt").unwrap();
+    writeln!(file, "Dispersion Render耗时: {:?}", duration).unwrap();
+}
+
+// 经纬球网格，顶点布局与 Sphere 的 (u, v) 约定一致；normals 为空时为平直着色
+fn uv_sphere_mesh(center: Point3, radius: f64, rings: usize, segments: usize, smooth: bool, mat: Arc<dyn Material + Send + Sync>) -> TriangleMesh {
+    use std::f64::consts::PI;
+    let mut positions = Vec::new();
+    let mut normals = Vec::new();
+    let mut uvs = Vec::new();
+    for i in 0..=rings {
+        let theta = PI * i as f64 / rings as f64;
+        for j in 0..=segments {
+            let phi = 2.0 * PI * j as f64 / segments as f64;
+            let n = Vec3::new(-phi.cos() * theta.sin(), -theta.cos(), phi.sin() * theta.sin());
+            positions.push(center + radius * n);
+            normals.push(n);
+            uvs.push((j as f64 / segments as f64, i as f64 / rings as f64));
+        }
+    }
+    let mut indices = Vec::new();
+    for i in 0..rings {
+        for j in 0..segments {
+            let a = i * (segments + 1) + j;
+            let b = a + segments + 1;
+            indices.push([a, a + 1, b + 1]);
+            indices.push([a, b + 1, b]);
+        }
+    }
+    if !smooth {
+        normals.clear();
+    }
+    // 两极处退化的三角形在构建时丢弃
+    TriangleMesh::with_attributes(positions, normals, uvs, indices, mat).expect("uv sphere attributes match its vertices")
+}
+
+// 三角网格：平滑与平直着色的地球网格，三角形面光源参与重要性采样
+fn mesh_showcase() {
+    use crate::material::LightUnit;
+    let mut world = HittableList::new();
+
+    let ground = Arc::new(Lambertian::from_color(Color::new(0.5, 0.5, 0.5)));
+    world.add(Arc::new(Sphere::new(Point3::new(0.0, -1000.0, 0.0), 1000.0, Some(ground))));
+
+    let earth = Arc::new(Lambertian::from_texture(Arc::new(ImageTexture::new("input/earthmap.jpg"))));
+    world.add(Arc::new(uv_sphere_mesh(Point3::new(-2.2, 1.2, 0.0), 1.2, 32, 64, true, earth.clone())));
+    world.add(Arc::new(uv_sphere_mesh(Point3::new(2.2, 1.2, 0.0), 1.2, 8, 16, false, earth)));
+
+    // OBJ + MTL 资源：石质底座上的金色星形
+    match load_obj("input/models/trophy.obj") {
+        Ok(mut trophy) => {
+            let trophy = Arc::new(BvhNode::new_from_list(&mut trophy.objects));
+            world.add(Arc::new(Translate::new(trophy, Vec3::new(0.0, 0.0, 2.5))));
+        }
+        Err(e) => eprintln!("ERROR: Could not load OBJ model: {}", e),
+    }
+
+    // 绕两个轴倾斜并缩小的盒子
+    let tilted = Mat4::translate(Vec3::new(3.3, 0.9, 3.0)) * Mat4::rotate_z(25.0) * Mat4::rotate_x(-20.0) * Mat4::uniform_scale(0.8);
+    let cube = make_box(Point3::new(-0.5, -0.5, -0.5), Point3::new(0.5, 0.5, 0.5), Arc::new(Metal::new(Color::new(0.8, 0.8, 0.85), 0.2)));
+    if let Some(cube) = Transform::new(make_prototype(&cube), tilted) {
+        world.add(Arc::new(cube));
+    }
+
+    // 边平移边翻滚的盒子：三个关键帧，旋转按球面插值
+    let spin_axis = Vec3::new(1.0, 1.0, 0.0);
+    let keys = vec![
+        Keyframe::new(0.0, Vec3::new(-1.2, 3.0, -1.5), Quat::identity(), Vec3::new(1.0, 1.0, 1.0)),
+        Keyframe::new(0.5, Vec3::new(0.0, 3.3, -1.5), Quat::from_axis_angle(spin_axis, 60.0), Vec3::new(1.0, 1.0, 1.0)),
+        Keyframe::new(1.0, Vec3::new(1.2, 3.0, -1.5), Quat::from_axis_angle(spin_axis, 120.0), Vec3::new(1.0, 1.0, 1.0)),
+    ];
+    let red = Arc::new(Lambertian::from_color(Color::new(0.7, 0.15, 0.1)));
+    let spinning = make_box(Point3::new(-0.4, -0.4, -0.4), Point3::new(0.4, 0.4, 0.4), red);
+    if let Some(spinning) = MotionTransform::new(make_prototype(&spinning), keys) {
+        world.add(Arc::new(spinning));
+    }
+
+    // 二进制 PLY 扫描网格，颜色取自顶点
+    let vertex_colored = Arc::new(Lambertian::from_texture(Arc::new(VertexColorTexture::new(Color::new(0.5, 0.5, 0.5)))));
+    match load_ply("input/models/blob.ply", vertex_colored) {
+        Ok(mut blob) => {
+            let blob = Arc::new(BvhNode::new_from_list(&mut blob.objects));
+            // 快门内缓慢上浮，演示网格的运动模糊
+            let start = Keyframe::translation(0.0, Vec3::new(-3.3, 0.0, 3.0));
+            let end = Keyframe::translation(1.0, Vec3::new(-3.3, 0.25, 3.0));
+            if let Some(rising) = MotionTransform::linear(blob, start, end) {
+                world.add(Arc::new(rising));
+            }
+        }
+        Err(e) => eprintln!("ERROR: Could not load PLY model: {}", e),
+    }
+
+    // 奖杯旁只有位置与索引的玻璃八面体，平直着色
+    let (gem_center, gem_radius) = (Point3::new(-1.6, 0.55, 3.4), 0.55);
+    let gem_vertices = [
+        Vec3::new(1.0, 0.0, 0.0), Vec3::new(-1.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0),
+        Vec3::new(0.0, -1.0, 0.0), Vec3::new(0.0, 0.0, 1.0), Vec3::new(0.0, 0.0, -1.0),
+    ];
+    let gem_faces = vec![[0, 2, 4], [4, 2, 1], [1, 2, 5], [5, 2, 0], [4, 3, 0], [1, 3, 4], [5, 3, 1], [0, 3, 5]];
+    let gem_positions = gem_vertices.iter().map(|&v| gem_center + gem_radius * v).collect();
+    world.add(Arc::new(TriangleMesh::new(gem_positions, gem_faces, Arc::new(Dielectric::new(1.5))).expect("gem faces index its vertices")));
+
+    // 三角形顶灯，按面积换算功率（双面发光）
+    let a = Point3::new(-2.0, 6.0, -1.0);
+    let b = Point3::new(2.0, 6.0, -1.0);
+    let c = Point3::new(0.0, 6.0, 2.5);
+    let panel = Arc::new(Triangle::new(a, b, c, Arc::new(Lambertian::from_color(Color::new(0.0, 0.0, 0.0)))));
+    let light = Arc::new(DiffuseLight::from_color(Color::new(1.0, 1.0, 1.0)).with_power_open(LightUnit::Watts(530.0), panel.area()));
+    world.add(Arc::new(Triangle::new(a, b, c, light)));
+
+    let mut cam = Camera::new();
+    cam.aspect_ratio = 16.0 / 9.0;
+    cam.image_width = 600;
+    cam.samples_per_pixel = 200;
+    cam.max_depth = 50;
+    cam.vfov = 30.0;
+    cam.lookfrom = Point3::new(0.0, 3.0, 12.0);
+    cam.lookat = Point3::new(0.0, 1.2, 0.0);
+    cam.vup = Vec3::new(0.0, 1.0, 0.0);
+    cam.defocus_angle = 0.0;
+    cam.focus_dist = 12.0;
+    cam.background = Color::new(0.02, 0.02, 0.03);
+
+    let mut lights = HittableList::new();
+    lights.add(panel);
+    let (duration, _) = time_it(|| cam.render(&world, &lights, &mut std::io::stdout()).unwrap());
+    let mut file = OpenOptions::new().create(true).append(true).open("output.txt").unwrap();
+    writeln!(file, "Mesh Showcase Render耗时: {:?}", duration).unwrap();
+}
+
+// glTF 场景：节点层级、PBR 材质、发光面板、聚光灯、平行光与相机都来自文件
+fn gltf_gallery() {
+    use crate::gltf::load_gltf;
+    let scene = match load_gltf("input/models/gallery.gltf") {
+        Ok(scene) => scene,
+        Err(e) => {
+            eprintln!("ERROR: Could not load glTF scene: {}", e);
+            return;
+        }
+    };
+    let mut objects = scene.world.objects;
+    let world = HittableList::with_object(Arc::new(BvhNode::new_from_list(&mut objects)));
+
+    let mut cam = Camera::new();
+    cam.aspect_ratio = 16.0 / 9.0;
+    // 优先使用名为 overview 的相机
+    let overview = scene.cameras.iter().find(|camera| camera.name == "overview");
+    if let Some(gltf_camera) = overview.or(scene.cameras.first()) {
+        gltf_camera.apply(&mut cam);
+    }
+    cam.image_width = 600;
+    cam.samples_per_pixel = 200;
+    cam.max_depth = 50;
+    cam.defocus_angle = 0.0;
+    cam.focus_dist = 7.0;
+    cam.background = Color::new(0.02, 0.02, 0.03);
+
+    let (duration, _) = time_it(|| cam.render(&world, &scene.lights, &mut std::io::stdout()).unwrap());
+    let mut file = OpenOptions::new().create(true).append(true).open("output.txt").unwrap();
+    writeln!(file, "glTF Gallery Render耗时: {:?}", duration).unwrap();
+}
+
+// 解析图元陈列：圆盘地面、圆柱立柱、圆锥、圆环面、三角形与椭圆
+fn primitive_gallery() {
+    use crate::cone::Cone;
+    use crate::cylinder::Cylinder;
+    use crate::material::LightUnit;
+    use crate::torus::Torus;
+    let mut world = HittableList::new();
+    let mut lights = HittableList::new();
+    let up = Vec3::new(0.0, 1.0, 0.0);
+
+    let checker = Arc::new(CheckerTexture::from_color(0.6, Color::new(0.2, 0.2, 0.22), Color::new(0.8, 0.8, 0.8)));
+    world.add(Arc::new(Quad::disk(Point3::new(0.0, 0.0, 0.0), up, 12.0, Arc::new(Lambertian::from_texture(checker)))));
+
+    // 两根带端盖的大理石立柱，顶上各有一团发光的圆锥火焰，按表面积换算功率
+    let marble = Arc::new(Lambertian::from_texture(Arc::new(NoiseTexture::with_scale(3.0))));
+    for x in [-4.5, 4.5] {
+        let base = Point3::new(x, 0.0, -1.0);
+        let top = base + Vec3::new(0.0, 3.0, 0.0);
+        world.add(Arc::new(Cylinder::new(base, top, 0.5, marble.clone())));
+        let flame = Cone::new(top, top + Vec3::new(0.0, 0.9, 0.0), 0.35, Arc::new(Lambertian::from_color(Color::new(0.0, 0.0, 0.0))));
+        let glow = Arc::new(DiffuseLight::from_temperature_and_power(1900.0, LightUnit::Watts(18.0), flame.area()));
+        let flame = Arc::new(Cone::new(top, top + Vec3::new(0.0, 0.9, 0.0), 0.35, glow));
+        world.add(flame.clone());
+        lights.add(flame);
+    }
+
+    // 中央倾斜的金色圆环面，以及悬在上方的暖色环形灯
+    let gold = Arc::new(Metal::new(Color::new(0.9, 0.7, 0.3), 0.15));
+    world.add(Arc::new(Torus::new(Point3::new(0.0, 1.3, 0.0), Vec3::new(0.0, 1.0, 0.6), 1.0, 0.35, gold)));
+    let ring_lamp = Torus::new(Point3::new(0.0, 3.6, 0.0), up, 0.8, 0.12, Arc::new(Lambertian::from_color(Color::new(0.0, 0.0, 0.0))));
+    let warm = Arc::new(DiffuseLight::from_temperature_and_power(2700.0, LightUnit::Watts(50.0), ring_lamp.area()));
+    let ring_lamp = Arc::new(Torus::new(Point3::new(0.0, 3.6, 0.0), up, 0.8, 0.12, warm));
+    world.add(ring_lamp.clone());
+    lights.add(ring_lamp);
+
+    // 实心红色圆锥与倒置的开口金属漏斗
+    world.add(Arc::new(Cone::new(Point3::new(-2.2, 0.0, 1.8), Point3::new(-2.2, 1.6, 1.8), 0.7, Arc::new(Lambertian::from_color(Color::new(0.7, 0.12, 0.1))))));
+    let steel = Arc::new(Metal::new(Color::new(0.75, 0.75, 0.8), 0.05));
+    world.add(Arc::new(Cone::new(Point3::new(2.2, 1.5, 1.8), Point3::new(2.2, 0.0, 1.8), 0.7, steel.clone()).uncapped()));
+    // 横躺的开口铜管，可以看到内壁
+    let copper = Arc::new(Metal::new(Color::new(0.85, 0.5, 0.35), 0.2));
+    world.add(Arc::new(Cylinder::new(Point3::new(-0.9, 0.3, 3.2), Point3::new(0.9, 0.3, 3.6), 0.3, copper).uncapped()));
+
+    // 左侧地面上的冷白色灯管
+    let (tube_start, tube_end) = (Point3::new(-3.6, 0.1, -2.6), Point3::new(-1.4, 0.1, -2.6));
+    let tube = Cylinder::new(tube_start, tube_end, 0.08, Arc::new(Lambertian::from_color(Color::new(0.0, 0.0, 0.0))));
+    let cool = Arc::new(DiffuseLight::from_temperature_and_power(6500.0, LightUnit::Watts(14.0), tube.area()));
+    let tube = Arc::new(Cylinder::new(tube_start, tube_end, 0.08, cool));
+    world.add(tube.clone());
+    lights.add(tube);
+
+    // 背后的椭圆镜面与两面三角旗
+    world.add(Arc::new(Quad::ellipse(Point3::new(0.0, 3.0, -4.5), Vec3::new(3.0, 0.0, 0.0), Vec3::new(0.0, 1.8, 0.0), steel)));
+    let teal = Arc::new(Lambertian::from_color(Color::new(0.1, 0.5, 0.5)));
+    world.add(Arc::new(Quad::triangle(Point3::new(-7.5, 0.0, -3.0), Vec3::new(2.5, 0.0, 0.5), Vec3::new(0.5, 4.0, 0.0), teal.clone())));
+    world.add(Arc::new(Quad::triangle(Point3::new(7.5, 0.0, -3.0), Vec3::new(-2.5, 0.0, 0.5), Vec3::new(-0.5, 4.0, 0.0), teal)));
+
+    // 头顶朝下的单面圆盘主光源
+    let sky = Quad::disk(Point3::new(0.0, 8.0, 1.0), -up, 3.0, Arc::new(Lambertian::from_color(Color::new(0.0, 0.0, 0.0))));
+    let daylight = Arc::new(DiffuseLight::from_temperature(5500.0, 1.0).one_sided().with_power_open(LightUnit::Watts(700.0), sky.area()));
+    let sky = Arc::new(Quad::disk(Point3::new(0.0, 8.0, 1.0), -up, 3.0, daylight));
+    world.add(sky.clone());
+    lights.add(sky);
+
+    let mut cam = Camera::new();
+    cam.aspect_ratio = 16.0 / 9.0;
+    cam.image_width = 600;
+    cam.samples_per_pixel = 200;
+    cam.max_depth = 50;
+    cam.vfov = 35.0;
+    cam.lookfrom = Point3::new(0.0, 3.5, 15.0);
+    cam.lookat = Point3::new(0.0, 1.5, 0.0);
+    cam.vup = up;
+    cam.defocus_angle = 0.0;
+    cam.focus_dist = 15.0;
+    cam.background = Color::new(0.03, 0.03, 0.05);
+    cam.set_russian_roulette_strategy(RussianRouletteStrategy::Conservative);
+
+    let (duration, _) = time_it(|| cam.render(&world, &lights, &mut std::io::stdout()).unwrap());
+    let mut file = OpenOptions::new().create(true).append(true).open("output.txt").unwrap();
+    writeln!(file, "Primitive Gallery Render耗时: {:?}", duration).unwrap();
+}
+
+// 七段数码管形状的实体：在 xz 平面内，左下角 corner，宽 width、长 length（沿 -z 向上书写），
+// 笔画粗 stroke，y 方向从 corner.y 起厚 depth
+fn seven_segment(digit: usize, corner: Point3, width: f64, length: f64, stroke: f64, depth: f64) -> Arc<dyn Hittable + Send + Sync> {
+    // a b c d e f g 七段是否点亮
+    const SEGMENTS: [[bool; 7]; 10] = [
+        [true, true, true, true, true, true, false],
+        [false, true, true, false, false, false, false],
+        [true, true, false, true, true, false, true],
+        [true, true, true, true, false, false, true],
+        [false, true, true, false, false, true, true],
+        [true, false, true, true, false, true, true],
+        [true, false, true, true, true, true, true],
+        [true, true, true, false, false, false, false],
+        [true, true, true, true, true, true, true],
+        [true, true, true, true, false, true, true],
+    ];
+    let half = length / 2.0;
+    // 每段的 (x0, z0, x1, z1)，z 取负值表示远离观察者
+    let rects = [
+        (0.0, length - stroke, width, length),               // a 上横
+        (width - stroke, half, width, length),               // b 右上
+        (width - stroke, 0.0, width, half),                  // c 右下
+        (0.0, 0.0, width, stroke),                           // d 下横
+        (0.0, 0.0, stroke, half),                            // e 左下
+        (0.0, half, stroke, length),                         // f 左上
+        (0.0, half - stroke / 2.0, width, half + stroke / 2.0), // g 中横
+    ];
+    let black = Arc::new(Lambertian::from_color(Color::new(0.05, 0.05, 0.05)));
+    let mut shape: Option<Arc<dyn Hittable + Send + Sync>> = None;
+    for (lit, (x0, z0, x1, z1)) in SEGMENTS[digit % 10].iter().zip(rects) {
+        if !lit {
+            continue;
+        }
+        let bar: Arc<dyn Hittable + Send + Sync> = make_box(
+            corner + Vec3::new(x0, 0.0, -z0),
+            corner + Vec3::new(x1, depth, -z1),
+            black.clone(),
+        );
+        shape = Some(match shape {
+            Some(s) => Arc::new(Csg::union(s, bar)),
+            None => bar,
+        });
+    }
+    shape.expect("every digit lights at least two segments")
+}
+
+// 构造实体几何：刻字石板、圆角骰子、透镜和剖开的网格地球
+fn csg_showcase() {
+    let mut world = HittableList::new();
+
+    let ground = Arc::new(Lambertian::from_color(Color::new(0.45, 0.42, 0.38)));
+    world.add(Arc::new(Sphere::new(Point3::new(0.0, -1000.0, 0.0), 1000.0, Some(ground))));
+
+    // 石板顶面刻出 "42"：数字实体从顶面下方 0.15 处伸出板外，再从石板中减去
+    let stone = Arc::new(Lambertian::from_texture(Arc::new(NoiseTexture::with_scale(2.0))));
+    let slab = make_box(Point3::new(-2.0, 0.0, -1.0), Point3::new(2.0, 0.5, 1.2), stone);
+    let four = seven_segment(4, Point3::new(-1.3, 0.35, 0.9), 1.0, 1.6, 0.22, 0.5);
+    let two = seven_segment(2, Point3::new(0.3, 0.35, 0.9), 1.0, 1.6, 0.22, 0.5);
+    let engraving = Arc::new(Csg::union(four, two));
+    world.add(Arc::new(Csg::difference(slab, engraving)));
+
+    // 圆角骰子：立方体与球求交，再挖去顶面的五个点
+    let ivory = Arc::new(Lambertian::from_color(Color::new(0.9, 0.88, 0.8)));
+    let die_center = Point3::new(-3.4, 0.6, 1.8);
+    let cube = make_box(die_center - Vec3::new(0.6, 0.6, 0.6), die_center + Vec3::new(0.6, 0.6, 0.6), ivory.clone());
+    let rounded = Arc::new(Csg::intersection(cube, Arc::new(Sphere::new(die_center, 0.8, Some(ivory)))));
+    let pip_mat = Arc::new(Lambertian::from_color(Color::new(0.1, 0.1, 0.1)));
+    let mut pips: Arc<dyn Hittable + Send + Sync> = Arc::new(Sphere::new(die_center + Vec3::new(0.0, 0.65, 0.0), 0.12, Some(pip_mat.clone())));
+    for (dx, dz) in [(-0.3, -0.3), (0.3, -0.3), (-0.3, 0.3), (0.3, 0.3)] {
+        let pip = Arc::new(Sphere::new(die_center + Vec3::new(dx, 0.65, dz), 0.12, Some(pip_mat.clone())));
+        pips = Arc::new(Csg::union(pips, pip));
+    }
+    world.add(Arc::new(Csg::difference(rounded, pips)));
+
+    // 两球相交得到的双凸玻璃透镜
+    let glass = Arc::new(Dielectric::new(1.5));
+    let lens_center = Point3::new(3.4, 0.8, 1.8);
+    let lens = Csg::intersection(
+        Arc::new(Sphere::new(lens_center - Vec3::new(0.0, 0.0, 1.2), 1.4, Some(glass.clone()))),
+        Arc::new(Sphere::new(lens_center + Vec3::new(0.0, 0.0, 1.2), 1.4, Some(glass))),
+    );
+    world.add(Arc::new(lens));
+
+    // 网格地球切掉朝向观察者的八分之一，切面取盒子的材质
+    let earth = Arc::new(Lambertian::from_texture(Arc::new(ImageTexture::new("input/earthmap.jpg"))));
+    let globe_center = Point3::new(0.0, 1.6, -2.6);
+    let globe = Arc::new(uv_sphere_mesh(globe_center, 1.1, 32, 64, true, earth));
+    let core = Arc::new(Lambertian::from_color(Color::new(0.8, 0.35, 0.1)));
+    let wedge = make_box(globe_center, globe_center + Vec3::new(2.0, 2.0, 2.0), core);
+    world.add(Arc::new(Csg::difference(globe, wedge)));
+
+    let light = Arc::new(DiffuseLight::from_color(Color::new(7.0, 7.0, 7.0)));
+    world.add(Arc::new(Quad::new(Point3::new(-5.0, 7.0, -1.0), Vec3::new(6.0, 0.0, 0.0), Vec3::new(0.0, 0.0, 4.0), light)));
+
+    let mut cam = Camera::new();
+    cam.aspect_ratio = 16.0 / 9.0;
+    cam.image_width = 600;
+    cam.samples_per_pixel = 200;
+    cam.max_depth = 50;
+    cam.vfov = 38.0;
+    cam.lookfrom = Point3::new(0.0, 6.0, 9.0);
+    cam.lookat = Point3::new(0.0, 0.4, 0.0);
+    cam.vup = Vec3::new(0.0, 1.0, 0.0);
+    cam.defocus_angle = 0.0;
+    cam.focus_dist = 10.0;
+    cam.background = Color::new(0.25, 0.3, 0.4);
+    cam.set_russian_roulette_strategy(RussianRouletteStrategy::Conservative);
+
+    let lights = HittableList::new();
+    let (duration, _) = time_it(|| cam.render(&world, &lights, &mut std::io::stdout()).unwrap());
+    let mut file = OpenOptions::new().create(true).append(true).open("output.txt").unwrap();
+    writeln!(file, "CSG Showcase Render耗时: {:?}", duration).unwrap();
+}
+
+// 有向距离场图元：圆角盒、平滑并、扭曲柱、Mandelbulb 与带孔的组合体
+fn sdf_showcase() {
+    use crate::aabb::Aabb;
+    use crate::sdf::{Sdf, SdfHittable};
+    let mut world = HittableList::new();
+
+    let checker = Arc::new(CheckerTexture::from_color(0.5, Color::new(0.15, 0.15, 0.18), Color::new(0.85, 0.85, 0.85)));
+    world.add(Arc::new(Sphere::new(Point3::new(0.0, -1000.0, 0.0), 1000.0, Some(Arc::new(Lambertian::from_texture(checker))))));
+
+    // 包围盒由调用者给出：中心加半宽
+    let around = |center: Point3, half: f64| Aabb::from_points(center - Vec3::new(half, half, half), center + Vec3::new(half, half, half));
+
+    // 绕 y 轴转过 30° 的圆角盒
+    let center = Point3::new(-4.4, 0.75, 0.0);
+    let rounded = Sdf::round_box(Vec3::new(0.6, 0.6, 0.6), 0.15).rotate(Vec3::new(0.0, 1.0, 0.0), 30.0).translate(center);
+    let teal = Arc::new(Lambertian::from_color(Color::new(0.1, 0.55, 0.5)));
+    world.add(Arc::new(SdfHittable::new(rounded, around(center, 1.1), teal)));
+
+    // 两球平滑并成的水滴
+    let center = Point3::new(-2.4, 0.75, 0.0);
+    let blob = Sdf::sphere(0.6).translate(Vec3::new(-0.35, -0.1, 0.0))
+        .smooth_union(Sdf::sphere(0.4).translate(Vec3::new(0.45, 0.3, 0.0)), 0.35)
+        .translate(center);
+    let glass = Arc::new(Dielectric::new(1.5));
+    world.add(Arc::new(SdfHittable::new(blob, around(center, 1.1), glass)));
+
+    // 扭曲的方柱：距离被高估，步长减半
+    let center = Point3::new(-0.6, 1.2, 0.0);
+    let column = Sdf::cuboid(Vec3::new(0.35, 1.2, 0.35)).twist(1.3).translate(center);
+    let copper = Arc::new(Metal::new(Color::new(0.85, 0.5, 0.35), 0.15));
+    world.add(Arc::new(SdfHittable::new(column, around(center, 1.3), copper).with_step_scale(0.5).with_max_steps(512)));
+
+    // Mandelbulb 分形，放大到半径约 1.3
+    let center = Point3::new(1.5, 1.35, 0.0);
+    let bulb = Sdf::mandelbulb(8.0, 10).scale(1.1).translate(center);
+    let gold = Arc::new(Metal::new(Color::new(0.9, 0.7, 0.3), 0.3));
+    world.add(Arc::new(SdfHittable::new(bulb, around(center, 1.45), gold).with_step_scale(0.8).with_max_steps(512).with_epsilon(5e-4)));
+
+    // 经典 CSG 组合：盒与球求交，再减去三根正交圆柱
+    let center = Point3::new(3.6, 0.75, 0.0);
+    let bar = || Sdf::cylinder(0.3, 1.0);
+    let cross = bar()
+        .union(bar().rotate(Vec3::new(1.0, 0.0, 0.0), 90.0))
+        .union(bar().rotate(Vec3::new(0.0, 0.0, 1.0), 90.0));
+    let nut = Sdf::cuboid(Vec3::new(0.6, 0.6, 0.6)).intersection(Sdf::sphere(0.8)).difference(cross).translate(center);
+    let red = Arc::new(Lambertian::from_color(Color::new(0.7, 0.12, 0.1)));
+    world.add(Arc::new(SdfHittable::new(nut, around(center, 1.0), red)));
+
+    // 圆饼平滑地挖去一圈圆环，再与球平滑求交并圆化棱角
+    let center = Point3::new(5.5, 0.6, 0.0);
+    let puck = Sdf::cylinder(0.55, 0.35)
+        .smooth_difference(Sdf::torus(0.55, 0.15).translate(Vec3::new(0.0, 0.35, 0.0)), 0.08)
+        .smooth_intersection(Sdf::sphere(0.75), 0.1)
+        .round(0.03)
+        .translate(center);
+    let blue = Arc::new(Lambertian::from_color(Color::new(0.15, 0.25, 0.7)));
+    world.add(Arc::new(SdfHittable::new(puck, around(center, 0.9), blue)));
+
+    let light = Arc::new(DiffuseLight::from_color(Color::new(6.0, 6.0, 6.0)));
+    world.add(Arc::new(Quad::new(Point3::new(-6.0, 7.0, -2.0), Vec3::new(12.0, 0.0, 0.0), Vec3::new(0.0, 0.0, 4.0), light)));
+
+    let mut cam = Camera::new();
+    cam.aspect_ratio = 16.0 / 9.0;
+    cam.image_width = 600;
+    cam.samples_per_pixel = 200;
+    cam.max_depth = 50;
+    cam.vfov = 30.0;
+    cam.lookfrom = Point3::new(0.5, 3.5, 16.0);
+    cam.lookat = Point3::new(0.5, 0.9, 0.0);
+    cam.vup = Vec3::new(0.0, 1.0, 0.0);
+    cam.defocus_angle = 0.0;
+    cam.focus_dist = 16.0;
+    cam.background = Color::new(0.05, 0.05, 0.07);
+    cam.set_russian_roulette_strategy(RussianRouletteStrategy::Conservative);
+
+    let lights = HittableList::new();
+    let (duration, _) = time_it(|| cam.render(&world, &lights, &mut std::io::stdout()).unwrap());
+    let mut file = OpenOptions::new().create(true).append(true).open("output.txt").unwrap();
+    writeln!(file, "SDF Showcase Render耗时: {:?}", duration).unwrap();
+}
+
+// 灰度高度图生成的海岛，同尺寸的颜色图按 UV 贴在地形上，海面是带吸收的水
+fn island() {
+    use crate::heightfield::Heightfield;
+    let mut world = HittableList::new();
+
+    let colors = Arc::new(Lambertian::from_texture(Arc::new(ImageTexture::new("input/terrain/island_color.png"))));
+    world.add(Arc::new(Heightfield::from_image(
+        "input/terrain/island_height.png",
+        Point3::new(-20.0, 0.0, -20.0), Vec3::new(40.0, 6.0, 40.0), colors,
+    )));
+
+    // 地形之外的海床，颜色与颜色图里的深水一致
+    let seabed = Arc::new(Lambertian::from_color(Color::new(30.0 / 255.0, 70.0 / 255.0, 120.0 / 255.0)));
+    world.add(Arc::new(Quad::new(Point3::new(-60.0, -0.01, -60.0), Vec3::new(0.0, 0.0, 120.0), Vec3::new(120.0, 0.0, 0.0), seabed)));
+
+    // 海平面与颜色图里的海岸线对齐（高度 0.22）
+    let water = Arc::new(Dielectric::with_absorption(1.33, Color::new(0.25, 0.08, 0.04)));
+    world.add(Arc::new(Quad::new(Point3::new(-60.0, 1.32, -60.0), Vec3::new(0.0, 0.0, 120.0), Vec3::new(120.0, 0.0, 0.0), water)));
+
+    let sun = Arc::new(DiffuseLight::from_temperature(5800.0, 40.0));
+    world.add(Arc::new(Sphere::new(Point3::new(-60.0, 80.0, -40.0), 12.0, Some(sun))));
+
+    let mut cam = Camera::new();
+    cam.aspect_ratio = 16.0 / 9.0;
+    cam.image_width = 600;
+    cam.samples_per_pixel = 200;
+    cam.max_depth = 50;
+    cam.vfov = 40.0;
+    cam.lookfrom = Point3::new(6.0, 16.0, 38.0);
+    cam.lookat = Point3::new(0.0, 2.0, 0.0);
+    cam.vup = Vec3::new(0.0, 1.0, 0.0);
+    cam.defocus_angle = 0.0;
+    cam.focus_dist = 40.0;
+    cam.background = Color::new(0.55, 0.7, 0.95);
+    cam.set_russian_roulette_strategy(RussianRouletteStrategy::Conservative);
+
+    let lights = HittableList::new();
+    let (duration, _) = time_it(|| cam.render(&world, &lights, &mut std::io::stdout()).unwrap());
+    let mut file = OpenOptions::new().create(true).append(true).open("output.txt").unwrap();
+    writeln!(file, "Island Render耗时: {:?}", duration).unwrap();
+}
+
+// 立方体控制网格的六个面，顶点下标的三个二进制位对应 x、y、z 的正负
+const CUBE_FACES: [[usize; 4]; 6] = [[0, 2, 3, 1], [4, 5, 7, 6], [0, 1, 5, 4], [2, 6, 7, 3], [0, 4, 6, 2], [1, 3, 7, 5]];
+
+fn subdivision_cube(center: Point3, half: f64) -> SubdivisionMesh {
+    let positions = (0..8)
+        .map(|i| {
+            let sign = |bit: usize| if i & bit == 0 { -half } else { half };
+            center + Vec3::new(sign(1), sign(2), sign(4))
+        })
+        .collect();
+    SubdivisionMesh::new(positions, CUBE_FACES.iter().map(|f| f.to_vec()).collect())
+}
+
+fn subdivision_octahedron(center: Point3, radius: f64) -> SubdivisionMesh {
+    let axes = [Vec3::new(1.0, 0.0, 0.0), Vec3::new(-1.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0), Vec3::new(0.0, -1.0, 0.0), Vec3::new(0.0, 0.0, 1.0), Vec3::new(0.0, 0.0, -1.0)];
+    let positions = axes.iter().map(|&a| center + radius * a).collect();
+    let faces = vec![
+        vec![0, 2, 4], vec![2, 1, 4], vec![1, 3, 4], vec![3, 0, 4],
+        vec![2, 0, 5], vec![1, 2, 5], vec![3, 1, 5], vec![0, 3, 5],
+    ];
+    SubdivisionMesh::new(positions, faces)
+}
+
+// 细分曲面：同一个立方体控制网格在不同折痕下的结果、位移的 Loop 岩石、按屏幕空间容差细分的 OBJ 奖杯
+fn subdivision_showcase() {
+    use crate::material::LightUnit;
+    use crate::obj::load_obj_subdivided;
+    use crate::subdivision::{Refinement, Scheme};
+    let mut world = HittableList::new();
+    let mut lights = HittableList::new();
+    let mut triangles = 0;
+    let mut add_mesh = |world: &mut HittableList, mesh: TriangleMesh| {
+        triangles += mesh.triangle_count();
+        world.add(Arc::new(mesh));
+    };
+
+    let ground = Arc::new(Lambertian::from_texture(Arc::new(CheckerTexture::from_color(0.8, Color::new(0.2, 0.2, 0.22), Color::new(0.75, 0.75, 0.75)))));
+    world.add(Arc::new(Quad::new(Point3::new(-20.0, 0.0, -20.0), Vec3::new(40.0, 0.0, 0.0), Vec3::new(0.0, 0.0, 40.0), ground)));
+
+    // 从左到右：控制网格本身、完全平滑、顶面四条边为硬折痕、所有边锐度 2 的圆角盒
+    let half = 0.8;
+    let cube_at = |x: f64| subdivision_cube(Point3::new(x, half, 0.0), half);
+    let clay = Arc::new(Lambertian::from_color(Color::new(0.6, 0.6, 0.6)));
+    add_mesh(&mut world, cube_at(-5.2).into_triangle_mesh(clay));
+    let red = Arc::new(Lambertian::from_color(Color::new(0.7, 0.15, 0.1)));
+    add_mesh(&mut world, cube_at(-2.6).build(Scheme::CatmullClark, Refinement::Levels(4), red));
+    // 顶面 [2, 6, 7, 3] 的四条边
+    let creased = [(2, 6), (6, 7), (7, 3), (3, 2)].iter().fold(cube_at(0.0), |mesh, &(a, b)| mesh.with_crease(a, b, f64::INFINITY));
+    let steel = Arc::new(Metal::new(Color::new(0.8, 0.8, 0.85), 0.1));
+    add_mesh(&mut world, creased.build(Scheme::CatmullClark, Refinement::Levels(4), steel));
+    let rounded = CUBE_FACES.iter().flat_map(|f| (0..4).map(move |i| (f[i], f[(i + 1) % 4]))).fold(cube_at(2.6), |mesh, (a, b)| mesh.with_crease(a, b, 2.0));
+    let blue = Arc::new(Lambertian::from_color(Color::new(0.15, 0.3, 0.7)));
+    add_mesh(&mut world, rounded.build(Scheme::CatmullClark, Refinement::Levels(4), blue));
+
+    // Loop 细分的八面体，沿法线按噪声位移成岩石
+    let noise = Arc::new(NoiseTexture::with_scale(3.0));
+    let rock = subdivision_octahedron(Point3::new(5.2, 0.95, 0.0), 1.1).with_displacement(noise.clone(), 0.12);
+    add_mesh(&mut world, rock.build(Scheme::Loop, Refinement::Levels(5), Arc::new(Lambertian::from_texture(noise))));
+
+    let mut cam = Camera::new();
+    cam.aspect_ratio = 16.0 / 9.0;
+    cam.image_width = 600;
+    cam.samples_per_pixel = 200;
+    cam.max_depth = 50;
+    cam.vfov = 30.0;
+    cam.lookfrom = Point3::new(0.0, 4.0, 15.0);
+    cam.lookat = Point3::new(0.0, 0.8, 0.0);
+    cam.vup = Vec3::new(0.0, 1.0, 0.0);
+    cam.defocus_angle = 0.0;
+    cam.focus_dist = 15.0;
+    cam.background = Color::new(0.45, 0.55, 0.7);
+
+    // 前排的 OBJ 奖杯：每条边细分到在画面上不超过 2 个像素
+    let image_height = (cam.image_width as f64 / cam.aspect_ratio) as usize;
+    let refinement = Refinement::screen_space(cam.lookfrom, cam.vfov, image_height, 2.0);
+    match load_obj_subdivided("input/models/trophy.obj", Scheme::CatmullClark, refinement) {
+        Ok(mut trophy) => {
+            let trophy = Arc::new(BvhNode::new_from_list(&mut trophy.objects));
+            world.add(Arc::new(Translate::new(trophy, Vec3::new(-1.3, 0.0, 3.0))));
+        }
+        Err(e) => eprintln!("ERROR: Could not load OBJ model: {}", e),
+    }
+
+    // 头顶的细分球形灯，按网格面积换算功率
+    let lamp = || subdivision_octahedron(Point3::new(1.5, 6.5, 4.0), 2.0);
+    let unlit = lamp().build(Scheme::Loop, Refinement::Levels(3), Arc::new(Lambertian::from_color(Color::new(0.0, 0.0, 0.0))));
+    let glow = Arc::new(DiffuseLight::from_temperature_and_power(4500.0, LightUnit::Watts(300.0), unlit.area()));
+    let lamp = Arc::new(lamp().build(Scheme::Loop, Refinement::Levels(3), glow));
+    world.add(lamp.clone());
+    lights.add(lamp);
+
+    let world = HittableList::with_object(Arc::new(BvhNode::new_from_list(&mut world.objects)));
+    let (duration, _) = time_it(|| cam.render(&world, &lights, &mut std::io::stdout()).unwrap());
+    let mut file = OpenOptions::new().create(true).append(true).open("output.txt").unwrap();
+    writeln!(file, "Subdivision Showcase Render耗时: {:?}（{} 个三角形）", duration, triangles).unwrap();
+}
+
 fn former_final_scene(image_width: usize, samples_per_pixel: usize, max_depth: usize) {
     let mut boxes = HittableList::new();
     let ground = Arc::new(Lambertian::from_color(Color::new(0.48, 0.83, 0.53)));
 
     let boxes_per_side = 20;
+    let unit_box = make_prototype(&make_box(Point3::new(0.0, 0.0, 0.0), Point3::new(1.0, 1.0, 1.0), ground.clone()));
     // 预计算常量，避免重复计算
     let w = 100.0;
     let base_x = -1000.0;
@@ -655,12 +1655,11 @@ fn former_final_scene(image_width: usize, samples_per_pixel: usize, max_depth: u
             let z1 = z0 + w;
             let y1 = random_double_range(1.0, 101.0);
 
-            // 避免在循环中clone，直接使用引用
-            boxes.addlist(make_box(
-                Point3::new(x0, y0, z0),
-                Point3::new(x1, y1, z1),
-                ground.clone(),
-            ));
+            // 所有地面方块共享一个单位立方体原型，按高度缩放后平移到位
+            let placement = Mat4::translate(Vec3::new(x0, y0, z0)) * Mat4::scale(Vec3::new(x1 - x0, y1 - y0, z1 - z0));
+            if let Some(instance) = Instance::new(unit_box.clone(), placement) {
+                boxes.add(Arc::new(instance));
+            }
         }
     }
 
@@ -931,6 +1930,30 @@ fn final_scene_2(image_width: usize, samples_per_pixel: usize, max_depth: usize)
     let top_y = base_y + box_height;
     let box_color = Arc::new(Lambertian::from_color(slab_color));
     let strip_light_mat = Arc::new(DiffuseLight::from_color(Color::new(8.0, 8.0, 8.0)));
+    // 石板、数字牌和灯带各建一个原型，环上的每块石板只是它们的实例
+    let slab_proto = make_prototype(&make_box(
+        Point3::new(-box_width/2.0, base_y, -box_depth/2.0),
+        Point3::new(box_width/2.0, top_y, box_depth/2.0),
+        box_color.clone(),
+    ));
+    let num_height = box_height / 3.0;
+    let num_width = box_width * 0.8;
+    let y_center = base_y + box_height * 0.75;
+    let number_proto: Arc<dyn Hittable + Send + Sync> = Arc::new(Quad::new(
+        Point3::new(-num_width/2.0, y_center - num_height/2.0, -box_depth/2.0 - 0.1),
+        Vec3::new(num_width, 0.0, 0.0),
+        Vec3::new(0.0, num_height, 0.0),
+        number_textures[0].clone(),
+    ));
+    let strip_width = box_width * 0.8;
+    let strip_height = 6.0;
+    let strip_y = base_y + 2.0;
+    let strip_proto: Arc<dyn Hittable + Send + Sync> = Arc::new(Quad::new(
+        Point3::new(-strip_width/2.0, strip_y, box_depth/2.0 + 0.2),
+        Vec3::new(strip_width, 0.0, 0.0),
+        Vec3::new(0.0, strip_height, 0.0),
+        strip_light_mat.clone(),
+    ));
     let mut slab_idx = 0;
     for i in 0..box_count {
         if i == box_count-3 {
@@ -940,37 +1963,16 @@ fn final_scene_2(image_width: usize, samples_per_pixel: usize, max_depth: usize)
         let x = 0.0 + radius * theta.cos(); // 圆心x=0
         let z = 0.0 + radius * theta.sin(); // 圆心z=0
         let angle = -theta * 180.0 / PI + 90.0;
-        let box_obj = make_box(
-            Point3::new(-box_width/2.0, base_y, -box_depth/2.0),
-            Point3::new(box_width/2.0, top_y, box_depth/2.0),
-            box_color.clone(),
-        );
-        let box_obj = Arc::new(RotateY::new(box_obj, angle));
-        let box_obj = Arc::new(Translate::new(box_obj, Vec3::new(x, 0.0, z)));
-        world.add(box_obj.clone());
+        let placement = Mat4::translate(Vec3::new(x, 0.0, z)) * Mat4::rotate_y(angle);
+        // 旋转 + 平移总是可逆的
+        let instance = |proto: &Arc<dyn Hittable + Send + Sync>| Instance::new(proto.clone(), placement).expect("rigid placement is invertible");
+        world.add(Arc::new(instance(&slab_proto)));
         if slab_idx < number_textures.len() {
             let num_mat = number_textures[slab_idx].clone();
-            let num_height = box_height / 3.0;
-            let num_width = box_width * 0.8;
-            let y_center = base_y + box_height * 0.75;
-            let quad_origin = Point3::new(-num_width/2.0, y_center - num_height/2.0, -box_depth/2.0 - 0.1);
-            let u_vec = Vec3::new(num_width, 0.0, 0.0);
-            let v_vec = Vec3::new(0.0, num_height, 0.0);
-            let rot = RotateY::new(Arc::new(Quad::new(quad_origin, u_vec, v_vec, num_mat)), angle);
-            let quad = Translate::new(Arc::new(rot), Vec3::new(x, 0.0, z));
-            world.add(Arc::new(quad));
+            world.add(Arc::new(instance(&number_proto).with_material(num_mat)));
             slab_idx += 1;
         }
-        let strip_width = box_width * 0.8;
-        let strip_height = 6.0;
-        let strip_y = base_y + 2.0;
-        let strip_origin = Point3::new(-strip_width/2.0, strip_y, box_depth/2.0 + 0.2);
-        let strip_u = Vec3::new(strip_width, 0.0, 0.0);
-        let strip_v = Vec3::new(0.0, strip_height, 0.0);
-        let strip_quad = Quad::new(strip_origin, strip_u, strip_v, strip_light_mat.clone());
-        let strip_rot = RotateY::new(Arc::new(strip_quad), angle);
-        let strip = Translate::new(Arc::new(strip_rot), Vec3::new(x, 0.0, z));
-        world.add(Arc::new(strip));
+        world.add(Arc::new(instance(&strip_proto)));
     }
 
     // 画面比例和高度
@@ -1002,8 +2004,13 @@ fn final_scene_2(image_width: usize, samples_per_pixel: usize, max_depth: usize)
     let jupiter_mat = Arc::new(Lambertian::from_texture_with_albedo(jupiter_tex, jupiter_albedo_boost));
     world.add(Arc::new(Sphere::new(jupiter_center, r_jupiter, Some(jupiter_mat))));
 
-    // 木星大气辉光层：包裹一层稍大的球体，使用ConstantcMedium和淡色
-    // 已去除木星大气辉光层
+    // 木星大气辉光层：Rayleigh + Mie 散射的大气壳，密度随高度指数衰减，
+    // 靠近太阳一侧的边缘透出淡蓝的辉光；散射点对光源列表中的太阳做单次散射估计，薄大气里噪声更小
+    let jupiter_atmosphere = Atmosphere::new(jupiter_center, r_jupiter, r_jupiter * 0.05)
+        .with_rayleigh(Color::new(0.10, 0.20, 0.45), r_jupiter * 0.012)
+        .with_mie(0.06, 0.95, r_jupiter * 0.004, 0.7)
+        .with_single_scattering();
+    world.add(Arc::new(jupiter_atmosphere));
 
     // 背景贴图为star.jpg
 
@@ -1027,7 +2034,7 @@ fn final_scene_2(image_width: usize, samples_per_pixel: usize, max_depth: usize)
     cam.focus_dist = 10.0;
     cam.set_russian_roulette_strategy(RussianRouletteStrategy::None);
 
-    // 构建光源列表，将太阳球加入lights，便于直接采样
+    // 构建光源列表，将太阳球加入lights，便于直接采样（大气的单次散射由此采样太阳）
     let mut lights = HittableList::new();
     lights.add(Arc::new(Sphere::new(sun_center, r_sun, None)));
     let (duration, _) = crate::rtweekend::time_it(|| cam.render(&world, &lights, &mut std::io::stdout()));
@@ -1082,6 +2089,19 @@ fn main() {
         10 => former_final_scene(400, 500, 20),
         11 => final_scene_1(800, 200, 20),
         12 => final_scene_2(1600, 4000, 40), // 1600x900, 400采样，20递归
+        13 => saturn(),
+        14 => fur_ball(),
+        15 => heterogeneous_smoke(),
+        16 => fireball(),
+        17 => material_showcase(),
+        18 => dispersion(),
+        19 => mesh_showcase(),
+        20 => gltf_gallery(),
+        21 => primitive_gallery(),
+        22 => csg_showcase(),
+        23 => sdf_showcase(),
+        24 => island(),
+        25 => subdivision_showcase(),
         _ => former_final_scene(400, 250, 4),
     }
 }
\ No newline at end of file
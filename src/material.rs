@@ -39,8 +39,8 @@ impl Material for NumberMaterial {
             false
         }
     }
-    fn emitted(&self, u: f64, v: f64, p: &crate::vec3::Vec3) -> crate::Color {
-        let c = self.base.value(u, v, p);
+    fn emitted(&self, _r_in: &crate::Ray, rec: &crate::hittable::HitRecord) -> crate::Color {
+        let c = self.base.value(rec.u, rec.v, &rec.p);
         if self.is_digit(&c) {
             self.emit_color
         } else {
@@ -52,8 +52,12 @@ impl Material for NumberMaterial {
 use crate::{Ray, Color};
 use crate::vec3::Vec3;
 use crate::texture::*;
+use crate::medium_stack::MediumEntry;
+use crate::spectrum::{self, Dispersion, Wavelengths};
+use crate::thin_film::ThinFilm;
 use crate::hittable::{HitRecord, Hittable};
 use crate::rtweekend::random::{random_double, random_unit_vector_with_rng};
+use crate::rtweekend::PI;
 use std::sync::Arc;
 use rand::{rngs::ThreadRng, Rng};
 
@@ -85,8 +89,25 @@ pub trait Material {
         0.0
     }
 
-    fn emitted(&self, u: f64, v: f64, p: &Vec3) -> Color {
-        self.emission_color(u, v, p)
+    // 彩色 BSDF × cosθ（如测量数据）；返回 None 时使用 attenuation × scattering_pdf
+    fn eval_bsdf(&self, _r_in: &Ray, _rec: &HitRecord, _scattered: &Ray) -> Option<Color> {
+        None
+    }
+
+    // 可见入射光线与完整的命中信息（front_face、法线等），用于单面发光等
+    fn emitted(&self, _r_in: &Ray, rec: &HitRecord) -> Color {
+        self.emission_color(rec.u, rec.v, &rec.p)
+    }
+
+    // 在路径的采样波长处求值的自发光；随波长变化的不只是 RGB 颜色时（如介质内逐波长的透射率）需要覆盖
+    fn emitted_at_wavelengths(&self, r_in: &Ray, rec: &HitRecord, wavelengths: &Wavelengths) -> Vec3 {
+        spectrum::uplift(&self.emitted(r_in, rec), wavelengths)
+    }
+
+    // 散射点处对场景光源的下一事件估计（如介质的单次散射），与自发光一样加到出射辐亮度上，按路径的通道给出；
+    // 方向由 lights 的 random / pdf_value 采样，阴影光线穿过 world
+    fn direct_light(&self, _r_in: &Ray, _rec: &HitRecord, _world: &dyn Hittable, _lights: &dyn Hittable) -> Vec3 {
+        Vec3::default()
     }
 }
 
@@ -122,7 +143,7 @@ impl Material for Lambertian {
     ) -> bool {
         use crate::pdf::CosinePdf;
         use std::sync::Arc;
-        srec.attenuation = self.tex.value(rec.u, rec.v, &rec.p) * self.albedo;
+        srec.attenuation = self.tex.value_at(rec) * self.albedo;
         srec.pdf_ptr = Some(Arc::new(CosinePdf::new(rec.normal)));
         srec.skip_pdf = false;
         srec.skip_pdf_ray = None;
@@ -142,6 +163,9 @@ impl Material for Lambertian {
 pub struct Metal {
     pub albedo: Color,
     pub fuzz: f64,
+    pub film: Option<ThinFilm>, // 薄膜涂层（阳极氧化等），albedo 作为基底法向反射率
+    pub albedo_tex: Option<Arc<dyn Texture + Send + Sync>>, // 与 albedo 相乘
+    pub fuzz_tex: Option<Arc<dyn Texture + Send + Sync>>,   // 通道均值即 fuzz，取代常数 fuzz
 }
 
 impl Metal {
@@ -149,6 +173,45 @@ impl Metal {
         Metal {
             albedo,
             fuzz: if fuzz < 1.0 { fuzz } else { 1.0 },
+            film: None,
+            albedo_tex: None,
+            fuzz_tex: None,
+        }
+    }
+
+    pub fn from_texture(tex: Arc<dyn Texture + Send + Sync>, fuzz: f64) -> Self {
+        Self::new(Color::new(1.0, 1.0, 1.0), fuzz).with_albedo_texture(tex)
+    }
+
+    pub fn with_thin_film(mut self, film: ThinFilm) -> Self {
+        self.film = Some(film);
+        self
+    }
+
+    pub fn with_albedo_texture(mut self, tex: Arc<dyn Texture + Send + Sync>) -> Self {
+        self.albedo_tex = Some(tex);
+        self
+    }
+
+    pub fn with_fuzz_texture(mut self, tex: Arc<dyn Texture + Send + Sync>) -> Self {
+        self.fuzz_tex = Some(tex);
+        self
+    }
+
+    fn albedo_at(&self, rec: &HitRecord) -> Color {
+        match self.albedo_tex {
+            Some(ref tex) => tex.value_at(rec) * self.albedo,
+            None => self.albedo,
+        }
+    }
+
+    fn fuzz_at(&self, rec: &HitRecord) -> f64 {
+        match self.fuzz_tex {
+            Some(ref tex) => {
+                let c = tex.value_at(rec);
+                ((c.x + c.y + c.z) / 3.0).clamp(0.0, 1.0)
+            }
+            None => self.fuzz,
         }
     }
 }
@@ -163,21 +226,90 @@ impl Material for Metal {
         rng: &mut ThreadRng,
     ) -> bool {
         let mut reflected = Vec3::reflect(&r_in.direction, &rec.normal);
-        reflected = Vec3::unit_vector(reflected) + (self.fuzz * random_unit_vector_with_rng(rng));
+        reflected = Vec3::unit_vector(reflected) + (self.fuzz_at(rec) * random_unit_vector_with_rng(rng));
 
-        srec.attenuation = self.albedo;
+        let albedo = self.albedo_at(rec);
+        srec.attenuation = albedo;
+        if let Some(ref film) = self.film {
+            // 光谱模式下基底反射率需先上采样到路径波长
+            let wavelengths = r_in.wavelengths.as_ref();
+            let r0 = wavelengths.map_or(albedo, |wl| spectrum::uplift(&albedo, wl));
+            let r0 = [r0.x, r0.y, r0.z];
+            let n1 = r_in.media.top().map_or(1.0, |m| m.ior);
+            let cos1 = Vec3::dot(&-Vec3::unit_vector(r_in.direction), &rec.normal).clamp(0.0, 1.0);
+            let thickness = film.thickness_at(rec);
+            srec.attenuation = ThinFilm::eval(wavelengths, |lambda, i| {
+                film.reflectance_over_conductor(thickness, n1, cos1, r0[i], lambda)
+            });
+            srec.spectral = wavelengths.is_some();
+        }
         srec.pdf_ptr = None;
         srec.skip_pdf = true;
-        srec.skip_pdf_ray = Some(Ray::new(rec.p, reflected, r_in.time()));
+        srec.skip_pdf_ray = Some(r_in.spawn(rec.p, reflected));
 
         true
     }
 }
 
-pub struct Dielectric { pub refraction_index: f64,}
+pub struct Dielectric {
+    pub refraction_index: f64,
+    pub absorption: Color, // Beer–Lambert 吸收系数（每单位长度），决定有色玻璃的颜色
+    pub priority: u32,     // 重叠介质的优先级，数值越大越优先
+    pub dispersion: Dispersion, // 光谱模式下的色散模型；RGB 模式使用 refraction_index
+    pub film: Option<ThinFilm>, // 表面薄膜涂层，调制菲涅尔反射
+}
 
 impl Dielectric {
-    pub fn new(refraction_index: f64) -> Self { Dielectric { refraction_index }}
+    pub fn new(refraction_index: f64) -> Self { Self::with_absorption(refraction_index, Color::new(0.0, 0.0, 0.0)) }
+
+    pub fn with_absorption(refraction_index: f64, absorption: Color) -> Self {
+        Dielectric { refraction_index, absorption, priority: 0, dispersion: Dispersion::None, film: None }
+    }
+
+    // 色散电介质，refraction_index 取 d 线（587.6nm）处的值
+    pub fn from_dispersion(dispersion: Dispersion) -> Self {
+        let mut d = Self::new(dispersion.ior(Dispersion::D_LINE).unwrap_or(1.5));
+        d.dispersion = dispersion;
+        d
+    }
+
+    pub fn cauchy(a: f64, b: f64) -> Self {
+        Self::from_dispersion(Dispersion::Cauchy { a, b })
+    }
+
+    pub fn sellmeier(b: [f64; 3], c: [f64; 3]) -> Self {
+        Self::from_dispersion(Dispersion::Sellmeier { b, c })
+    }
+
+    // 该路径上的折射率：光谱模式下按主波长计算
+    fn path_ior(&self, r: &Ray) -> f64 {
+        r.wavelengths
+            .and_then(|wl| self.dispersion.ior(wl.hero()))
+            .unwrap_or(self.refraction_index)
+    }
+
+    pub fn with_priority(mut self, priority: u32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    pub fn with_thin_film(mut self, film: ThinFilm) -> Self {
+        self.film = Some(film);
+        self
+    }
+
+    fn medium_id(&self) -> usize {
+        self as *const Self as usize
+    }
+
+    fn medium_entry(&self, ior: f64) -> MediumEntry {
+        MediumEntry {
+            id: self.medium_id(),
+            priority: self.priority,
+            ior,
+            absorption: self.absorption,
+        }
+    }
 
     // Schlick 近似反射率
     fn reflectance(cosine: f64, ref_idx: f64) -> f64 {
@@ -189,6 +321,7 @@ impl Dielectric {
 
 impl Material for Dielectric {
     // C++: bool scatter(const ray& r_in, const hit_record& rec, scatter_record& srec) const override
+    // 吸收在 Camera 中按光线介质栈对每段路径统一计算
     fn scatter(
         &self,
         r_in: &Ray,
@@ -199,34 +332,168 @@ impl Material for Dielectric {
         srec.attenuation = Color::new(1.0, 1.0, 1.0);
         srec.pdf_ptr = None;
         srec.skip_pdf = true;
-        let ri = if rec.front_face { 1.0 / self.refraction_index } else { self.refraction_index };
         let unit_direction = Vec3::unit_vector(r_in.direction);
+        let ior = self.path_ior(r_in);
+
+        // 穿过界面后的介质栈
+        let id = self.medium_id();
+        let mut media = r_in.media;
+        if rec.front_face {
+            media.push(self.medium_entry(ior));
+        } else {
+            media.remove(id);
+        }
+
+        // 被更高优先级介质包含的表面是伪界面，直接穿过
+        let outside = r_in.media.top_excluding(id);
+        if outside.is_some_and(|m| m.priority > self.priority) {
+            let mut passed = r_in.spawn(rec.p, unit_direction);
+            passed.media = media;
+            srec.skip_pdf_ray = Some(passed);
+            return true;
+        }
+
+        // 界面两侧的折射率由介质栈给出，栈为空时外侧为真空
+        let outside_ior = outside.map_or(1.0, |m| m.ior);
+        let ri = if rec.front_face { outside_ior / ior } else { ior / outside_ior };
         let cos_theta = Vec3::dot(&-unit_direction, &rec.normal).min(1.0);
         let sin_theta = (1.0 - cos_theta * cos_theta).sqrt();
         let cannot_refract = ri * sin_theta > 1.0;
-        let direction = if cannot_refract || Dielectric::reflectance(cos_theta, ri) > random_double() {
-            Vec3::reflect(&unit_direction, &rec.normal)
+        let reflects = if cannot_refract {
+            true
+        } else if let Some(ref film) = self.film {
+            // 薄膜反射率随波长变化：按均值选择反射/折射，衰减除以选择概率
+            let (n1, n3) = if rec.front_face { (outside_ior, ior) } else { (ior, outside_ior) };
+            let wavelengths = r_in.wavelengths.as_ref();
+            let thickness = film.thickness_at(rec);
+            let reflectance = ThinFilm::eval(wavelengths, |lambda, _| {
+                film.reflectance(thickness, n1, cos_theta, n3, lambda)
+            });
+            let p = ((reflectance.x + reflectance.y + reflectance.z) / 3.0).clamp(1e-4, 1.0 - 1e-4);
+            let reflects = p > random_double();
+            srec.attenuation = if reflects {
+                reflectance / p
+            } else {
+                (Color::new(1.0, 1.0, 1.0) - reflectance) / (1.0 - p)
+            };
+            srec.spectral = wavelengths.is_some();
+            reflects
+        } else {
+            Dielectric::reflectance(cos_theta, ri) > random_double()
+        };
+        srec.skip_pdf_ray = if reflects {
+            Some(r_in.spawn(rec.p, Vec3::reflect(&unit_direction, &rec.normal)))
         } else {
-            Vec3::refract(&unit_direction, &rec.normal, ri)
+            let mut refracted = r_in.spawn(rec.p, Vec3::refract(&unit_direction, &rec.normal, ri));
+            refracted.media = media;
+            Some(refracted)
         };
-        srec.skip_pdf_ray = Some(Ray::new(rec.p, direction, r_in.time()));
+        // 色散使方向依赖于波长，副波长无法沿同一路径继续
+        if self.dispersion.is_dispersive() {
+            if let Some(ray) = srec.skip_pdf_ray.as_mut() {
+                if let Some(wl) = ray.wavelengths.as_mut() {
+                    wl.terminate_secondary();
+                }
+            }
+        }
         true
     }
 }
 
+// 光源强度的物理单位。渲染器的辐亮度单位为 W·sr⁻¹·m⁻²，场景长度单位按米计；
+// 光度量按 683 lm/W 换算。发光颜色应归一化为亮度 1（如色温颜色）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LightUnit {
+    Watts(f64),  // 辐射通量
+    Lumens(f64), // 光通量
+    Nits(f64),   // 亮度（cd/m²），与面积无关
+}
+
+impl LightUnit {
+    pub const LUMINOUS_EFFICACY: f64 = 683.0;
+
+    // 朗伯发光面的辐亮度：L = Φ / (π·A)，A 为通量实际离开的面积
+    pub fn radiance(&self, emitting_area: f64) -> f64 {
+        let flux_to_radiance = |flux: f64| if emitting_area > 0.0 { flux / (PI * emitting_area) } else { 0.0 };
+        match *self {
+            LightUnit::Watts(w) => flux_to_radiance(w),
+            LightUnit::Lumens(lm) => flux_to_radiance(lm / Self::LUMINOUS_EFFICACY),
+            LightUnit::Nits(nits) => nits / Self::LUMINOUS_EFFICACY,
+        }
+    }
+}
+
 pub struct DiffuseLight {
     pub tex: Arc<dyn Texture + Send + Sync>,
+    pub two_sided: bool,          // 单面时只有法线一侧（front_face）发光
+    pub intensity: f64,           // 发光强度缩放
+    pub temperature: Option<f64>, // 黑体色温（K），与纹理颜色相乘
+    pub power: Option<(LightUnit, f64, bool)>, // 物理单位强度、面积与是否为开放曲面，与 intensity 相乘
+    blackbody: Color,             // 色温对应的归一化颜色缓存
 }
 
 impl DiffuseLight {
     pub fn from_texture(tex: Arc<dyn Texture + Send + Sync>) -> Self {
-        DiffuseLight { tex }
+        DiffuseLight {
+            tex,
+            two_sided: true,
+            intensity: 1.0,
+            temperature: None,
+            power: None,
+            blackbody: Color::new(1.0, 1.0, 1.0),
+        }
     }
 
     pub fn from_color(emit: Color) -> Self {
-        DiffuseLight {
-            tex: Arc::new(SolidColor::new(emit)),
-        }
+        Self::from_texture(Arc::new(SolidColor::new(emit)))
+    }
+
+    // 黑体光源：颜色由色温决定，亮度由 intensity 决定
+    pub fn from_temperature(kelvin: f64, intensity: f64) -> Self {
+        Self::from_color(Color::new(1.0, 1.0, 1.0))
+            .with_temperature(kelvin)
+            .with_intensity(intensity)
+    }
+
+    pub fn with_intensity(mut self, intensity: f64) -> Self {
+        self.intensity = intensity;
+        self
+    }
+
+    pub fn with_temperature(mut self, kelvin: f64) -> Self {
+        self.temperature = Some(kelvin);
+        self.blackbody = spectrum::color_temperature_rgb(kelvin);
+        self
+    }
+
+    // 以物理单位指定强度；area 为封闭发光体（球、盒、封闭网格）的表面积，
+    // 通量只从外侧离开，与 two_sided 无关。使不同大小的光源在相同功率下亮度一致
+    pub fn with_power(mut self, unit: LightUnit, area: f64) -> Self {
+        self.power = Some((unit, area, false));
+        self
+    }
+
+    // 开放曲面（Quad、圆盘等）的物理单位强度；双面发光时通量分到两侧，求值时按当前的 two_sided 决定
+    pub fn with_power_open(mut self, unit: LightUnit, area: f64) -> Self {
+        self.power = Some((unit, area, true));
+        self
+    }
+
+    // 色温 + 物理单位的光源
+    pub fn from_temperature_and_power(kelvin: f64, unit: LightUnit, area: f64) -> Self {
+        Self::from_temperature(kelvin, 1.0).with_power(unit, area)
+    }
+
+    fn radiance_scale(&self) -> f64 {
+        let physical = self.power.map_or(1.0, |(unit, area, open)| {
+            unit.radiance(if open && self.two_sided { 2.0 * area } else { area })
+        });
+        self.intensity * physical
+    }
+
+    pub fn one_sided(mut self) -> Self {
+        self.two_sided = false;
+        self
     }
 }
 
@@ -245,7 +512,14 @@ impl Material for DiffuseLight {
     // 保留 trait 默认实现，不在此重复定义
 
     fn emission_color(&self, u: f64, v: f64, p: &Vec3) -> Color {
-        self.tex.value(u, v, p)
+        self.tex.value(u, v, p) * self.blackbody * self.radiance_scale()
+    }
+
+    fn emitted(&self, _r_in: &Ray, rec: &HitRecord) -> Color {
+        if !self.two_sided && !rec.front_face {
+            return Color::new(0.0, 0.0, 0.0);
+        }
+        self.tex.value_at(rec) * self.blackbody * self.radiance_scale()
     }
 
     fn is_emissive(&self) -> bool {
@@ -281,7 +555,7 @@ impl Material for Isotropic {
     ) -> bool {
         use crate::pdf::SpherePdf;
         use std::sync::Arc;
-        srec.attenuation = self.tex.value(rec.u, rec.v, &rec.p);
+        srec.attenuation = self.tex.value_at(rec);
         srec.pdf_ptr = Some(Arc::new(SpherePdf::new()));
         srec.skip_pdf = false;
         srec.skip_pdf_ray = None;
@@ -293,9 +567,550 @@ impl Material for Isotropic {
     }
 }
 
+// 由命中点和入射方向得到 [0,1) 的确定性伪随机数。
+// 组合材质在 scatter 与 scattering_pdf 中需要做出相同的选择，而两次调用之间没有共享状态，
+// 因此用同一组输入重新计算即可保持一致；salt 用于区分嵌套的组合材质。
+fn hit_hash(r_in: &Ray, rec: &HitRecord, salt: usize) -> f64 {
+    let mut h = salt as u64 ^ 0x9e37_79b9_7f4a_7c15;
+    for x in [rec.p.x, rec.p.y, rec.p.z, r_in.direction.x, r_in.direction.y, r_in.direction.z] {
+        // splitmix64
+        h = h.wrapping_add(x.to_bits()).wrapping_add(0x9e37_79b9_7f4a_7c15);
+        h = (h ^ (h >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        h = (h ^ (h >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        h ^= h >> 31;
+    }
+    (h >> 11) as f64 / (1u64 << 53) as f64
+}
+
+// 混合方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MixMode {
+    Stochastic, // 按遮罩概率随机选择其中一种材质
+    Weighted,   // 按遮罩权重混合两种材质的散射瓣
+}
+
+// 混合材质：遮罩纹理的取值（三通道平均）为 b 的权重，1 - 取值为 a 的权重
+pub struct MixMaterial {
+    pub a: Arc<dyn Material + Send + Sync>,
+    pub b: Arc<dyn Material + Send + Sync>,
+    pub mask: Arc<dyn Texture + Send + Sync>,
+    pub mode: MixMode,
+}
+
+impl MixMaterial {
+    pub fn new(
+        a: Arc<dyn Material + Send + Sync>,
+        b: Arc<dyn Material + Send + Sync>,
+        mask: Arc<dyn Texture + Send + Sync>,
+        mode: MixMode,
+    ) -> Self {
+        MixMaterial { a, b, mask, mode }
+    }
+
+    pub fn stochastic(a: Arc<dyn Material + Send + Sync>, b: Arc<dyn Material + Send + Sync>, mask: Arc<dyn Texture + Send + Sync>) -> Self {
+        Self::new(a, b, mask, MixMode::Stochastic)
+    }
+
+    pub fn weighted(a: Arc<dyn Material + Send + Sync>, b: Arc<dyn Material + Send + Sync>, mask: Arc<dyn Texture + Send + Sync>) -> Self {
+        Self::new(a, b, mask, MixMode::Weighted)
+    }
+
+    fn weight(&self, rec: &HitRecord) -> f64 {
+        let m = self.mask.value_at(rec);
+        ((m.x + m.y + m.z) / 3.0).clamp(0.0, 1.0)
+    }
+
+    fn choose_b(&self, r_in: &Ray, rec: &HitRecord) -> bool {
+        hit_hash(r_in, rec, self as *const Self as usize) < self.weight(rec)
+    }
+}
+
+impl Material for MixMaterial {
+    fn is_emissive(&self) -> bool {
+        self.a.is_emissive() || self.b.is_emissive()
+    }
+
+    fn scatter(
+        &self,
+        r_in: &Ray,
+        rec: &HitRecord,
+        srec: &mut ScatterRecord,
+        rng: &mut ThreadRng,
+    ) -> bool {
+        let chosen = if self.choose_b(r_in, rec) { &self.b } else { &self.a };
+        if self.mode == MixMode::Stochastic {
+            return chosen.scatter(r_in, rec, srec, rng);
+        }
+
+        // Weighted：两者都基于 PDF 时按混合 PDF 采样方向，颜色全部由 eval_bsdf 给出
+        let w = self.weight(rec);
+        let mut srec_a = ScatterRecord::new();
+        let mut srec_b = ScatterRecord::new();
+        let scatter_a = self.a.scatter(r_in, rec, &mut srec_a, rng) && !srec_a.skip_pdf;
+        let scatter_b = self.b.scatter(r_in, rec, &mut srec_b, rng) && !srec_b.skip_pdf;
+        if let (true, true, Some(pdf_a), Some(pdf_b)) = (scatter_a, scatter_b, srec_a.pdf_ptr.clone(), srec_b.pdf_ptr.clone()) {
+            srec.attenuation = Color::new(1.0, 1.0, 1.0);
+            srec.spectral = false;
+            srec.pdf_ptr = Some(Arc::new(crate::pdf::MixturePdf::with_weight(pdf_a, pdf_b, w)));
+            srec.skip_pdf = false;
+            srec.skip_pdf_ray = None;
+            return true;
+        }
+
+        // 含镜面或不散射的一方时退化为随机选择；eval_bsdf 仍按权重混合，
+        // 而镜面一方在其中恒为 0，因此被选中的漫反射一方需除以自身权重
+        if !chosen.scatter(r_in, rec, srec, rng) {
+            return false;
+        }
+        if !srec.skip_pdf {
+            let chosen_weight = if std::ptr::eq(chosen, &self.b) { w } else { 1.0 - w };
+            srec.attenuation = Color::new(1.0, 1.0, 1.0) / chosen_weight;
+            srec.spectral = false;
+        }
+        true
+    }
+
+    fn scattering_pdf(&self, r_in: &Ray, rec: &HitRecord, scattered: &Ray) -> f64 {
+        match self.mode {
+            MixMode::Stochastic => {
+                let chosen = if self.choose_b(r_in, rec) { &self.b } else { &self.a };
+                chosen.scattering_pdf(r_in, rec, scattered)
+            }
+            MixMode::Weighted => {
+                let w = self.weight(rec);
+                (1.0 - w) * self.a.scattering_pdf(r_in, rec, scattered) + w * self.b.scattering_pdf(r_in, rec, scattered)
+            }
+        }
+    }
+
+    // Weighted：(1 - w)·f_a + w·f_b，不提供 eval_bsdf 的一方取 attenuation × scattering_pdf
+    fn eval_bsdf(&self, r_in: &Ray, rec: &HitRecord, scattered: &Ray) -> Option<Color> {
+        match self.mode {
+            MixMode::Stochastic => {
+                let chosen = if self.choose_b(r_in, rec) { &self.b } else { &self.a };
+                chosen.eval_bsdf(r_in, rec, scattered)
+            }
+            MixMode::Weighted => {
+                let lobe = |m: &Arc<dyn Material + Send + Sync>| {
+                    m.eval_bsdf(r_in, rec, scattered).unwrap_or_else(|| {
+                        let mut srec = ScatterRecord::new();
+                        if m.scatter(r_in, rec, &mut srec, &mut rand::thread_rng()) && !srec.skip_pdf {
+                            srec.attenuation * m.scattering_pdf(r_in, rec, scattered)
+                        } else {
+                            Color::new(0.0, 0.0, 0.0)
+                        }
+                    })
+                };
+                let w = self.weight(rec);
+                Some(lobe(&self.a) * (1.0 - w) + lobe(&self.b) * w)
+            }
+        }
+    }
+
+    fn emitted(&self, r_in: &Ray, rec: &HitRecord) -> Color {
+        // 发光与散射选择无关，直接按权重混合，方差更低
+        let w = self.weight(rec);
+        self.a.emitted(r_in, rec) * (1.0 - w) + self.b.emitted(r_in, rec) * w
+    }
+}
+
+// 清漆涂层材质：在任意基底材质上覆盖一层电介质透明涂层。
+// 正面命中时按 Schlick 菲涅尔项随机选择涂层镜面反射或进入基底，权重与选择概率相互抵消；
+// tint 为光线往返穿过涂层后的颜色衰减
+pub struct CoatedMaterial {
+    pub base: Arc<dyn Material + Send + Sync>,
+    pub coat_ior: f64,
+    pub tint: Color,
+}
+
+impl CoatedMaterial {
+    pub fn new(base: Arc<dyn Material + Send + Sync>, coat_ior: f64) -> Self {
+        Self::with_tint(base, coat_ior, Color::new(1.0, 1.0, 1.0))
+    }
+
+    pub fn with_tint(base: Arc<dyn Material + Send + Sync>, coat_ior: f64, tint: Color) -> Self {
+        CoatedMaterial { base, coat_ior, tint }
+    }
+
+    fn coat_reflects(&self, r_in: &Ray, rec: &HitRecord) -> bool {
+        if !rec.front_face {
+            return false;
+        }
+        let unit_direction = Vec3::unit_vector(r_in.direction);
+        let cos_theta = Vec3::dot(&-unit_direction, &rec.normal).clamp(0.0, 1.0);
+        let fresnel = Dielectric::reflectance(cos_theta, 1.0 / self.coat_ior);
+        hit_hash(r_in, rec, self as *const Self as usize) < fresnel
+    }
+}
+
+impl Material for CoatedMaterial {
+    fn is_emissive(&self) -> bool {
+        self.base.is_emissive()
+    }
+
+    fn scatter(
+        &self,
+        r_in: &Ray,
+        rec: &HitRecord,
+        srec: &mut ScatterRecord,
+        rng: &mut ThreadRng,
+    ) -> bool {
+        if self.coat_reflects(r_in, rec) {
+            let reflected = Vec3::reflect(&Vec3::unit_vector(r_in.direction), &rec.normal);
+            srec.attenuation = Color::new(1.0, 1.0, 1.0);
+            srec.pdf_ptr = None;
+            srec.skip_pdf = true;
+            srec.skip_pdf_ray = Some(r_in.spawn(rec.p, reflected));
+            return true;
+        }
+        if !self.base.scatter(r_in, rec, srec, rng) {
+            return false;
+        }
+        if rec.front_face {
+            let tint = match r_in.wavelengths {
+                Some(ref wl) if srec.spectral => spectrum::uplift(&self.tint, wl),
+                _ => self.tint,
+            };
+            srec.attenuation = srec.attenuation * tint;
+        }
+        true
+    }
+
+    fn scattering_pdf(&self, r_in: &Ray, rec: &HitRecord, scattered: &Ray) -> f64 {
+        if self.coat_reflects(r_in, rec) {
+            0.0
+        } else {
+            self.base.scattering_pdf(r_in, rec, scattered)
+        }
+    }
+
+    fn eval_bsdf(&self, r_in: &Ray, rec: &HitRecord, scattered: &Ray) -> Option<Color> {
+        if self.coat_reflects(r_in, rec) {
+            None
+        } else {
+            self.base.eval_bsdf(r_in, rec, scattered)
+        }
+    }
+
+    fn emitted(&self, r_in: &Ray, rec: &HitRecord) -> Color {
+        self.base.emitted(r_in, rec)
+    }
+}
+
+// 凹凸贴图：按高度纹理的 UV 梯度扰动着色法线后交给 base
+pub struct BumpMaterial {
+    pub base: Arc<dyn Material + Send + Sync>,
+    pub bump: Arc<dyn Texture + Send + Sync>, // 高度取纹理通道均值
+    pub scale: f64,
+}
+
+impl BumpMaterial {
+    // 有限差分的 UV 步长
+    const DELTA: f64 = 0.0005;
+
+    pub fn new(base: Arc<dyn Material + Send + Sync>, bump: Arc<dyn Texture + Send + Sync>, scale: f64) -> Self {
+        BumpMaterial { base, bump, scale }
+    }
+
+    fn height(&self, u: f64, v: f64, p: &Vec3) -> f64 {
+        let c = self.bump.value(u, v, p);
+        self.scale * (c.x + c.y + c.z) / 3.0
+    }
+
+    fn bumped(&self, rec: &HitRecord) -> HitRecord {
+        // 几何体未提供切线时由法线构造任意切线
+        let (dpdu, dpdv) = if rec.dpdu.near_zero() || rec.dpdv.near_zero() {
+            let uvw = crate::onb::Onb::new(&rec.normal);
+            (*uvw.u(), *uvw.v())
+        } else {
+            (rec.dpdu, rec.dpdv)
+        };
+        let d = self.height(rec.u, rec.v, &rec.p);
+        let dddu = (self.height(rec.u + Self::DELTA, rec.v, &rec.p) - d) / Self::DELTA;
+        let dddv = (self.height(rec.u, rec.v + Self::DELTA, &rec.p) - d) / Self::DELTA;
+        let n = Vec3::cross(&(dpdu + dddu * rec.normal), &(dpdv + dddv * rec.normal));
+
+        let mut bumped = rec.clone();
+        if n.length_squared() > 0.0 {
+            let n = Vec3::unit_vector(n);
+            bumped.normal = if Vec3::dot(&n, &rec.normal) < 0.0 { -n } else { n };
+        }
+        bumped
+    }
+}
+
+impl Material for BumpMaterial {
+    fn is_emissive(&self) -> bool {
+        self.base.is_emissive()
+    }
+
+    fn scatter(
+        &self,
+        r_in: &Ray,
+        rec: &HitRecord,
+        srec: &mut ScatterRecord,
+        rng: &mut ThreadRng,
+    ) -> bool {
+        self.base.scatter(r_in, &self.bumped(rec), srec, rng)
+    }
+
+    fn scattering_pdf(&self, r_in: &Ray, rec: &HitRecord, scattered: &Ray) -> f64 {
+        self.base.scattering_pdf(r_in, &self.bumped(rec), scattered)
+    }
+
+    fn eval_bsdf(&self, r_in: &Ray, rec: &HitRecord, scattered: &Ray) -> Option<Color> {
+        self.base.eval_bsdf(r_in, &self.bumped(rec), scattered)
+    }
+
+    fn emitted(&self, r_in: &Ray, rec: &HitRecord) -> Color {
+        self.base.emitted(r_in, rec)
+    }
+}
+
+// 切线空间法线贴图（glTF、OpenGL 约定）：RGB 映射到 [-1, 1]，X 沿 ∂p/∂u，Y 沿 ∂p/∂v，Z 沿法线；
+// scale 缩放 X、Y 分量
+pub struct NormalMapMaterial {
+    pub base: Arc<dyn Material + Send + Sync>,
+    pub normal_map: Arc<dyn Texture + Send + Sync>,
+    pub scale: f64,
+}
+
+impl NormalMapMaterial {
+    pub fn new(base: Arc<dyn Material + Send + Sync>, normal_map: Arc<dyn Texture + Send + Sync>, scale: f64) -> Self {
+        NormalMapMaterial { base, normal_map, scale }
+    }
+
+    fn mapped(&self, rec: &HitRecord) -> HitRecord {
+        let n = rec.normal;
+        // 切线对法线正交化；几何体未提供切线时由法线构造任意切线
+        let t = rec.dpdu - Vec3::dot(&rec.dpdu, &n) * n;
+        let (t, b) = if t.near_zero() {
+            let uvw = crate::onb::Onb::new(&n);
+            (*uvw.u(), *uvw.v())
+        } else {
+            let t = Vec3::unit_vector(t);
+            let b = rec.dpdv - Vec3::dot(&rec.dpdv, &n) * n - Vec3::dot(&rec.dpdv, &t) * t;
+            let b = if b.near_zero() { Vec3::cross(&n, &t) } else { Vec3::unit_vector(b) };
+            (t, b)
+        };
+        let c = self.normal_map.value_at(rec);
+        let local = Vec3::new((2.0 * c.x - 1.0) * self.scale, (2.0 * c.y - 1.0) * self.scale, 2.0 * c.z - 1.0);
+        let mapped = local.x * t + local.y * b + local.z * n;
+
+        let mut out = rec.clone();
+        if Vec3::dot(&mapped, &n) > 0.0 {
+            out.normal = Vec3::unit_vector(mapped);
+        }
+        out
+    }
+}
+
+impl Material for NormalMapMaterial {
+    fn is_emissive(&self) -> bool {
+        self.base.is_emissive()
+    }
+
+    fn scatter(
+        &self,
+        r_in: &Ray,
+        rec: &HitRecord,
+        srec: &mut ScatterRecord,
+        rng: &mut ThreadRng,
+    ) -> bool {
+        self.base.scatter(r_in, &self.mapped(rec), srec, rng)
+    }
+
+    fn scattering_pdf(&self, r_in: &Ray, rec: &HitRecord, scattered: &Ray) -> f64 {
+        self.base.scattering_pdf(r_in, &self.mapped(rec), scattered)
+    }
+
+    fn eval_bsdf(&self, r_in: &Ray, rec: &HitRecord, scattered: &Ray) -> Option<Color> {
+        self.base.eval_bsdf(r_in, &self.mapped(rec), scattered)
+    }
+
+    fn emitted(&self, r_in: &Ray, rec: &HitRecord) -> Color {
+        self.base.emitted(r_in, rec)
+    }
+}
+
 pub struct ScatterRecord {
     pub attenuation: Color,
     pub pdf_ptr: Option<std::sync::Arc<dyn crate::pdf::Pdf + Send + Sync>>,
     pub skip_pdf: bool,
     pub skip_pdf_ray: Option<crate::Ray>,
-}
\ No newline at end of file
+    pub spectral: bool, // attenuation 已在光线的采样波长处求值，无需再上采样
+}
+
+impl ScatterRecord {
+    pub fn new() -> Self {
+        ScatterRecord {
+            attenuation: Color::new(0.0, 0.0, 0.0),
+            pdf_ptr: None,
+            skip_pdf: false,
+            skip_pdf_ray: None,
+            spectral: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rtweekend::random::random_double_range;
+    use rand::thread_rng;
+
+    // 朝 -y 方向命中 y = 0 平面上随机一点
+    fn random_hit() -> (Ray, HitRecord) {
+        let p = Vec3::new(random_double_range(-10.0, 10.0), 0.0, random_double_range(-10.0, 10.0));
+        let r = Ray::new(p + Vec3::new(0.0, 1.0, 0.0), Vec3::new(0.0, -1.0, 0.0), 0.0);
+        let mut rec = HitRecord::default();
+        rec.p = p;
+        rec.normal = Vec3::new(0.0, 1.0, 0.0);
+        rec.front_face = true;
+        (r, rec)
+    }
+
+    fn mask(w: f64) -> Arc<dyn Texture + Send + Sync> {
+        Arc::new(SolidColor::new(Color::new(w, w, w)))
+    }
+
+    #[test]
+    fn mix_emission_is_blended_by_mask() {
+        let red = Arc::new(DiffuseLight::from_color(Color::new(4.0, 0.0, 0.0)));
+        let blue = Arc::new(DiffuseLight::from_color(Color::new(0.0, 0.0, 4.0)));
+        let mix = MixMaterial::stochastic(red, blue, mask(0.25));
+        let (r, rec) = random_hit();
+        let e = mix.emitted(&r, &rec);
+        assert!((e.x - 3.0).abs() < 1e-12 && e.y == 0.0 && (e.z - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn stochastic_mix_picks_b_with_mask_probability() {
+        let diffuse = Arc::new(Lambertian::from_color(Color::new(0.5, 0.5, 0.5)));
+        let mirror = Arc::new(Metal::new(Color::new(0.9, 0.9, 0.9), 0.0));
+        let mix = MixMaterial::stochastic(diffuse, mirror, mask(0.3));
+        let mut rng = thread_rng();
+        let n = 20000;
+        let specular = (0..n)
+            .filter(|_| {
+                let (r, rec) = random_hit();
+                let mut srec = ScatterRecord::new();
+                mix.scatter(&r, &rec, &mut srec, &mut rng) && srec.skip_pdf
+            })
+            .count();
+        assert!((specular as f64 / n as f64 - 0.3).abs() < 0.02);
+    }
+
+    // 含镜面一方的 Weighted 混合退化为随机选择，被选中的漫反射一方按自身权重放大
+    #[test]
+    fn weighted_mix_with_specular_divides_by_chosen_weight() {
+        let diffuse = Arc::new(Lambertian::from_color(Color::new(0.5, 0.5, 0.5)));
+        let mirror = Arc::new(Metal::new(Color::new(0.9, 0.9, 0.9), 0.0));
+        let mix = MixMaterial::weighted(diffuse, mirror, mask(0.2));
+        let mut rng = thread_rng();
+        for _ in 0..100 {
+            let (r, rec) = random_hit();
+            let mut srec = ScatterRecord::new();
+            assert!(mix.scatter(&r, &rec, &mut srec, &mut rng));
+            if !srec.skip_pdf {
+                assert!((srec.attenuation.x - 1.0 / 0.8).abs() < 1e-12);
+                let scattered = r.spawn(rec.p, Vec3::new(0.0, 1.0, 0.0));
+                let f = mix.eval_bsdf(&r, &rec, &scattered).unwrap();
+                assert!((f.x - 0.8 * 0.5 / PI).abs() < 1e-12);
+            }
+        }
+    }
+
+    // 两个都基于 PDF 的瓣：BSDF 为 (1 - w)·f_a + w·f_b，而不是混合衰减乘以混合 PDF
+    #[test]
+    fn weighted_mix_sums_the_weighted_lobes() {
+        let red = Arc::new(Lambertian::from_color(Color::new(0.8, 0.1, 0.1)));
+        let fog = Arc::new(Isotropic::new_with_color(Color::new(0.1, 0.1, 0.8)));
+        let mix = MixMaterial::weighted(red.clone(), fog.clone(), mask(0.25));
+        // 提供 eval_bsdf 的一方（嵌套的 Weighted 混合）保留自身颜色
+        let nested = MixMaterial::weighted(fog.clone(), Arc::new(MixMaterial::weighted(red.clone(), fog.clone(), mask(0.25))), mask(0.5));
+        let mut rng = thread_rng();
+        for _ in 0..100 {
+            let (r, rec) = random_hit();
+            let mut srec = ScatterRecord::new();
+            assert!(mix.scatter(&r, &rec, &mut srec, &mut rng) && !srec.skip_pdf);
+            assert_eq!((srec.attenuation.x, srec.attenuation.y, srec.attenuation.z), (1.0, 1.0, 1.0));
+            let scattered = r.spawn(rec.p, srec.pdf_ptr.unwrap().generate());
+            let f = mix.eval_bsdf(&r, &rec, &scattered).unwrap();
+            let lobe = |m: &dyn Material| {
+                let mut s = ScatterRecord::new();
+                m.scatter(&r, &rec, &mut s, &mut thread_rng());
+                s.attenuation * m.scattering_pdf(&r, &rec, &scattered)
+            };
+            let expected = lobe(red.as_ref()) * 0.75 + lobe(fog.as_ref()) * 0.25;
+            assert!((f - expected).length() < 1e-12);
+            let g = nested.eval_bsdf(&r, &rec, &scattered).unwrap();
+            assert!((g - (lobe(fog.as_ref()) + expected) * 0.5).length() < 1e-12);
+        }
+    }
+
+    // 被更高优先级介质包含的电介质表面是伪界面：方向不变，但仍记录进入
+    #[test]
+    fn lower_priority_surface_inside_higher_is_passed_through() {
+        let glass = Dielectric::new(1.5).with_priority(2);
+        let water = Dielectric::with_absorption(1.33, Color::new(0.1, 0.05, 0.0)).with_priority(1);
+        let (mut r, rec) = random_hit();
+        r.media.push(glass.medium_entry(1.5));
+        let mut srec = ScatterRecord::new();
+        assert!(water.scatter(&r, &rec, &mut srec, &mut thread_rng()));
+        let passed = srec.skip_pdf_ray.expect("dielectric scatters a skip-pdf ray");
+        assert_eq!(passed.direction.y, -1.0);
+        assert!(passed.media.contains(water.medium_id()) && passed.media.contains(glass.medium_id()));
+        assert_eq!(passed.media.top().map(|m| m.priority), Some(2));
+    }
+
+    // 正入射时涂层按菲涅尔反射率 ((n - 1) / (n + 1))² 反射
+    #[test]
+    fn coat_reflects_with_fresnel_probability() {
+        let coated = CoatedMaterial::new(Arc::new(Lambertian::from_color(Color::new(0.5, 0.5, 0.5))), 1.5);
+        let mut rng = thread_rng();
+        let n = 40000;
+        let reflected = (0..n)
+            .filter(|_| {
+                let (r, rec) = random_hit();
+                let mut srec = ScatterRecord::new();
+                coated.scatter(&r, &rec, &mut srec, &mut rng) && srec.skip_pdf
+            })
+            .count();
+        assert!((reflected as f64 / n as f64 - 0.04).abs() < 0.006);
+    }
+
+    #[test]
+    fn one_sided_light_only_emits_from_front_face() {
+        let light = DiffuseLight::from_color(Color::new(2.0, 2.0, 2.0)).with_intensity(3.0).one_sided();
+        let (r, mut rec) = random_hit();
+        assert_eq!(light.emitted(&r, &rec).y, 6.0);
+        rec.front_face = false;
+        assert_eq!(light.emitted(&r, &rec).y, 0.0);
+        // 默认双面发光
+        assert_eq!(DiffuseLight::from_color(Color::new(2.0, 2.0, 2.0)).with_intensity(3.0).emitted(&r, &rec).y, 6.0);
+    }
+
+    #[test]
+    fn light_units_convert_flux_over_area_to_radiance() {
+        assert!((LightUnit::Watts(2.0 * PI).radiance(2.0) - 1.0).abs() < 1e-12);
+        assert!((LightUnit::Lumens(683.0 * PI).radiance(1.0) - 1.0).abs() < 1e-12);
+        assert!((LightUnit::Nits(683.0).radiance(100.0) - 1.0).abs() < 1e-12);
+        assert_eq!(LightUnit::Watts(1.0).radiance(0.0), 0.0);
+    }
+
+    // 相同功率下，双面开放光源每一侧的辐亮度是单面的一半；封闭光源与 two_sided 无关
+    #[test]
+    fn open_emitter_power_is_split_between_sides() {
+        let (r, rec) = random_hit();
+        let one = DiffuseLight::from_color(Color::new(1.0, 1.0, 1.0)).one_sided().with_power_open(LightUnit::Watts(PI), 1.0);
+        let two = DiffuseLight::from_color(Color::new(1.0, 1.0, 1.0)).with_power_open(LightUnit::Watts(PI), 1.0);
+        // 与调用顺序无关
+        let late = DiffuseLight::from_color(Color::new(1.0, 1.0, 1.0)).with_power_open(LightUnit::Watts(PI), 1.0).one_sided();
+        assert!((late.emitted(&r, &rec).y - 1.0).abs() < 1e-12);
+        assert!((one.emitted(&r, &rec).y - 1.0).abs() < 1e-12);
+        assert!((two.emitted(&r, &rec).y - 0.5).abs() < 1e-12);
+        let closed = DiffuseLight::from_color(Color::new(1.0, 1.0, 1.0)).with_power(LightUnit::Watts(PI), 1.0);
+        assert!((closed.emitted(&r, &rec).y - 1.0).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,426 @@
+// 4x4 仿射矩阵与单位四元数，供 Transform 与场景导入使用
+use std::ops::Mul;
+use crate::vec3::{Point3, Vec3};
+
+/// 行主序 4x4 矩阵，m[行][列]，作用于列向量
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Mat4 {
+    pub m: [[f64; 4]; 4],
+}
+
+impl Mat4 {
+    pub fn new(m: [[f64; 4]; 4]) -> Self { Self { m } }
+
+    pub fn identity() -> Self {
+        Self::new([[1.0, 0.0, 0.0, 0.0], [0.0, 1.0, 0.0, 0.0], [0.0, 0.0, 1.0, 0.0], [0.0, 0.0, 0.0, 1.0]])
+    }
+
+    /// 由列主序的 16 个数构造（glTF、OpenGL 的存储顺序）
+    pub fn from_cols_slice(v: &[f64]) -> Self {
+        let mut m = [[0.0; 4]; 4];
+        for (i, x) in v.iter().take(16).enumerate() {
+            m[i % 4][i / 4] = *x;
+        }
+        Self::new(m)
+    }
+
+    pub fn translate(offset: Vec3) -> Self {
+        let mut t = Self::identity();
+        t.m[0][3] = offset.x;
+        t.m[1][3] = offset.y;
+        t.m[2][3] = offset.z;
+        t
+    }
+
+    pub fn scale(s: Vec3) -> Self {
+        Self::new([[s.x, 0.0, 0.0, 0.0], [0.0, s.y, 0.0, 0.0], [0.0, 0.0, s.z, 0.0], [0.0, 0.0, 0.0, 1.0]])
+    }
+
+    pub fn uniform_scale(s: f64) -> Self { Self::scale(Vec3::new(s, s, s)) }
+
+    // 绕坐标轴旋转，角度为度，右手系
+    pub fn rotate_x(angle: f64) -> Self { Quat::from_axis_angle(Vec3::new(1.0, 0.0, 0.0), angle).to_mat4() }
+
+    pub fn rotate_y(angle: f64) -> Self { Quat::from_axis_angle(Vec3::new(0.0, 1.0, 0.0), angle).to_mat4() }
+
+    pub fn rotate_z(angle: f64) -> Self { Quat::from_axis_angle(Vec3::new(0.0, 0.0, 1.0), angle).to_mat4() }
+
+    /// T * R * S，先缩放、再旋转、最后平移
+    pub fn trs(translation: Vec3, rotation: Quat, scale: Vec3) -> Self {
+        Self::translate(translation) * rotation.to_mat4() * Self::scale(scale)
+    }
+
+    // 左上 3x3（线性部分）的行列式，小于 0 表示镜像
+    pub fn determinant3(&self) -> f64 {
+        let m = &self.m;
+        m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1]) - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+            + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+    }
+
+    /// 一般 4x4 逆矩阵（余子式展开），奇异时返回 None。
+    /// 行列式与各行长度之积（Hadamard 上界）比较，判断与矩阵的整体缩放无关
+    pub fn inverse(&self) -> Option<Self> {
+        let a = &self.m;
+        let s0 = a[0][0] * a[1][1] - a[1][0] * a[0][1];
+        let s1 = a[0][0] * a[1][2] - a[1][0] * a[0][2];
+        let s2 = a[0][0] * a[1][3] - a[1][0] * a[0][3];
+        let s3 = a[0][1] * a[1][2] - a[1][1] * a[0][2];
+        let s4 = a[0][1] * a[1][3] - a[1][1] * a[0][3];
+        let s5 = a[0][2] * a[1][3] - a[1][2] * a[0][3];
+        let c5 = a[2][2] * a[3][3] - a[3][2] * a[2][3];
+        let c4 = a[2][1] * a[3][3] - a[3][1] * a[2][3];
+        let c3 = a[2][1] * a[3][2] - a[3][1] * a[2][2];
+        let c2 = a[2][0] * a[3][3] - a[3][0] * a[2][3];
+        let c1 = a[2][0] * a[3][2] - a[3][0] * a[2][2];
+        let c0 = a[2][0] * a[3][1] - a[3][0] * a[2][1];
+
+        let det = s0 * c5 - s1 * c4 + s2 * c3 + s3 * c2 - s4 * c1 + s5 * c0;
+        let bound: f64 = a.iter().map(|row| row.iter().map(|x| x * x).sum::<f64>().sqrt()).product();
+        if det.is_nan() || det.abs() <= 1e-12 * bound {
+            return None;
+        }
+        let inv = 1.0 / det;
+        Some(Self::new([
+            [
+                (a[1][1] * c5 - a[1][2] * c4 + a[1][3] * c3) * inv,
+                (-a[0][1] * c5 + a[0][2] * c4 - a[0][3] * c3) * inv,
+                (a[3][1] * s5 - a[3][2] * s4 + a[3][3] * s3) * inv,
+                (-a[2][1] * s5 + a[2][2] * s4 - a[2][3] * s3) * inv,
+            ],
+            [
+                (-a[1][0] * c5 + a[1][2] * c2 - a[1][3] * c1) * inv,
+                (a[0][0] * c5 - a[0][2] * c2 + a[0][3] * c1) * inv,
+                (-a[3][0] * s5 + a[3][2] * s2 - a[3][3] * s1) * inv,
+                (a[2][0] * s5 - a[2][2] * s2 + a[2][3] * s1) * inv,
+            ],
+            [
+                (a[1][0] * c4 - a[1][1] * c2 + a[1][3] * c0) * inv,
+                (-a[0][0] * c4 + a[0][1] * c2 - a[0][3] * c0) * inv,
+                (a[3][0] * s4 - a[3][1] * s2 + a[3][3] * s0) * inv,
+                (-a[2][0] * s4 + a[2][1] * s2 - a[2][3] * s0) * inv,
+            ],
+            [
+                (-a[1][0] * c3 + a[1][1] * c1 - a[1][2] * c0) * inv,
+                (a[0][0] * c3 - a[0][1] * c1 + a[0][2] * c0) * inv,
+                (-a[3][0] * s3 + a[3][1] * s1 - a[3][2] * s0) * inv,
+                (a[2][0] * s3 - a[2][1] * s1 + a[2][2] * s0) * inv,
+            ],
+        ]))
+    }
+
+    pub fn transform_point(&self, p: &Point3) -> Point3 {
+        let m = &self.m;
+        let q = Point3::new(
+            m[0][0] * p.x + m[0][1] * p.y + m[0][2] * p.z + m[0][3],
+            m[1][0] * p.x + m[1][1] * p.y + m[1][2] * p.z + m[1][3],
+            m[2][0] * p.x + m[2][1] * p.y + m[2][2] * p.z + m[2][3],
+        );
+        let w = m[3][0] * p.x + m[3][1] * p.y + m[3][2] * p.z + m[3][3];
+        if w == 1.0 { q } else { q / w }
+    }
+
+    // 方向向量只受线性部分影响
+    pub fn transform_vector(&self, v: &Vec3) -> Vec3 {
+        let m = &self.m;
+        Vec3::new(
+            m[0][0] * v.x + m[0][1] * v.y + m[0][2] * v.z,
+            m[1][0] * v.x + m[1][1] * v.y + m[1][2] * v.z,
+            m[2][0] * v.x + m[2][1] * v.y + m[2][2] * v.z,
+        )
+    }
+
+    /// 法线按线性部分的余子式矩阵变换，与逆转置只差 1/det，归一化后相同；
+    /// 不需要求逆，奇异（如某轴缩放为 0）时仍有定义，结果未归一化
+    pub fn transform_normal(&self, n: &Vec3) -> Vec3 {
+        let m = &self.m;
+        let (a0, a1, a2) = (Vec3::new(m[0][0], m[1][0], m[2][0]), Vec3::new(m[0][1], m[1][1], m[2][1]), Vec3::new(m[0][2], m[1][2], m[2][2]));
+        n.x * Vec3::cross(&a1, &a2) + n.y * Vec3::cross(&a2, &a0) + n.z * Vec3::cross(&a0, &a1)
+    }
+
+    /// 法线按逆转置变换；self 须为逆矩阵（Transform 中已缓存），结果未归一化
+    pub fn transform_normal_by_inverse(&self, n: &Vec3) -> Vec3 {
+        let m = &self.m;
+        Vec3::new(
+            m[0][0] * n.x + m[1][0] * n.y + m[2][0] * n.z,
+            m[0][1] * n.x + m[1][1] * n.y + m[2][1] * n.z,
+            m[0][2] * n.x + m[1][2] * n.y + m[2][2] * n.z,
+        )
+    }
+}
+
+impl Default for Mat4 {
+    fn default() -> Self { Self::identity() }
+}
+
+impl Mul for Mat4 {
+    type Output = Mat4;
+    fn mul(self, rhs: Mat4) -> Mat4 {
+        let mut m = [[0.0; 4]; 4];
+        for (r, row) in m.iter_mut().enumerate() {
+            for (c, x) in row.iter_mut().enumerate() {
+                *x = (0..4).map(|k| self.m[r][k] * rhs.m[k][c]).sum();
+            }
+        }
+        Mat4::new(m)
+    }
+}
+
+/// 3x4 仿射矩阵：省略恒为 (0, 0, 0, 1) 的末行，供大量实例紧凑存储
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Affine {
+    pub m: [[f64; 4]; 3],
+}
+
+impl Affine {
+    /// 末行不是 (0, 0, 0, 1)（投影变换）时返回 None
+    pub fn from_mat4(m: &Mat4) -> Option<Self> {
+        (m.m[3] == [0.0, 0.0, 0.0, 1.0]).then(|| Self { m: [m.m[0], m.m[1], m.m[2]] })
+    }
+
+    pub fn to_mat4(self) -> Mat4 {
+        Mat4::new([self.m[0], self.m[1], self.m[2], [0.0, 0.0, 0.0, 1.0]])
+    }
+
+    pub fn determinant3(&self) -> f64 {
+        self.to_mat4().determinant3()
+    }
+
+    pub fn transform_point(&self, p: &Point3) -> Point3 {
+        let m = &self.m;
+        Point3::new(
+            m[0][0] * p.x + m[0][1] * p.y + m[0][2] * p.z + m[0][3],
+            m[1][0] * p.x + m[1][1] * p.y + m[1][2] * p.z + m[1][3],
+            m[2][0] * p.x + m[2][1] * p.y + m[2][2] * p.z + m[2][3],
+        )
+    }
+
+    pub fn transform_vector(&self, v: &Vec3) -> Vec3 {
+        let m = &self.m;
+        Vec3::new(
+            m[0][0] * v.x + m[0][1] * v.y + m[0][2] * v.z,
+            m[1][0] * v.x + m[1][1] * v.y + m[1][2] * v.z,
+            m[2][0] * v.x + m[2][1] * v.y + m[2][2] * v.z,
+        )
+    }
+
+    /// 线性部分的逆作用于 v（Cramer 法则），只存逆矩阵时用来把方向变回原空间
+    pub fn inverse_transform_vector(&self, v: &Vec3) -> Vec3 {
+        let m = &self.m;
+        let (a0, a1, a2) = (Vec3::new(m[0][0], m[1][0], m[2][0]), Vec3::new(m[0][1], m[1][1], m[2][1]), Vec3::new(m[0][2], m[1][2], m[2][2]));
+        Vec3::new(
+            Vec3::dot(v, &Vec3::cross(&a1, &a2)),
+            Vec3::dot(v, &Vec3::cross(&a2, &a0)),
+            Vec3::dot(v, &Vec3::cross(&a0, &a1)),
+        ) / self.determinant3()
+    }
+
+    /// 同 Mat4::transform_normal_by_inverse，self 须为逆矩阵
+    pub fn transform_normal_by_inverse(&self, n: &Vec3) -> Vec3 {
+        let m = &self.m;
+        Vec3::new(
+            m[0][0] * n.x + m[1][0] * n.y + m[2][0] * n.z,
+            m[0][1] * n.x + m[1][1] * n.y + m[2][1] * n.z,
+            m[0][2] * n.x + m[1][2] * n.y + m[2][2] * n.z,
+        )
+    }
+}
+
+/// 单位四元数 x i + y j + z k + w
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quat {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+    pub w: f64,
+}
+
+impl Quat {
+    pub fn new(x: f64, y: f64, z: f64, w: f64) -> Self { Self { x, y, z, w } }
+
+    pub fn identity() -> Self { Self::new(0.0, 0.0, 0.0, 1.0) }
+
+    // 角度为度
+    pub fn from_axis_angle(axis: Vec3, angle: f64) -> Self {
+        let axis = Vec3::unit_vector(axis);
+        let (s, c) = (angle.to_radians() / 2.0).sin_cos();
+        Self::new(axis.x * s, axis.y * s, axis.z * s, c)
+    }
+
+    pub fn dot(&self, q: &Quat) -> f64 { self.x * q.x + self.y * q.y + self.z * q.z + self.w * q.w }
+
+    pub fn normalize(&self) -> Self {
+        let len = self.dot(self).sqrt();
+        if len == 0.0 { Self::identity() } else { Self::new(self.x / len, self.y / len, self.z / len, self.w / len) }
+    }
+
+    pub fn conjugate(&self) -> Self { Self::new(-self.x, -self.y, -self.z, self.w) }
+
+    pub fn rotate(&self, v: &Vec3) -> Vec3 {
+        // v' = v + 2w(q×v) + 2q×(q×v)
+        let q = Vec3::new(self.x, self.y, self.z);
+        let t = 2.0 * Vec3::cross(&q, v);
+        *v + self.w * t + Vec3::cross(&q, &t)
+    }
+
+    /// 球面线性插值，取最短弧；夹角很小时退化为归一化线性插值
+    pub fn slerp(&self, other: &Quat, t: f64) -> Self {
+        let mut cos = self.dot(other);
+        let mut b = *other;
+        if cos < 0.0 {
+            cos = -cos;
+            b = Self::new(-b.x, -b.y, -b.z, -b.w);
+        }
+        let (wa, wb) = if cos > 0.9995 {
+            (1.0 - t, t)
+        } else {
+            let theta = cos.acos();
+            let sin = theta.sin();
+            (((1.0 - t) * theta).sin() / sin, (t * theta).sin() / sin)
+        };
+        Self::new(wa * self.x + wb * b.x, wa * self.y + wb * b.y, wa * self.z + wb * b.z, wa * self.w + wb * b.w)
+            .normalize()
+    }
+
+    pub fn to_mat4(self) -> Mat4 {
+        let Quat { x, y, z, w } = self.normalize();
+        Mat4::new([
+            [1.0 - 2.0 * (y * y + z * z), 2.0 * (x * y - z * w), 2.0 * (x * z + y * w), 0.0],
+            [2.0 * (x * y + z * w), 1.0 - 2.0 * (x * x + z * z), 2.0 * (y * z - x * w), 0.0],
+            [2.0 * (x * z - y * w), 2.0 * (y * z + x * w), 1.0 - 2.0 * (x * x + y * y), 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+}
+
+impl Default for Quat {
+    fn default() -> Self { Self::identity() }
+}
+
+impl Mul for Quat {
+    type Output = Quat;
+    // 哈密顿积：先施加 rhs 的旋转，再施加 self
+    fn mul(self, q: Quat) -> Quat {
+        Quat::new(
+            self.w * q.x + self.x * q.w + self.y * q.z - self.z * q.y,
+            self.w * q.y - self.x * q.z + self.y * q.w + self.z * q.x,
+            self.w * q.z + self.x * q.y - self.y * q.x + self.z * q.w,
+            self.w * q.w - self.x * q.x - self.y * q.y - self.z * q.z,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_mat_eq(a: &Mat4, b: &Mat4, eps: f64) {
+        for r in 0..4 {
+            for c in 0..4 {
+                assert!((a.m[r][c] - b.m[r][c]).abs() < eps, "{:?} != {:?}", a, b);
+            }
+        }
+    }
+
+    fn assert_vec_eq(a: &Vec3, b: &Vec3, eps: f64) {
+        assert!((*a - *b).length() < eps, "{:?} != {:?}", a, b);
+    }
+
+    // 带剪切的一般仿射矩阵
+    fn sheared() -> Mat4 {
+        let mut shear = Mat4::identity();
+        shear.m[0][1] = 0.7;
+        shear.m[2][0] = -0.3;
+        Mat4::trs(Vec3::new(1.0, -2.0, 3.0), Quat::from_axis_angle(Vec3::new(1.0, 2.0, 0.5), 40.0), Vec3::new(0.5, 2.0, 3.0)) * shear
+    }
+
+    #[test]
+    fn inverse_round_trips() {
+        let m = sheared();
+        let inv = m.inverse().unwrap();
+        assert_mat_eq(&(m * inv), &Mat4::identity(), 1e-12);
+        assert_mat_eq(&(inv * m), &Mat4::identity(), 1e-12);
+        // 判定相对于矩阵的尺度，整体很小的缩放仍可逆
+        let tiny = Mat4::uniform_scale(1e-5);
+        assert_mat_eq(&(tiny * tiny.inverse().unwrap()), &Mat4::identity(), 1e-9);
+        assert!(Mat4::scale(Vec3::new(1.0, 1.0, 0.0)).inverse().is_none());
+        assert!(Mat4::uniform_scale(f64::NAN).inverse().is_none());
+    }
+
+    // 变换后的法线仍垂直于变换后的切线，两种法线变换方向一致
+    #[test]
+    fn normals_stay_perpendicular_to_transformed_tangents() {
+        let m = sheared();
+        let inv = m.inverse().unwrap();
+        let n = Vec3::new(0.3, -0.4, 0.8);
+        let tangents = [Vec3::cross(&n, &Vec3::new(1.0, 0.0, 0.0)), Vec3::cross(&n, &Vec3::new(0.0, 1.0, 0.0))];
+        let by_cofactor = Vec3::unit_vector(m.transform_normal(&n));
+        let by_inverse = Vec3::unit_vector(inv.transform_normal_by_inverse(&n));
+        assert_vec_eq(&by_cofactor, &by_inverse, 1e-12);
+        for t in tangents {
+            assert!(Vec3::dot(&m.transform_vector(&t), &by_cofactor).abs() < 1e-12);
+        }
+        // 奇异变换下余子式法线仍有定义
+        let flat = Mat4::scale(Vec3::new(1.0, 0.0, 1.0));
+        assert_vec_eq(&flat.transform_normal(&Vec3::new(0.0, 1.0, 0.0)), &Vec3::new(0.0, 1.0, 0.0), 1e-12);
+    }
+
+    #[test]
+    fn axis_rotations_are_right_handed() {
+        let (x, y, z) = (Vec3::new(1.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0), Vec3::new(0.0, 0.0, 1.0));
+        assert_vec_eq(&Mat4::rotate_x(90.0).transform_vector(&y), &z, 1e-12);
+        assert_vec_eq(&Mat4::rotate_y(90.0).transform_vector(&z), &x, 1e-12);
+        assert_vec_eq(&Mat4::rotate_z(90.0).transform_vector(&x), &y, 1e-12);
+    }
+
+    #[test]
+    fn quaternions_match_matrices() {
+        let a = Quat::from_axis_angle(Vec3::new(1.0, 1.0, 0.0), 70.0);
+        let b = Quat::from_axis_angle(Vec3::new(0.0, 0.3, 1.0), -25.0);
+        let v = Vec3::new(0.2, -1.0, 3.0);
+        assert_vec_eq(&a.rotate(&v), &a.to_mat4().transform_vector(&v), 1e-12);
+        assert_mat_eq(&(a * b).to_mat4(), &(a.to_mat4() * b.to_mat4()), 1e-12);
+        assert_vec_eq(&a.conjugate().rotate(&a.rotate(&v)), &v, 1e-12);
+    }
+
+    #[test]
+    fn slerp_interpolates_along_the_shortest_arc() {
+        let a = Quat::identity();
+        let b = Quat::from_axis_angle(Vec3::new(0.0, 0.0, 1.0), 90.0);
+        assert_mat_eq(&a.slerp(&b, 0.0).to_mat4(), &a.to_mat4(), 1e-12);
+        assert_mat_eq(&a.slerp(&b, 1.0).to_mat4(), &b.to_mat4(), 1e-12);
+        assert_mat_eq(&a.slerp(&b, 0.5).to_mat4(), &Mat4::rotate_z(45.0), 1e-12);
+        // -b 表示同一旋转，插值仍走短弧
+        let neg_b = Quat::new(-b.x, -b.y, -b.z, -b.w);
+        assert_mat_eq(&a.slerp(&neg_b, 0.5).to_mat4(), &Mat4::rotate_z(45.0), 1e-12);
+    }
+
+    #[test]
+    fn affine_matches_the_full_matrix() {
+        let m = sheared();
+        let affine = Affine::from_mat4(&m).unwrap();
+        assert_eq!(affine.to_mat4(), m);
+        let inverse = m.inverse().unwrap();
+        let inv = Affine { m: [inverse.m[0], inverse.m[1], inverse.m[2]] };
+        let (p, v) = (Point3::new(0.3, -1.2, 2.5), Vec3::new(-0.7, 0.4, 1.1));
+        assert_vec_eq(&affine.transform_point(&p), &m.transform_point(&p), 1e-12);
+        assert_vec_eq(&affine.transform_vector(&v), &m.transform_vector(&v), 1e-12);
+        assert_vec_eq(&inv.inverse_transform_vector(&v), &m.transform_vector(&v), 1e-12);
+        assert!((affine.determinant3() - m.determinant3()).abs() < 1e-12);
+        let mut projective = Mat4::identity();
+        projective.m[3][2] = 1.0;
+        assert!(Affine::from_mat4(&projective).is_none());
+    }
+
+    #[test]
+    fn column_major_slices_put_translation_in_the_last_column() {
+        let mut v = [0.0; 16];
+        v[0] = 1.0;
+        v[5] = 1.0;
+        v[10] = 1.0;
+        v[15] = 1.0;
+        v[12] = 4.0;
+        v[13] = 5.0;
+        v[14] = 6.0;
+        assert_eq!(Mat4::from_cols_slice(&v), Mat4::translate(Vec3::new(4.0, 5.0, 6.0)));
+    }
+}
@@ -0,0 +1,359 @@
+// 测量 BRDF：MERL .binary 各向同性表，半角/差角（Rusinkiewicz）参数化
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::Arc;
+use rand::rngs::ThreadRng;
+use crate::hittable::HitRecord;
+use crate::material::{Material, ScatterRecord};
+use crate::onb::Onb;
+use crate::pdf::Pdf;
+use crate::rtweekend::random::random_double;
+use crate::rtweekend::PI;
+use crate::vec3::{Color, Vec3};
+use crate::Ray;
+
+const THETA_H_RES: usize = 90;
+const THETA_D_RES: usize = 90;
+const PHI_D_RES: usize = 180;
+const TABLE_SIZE: usize = THETA_H_RES * THETA_D_RES * PHI_D_RES;
+// MERL 数据各通道的缩放系数
+const RED_SCALE: f64 = 1.0 / 1500.0;
+const GREEN_SCALE: f64 = 1.15 / 1500.0;
+const BLUE_SCALE: f64 = 1.66 / 1500.0;
+
+pub struct MerlBrdf {
+    data: Vec<f64>,        // R、G、B 三块，每块 TABLE_SIZE 个值
+    theta_h_cdf: Vec<f64>, // θh 分箱的累积分布（THETA_H_RES + 1 项），用于采样半角向量
+}
+
+impl MerlBrdf {
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::from_bytes(&fs::read(path)?)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> io::Result<Self> {
+        let invalid = |msg: String| io::Error::new(io::ErrorKind::InvalidData, msg);
+        if bytes.len() < 12 {
+            return Err(invalid("MERL header truncated".to_string()));
+        }
+        let dims: Vec<i32> = bytes[..12]
+            .chunks_exact(4)
+            .map(|c| i32::from_le_bytes([c[0], c[1], c[2], c[3]]))
+            .collect();
+        if dims.iter().any(|&d| d <= 0) || dims.iter().map(|&d| d as usize).product::<usize>() != TABLE_SIZE {
+            return Err(invalid(format!("unexpected MERL dimensions {:?}", dims)));
+        }
+        let body = &bytes[12..];
+        if body.len() != 3 * TABLE_SIZE * 8 {
+            return Err(invalid(format!("expected {} bytes of MERL data, found {}", 3 * TABLE_SIZE * 8, body.len())));
+        }
+        let data = body
+            .chunks_exact(8)
+            .map(|c| f64::from_le_bytes([c[0], c[1], c[2], c[3], c[4], c[5], c[6], c[7]]))
+            .collect();
+        Ok(Self::from_table(data))
+    }
+
+    /// 按 MERL 的分箱对解析 BRDF f(θh, θd, φd) 制表（取各分箱中心），与测量数据共用查表与采样
+    pub fn tabulate(f: impl Fn(f64, f64, f64) -> Color) -> Self {
+        let mut data = vec![0.0; 3 * TABLE_SIZE];
+        for i in 0..THETA_H_RES {
+            let t = (i as f64 + 0.5) / THETA_H_RES as f64;
+            let theta_h = t * t * PI * 0.5;
+            for j in 0..THETA_D_RES {
+                let theta_d = (j as f64 + 0.5) / THETA_D_RES as f64 * PI * 0.5;
+                for k in 0..PHI_D_RES {
+                    let phi_d = (k as f64 + 0.5) / PHI_D_RES as f64 * PI;
+                    let c = f(theta_h, theta_d, phi_d);
+                    let idx = k + j * PHI_D_RES + i * PHI_D_RES * THETA_D_RES;
+                    data[idx] = c.x / RED_SCALE;
+                    data[idx + TABLE_SIZE] = c.y / GREEN_SCALE;
+                    data[idx + 2 * TABLE_SIZE] = c.z / BLUE_SCALE;
+                }
+            }
+        }
+        Self::from_table(data)
+    }
+
+    fn from_table(data: Vec<f64>) -> Self {
+        let mut brdf = MerlBrdf { data, theta_h_cdf: Vec::new() };
+        // 边缘分布：每个 θh 分箱内 BRDF 亮度均值 × cosθh × 分箱立体角
+        let mut cdf = Vec::with_capacity(THETA_H_RES + 1);
+        let mut total = 0.0;
+        cdf.push(0.0);
+        for i in 0..THETA_H_RES {
+            let base = i * THETA_D_RES * PHI_D_RES;
+            let sum: f64 = (base..base + THETA_D_RES * PHI_D_RES).map(|idx| luminance(&brdf.lookup(idx))).sum();
+            let mean = sum / (THETA_D_RES * PHI_D_RES) as f64;
+            let theta_mid = 0.5 * (theta_h_edge(i) + theta_h_edge(i + 1));
+            total += mean * theta_mid.cos() * band_solid_angle(i);
+            cdf.push(total);
+        }
+        if total > 0.0 {
+            cdf.iter_mut().for_each(|c| *c /= total);
+        } else {
+            // 全零的表退化为按立体角均匀
+            cdf = (0..=THETA_H_RES).map(|i| 1.0 - theta_h_edge(i).cos()).collect();
+        }
+        brdf.theta_h_cdf = cdf;
+        brdf
+    }
+
+    fn lookup(&self, idx: usize) -> Color {
+        // 表中负值表示无效测量
+        Color::new(
+            (self.data[idx] * RED_SCALE).max(0.0),
+            (self.data[idx + TABLE_SIZE] * GREEN_SCALE).max(0.0),
+            (self.data[idx + 2 * TABLE_SIZE] * BLUE_SCALE).max(0.0),
+        )
+    }
+
+    // 局部坐标系（z 为法线）下的 BRDF 值
+    pub fn eval(&self, wi: &Vec3, wo: &Vec3) -> Color {
+        if wi.z <= 0.0 || wo.z <= 0.0 {
+            return Color::new(0.0, 0.0, 0.0);
+        }
+        let (theta_h, theta_d, phi_d) = half_diff(wi, wo);
+        let idx = phi_d_index(phi_d)
+            + theta_d_index(theta_d) * PHI_D_RES
+            + theta_h_index(theta_h) * PHI_D_RES * THETA_D_RES;
+        self.lookup(idx)
+    }
+
+    // 半角向量（局部坐标）的立体角密度
+    fn half_pdf(&self, cos_theta_h: f64) -> f64 {
+        if cos_theta_h <= 0.0 {
+            return 0.0;
+        }
+        let i = theta_h_index(cos_theta_h.min(1.0).acos());
+        (self.theta_h_cdf[i + 1] - self.theta_h_cdf[i]) / band_solid_angle(i)
+    }
+
+    // 按 θh 边缘分布选择分箱，分箱内按立体角均匀采样
+    fn sample_half(&self) -> Vec3 {
+        let xi = random_double();
+        let i = self.theta_h_cdf.partition_point(|&c| c <= xi).clamp(1, THETA_H_RES) - 1;
+        let (cos_lo, cos_hi) = (theta_h_edge(i).cos(), theta_h_edge(i + 1).cos());
+        let cos_theta = cos_lo + (cos_hi - cos_lo) * random_double();
+        let sin_theta = (1.0 - cos_theta * cos_theta).max(0.0).sqrt();
+        let phi = 2.0 * PI * random_double();
+        Vec3::new(sin_theta * phi.cos(), sin_theta * phi.sin(), cos_theta)
+    }
+}
+
+fn luminance(c: &Color) -> f64 {
+    0.2126 * c.x + 0.7152 * c.y + 0.0722 * c.z
+}
+
+// θh 分箱是非线性的：index = sqrt(θh / (π/2)) * 90
+fn theta_h_edge(i: usize) -> f64 {
+    let t = i as f64 / THETA_H_RES as f64;
+    t * t * PI * 0.5
+}
+
+fn band_solid_angle(i: usize) -> f64 {
+    2.0 * PI * (theta_h_edge(i).cos() - theta_h_edge(i + 1).cos())
+}
+
+fn theta_h_index(theta_h: f64) -> usize {
+    if theta_h <= 0.0 {
+        return 0;
+    }
+    let idx = ((theta_h / (PI * 0.5)).sqrt() * THETA_H_RES as f64) as usize;
+    idx.min(THETA_H_RES - 1)
+}
+
+fn theta_d_index(theta_d: f64) -> usize {
+    let idx = (theta_d / (PI * 0.5) * THETA_D_RES as f64) as usize;
+    idx.min(THETA_D_RES - 1)
+}
+
+// 互易性：φd 与 φd + π 等价，表中只存一半
+fn phi_d_index(phi_d: f64) -> usize {
+    let phi_d = if phi_d < 0.0 { phi_d + PI } else { phi_d };
+    let idx = (phi_d / PI * PHI_D_RES as f64) as usize;
+    idx.min(PHI_D_RES - 1)
+}
+
+fn rotate(v: &Vec3, axis: &Vec3, angle: f64) -> Vec3 {
+    let (sin, cos) = angle.sin_cos();
+    *v * cos + *axis * (Vec3::dot(axis, v) * (1.0 - cos)) + Vec3::cross(axis, v) * sin
+}
+
+// (wi, wo) → (θh, θd, φd)；各向同性，φh 不参与查表
+fn half_diff(wi: &Vec3, wo: &Vec3) -> (f64, f64, f64) {
+    let h = Vec3::unit_vector(*wi + *wo);
+    let theta_h = h.z.clamp(-1.0, 1.0).acos();
+    let phi_h = h.y.atan2(h.x);
+    let d = rotate(wi, &Vec3::new(0.0, 0.0, 1.0), -phi_h);
+    let d = rotate(&d, &Vec3::new(0.0, 1.0, 0.0), -theta_h);
+    (theta_h, d.z.clamp(-1.0, 1.0).acos(), d.y.atan2(d.x))
+}
+
+// 一半余弦采样，一半按表格边缘分布采样半角向量后镜面反射
+pub struct MeasuredPdf {
+    brdf: Arc<MerlBrdf>,
+    uvw: Onb,
+    wo: Vec3, // 局部坐标下的出射（观察）方向
+}
+
+impl MeasuredPdf {
+    pub fn new(brdf: Arc<MerlBrdf>, normal: &Vec3, wo: &Vec3) -> Self {
+        let uvw = Onb::new(normal);
+        let wo = uvw.to_local(&Vec3::unit_vector(*wo));
+        Self { brdf, uvw, wo }
+    }
+}
+
+impl Pdf for MeasuredPdf {
+    fn value(&self, direction: &Vec3) -> f64 {
+        let wi = self.uvw.to_local(&Vec3::unit_vector(*direction));
+        let cosine = wi.z.max(0.0) / PI;
+        let h = wi + self.wo;
+        if h.length_squared() <= 0.0 {
+            return 0.5 * cosine;
+        }
+        let h = Vec3::unit_vector(h);
+        let wo_dot_h = Vec3::dot(&self.wo, &h).abs();
+        let half = if wo_dot_h > 0.0 { self.brdf.half_pdf(h.z) / (4.0 * wo_dot_h) } else { 0.0 };
+        0.5 * cosine + 0.5 * half
+    }
+
+    fn generate(&self) -> Vec3 {
+        if random_double() < 0.5 {
+            return self.uvw.transform(&Vec3::random_cosine_direction());
+        }
+        let h = self.brdf.sample_half();
+        let wi = 2.0 * Vec3::dot(&self.wo, &h) * h - self.wo;
+        self.uvw.transform(&wi)
+    }
+}
+
+pub struct MeasuredMaterial {
+    pub brdf: Arc<MerlBrdf>,
+}
+
+impl MeasuredMaterial {
+    pub fn new(brdf: Arc<MerlBrdf>) -> Self {
+        MeasuredMaterial { brdf }
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Ok(Self::new(Arc::new(MerlBrdf::load(path)?)))
+    }
+
+    // BRDF × cosθi
+    fn eval_cos(&self, r_in: &Ray, rec: &HitRecord, scattered: &Ray) -> Color {
+        let uvw = Onb::new(&rec.normal);
+        let wo = uvw.to_local(&-Vec3::unit_vector(r_in.direction));
+        let wi = uvw.to_local(&Vec3::unit_vector(scattered.direction));
+        self.brdf.eval(&wi, &wo) * wi.z.max(0.0)
+    }
+}
+
+impl Material for MeasuredMaterial {
+    fn scatter(
+        &self,
+        r_in: &Ray,
+        rec: &HitRecord,
+        srec: &mut ScatterRecord,
+        _rng: &mut ThreadRng,
+    ) -> bool {
+        // 颜色完全由 eval_bsdf 给出
+        srec.attenuation = Color::new(1.0, 1.0, 1.0);
+        srec.pdf_ptr = Some(Arc::new(MeasuredPdf::new(self.brdf.clone(), &rec.normal, &-r_in.direction)));
+        srec.skip_pdf = false;
+        srec.skip_pdf_ray = None;
+        true
+    }
+
+    fn eval_bsdf(&self, r_in: &Ray, rec: &HitRecord, scattered: &Ray) -> Option<Color> {
+        Some(self.eval_cos(r_in, rec, scattered))
+    }
+
+    // 与 scatter 给出的采样分布一致；颜色只由 eval_bsdf 给出
+    fn scattering_pdf(&self, r_in: &Ray, rec: &HitRecord, scattered: &Ray) -> f64 {
+        MeasuredPdf::new(self.brdf.clone(), &rec.normal, &-r_in.direction).value(&scattered.direction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn constant_brdf() -> MerlBrdf {
+        MerlBrdf::from_table(vec![1500.0; 3 * TABLE_SIZE])
+    }
+
+    fn header(dims: [i32; 3]) -> Vec<u8> {
+        dims.iter().flat_map(|d| d.to_le_bytes()).collect()
+    }
+
+    #[test]
+    fn rejects_malformed_files() {
+        assert!(MerlBrdf::from_bytes(&[0; 8]).is_err());
+        assert!(MerlBrdf::from_bytes(&header([90, 90, 90])).is_err());
+        assert!(MerlBrdf::from_bytes(&header([-90, -90, 180])).is_err());
+        let mut truncated = header([90, 90, 180]);
+        truncated.extend_from_slice(&[0; 64]);
+        assert!(MerlBrdf::from_bytes(&truncated).is_err());
+    }
+
+    #[test]
+    fn eval_applies_channel_scales_above_the_horizon_only() {
+        let brdf = constant_brdf();
+        let up = Vec3::unit_vector(Vec3::new(0.3, 0.2, 1.0));
+        let c = brdf.eval(&up, &Vec3::new(0.0, 0.0, 1.0));
+        assert!((c.x - 1.0).abs() < 1e-12 && (c.y - 1.15).abs() < 1e-12 && (c.z - 1.66).abs() < 1e-12);
+        assert_eq!(brdf.eval(&Vec3::new(0.0, 0.0, -1.0), &up).x, 0.0);
+    }
+
+    // 关于法线对称的方向对：半角向量即法线，差角即入射角
+    #[test]
+    fn half_diff_of_mirror_pair() {
+        let theta: f64 = 0.6;
+        let wi = Vec3::new(theta.sin(), 0.0, theta.cos());
+        let wo = Vec3::new(-theta.sin(), 0.0, theta.cos());
+        let (theta_h, theta_d, _) = half_diff(&wi, &wo);
+        assert!(theta_h.abs() < 1e-6);
+        assert!((theta_d - theta).abs() < 1e-6);
+    }
+
+    // 对整个球面积分为 1：用均匀球面方向做蒙特卡洛估计
+    #[test]
+    fn sampling_pdf_integrates_to_one() {
+        let pdf = MeasuredPdf::new(Arc::new(constant_brdf()), &Vec3::new(0.0, 1.0, 0.0), &Vec3::new(0.3, 1.0, 0.2));
+        let n = 200000;
+        let integral = (0..n).map(|_| pdf.value(&Vec3::random_unit_vector())).sum::<f64>() * 4.0 * PI / n as f64;
+        assert!((integral - 1.0).abs() < 0.03, "{}", integral);
+    }
+
+    #[test]
+    fn scattering_pdf_is_the_sampling_density() {
+        let material = MeasuredMaterial::new(Arc::new(constant_brdf()));
+        let mut rec = HitRecord::default();
+        rec.normal = Vec3::new(0.0, 1.0, 0.0);
+        let r = Ray::new(Vec3::new(0.3, 1.0, 0.2), Vec3::new(-0.3, -1.0, -0.2), 0.0);
+        let mut srec = ScatterRecord::new();
+        assert!(material.scatter(&r, &rec, &mut srec, &mut rand::thread_rng()));
+        let pdf = srec.pdf_ptr.unwrap();
+        for _ in 0..100 {
+            let scattered = r.spawn(rec.p, Vec3::random_unit_vector());
+            assert!((material.scattering_pdf(&r, &rec, &scattered) - pdf.value(&scattered.direction)).abs() < 1e-12);
+        }
+    }
+
+    // 制表后在分箱中心处还原函数值
+    #[test]
+    fn tabulated_brdf_reproduces_the_function() {
+        let f = |theta_h: f64, theta_d: f64, phi_d: f64| Color::new(1.0 + theta_h.cos(), 0.5 * theta_d.cos(), 0.1 * phi_d.sin());
+        let brdf = MerlBrdf::tabulate(f);
+        for _ in 0..200 {
+            let (wi, wo) = (Vec3::random_cosine_direction(), Vec3::random_cosine_direction());
+            let (theta_h, theta_d, phi_d) = half_diff(&wi, &wo);
+            let (expected, c) = (f(theta_h, theta_d, phi_d.rem_euclid(PI)), brdf.eval(&wi, &wo));
+            assert!((c - expected).length() < 0.05, "{:?} vs {:?}", c, expected);
+        }
+    }
+}
@@ -0,0 +1,132 @@
+use crate::vec3::Color;
+
+// 光线路径上同时所处的嵌套介质数量上限（冰在水中、液体在玻璃杯中等）
+const MAX_NESTED_MEDIA: usize = 4;
+
+// 介质栈中的一项：一种电介质介质
+#[derive(Debug, Clone, Copy)]
+pub struct MediumEntry {
+    pub id: usize,          // 介质标识（材质实例地址）
+    pub priority: u32,      // 优先级，数值越大越优先
+    pub ior: f64,
+    pub absorption: Color,  // Beer–Lambert 吸收系数（每单位长度）
+}
+
+// 随路径传递的介质栈。重叠的电介质按优先级决定真正的界面：
+// 优先级低于当前最高优先级介质的表面视为“伪界面”，光线直接穿过但仍记录进出。
+#[derive(Debug, Clone, Copy)]
+pub struct MediumStack {
+    entries: [Option<MediumEntry>; MAX_NESTED_MEDIA],
+    len: usize,
+}
+
+impl MediumStack {
+    pub fn new() -> Self {
+        Self { entries: [None; MAX_NESTED_MEDIA], len: 0 }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn contains(&self, id: usize) -> bool {
+        self.entries[..self.len].iter().flatten().any(|e| e.id == id)
+    }
+
+    // 进入介质；已在栈中或栈满时忽略
+    pub fn push(&mut self, entry: MediumEntry) {
+        if self.contains(entry.id) || self.len == MAX_NESTED_MEDIA {
+            return;
+        }
+        self.entries[self.len] = Some(entry);
+        self.len += 1;
+    }
+
+    // 离开介质
+    pub fn remove(&mut self, id: usize) {
+        if let Some(pos) = self.entries[..self.len].iter().position(|e| e.is_some_and(|e| e.id == id)) {
+            for i in pos..self.len - 1 {
+                self.entries[i] = self.entries[i + 1];
+            }
+            self.len -= 1;
+            self.entries[self.len] = None;
+        }
+    }
+
+    // 当前起作用的介质：优先级最高者，同优先级取最后进入的
+    pub fn top(&self) -> Option<MediumEntry> {
+        self.top_excluding(usize::MAX)
+    }
+
+    // 除 id 以外起作用的介质
+    pub fn top_excluding(&self, id: usize) -> Option<MediumEntry> {
+        let mut best: Option<MediumEntry> = None;
+        for e in self.entries[..self.len].iter().flatten() {
+            if e.id != id && best.is_none_or(|b| e.priority >= b.priority) {
+                best = Some(*e);
+            }
+        }
+        best
+    }
+
+    // 在当前介质中传播 distance 后的透射率 exp(-σa d)
+    pub fn transmittance(&self, distance: f64) -> Color {
+        match self.top() {
+            Some(m) => Color::new(
+                (-m.absorption.x * distance).exp(),
+                (-m.absorption.y * distance).exp(),
+                (-m.absorption.z * distance).exp(),
+            ),
+            None => Color::new(1.0, 1.0, 1.0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: usize, priority: u32) -> MediumEntry {
+        MediumEntry { id, priority, ior: 1.0 + id as f64 / 10.0, absorption: Color::new(0.0, 0.0, 0.0) }
+    }
+
+    #[test]
+    fn top_prefers_priority_then_latest_entry() {
+        let mut stack = MediumStack::new();
+        stack.push(entry(1, 2));
+        stack.push(entry(2, 1));
+        assert_eq!(stack.top().map(|e| e.id), Some(1));
+        stack.push(entry(3, 2));
+        assert_eq!(stack.top().map(|e| e.id), Some(3));
+        assert_eq!(stack.top_excluding(3).map(|e| e.id), Some(1));
+        stack.remove(3);
+        stack.remove(1);
+        assert_eq!(stack.top().map(|e| e.id), Some(2));
+        stack.remove(2);
+        assert!(stack.is_empty() && stack.top().is_none());
+    }
+
+    #[test]
+    fn push_ignores_duplicates_and_overflow() {
+        let mut stack = MediumStack::new();
+        stack.push(entry(1, 0));
+        stack.push(entry(1, 5));
+        assert_eq!(stack.top().map(|e| e.priority), Some(0));
+        for id in 2..=MAX_NESTED_MEDIA + 2 {
+            stack.push(entry(id, 0));
+        }
+        assert!(stack.contains(MAX_NESTED_MEDIA));
+        assert!(!stack.contains(MAX_NESTED_MEDIA + 1));
+    }
+
+    #[test]
+    fn transmittance_follows_beer_lambert_in_top_medium() {
+        let mut stack = MediumStack::new();
+        assert_eq!(stack.transmittance(3.0).x, 1.0);
+        stack.push(MediumEntry { id: 1, priority: 0, ior: 1.33, absorption: Color::new(1.0, 2.0, 0.0) });
+        let tr = stack.transmittance(0.5);
+        assert!((tr.x - (-0.5f64).exp()).abs() < 1e-12);
+        assert!((tr.y - (-1.0f64).exp()).abs() < 1e-12);
+        assert_eq!(tr.z, 1.0);
+    }
+}
@@ -0,0 +1,253 @@
+// 关键帧变换的运动模糊：任意 Hittable 在快门区间内按 TRS 关键帧插值运动
+// 平移、缩放线性插值，旋转球面插值；光线按 Ray::time 取对应时刻的变换
+use std::sync::Arc;
+use crate::aabb::Aabb;
+use crate::hittable::{hit_transformed, pdf_value_transformed, transform_bbox, HitRecord, Hittable};
+use crate::interval::Interval;
+use crate::matrix::{Mat4, Quat};
+use crate::ray::Ray;
+use crate::vec3::{Point3, Vec3};
+
+// 每段关键帧之间采样包围盒的次数
+const BBOX_STEPS: usize = 32;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Keyframe {
+    pub time: f64,
+    pub translation: Vec3,
+    pub rotation: Quat,
+    pub scale: Vec3,
+}
+
+impl Keyframe {
+    pub fn new(time: f64, translation: Vec3, rotation: Quat, scale: Vec3) -> Self {
+        Self { time, translation, rotation: rotation.normalize(), scale }
+    }
+
+    // 只有平移的关键帧
+    pub fn translation(time: f64, translation: Vec3) -> Self {
+        Self::new(time, translation, Quat::identity(), Vec3::new(1.0, 1.0, 1.0))
+    }
+
+    fn lerp(&self, other: &Keyframe, t: f64) -> Keyframe {
+        Keyframe {
+            time: self.time + (other.time - self.time) * t,
+            translation: self.translation + t * (other.translation - self.translation),
+            rotation: self.rotation.slerp(&other.rotation, t),
+            scale: self.scale + t * (other.scale - self.scale),
+        }
+    }
+
+    fn world_matrix(&self) -> Mat4 {
+        Mat4::trs(self.translation, self.rotation, self.scale)
+    }
+
+    // TRS 的逆直接写出：S⁻¹ R⁻¹ T⁻¹，省去一般求逆
+    fn object_matrix(&self) -> Mat4 {
+        let inv_scale = Vec3::new(1.0 / self.scale.x, 1.0 / self.scale.y, 1.0 / self.scale.z);
+        Mat4::scale(inv_scale) * self.rotation.conjugate().to_mat4() * Mat4::translate(-self.translation)
+    }
+}
+
+pub struct MotionTransform {
+    object: Arc<dyn Hittable + Send + Sync>,
+    keys: Vec<Keyframe>, // 按时间升序
+    bbox: Aabb,
+}
+
+impl MotionTransform {
+    /// 关键帧时间与 Ray::time 同单位（相机快门为 [0, 1)），区间外保持首尾关键帧；
+    /// 没有关键帧或某个关键帧的缩放分量为 0（变换不可逆）时返回 None
+    pub fn new(object: Arc<dyn Hittable + Send + Sync>, mut keys: Vec<Keyframe>) -> Option<Self> {
+        if keys.is_empty() || keys.iter().any(|k| k.scale.x == 0.0 || k.scale.y == 0.0 || k.scale.z == 0.0) {
+            return None;
+        }
+        keys.sort_by(|a, b| a.time.total_cmp(&b.time));
+        let bbox = motion_bbox(&object.bounding_box(), &keys);
+        Some(Self { object, keys, bbox })
+    }
+
+    // 两个时刻之间的线性运动
+    pub fn linear(object: Arc<dyn Hittable + Send + Sync>, start: Keyframe, end: Keyframe) -> Option<Self> {
+        Self::new(object, vec![start, end])
+    }
+
+    fn key_at(&self, time: f64) -> Keyframe {
+        let first = &self.keys[0];
+        let last = &self.keys[self.keys.len() - 1];
+        if time <= first.time {
+            return *first;
+        }
+        if time >= last.time {
+            return *last;
+        }
+        let i = self.keys.partition_point(|k| k.time <= time) - 1;
+        let (a, b) = (&self.keys[i], &self.keys[i + 1]);
+        a.lerp(b, (time - a.time) / (b.time - a.time))
+    }
+}
+
+// 保守的运动包围盒：每段采样若干时刻并合并，
+// 再按相邻采样间角点最大位移的一半外扩，覆盖旋转造成的弧线外凸
+fn motion_bbox(bbox: &Aabb, keys: &[Keyframe]) -> Aabb {
+    if keys.len() == 1 {
+        return transform_bbox(bbox, &keys[0].world_matrix());
+    }
+    let corners: Vec<Point3> = (0..8)
+        .map(|i| Point3::new(
+            if i & 1 == 0 { bbox.x.min } else { bbox.x.max },
+            if i & 2 == 0 { bbox.y.min } else { bbox.y.max },
+            if i & 4 == 0 { bbox.z.min } else { bbox.z.max },
+        ))
+        .collect();
+
+    let mut min = Point3::new(f64::INFINITY, f64::INFINITY, f64::INFINITY);
+    let mut max = Point3::new(f64::NEG_INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY);
+    let mut pad: f64 = 0.0;
+    for pair in keys.windows(2) {
+        let mut previous: Option<Vec<Point3>> = None;
+        for step in 0..=BBOX_STEPS {
+            let m = pair[0].lerp(&pair[1], step as f64 / BBOX_STEPS as f64).world_matrix();
+            let moved: Vec<Point3> = corners.iter().map(|c| m.transform_point(c)).collect();
+            for p in &moved {
+                min = Point3::new(min.x.min(p.x), min.y.min(p.y), min.z.min(p.z));
+                max = Point3::new(max.x.max(p.x), max.y.max(p.y), max.z.max(p.z));
+            }
+            if let Some(prev) = previous {
+                for (a, b) in prev.iter().zip(&moved) {
+                    pad = pad.max((*b - *a).length() / 2.0);
+                }
+            }
+            previous = Some(moved);
+        }
+    }
+    let pad = Vec3::new(pad, pad, pad);
+    Aabb::from_points(min - pad, max + pad)
+}
+
+impl Hittable for MotionTransform {
+    fn hit(
+        &self,
+        r: &Ray,
+        ray_t: Interval,
+        rec: &mut HitRecord,
+    ) -> bool {
+        let key = self.key_at(r.time());
+        hit_transformed(self.object.as_ref(), &key.world_matrix(), &key.object_matrix(), r, ray_t, rec)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+
+    // 光源采样没有时间参数，与 Sphere::moving 一致取 time = 0 的位置
+    fn pdf_value(&self, origin: &Point3, direction: &Vec3) -> f64 {
+        pdf_value_transformed(self.object.as_ref(), &self.key_at(0.0).object_matrix(), origin, direction)
+    }
+
+    fn random(&self, origin: &Point3) -> Vec3 {
+        let key = self.key_at(0.0);
+        let local_dir = self.object.random(&key.object_matrix().transform_point(origin));
+        key.world_matrix().transform_vector(&local_dir)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bvh::BvhNode;
+    use crate::hittable::HittableList;
+    use crate::material::Lambertian;
+    use crate::quad::make_box;
+    use crate::sphere::Sphere;
+    use crate::vec3::Color;
+
+    fn unit_box() -> Arc<dyn Hittable + Send + Sync> {
+        let sides = make_box(Point3::new(-0.5, -0.5, -0.5), Point3::new(0.5, 0.5, 0.5), Arc::new(Lambertian::from_color(Color::new(0.5, 0.5, 0.5))));
+        Arc::new(BvhNode::new_from_list(&mut sides.objects.clone()))
+    }
+
+    fn tumbling(object: Arc<dyn Hittable + Send + Sync>) -> MotionTransform {
+        let y = Vec3::new(0.0, 1.0, 0.0);
+        MotionTransform::new(object, vec![
+            Keyframe::new(1.0, Vec3::new(2.0, 1.0, 0.0), Quat::from_axis_angle(Vec3::new(1.0, 1.0, 0.0), 170.0), Vec3::new(1.0, 1.0, 1.0)),
+            Keyframe::new(0.0, Vec3::new(-2.0, 0.0, 0.0), Quat::identity(), Vec3::new(1.0, 2.0, 1.0)),
+            Keyframe::new(0.5, Vec3::new(0.0, 1.0, 0.0), Quat::from_axis_angle(y, 90.0), Vec3::new(1.5, 1.0, 1.0)),
+        ]).unwrap()
+    }
+
+    #[test]
+    fn key_at_interpolates_and_clamps_outside_the_keys() {
+        let motion = tumbling(unit_box());
+        let times: Vec<f64> = motion.keys.iter().map(|k| k.time).collect();
+        assert_eq!(times, vec![0.0, 0.5, 1.0]);
+
+        let quarter = motion.key_at(0.25);
+        assert!((quarter.translation - Vec3::new(-1.0, 0.5, 0.0)).length() < 1e-12);
+        assert!((quarter.scale - Vec3::new(1.25, 1.5, 1.0)).length() < 1e-12);
+        let half_turn = Quat::from_axis_angle(Vec3::new(0.0, 1.0, 0.0), 45.0);
+        let v = Vec3::new(1.0, 0.3, -0.2);
+        assert!((quarter.rotation.rotate(&v) - half_turn.rotate(&v)).length() < 1e-12);
+
+        assert!((motion.key_at(-3.0).translation - Vec3::new(-2.0, 0.0, 0.0)).length() == 0.0);
+        assert!((motion.key_at(7.0).translation - Vec3::new(2.0, 1.0, 0.0)).length() == 0.0);
+        let inverse = quarter.world_matrix() * quarter.object_matrix();
+        assert!((inverse.transform_point(&v) - v).length() < 1e-12);
+    }
+
+    #[test]
+    fn rejects_missing_or_degenerate_keys() {
+        assert!(MotionTransform::new(unit_box(), Vec::new()).is_none());
+        let flat = Keyframe::new(1.0, Vec3::new(0.0, 0.0, 0.0), Quat::identity(), Vec3::new(1.0, 0.0, 1.0));
+        assert!(MotionTransform::linear(unit_box(), Keyframe::translation(0.0, Vec3::new(0.0, 0.0, 0.0)), flat).is_none());
+    }
+
+    // 任意时刻的角点都落在运动包围盒内
+    #[test]
+    fn motion_bbox_is_conservative() {
+        let motion = tumbling(unit_box());
+        let bbox = motion.bounding_box();
+        for step in 0..=1000 {
+            let m = motion.key_at(step as f64 / 1000.0).world_matrix();
+            for i in 0..8 {
+                let corner = Point3::new(
+                    if i & 1 == 0 { -0.5 } else { 0.5 },
+                    if i & 2 == 0 { -0.5 } else { 0.5 },
+                    if i & 4 == 0 { -0.5 } else { 0.5 },
+                );
+                let p = m.transform_point(&corner);
+                assert!(bbox.x.contains(p.x) && bbox.y.contains(p.y) && bbox.z.contains(p.z), "{:?} escapes at step {}", p, step);
+            }
+        }
+    }
+
+    // 放进 BVH 后，不同时刻的光线与直接求交结果一致
+    #[test]
+    fn moving_box_inside_a_bvh_matches_direct_hits() {
+        let motion = Arc::new(tumbling(unit_box()));
+        let mut list = HittableList::new();
+        list.add(motion.clone());
+        for i in 0..8 {
+            list.add(Arc::new(Sphere::new(Point3::new(-6.0 + 1.5 * i as f64, -3.0, 0.0), 0.5, None)));
+        }
+        let bvh = BvhNode::new_from_list(&mut list.objects);
+        let mut hits = 0;
+        for i in 0..400 {
+            let time = (i % 20) as f64 / 20.0;
+            let x = -3.0 + 6.0 * (i / 20) as f64 / 20.0;
+            let r = Ray::new(Point3::new(x, 0.5, 10.0), Vec3::new(0.0, 0.0, -1.0), time);
+            let (mut a, mut b) = (HitRecord::default(), HitRecord::default());
+            let direct = motion.hit(&r, Interval::new(0.001, f64::INFINITY), &mut a);
+            assert_eq!(direct, bvh.hit(&r, Interval::new(0.001, f64::INFINITY), &mut b));
+            if direct {
+                hits += 1;
+                assert!((a.t - b.t).abs() < 1e-12);
+                let key = motion.key_at(time);
+                let local = key.object_matrix().transform_point(&a.p);
+                assert!(local.x.abs().max(local.y.abs()).max(local.z.abs()) - 0.5 < 1e-9);
+            }
+        }
+        assert!(hits > 20);
+    }
+}
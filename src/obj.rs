@@ -0,0 +1,573 @@
+// Wavefront OBJ / MTL 导入：每个（组, 材质）组合生成一个 TriangleMesh
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use crate::hittable::HittableList;
+use crate::material::{BumpMaterial, Dielectric, DiffuseLight, Lambertian, Material, Metal};
+use crate::subdivision::{Refinement, Scheme, SubdivisionMesh};
+use crate::texture::ImageTexture;
+use crate::triangle::{MeshError, TriangleMesh};
+use crate::vec3::{Color, Point3, Vec3};
+
+type MaterialRef = Arc<dyn Material + Send + Sync>;
+
+#[derive(Debug, Clone)]
+pub struct ObjError {
+    pub file: PathBuf,
+    pub line: usize, // 从 1 开始；0 表示与具体行无关（如文件无法读取）
+    pub message: String,
+}
+
+impl ObjError {
+    fn new(file: &Path, line: usize, message: impl Into<String>) -> Self {
+        ObjError { file: file.to_path_buf(), line, message: message.into() }
+    }
+}
+
+impl fmt::Display for ObjError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.line > 0 {
+            write!(f, "{}:{}: {}", self.file.display(), self.line, self.message)
+        } else {
+            write!(f, "{}: {}", self.file.display(), self.message)
+        }
+    }
+}
+
+impl std::error::Error for ObjError {}
+
+fn read_source(path: &Path) -> Result<String, ObjError> {
+    fs::read_to_string(path).map_err(|e| ObjError::new(path, 0, format!("cannot read file: {}", e)))
+}
+
+// 按逻辑行迭代：去掉注释，合并以 '\' 结尾的续行，返回 (起始行号, 内容)
+fn logical_lines(source: &str) -> Vec<(usize, String)> {
+    let mut lines = Vec::new();
+    let mut pending: Option<(usize, String)> = None;
+    for (i, raw) in source.lines().enumerate() {
+        let text = raw.split('#').next().unwrap_or("").trim_end();
+        let (start, mut acc) = pending.take().unwrap_or((i + 1, String::new()));
+        if let Some(stripped) = text.strip_suffix('\\') {
+            acc.push_str(stripped);
+            acc.push(' ');
+            pending = Some((start, acc));
+        } else {
+            acc.push_str(text);
+            lines.push((start, acc));
+        }
+    }
+    if let Some(last) = pending {
+        lines.push(last);
+    }
+    lines
+}
+
+fn parse_f64(token: Option<&str>, what: &str, file: &Path, line: usize) -> Result<f64, ObjError> {
+    let token = token.ok_or_else(|| ObjError::new(file, line, format!("missing {}", what)))?;
+    token
+        .parse::<f64>()
+        .map_err(|_| ObjError::new(file, line, format!("invalid {} '{}'", what, token)))
+}
+
+fn parse_vec3<'a>(tokens: &mut impl Iterator<Item = &'a str>, what: &str, file: &Path, line: usize) -> Result<Vec3, ObjError> {
+    Ok(Vec3::new(
+        parse_f64(tokens.next(), what, file, line)?,
+        parse_f64(tokens.next(), what, file, line)?,
+        parse_f64(tokens.next(), what, file, line)?,
+    ))
+}
+
+// OBJ 索引从 1 开始，负数表示相对于当前末尾
+fn resolve_index(token: &str, count: usize, what: &str, file: &Path, line: usize) -> Result<usize, ObjError> {
+    let i: i64 = token
+        .parse()
+        .map_err(|_| ObjError::new(file, line, format!("invalid {} index '{}'", what, token)))?;
+    let resolved = if i > 0 { i - 1 } else { count as i64 + i };
+    if i == 0 || resolved < 0 || resolved >= count as i64 {
+        return Err(ObjError::new(file, line, format!("{} index {} out of range (have {})", what, i, count)));
+    }
+    Ok(resolved as usize)
+}
+
+// 多边形三角化：投影到 Newell 法线的主平面后耳切，可处理凹多边形；
+// 退化时剩余部分按扇形划分
+pub(crate) fn triangulate(points: &[Point3]) -> Vec<[usize; 3]> {
+    let n = points.len();
+    if n == 3 {
+        return vec![[0, 1, 2]];
+    }
+    let mut normal = Vec3::default();
+    for i in 0..n {
+        let (a, b) = (points[i], points[(i + 1) % n]);
+        normal += Vec3::new((a.y - b.y) * (a.z + b.z), (a.z - b.z) * (a.x + b.x), (a.x - b.x) * (a.y + b.y));
+    }
+    let (ax, ay, az) = (normal.x.abs(), normal.y.abs(), normal.z.abs());
+    let project = |p: &Point3| {
+        if az >= ax && az >= ay {
+            (p.x, p.y, normal.z)
+        } else if ax >= ay {
+            (p.y, p.z, normal.x)
+        } else {
+            (p.z, p.x, normal.y)
+        }
+    };
+    let pts: Vec<(f64, f64)> = points.iter().map(|p| { let (x, y, _) = project(p); (x, y) }).collect();
+    let orientation = project(&points[0]).2.signum();
+    let cross = |o: (f64, f64), a: (f64, f64), b: (f64, f64)| (a.0 - o.0) * (b.1 - o.1) - (a.1 - o.1) * (b.0 - o.0);
+
+    let mut remaining: Vec<usize> = (0..n).collect();
+    let mut triangles = Vec::with_capacity(n - 2);
+    while remaining.len() > 3 {
+        let m = remaining.len();
+        let ear = (0..m).find(|&k| {
+            let (ia, ib, ic) = (remaining[(k + m - 1) % m], remaining[k], remaining[(k + 1) % m]);
+            let (a, b, c) = (pts[ia], pts[ib], pts[ic]);
+            if cross(a, b, c) * orientation <= 0.0 {
+                return false;
+            }
+            remaining.iter().all(|&j| {
+                j == ia || j == ib || j == ic || {
+                    let p = pts[j];
+                    let inside = cross(a, b, p) * orientation >= 0.0
+                        && cross(b, c, p) * orientation >= 0.0
+                        && cross(c, a, p) * orientation >= 0.0;
+                    !inside
+                }
+            })
+        });
+        match ear {
+            Some(k) => {
+                triangles.push([remaining[(k + m - 1) % m], remaining[k], remaining[(k + 1) % m]]);
+                remaining.remove(k);
+            }
+            None => break,
+        }
+    }
+    for k in 1..remaining.len() - 1 {
+        triangles.push([remaining[0], remaining[k], remaining[k + 1]]);
+    }
+    triangles
+}
+
+// 一个（组, 材质）对应的网格，顶点按 (v, vt, vn) 组合去重
+struct MeshBuilder {
+    material: MaterialRef,
+    positions: Vec<Point3>,
+    normals: Vec<Vec3>,
+    uvs: Vec<(f64, f64)>,
+    indices: Vec<[usize; 3]>,
+    remap: HashMap<(usize, Option<usize>, Option<usize>), usize>,
+    polygons: Vec<Vec<usize>>, // 细分用：按全局位置下标记录的原始多边形及其角 UV
+    polygon_uvs: Vec<Vec<(f64, f64)>>,
+    missing_normal: bool,
+    any_uv: bool,
+}
+
+impl MeshBuilder {
+    fn new(material: MaterialRef) -> Self {
+        MeshBuilder {
+            material,
+            positions: Vec::new(),
+            normals: Vec::new(),
+            uvs: Vec::new(),
+            indices: Vec::new(),
+            remap: HashMap::new(),
+            polygons: Vec::new(),
+            polygon_uvs: Vec::new(),
+            missing_normal: false,
+            any_uv: false,
+        }
+    }
+
+    fn vertex(&mut self, key: (usize, Option<usize>, Option<usize>), data: &ObjData) -> usize {
+        if let Some(&i) = self.remap.get(&key) {
+            return i;
+        }
+        let (v, vt, vn) = key;
+        let i = self.positions.len();
+        self.positions.push(data.positions[v]);
+        self.uvs.push(vt.map_or((0.0, 0.0), |t| data.uvs[t]));
+        self.normals.push(vn.map_or(Vec3::default(), |n| data.normals[n]));
+        self.any_uv |= vt.is_some();
+        self.missing_normal |= vn.is_none();
+        self.remap.insert(key, i);
+        i
+    }
+
+    // 只有全部顶点都带法线时才使用着色法线
+    fn finish(self) -> Result<TriangleMesh, MeshError> {
+        let normals = if self.missing_normal { Vec::new() } else { self.normals };
+        let uvs = if self.any_uv { self.uvs } else { Vec::new() };
+        TriangleMesh::with_attributes(self.positions, normals, uvs, self.indices, self.material)
+    }
+
+    // 以原始多边形为控制网格细分；顶点按位置共享，UV 接缝两侧一起细分而不开裂。
+    // 文件中的法线在细分后失效，改用细分曲面自己的法线
+    fn finish_subdivided(self, data: &ObjData, scheme: Scheme, refinement: Refinement) -> TriangleMesh {
+        let mut local: HashMap<usize, usize> = HashMap::new();
+        let mut positions = Vec::new();
+        let faces = self
+            .polygons
+            .iter()
+            .map(|polygon| {
+                polygon
+                    .iter()
+                    .map(|&v| {
+                        *local.entry(v).or_insert_with(|| {
+                            positions.push(data.positions[v]);
+                            positions.len() - 1
+                        })
+                    })
+                    .collect()
+            })
+            .collect();
+        let face_uvs = if self.any_uv { self.polygon_uvs } else { Vec::new() };
+        SubdivisionMesh::new(positions, faces)
+            .with_face_uvs(face_uvs)
+            .build(scheme, refinement, self.material)
+    }
+}
+
+#[derive(Default)]
+struct ObjData {
+    positions: Vec<Point3>,
+    normals: Vec<Vec3>,
+    uvs: Vec<(f64, f64)>,
+}
+
+pub fn load_obj<P: AsRef<Path>>(path: P) -> Result<HittableList, ObjError> {
+    let path = path.as_ref();
+    parse_obj(&read_source(path)?, path)
+}
+
+// path 用于错误信息以及解析 mtllib 的相对路径
+pub fn parse_obj(source: &str, path: &Path) -> Result<HittableList, ObjError> {
+    parse_obj_impl(source, path, None)
+}
+
+/// 与 load_obj 相同，但每个网格先作为控制网格细分再三角化
+pub fn load_obj_subdivided<P: AsRef<Path>>(path: P, scheme: Scheme, refinement: Refinement) -> Result<HittableList, ObjError> {
+    let path = path.as_ref();
+    parse_obj_subdivided(&read_source(path)?, path, scheme, refinement)
+}
+
+pub fn parse_obj_subdivided(source: &str, path: &Path, scheme: Scheme, refinement: Refinement) -> Result<HittableList, ObjError> {
+    parse_obj_impl(source, path, Some((scheme, refinement)))
+}
+
+fn parse_obj_impl(source: &str, path: &Path, subdivision: Option<(Scheme, Refinement)>) -> Result<HittableList, ObjError> {
+    let dir = path.parent().unwrap_or(Path::new("."));
+    let default_material: MaterialRef = Arc::new(Lambertian::from_color(Color::new(0.8, 0.8, 0.8)));
+    let mut materials: HashMap<String, MaterialRef> = HashMap::new();
+    let mut data = ObjData::default();
+    let mut builders: Vec<MeshBuilder> = Vec::new();
+    let mut builder_index: HashMap<(String, String), usize> = HashMap::new();
+    let mut group = String::new();
+    let mut material_name = String::new();
+    let mut current_material = default_material.clone();
+
+    for (line, text) in logical_lines(source) {
+        let mut tokens = text.split_whitespace();
+        let Some(keyword) = tokens.next() else { continue };
+        match keyword {
+            "v" => data.positions.push(parse_vec3(&mut tokens, "vertex coordinate", path, line)?),
+            "vn" => data.normals.push(parse_vec3(&mut tokens, "normal component", path, line)?),
+            "vt" => {
+                let u = parse_f64(tokens.next(), "texture coordinate", path, line)?;
+                let v = match tokens.next() {
+                    Some(t) => parse_f64(Some(t), "texture coordinate", path, line)?,
+                    None => 0.0,
+                };
+                data.uvs.push((u, v));
+            }
+            "f" => {
+                let mut corners = Vec::new();
+                for corner in tokens {
+                    let mut parts = corner.split('/');
+                    let v = resolve_index(parts.next().unwrap_or(""), data.positions.len(), "vertex", path, line)?;
+                    let vt = match parts.next() {
+                        Some(t) if !t.is_empty() => Some(resolve_index(t, data.uvs.len(), "texture coordinate", path, line)?),
+                        _ => None,
+                    };
+                    let vn = match parts.next() {
+                        Some(t) if !t.is_empty() => Some(resolve_index(t, data.normals.len(), "normal", path, line)?),
+                        _ => None,
+                    };
+                    corners.push((v, vt, vn));
+                }
+                if corners.len() < 3 {
+                    return Err(ObjError::new(path, line, format!("face needs at least 3 vertices, found {}", corners.len())));
+                }
+
+                let key = (group.clone(), material_name.clone());
+                let b = *builder_index.entry(key).or_insert_with(|| {
+                    builders.push(MeshBuilder::new(current_material.clone()));
+                    builders.len() - 1
+                });
+                let builder = &mut builders[b];
+                let ids: Vec<usize> = corners.iter().map(|&c| builder.vertex(c, &data)).collect();
+                let points: Vec<Point3> = corners.iter().map(|&(v, _, _)| data.positions[v]).collect();
+                for [a, b, c] in triangulate(&points) {
+                    builder.indices.push([ids[a], ids[b], ids[c]]);
+                }
+                builder.polygons.push(corners.iter().map(|&(v, _, _)| v).collect());
+                builder.polygon_uvs.push(corners.iter().map(|&(_, vt, _)| vt.map_or((0.0, 0.0), |t| data.uvs[t])).collect());
+            }
+            "g" | "o" => group = tokens.collect::<Vec<_>>().join(" "),
+            "usemtl" => {
+                let name = tokens.collect::<Vec<_>>().join(" ");
+                current_material = materials
+                    .get(&name)
+                    .cloned()
+                    .ok_or_else(|| ObjError::new(path, line, format!("unknown material '{}'", name)))?;
+                material_name = name;
+            }
+            "mtllib" => {
+                for lib in tokens {
+                    let lib_path = dir.join(lib);
+                    if !lib_path.is_file() {
+                        return Err(ObjError::new(path, line, format!("material library '{}' not found", lib_path.display())));
+                    }
+                    materials.extend(load_mtl(&lib_path)?);
+                }
+            }
+            // 平滑组、线、点、自由曲面等不影响三角网格
+            _ => {}
+        }
+    }
+
+    let mut list = HittableList::new();
+    for builder in builders {
+        if builder.indices.is_empty() {
+            continue;
+        }
+        match subdivision {
+            Some((scheme, refinement)) => list.add(Arc::new(builder.finish_subdivided(&data, scheme, refinement))),
+            None => list.add(Arc::new(builder.finish().map_err(|e| ObjError::new(path, 0, e.to_string()))?)),
+        }
+    }
+    Ok(list)
+}
+
+// MTL 中单个材质的参数
+struct MtlDesc {
+    kd: Color,
+    ks: Color,
+    ke: Color,
+    ns: f64,
+    ni: f64,
+    d: f64,
+    illum: u32,
+    map_kd: Option<Arc<ImageTexture>>,
+    map_bump: Option<(Arc<ImageTexture>, f64)>,
+}
+
+impl MtlDesc {
+    fn new() -> Self {
+        MtlDesc {
+            kd: Color::new(0.8, 0.8, 0.8),
+            ks: Color::new(0.0, 0.0, 0.0),
+            ke: Color::new(0.0, 0.0, 0.0),
+            ns: 0.0,
+            ni: 1.5,
+            d: 1.0,
+            illum: 2,
+            map_kd: None,
+            map_bump: None,
+        }
+    }
+
+    // 发光 → DiffuseLight；透明（d < 1 或折射类 illum）→ Dielectric；
+    // 镜面分量占优 → Metal（由 Phong 指数换算 fuzz）；否则 Lambertian
+    fn to_material(&self) -> MaterialRef {
+        let luminance = |c: &Color| 0.2126 * c.x + 0.7152 * c.y + 0.0722 * c.z;
+        let material: MaterialRef = if self.ke.x.max(self.ke.y).max(self.ke.z) > 0.0 {
+            Arc::new(DiffuseLight::from_color(self.ke))
+        } else if self.d < 1.0 || matches!(self.illum, 4 | 6 | 7 | 9) {
+            Arc::new(Dielectric::new(self.ni))
+        } else if luminance(&self.ks) > luminance(&self.kd) {
+            let fuzz = (2.0 / (self.ns.max(0.0) + 2.0)).sqrt();
+            Arc::new(Metal::new(self.ks, fuzz))
+        } else {
+            match self.map_kd {
+                Some(ref tex) => Arc::new(Lambertian::from_texture(tex.clone())),
+                None => Arc::new(Lambertian::from_color(self.kd)),
+            }
+        };
+        match self.map_bump {
+            Some((ref tex, scale)) => Arc::new(BumpMaterial::new(material, tex.clone(), scale)),
+            None => material,
+        }
+    }
+}
+
+// 贴图语句：选项在前，文件名在最后；返回 (文件名, -bm 缩放)
+fn parse_map<'a>(tokens: &[&'a str]) -> (Option<&'a str>, f64) {
+    let mut bump_scale = 1.0;
+    for (i, t) in tokens.iter().enumerate() {
+        if *t == "-bm" {
+            if let Some(v) = tokens.get(i + 1).and_then(|v| v.parse().ok()) {
+                bump_scale = v;
+            }
+        }
+    }
+    (tokens.last().copied(), bump_scale)
+}
+
+pub fn load_mtl(path: &Path) -> Result<HashMap<String, MaterialRef>, ObjError> {
+    let source = read_source(path)?;
+    let dir = path.parent().unwrap_or(Path::new("."));
+    let mut textures: HashMap<PathBuf, Arc<ImageTexture>> = HashMap::new();
+    let mut texture = |name: &str, line: usize| -> Result<Arc<ImageTexture>, ObjError> {
+        let file = dir.join(name);
+        if !file.is_file() {
+            return Err(ObjError::new(path, line, format!("texture '{}' not found", file.display())));
+        }
+        Ok(textures
+            .entry(file.clone())
+            .or_insert_with(|| Arc::new(ImageTexture::new(&file.to_string_lossy())))
+            .clone())
+    };
+
+    let mut materials = HashMap::new();
+    let mut current: Option<(String, MtlDesc)> = None;
+    for (line, text) in logical_lines(&source) {
+        let mut tokens = text.split_whitespace();
+        let Some(keyword) = tokens.next() else { continue };
+        if keyword == "newmtl" {
+            if let Some((name, desc)) = current.take() {
+                materials.insert(name, desc.to_material());
+            }
+            current = Some((tokens.collect::<Vec<_>>().join(" "), MtlDesc::new()));
+            continue;
+        }
+        let Some((_, desc)) = current.as_mut() else {
+            return Err(ObjError::new(path, line, format!("'{}' before any newmtl", keyword)));
+        };
+        match keyword {
+            "Kd" => desc.kd = parse_vec3(&mut tokens, "Kd component", path, line)?,
+            "Ks" => desc.ks = parse_vec3(&mut tokens, "Ks component", path, line)?,
+            "Ke" => desc.ke = parse_vec3(&mut tokens, "Ke component", path, line)?,
+            "Ns" => desc.ns = parse_f64(tokens.next(), "Ns", path, line)?,
+            "Ni" => desc.ni = parse_f64(tokens.next(), "Ni", path, line)?,
+            "d" => desc.d = parse_f64(tokens.next(), "d", path, line)?,
+            "Tr" => desc.d = 1.0 - parse_f64(tokens.next(), "Tr", path, line)?,
+            "illum" => {
+                let value = tokens.next().unwrap_or("");
+                desc.illum = value
+                    .parse()
+                    .map_err(|_| ObjError::new(path, line, format!("invalid illum '{}'", value)))?;
+            }
+            "map_Kd" | "map_Bump" | "map_bump" | "bump" => {
+                let args: Vec<&str> = tokens.collect();
+                let (name, scale) = parse_map(&args);
+                let name = name.ok_or_else(|| ObjError::new(path, line, format!("{} without a file name", keyword)))?;
+                let tex = texture(name, line)?;
+                if keyword == "map_Kd" {
+                    desc.map_kd = Some(tex);
+                } else {
+                    desc.map_bump = Some((tex, scale));
+                }
+            }
+            // Ka、Tf、其他贴图等不受支持的参数忽略
+            _ => {}
+        }
+    }
+    if let Some((name, desc)) = current {
+        materials.insert(name, desc.to_material());
+    }
+    Ok(materials)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hittable::{HitRecord, Hittable};
+    use crate::interval::Interval;
+    use crate::ray::Ray;
+
+    fn parse(source: &str) -> Result<HittableList, ObjError> {
+        parse_obj(source, Path::new("test.obj"))
+    }
+
+    fn error_line(source: &str) -> usize {
+        parse(source).err().expect("malformed file should fail").line
+    }
+
+    fn hits_down(list: &HittableList, x: f64, z: f64) -> bool {
+        let r = Ray::new(Point3::new(x, 1.0, z), Vec3::new(0.0, -1.0, 0.0), 0.0);
+        list.hit(&r, Interval::new(0.001, f64::INFINITY), &mut HitRecord::default())
+    }
+
+    #[test]
+    fn negative_indices_refer_to_latest_vertices() {
+        let list = parse("v 9 9 9\nv 0 0 0\nv 1 0 0\nv 1 0 1\nv 0 0 1\nf -4 -1 -2 -3\n").unwrap();
+        assert_eq!(list.objects.len(), 1);
+        assert!(hits_down(&list, 0.5, 0.5));
+        assert!(!hits_down(&list, 1.5, 0.5));
+    }
+
+    #[test]
+    fn groups_and_materials_split_meshes() {
+        let source = "v 0 0 0\nv 1 0 0\nv 0 0 1\ng a\nf 1 2 3\ng b\nf 1 3 2\ng a\nf 3 2 1\n";
+        assert_eq!(parse(source).unwrap().objects.len(), 2);
+    }
+
+    // 凹的 L 形：耳切得到 n - 2 个同向三角形，面积之和等于多边形面积
+    #[test]
+    fn ear_clipping_handles_concave_polygons() {
+        let points = [(0.0, 0.0), (2.0, 0.0), (2.0, 1.0), (1.0, 1.0), (1.0, 2.0), (0.0, 2.0)]
+            .map(|(x, z)| Point3::new(x, 0.0, -z));
+        let triangles = triangulate(&points);
+        assert_eq!(triangles.len(), 4);
+        let area: f64 = triangles
+            .iter()
+            .map(|&[a, b, c]| {
+                let n = Vec3::cross(&(points[b] - points[a]), &(points[c] - points[a]));
+                assert!(n.y > 0.0);
+                0.5 * n.length()
+            })
+            .sum();
+        assert!((area - 3.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn errors_report_the_offending_line() {
+        assert_eq!(error_line("v 0 0 0\nv 1 0 0\n\nf 1 2 4\n"), 4);
+        assert_eq!(error_line("v 0 0 0\nv 1 0 0\nf 1 2\n"), 3);
+        assert_eq!(error_line("v 0 0 0\nv 1 x 0\n"), 2);
+        assert_eq!(error_line("v 0 0 0\nv 1 0 0\nv 0 0 1\nf 0 1 2\n"), 4);
+        assert_eq!(error_line("usemtl missing\n"), 1);
+        // 续行合并后仍报告逻辑行的起始行号
+        assert_eq!(error_line("# comment\nv 0 0 0\nv 1 0 0\nf 1 \\\n  2 \\\n  3\n"), 4);
+        let err = parse("v 1 0\n").err().unwrap();
+        assert_eq!(err.to_string(), "test.obj:1: missing vertex coordinate");
+    }
+
+    #[test]
+    fn loads_the_trophy_model_with_its_materials() {
+        let list = load_obj("input/models/trophy.obj").unwrap();
+        assert_eq!(list.objects.len(), 2);
+        let materials = load_mtl(Path::new("input/models/trophy.mtl")).unwrap();
+        assert_eq!(materials.len(), 2);
+        assert!(hits_down(&list, 0.0, 0.0));
+    }
+
+    #[test]
+    fn mtl_errors_report_the_offending_line() {
+        let dir = std::env::temp_dir().join(format!("obj-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let mtl = dir.join("bad.mtl");
+        fs::write(&mtl, "newmtl a\nKd 1 1 1\nnewmtl b\nmap_Kd missing.png\n").unwrap();
+        assert_eq!(load_mtl(&mtl).err().unwrap().line, 4);
+        fs::write(&mtl, "Kd 1 1 1\n").unwrap();
+        assert_eq!(load_mtl(&mtl).err().unwrap().line, 1);
+        fs::write(&mtl, "newmtl lamp\nKe 4 4 4\nnewmtl glass\nd 0.2\nNi 1.33\n").unwrap();
+        let materials = load_mtl(&mtl).unwrap();
+        assert!(materials["lamp"].is_emissive());
+        assert!(!materials["glass"].is_emissive());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
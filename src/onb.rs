@@ -33,4 +33,9 @@ impl Onb {
     pub fn transform(&self, v: &Vec3) -> Vec3 {
         v.x * self.axis[0] + v.y * self.axis[1] + v.z * self.axis[2]
     }
+
+    // transform 的逆：世界坐标 → 局部坐标
+    pub fn to_local(&self, v: &Vec3) -> Vec3 {
+        Vec3::new(Vec3::dot(v, &self.axis[0]), Vec3::dot(v, &self.axis[1]), Vec3::dot(v, &self.axis[2]))
+    }
 }
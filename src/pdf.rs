@@ -78,21 +78,28 @@ impl Pdf for HittablePdf {
 
 pub struct MixturePdf {
     p: [Arc<dyn Pdf + Send + Sync>; 2],
+    // 选择 p1 的概率
+    weight: f64,
 }
 
 impl MixturePdf {
     pub fn new(p0: Arc<dyn Pdf + Send + Sync>, p1: Arc<dyn Pdf + Send + Sync>) -> Self {
-        Self { p: [p0, p1] }
+        Self::with_weight(p0, p1, 0.5)
+    }
+
+    // 按权重混合：以 weight 的概率采样 p1，1 - weight 的概率采样 p0
+    pub fn with_weight(p0: Arc<dyn Pdf + Send + Sync>, p1: Arc<dyn Pdf + Send + Sync>, weight: f64) -> Self {
+        Self { p: [p0, p1], weight: weight.clamp(0.0, 1.0) }
     }
 }
 
 impl Pdf for MixturePdf {
     fn value(&self, direction: &Vec3) -> f64 {
-        0.5 * self.p[0].value(direction) + 0.5 * self.p[1].value(direction)
+        (1.0 - self.weight) * self.p[0].value(direction) + self.weight * self.p[1].value(direction)
     }
 
     fn generate(&self) -> Vec3 {
-        if random_double() < 0.5 {
+        if random_double() >= self.weight {
             self.p[0].generate()
         } else {
             self.p[1].generate()
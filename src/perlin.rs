@@ -162,6 +162,13 @@ impl Perlin {
         accum.abs()
     }
 
+    // turb 的保证上界：noise 是各角点 dot(c, w) 的凸组合，|w| 各分量不超过 1，
+    // 故 |noise| ≤ max Σ|c_i|；各层权重之和为 2 - 2^(1 - depth)
+    pub fn turb_bound(&self, depth: i32) -> f64 {
+        let noise_bound = self.randvec.iter().map(|c| c.x.abs() + c.y.abs() + c.z.abs()).fold(0.0, f64::max);
+        noise_bound * (2.0 - 0.5f64.powi(depth.max(0) - 1))
+    }
+
     fn perlin_generate_perm() -> Vec<i32> {
         let mut p = vec![0; Self::POINT_COUNT];
 
@@ -0,0 +1,278 @@
+// 参与介质的相位函数：绕入射方向旋转对称，只依赖散射角余弦 μ = dot(入射传播方向, 出射方向)
+// 每种相位函数都能精确按自身分布采样，PhasePdf 的 value 与相位函数值一致
+use std::sync::Arc;
+use rand::rngs::ThreadRng;
+use crate::hittable::HitRecord;
+use crate::material::{Material, ScatterRecord};
+use crate::onb::Onb;
+use crate::pdf::Pdf;
+use crate::ray::Ray;
+use crate::rtweekend::random::random_double;
+use crate::rtweekend::PI;
+use crate::texture::{SolidColor, Texture};
+use crate::vec3::{Color, Vec3};
+
+pub trait PhaseFunction {
+    /// 单位立体角上的概率密度，对整个球面积分为 1
+    fn eval(&self, cos_theta: f64) -> f64;
+    /// 按 eval 的分布采样 μ
+    fn sample_cos_theta(&self) -> f64;
+}
+
+/// g ∈ (-1, 1)：g > 0 前向散射，g < 0 后向散射，g = 0 即各向同性
+pub struct HenyeyGreenstein {
+    g: f64,
+}
+
+impl HenyeyGreenstein {
+    pub fn new(g: f64) -> Self {
+        Self { g: g.clamp(-0.999, 0.999) }
+    }
+}
+
+fn henyey_greenstein(g: f64, cos_theta: f64) -> f64 {
+    let denom = 1.0 + g * g - 2.0 * g * cos_theta;
+    (1.0 - g * g) / (4.0 * PI * denom * denom.max(1e-12).sqrt())
+}
+
+fn sample_henyey_greenstein(g: f64) -> f64 {
+    let xi = random_double();
+    if g.abs() < 1e-3 {
+        return 1.0 - 2.0 * xi;
+    }
+    let s = (1.0 - g * g) / (1.0 - g + 2.0 * g * xi);
+    ((1.0 + g * g - s * s) / (2.0 * g)).clamp(-1.0, 1.0)
+}
+
+impl PhaseFunction for HenyeyGreenstein {
+    fn eval(&self, cos_theta: f64) -> f64 {
+        henyey_greenstein(self.g, cos_theta)
+    }
+
+    fn sample_cos_theta(&self) -> f64 {
+        sample_henyey_greenstein(self.g)
+    }
+}
+
+/// 两个 HG 波瓣的混合 w·HG(g1) + (1 − w)·HG(g2)，常用一个前向、一个后向波瓣描述云
+pub struct DoubleHenyeyGreenstein {
+    g1: f64,
+    g2: f64,
+    w: f64,
+}
+
+impl DoubleHenyeyGreenstein {
+    pub fn new(g1: f64, g2: f64, w: f64) -> Self {
+        Self { g1: g1.clamp(-0.999, 0.999), g2: g2.clamp(-0.999, 0.999), w: w.clamp(0.0, 1.0) }
+    }
+}
+
+impl PhaseFunction for DoubleHenyeyGreenstein {
+    fn eval(&self, cos_theta: f64) -> f64 {
+        self.w * henyey_greenstein(self.g1, cos_theta) + (1.0 - self.w) * henyey_greenstein(self.g2, cos_theta)
+    }
+
+    fn sample_cos_theta(&self) -> f64 {
+        if random_double() < self.w {
+            sample_henyey_greenstein(self.g1)
+        } else {
+            sample_henyey_greenstein(self.g2)
+        }
+    }
+}
+
+/// 远小于波长的粒子（空气分子）：3/(16π)·(1 + μ²)
+pub struct Rayleigh;
+
+impl PhaseFunction for Rayleigh {
+    fn eval(&self, cos_theta: f64) -> f64 {
+        3.0 / (16.0 * PI) * (1.0 + cos_theta * cos_theta)
+    }
+
+    // 累积分布 (μ³ + 3μ + 4) / 8 = ξ，用 Cardano 公式解三次方程
+    fn sample_cos_theta(&self) -> f64 {
+        let q = 4.0 - 8.0 * random_double();
+        let s = (q * q / 4.0 + 1.0).sqrt();
+        ((-q / 2.0 + s).cbrt() + (-q / 2.0 - s).cbrt()).clamp(-1.0, 1.0)
+    }
+}
+
+/// Cornette–Shanks 对 Mie 散射（气溶胶、水滴）的近似，g = 0 时退化为 Rayleigh
+pub struct CornetteShanks {
+    g: f64,
+}
+
+impl CornetteShanks {
+    pub fn new(g: f64) -> Self {
+        Self { g: g.clamp(-0.999, 0.999) }
+    }
+}
+
+impl PhaseFunction for CornetteShanks {
+    fn eval(&self, cos_theta: f64) -> f64 {
+        let g = self.g;
+        let denom = 1.0 + g * g - 2.0 * g * cos_theta;
+        3.0 / (8.0 * PI) * (1.0 - g * g) * (1.0 + cos_theta * cos_theta)
+            / ((2.0 + g * g) * denom * denom.max(1e-12).sqrt())
+    }
+
+    // 与同一 g 的 HG 之比正比于 1 + μ²，按 HG 采样后以 (1 + μ²) / 2 的概率接受
+    fn sample_cos_theta(&self) -> f64 {
+        loop {
+            let mu = sample_henyey_greenstein(self.g);
+            if 2.0 * random_double() < 1.0 + mu * mu {
+                return mu;
+            }
+        }
+    }
+}
+
+/// 以入射传播方向为轴按相位函数采样的方向分布
+pub struct PhasePdf {
+    phase: Arc<dyn PhaseFunction + Send + Sync>,
+    uvw: Onb,
+}
+
+impl PhasePdf {
+    pub fn new(phase: Arc<dyn PhaseFunction + Send + Sync>, direction: &Vec3) -> Self {
+        Self { phase, uvw: Onb::new(direction) }
+    }
+}
+
+impl Pdf for PhasePdf {
+    fn value(&self, direction: &Vec3) -> f64 {
+        self.phase.eval(Vec3::dot(&Vec3::unit_vector(*direction), self.uvw.w()))
+    }
+
+    fn generate(&self) -> Vec3 {
+        let cos_theta = self.phase.sample_cos_theta();
+        let sin_theta = (1.0 - cos_theta * cos_theta).max(0.0).sqrt();
+        let phi = 2.0 * PI * random_double();
+        self.uvw.transform(&Vec3::new(sin_theta * phi.cos(), sin_theta * phi.sin(), cos_theta))
+    }
+}
+
+/// 介质内的散射“材质”：反照率 × 相位函数，取代 material::Isotropic
+pub struct PhaseMaterial {
+    phase: Arc<dyn PhaseFunction + Send + Sync>,
+    tex: Arc<dyn Texture + Send + Sync>,
+}
+
+impl PhaseMaterial {
+    pub fn new_with_color(phase: Arc<dyn PhaseFunction + Send + Sync>, albedo: Color) -> Self {
+        Self { phase, tex: Arc::new(SolidColor::new(albedo)) }
+    }
+}
+
+impl Material for PhaseMaterial {
+    fn scatter(
+        &self,
+        r_in: &Ray,
+        rec: &HitRecord,
+        srec: &mut ScatterRecord,
+        _rng: &mut ThreadRng,
+    ) -> bool {
+        srec.attenuation = self.tex.value_at(rec);
+        srec.pdf_ptr = Some(Arc::new(PhasePdf::new(self.phase.clone(), &r_in.direction)));
+        srec.skip_pdf = false;
+        srec.skip_pdf_ray = None;
+        true
+    }
+
+    fn scattering_pdf(&self, r_in: &Ray, _rec: &HitRecord, scattered: &Ray) -> f64 {
+        let cos_theta = Vec3::dot(&Vec3::unit_vector(r_in.direction), &Vec3::unit_vector(scattered.direction));
+        self.phase.eval(cos_theta)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn phases() -> Vec<(&'static str, Arc<dyn PhaseFunction + Send + Sync>)> {
+        vec![
+            ("hg 0", Arc::new(HenyeyGreenstein::new(0.0))),
+            ("hg 0.85", Arc::new(HenyeyGreenstein::new(0.85))),
+            ("hg -0.5", Arc::new(HenyeyGreenstein::new(-0.5))),
+            ("double hg", Arc::new(DoubleHenyeyGreenstein::new(0.8, -0.3, 0.7))),
+            ("rayleigh", Arc::new(Rayleigh)),
+            ("cornette-shanks", Arc::new(CornetteShanks::new(0.76))),
+        ]
+    }
+
+    // 中点法求 2π ∫ eval(μ) dμ，区间 [a, b]
+    fn integral(phase: &dyn PhaseFunction, a: f64, b: f64) -> f64 {
+        let n = 20000;
+        let h = (b - a) / n as f64;
+        (0..n).map(|i| phase.eval(a + (i as f64 + 0.5) * h)).sum::<f64>() * 2.0 * PI * h
+    }
+
+    #[test]
+    fn phase_functions_are_normalised() {
+        for (name, phase) in phases() {
+            assert!((integral(phase.as_ref(), -1.0, 1.0) - 1.0).abs() < 1e-4, "{name}");
+        }
+        // g = 0 时 HG 各向同性，Cornette–Shanks 退化为 Rayleigh
+        assert!((HenyeyGreenstein::new(0.0).eval(0.3) - 1.0 / (4.0 * PI)).abs() < 1e-12);
+        for mu in [-1.0, -0.2, 0.6, 1.0] {
+            assert!((CornetteShanks::new(0.0).eval(mu) - Rayleigh.eval(mu)).abs() < 1e-12);
+        }
+    }
+
+    // 采样的 μ 落在各区间的频率与 eval 在该区间的积分一致
+    #[test]
+    fn sampling_follows_eval() {
+        let bins = 16;
+        let n = 100000;
+        for (name, phase) in phases() {
+            let mut counts = vec![0usize; bins];
+            for _ in 0..n {
+                let mu = phase.sample_cos_theta();
+                assert!((-1.0..=1.0).contains(&mu));
+                counts[(((mu + 1.0) / 2.0 * bins as f64) as usize).min(bins - 1)] += 1;
+            }
+            for (i, &count) in counts.iter().enumerate() {
+                let (a, b) = (-1.0 + 2.0 * i as f64 / bins as f64, -1.0 + 2.0 * (i + 1) as f64 / bins as f64);
+                let expected = integral(phase.as_ref(), a, b);
+                assert!((count as f64 / n as f64 - expected).abs() < 0.006, "{name} bin {i}");
+            }
+        }
+    }
+
+    // HG 的平均散射角余弦为 g，双波瓣为两者按权重的平均
+    #[test]
+    fn mean_cosine_matches_g() {
+        let n = 200000;
+        let mean = |phase: &dyn PhaseFunction| (0..n).map(|_| phase.sample_cos_theta()).sum::<f64>() / n as f64;
+        assert!((mean(&HenyeyGreenstein::new(0.6)) - 0.6).abs() < 0.01);
+        assert!((mean(&HenyeyGreenstein::new(-0.4)) + 0.4).abs() < 0.01);
+        assert!((mean(&DoubleHenyeyGreenstein::new(0.8, -0.3, 0.7)) - (0.7 * 0.8 - 0.3 * 0.3)).abs() < 0.01);
+    }
+
+    // 方向分布以入射传播方向为轴：value 与 generate 一致，前向散射的方向偏向该轴
+    #[test]
+    fn phase_pdf_is_centred_on_the_incoming_direction() {
+        let axis = Vec3::unit_vector(Vec3::new(1.0, -2.0, 0.5));
+        let pdf = PhasePdf::new(Arc::new(HenyeyGreenstein::new(0.7)), &(3.0 * axis));
+        let n = 100000;
+        let uniform = 1.0 / (4.0 * PI);
+        let mut mean_cos = 0.0;
+        let integral = (0..n)
+            .map(|_| {
+                let sampled = pdf.generate();
+                mean_cos += Vec3::dot(&sampled, &axis);
+                let direction = if random_double() < 0.5 { Vec3::random_unit_vector() } else { sampled };
+                let p = pdf.value(&direction);
+                p / (0.5 * uniform + 0.5 * p)
+            })
+            .sum::<f64>() / n as f64;
+        assert!((integral - 1.0).abs() < 0.02);
+        assert!((mean_cos / n as f64 - 0.7).abs() < 0.01);
+
+        let material = PhaseMaterial::new_with_color(Arc::new(Rayleigh), Color::new(1.0, 1.0, 1.0));
+        let r_in = Ray::new(Vec3::default(), axis, 0.0);
+        let scattered = Ray::new(Vec3::default(), Vec3::new(0.0, 0.0, 2.0), 0.0);
+        let expected = Rayleigh.eval(axis.z);
+        assert!((material.scattering_pdf(&r_in, &HitRecord::default(), &scattered) - expected).abs() < 1e-12);
+    }
+}
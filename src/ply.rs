@@ -0,0 +1,415 @@
+// PLY 网格导入：ASCII 与二进制（小端/大端）编码，支持顶点法线、UV、顶点颜色与任意边数的面
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use crate::hittable::HittableList;
+use crate::material::Material;
+use crate::obj::triangulate;
+use crate::triangle::TriangleMesh;
+use crate::vec3::{Color, Point3, Vec3};
+
+#[derive(Debug, Clone)]
+pub struct PlyError {
+    pub file: PathBuf,
+    pub message: String,
+}
+
+impl PlyError {
+    fn new(file: &Path, message: impl Into<String>) -> Self {
+        PlyError { file: file.to_path_buf(), message: message.into() }
+    }
+}
+
+impl fmt::Display for PlyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.file.display(), self.message)
+    }
+}
+
+impl std::error::Error for PlyError {}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Format {
+    Ascii,
+    BinaryLittleEndian,
+    BinaryBigEndian,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum ScalarType {
+    I8,
+    U8,
+    I16,
+    U16,
+    I32,
+    U32,
+    F32,
+    F64,
+}
+
+impl ScalarType {
+    fn parse(name: &str) -> Option<Self> {
+        Some(match name {
+            "char" | "int8" => ScalarType::I8,
+            "uchar" | "uint8" => ScalarType::U8,
+            "short" | "int16" => ScalarType::I16,
+            "ushort" | "uint16" => ScalarType::U16,
+            "int" | "int32" => ScalarType::I32,
+            "uint" | "uint32" => ScalarType::U32,
+            "float" | "float32" => ScalarType::F32,
+            "double" | "float64" => ScalarType::F64,
+            _ => return None,
+        })
+    }
+
+    fn size(&self) -> usize {
+        match self {
+            ScalarType::I8 | ScalarType::U8 => 1,
+            ScalarType::I16 | ScalarType::U16 => 2,
+            ScalarType::I32 | ScalarType::U32 | ScalarType::F32 => 4,
+            ScalarType::F64 => 8,
+        }
+    }
+
+    // 整数颜色通道按类型最大值归一化到 [0, 1]
+    fn color_scale(&self) -> f64 {
+        match self {
+            ScalarType::U8 | ScalarType::I8 => 1.0 / 255.0,
+            ScalarType::U16 | ScalarType::I16 => 1.0 / 65535.0,
+            _ => 1.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Property {
+    Scalar { name: String, ty: ScalarType },
+    List { name: String, count: ScalarType, item: ScalarType },
+}
+
+impl Property {
+    fn name(&self) -> &str {
+        match self {
+            Property::Scalar { name, .. } | Property::List { name, .. } => name,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Element {
+    name: String,
+    count: usize,
+    properties: Vec<Property>,
+}
+
+// 数据区读取器：ASCII 按空白分词，二进制按字节序解码
+struct Reader<'a> {
+    format: Format,
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn read(&mut self, ty: ScalarType) -> Result<f64, String> {
+        match self.format {
+            Format::Ascii => {
+                while self.pos < self.data.len() && self.data[self.pos].is_ascii_whitespace() {
+                    self.pos += 1;
+                }
+                let start = self.pos;
+                while self.pos < self.data.len() && !self.data[self.pos].is_ascii_whitespace() {
+                    self.pos += 1;
+                }
+                if start == self.pos {
+                    return Err("unexpected end of data".to_string());
+                }
+                let token = String::from_utf8_lossy(&self.data[start..self.pos]);
+                token.parse::<f64>().map_err(|_| format!("invalid number '{}'", token))
+            }
+            Format::BinaryLittleEndian | Format::BinaryBigEndian => {
+                let size = ty.size();
+                if self.pos + size > self.data.len() {
+                    return Err("unexpected end of data".to_string());
+                }
+                let mut bytes = [0u8; 8];
+                bytes[..size].copy_from_slice(&self.data[self.pos..self.pos + size]);
+                self.pos += size;
+                if self.format == Format::BinaryBigEndian {
+                    bytes[..size].reverse();
+                }
+                Ok(match ty {
+                    ScalarType::I8 => bytes[0] as i8 as f64,
+                    ScalarType::U8 => bytes[0] as f64,
+                    ScalarType::I16 => i16::from_le_bytes([bytes[0], bytes[1]]) as f64,
+                    ScalarType::U16 => u16::from_le_bytes([bytes[0], bytes[1]]) as f64,
+                    ScalarType::I32 => i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f64,
+                    ScalarType::U32 => u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f64,
+                    ScalarType::F32 => f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f64,
+                    ScalarType::F64 => f64::from_le_bytes(bytes),
+                })
+            }
+        }
+    }
+}
+
+// 解析头部，返回 (编码, 元素列表, 数据区起始偏移)
+fn parse_header(bytes: &[u8], path: &Path) -> Result<(Format, Vec<Element>, usize), PlyError> {
+    let mut pos = 0;
+    let mut next_line = || -> Option<String> {
+        if pos >= bytes.len() {
+            return None;
+        }
+        let end = bytes[pos..].iter().position(|&b| b == b'\n').map_or(bytes.len(), |i| pos + i);
+        let line = String::from_utf8_lossy(&bytes[pos..end]).trim().to_string();
+        pos = (end + 1).min(bytes.len());
+        Some(line)
+    };
+
+    if next_line().as_deref() != Some("ply") {
+        return Err(PlyError::new(path, "missing 'ply' magic number"));
+    }
+    let mut format = None;
+    let mut elements: Vec<Element> = Vec::new();
+    let mut line_no = 1;
+    loop {
+        line_no += 1;
+        let line = next_line().ok_or_else(|| PlyError::new(path, "header is not terminated by end_header"))?;
+        let err = |msg: String| PlyError::new(path, format!("header line {}: {}", line_no, msg));
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        match tokens.first().copied() {
+            Some("end_header") => break,
+            Some("format") => {
+                format = Some(match tokens.get(1).copied() {
+                    Some("ascii") => Format::Ascii,
+                    Some("binary_little_endian") => Format::BinaryLittleEndian,
+                    Some("binary_big_endian") => Format::BinaryBigEndian,
+                    other => return Err(err(format!("unsupported format {:?}", other))),
+                });
+            }
+            Some("element") => {
+                let (Some(name), Some(count)) = (tokens.get(1), tokens.get(2)) else {
+                    return Err(err("element needs a name and a count".to_string()));
+                };
+                let count = count.parse().map_err(|_| err(format!("invalid element count '{}'", count)))?;
+                elements.push(Element { name: name.to_string(), count, properties: Vec::new() });
+            }
+            Some("property") => {
+                let element = elements.last_mut().ok_or_else(|| err("property before any element".to_string()))?;
+                let scalar = |name: Option<&&str>| {
+                    name.and_then(|n| ScalarType::parse(n)).ok_or_else(|| err(format!("unknown property type {:?}", name)))
+                };
+                let property = if tokens.get(1) == Some(&"list") {
+                    Property::List {
+                        count: scalar(tokens.get(2))?,
+                        item: scalar(tokens.get(3))?,
+                        name: tokens.get(4).ok_or_else(|| err("list property without a name".to_string()))?.to_string(),
+                    }
+                } else {
+                    Property::Scalar {
+                        ty: scalar(tokens.get(1))?,
+                        name: tokens.get(2).ok_or_else(|| err("property without a name".to_string()))?.to_string(),
+                    }
+                };
+                element.properties.push(property);
+            }
+            // comment、obj_info 与空行
+            _ => {}
+        }
+    }
+    let format = format.ok_or_else(|| PlyError::new(path, "header has no format line"))?;
+    Ok((format, elements, pos))
+}
+
+pub fn load_ply<P: AsRef<Path>>(path: P, mat: Arc<dyn Material + Send + Sync>) -> Result<HittableList, PlyError> {
+    let path = path.as_ref();
+    let bytes = fs::read(path).map_err(|e| PlyError::new(path, format!("cannot read file: {}", e)))?;
+    parse_ply(&bytes, path, mat)
+}
+
+// 输出与 OBJ 导入一致：单个 TriangleMesh 组成的 HittableList。
+// 顶点颜色写入网格，可通过 VertexColorTexture 使用
+pub fn parse_ply(bytes: &[u8], path: &Path, mat: Arc<dyn Material + Send + Sync>) -> Result<HittableList, PlyError> {
+    let (format, elements, data_start) = parse_header(bytes, path)?;
+    let mut reader = Reader { format, data: &bytes[data_start..], pos: 0 };
+
+    let mut positions = Vec::new();
+    let mut normals = Vec::new();
+    let mut uvs = Vec::new();
+    let mut colors = Vec::new();
+    let mut faces: Vec<Vec<usize>> = Vec::new();
+
+    for element in &elements {
+        let slot = |names: &[&str]| element.properties.iter().position(|p| names.contains(&p.name()));
+        let (x, y, z) = (slot(&["x"]), slot(&["y"]), slot(&["z"]));
+        let (nx, ny, nz) = (slot(&["nx"]), slot(&["ny"]), slot(&["nz"]));
+        let (u, v) = (slot(&["u", "s", "texture_u", "texture_s"]), slot(&["v", "t", "texture_v", "texture_t"]));
+        let (r, g, b) = (slot(&["red", "r"]), slot(&["green", "g"]), slot(&["blue", "b"]));
+        let indices = slot(&["vertex_indices", "vertex_index"]);
+        let is_vertex = element.name == "vertex";
+        let is_face = element.name == "face";
+        if is_vertex && (x.is_none() || y.is_none() || z.is_none()) {
+            return Err(PlyError::new(path, "vertex element lacks x/y/z properties"));
+        }
+
+        for row in 0..element.count {
+            let err = |msg: String| PlyError::new(path, format!("{} {}: {}", element.name, row, msg));
+            let mut scalars = vec![0.0; element.properties.len()];
+            let mut lists: Vec<Option<Vec<f64>>> = vec![None; element.properties.len()];
+            for (i, property) in element.properties.iter().enumerate() {
+                match *property {
+                    Property::Scalar { ty, .. } => scalars[i] = reader.read(ty).map_err(err)?,
+                    Property::List { count, item, .. } => {
+                        let n = reader.read(count).map_err(err)?;
+                        if n < 0.0 {
+                            return Err(err(format!("negative list length {}", n)));
+                        }
+                        let items = (0..n as usize).map(|_| reader.read(item)).collect::<Result<Vec<_>, _>>().map_err(err)?;
+                        lists[i] = Some(items);
+                    }
+                }
+            }
+
+            if is_vertex {
+                let get = |slot: Option<usize>| slot.map(|i| scalars[i]);
+                positions.push(Point3::new(scalars[x.unwrap()], scalars[y.unwrap()], scalars[z.unwrap()]));
+                if let (Some(nx), Some(ny), Some(nz)) = (get(nx), get(ny), get(nz)) {
+                    normals.push(Vec3::new(nx, ny, nz));
+                }
+                if let (Some(u), Some(v)) = (get(u), get(v)) {
+                    uvs.push((u, v));
+                }
+                if let (Some(ri), Some(gi), Some(bi)) = (r, g, b) {
+                    let scale = |i: usize| match element.properties[i] {
+                        Property::Scalar { ty, .. } => ty.color_scale(),
+                        _ => 1.0,
+                    };
+                    colors.push(Color::new(scalars[ri] * scale(ri), scalars[gi] * scale(gi), scalars[bi] * scale(bi)));
+                }
+            } else if is_face {
+                let list = indices
+                    .and_then(|i| lists[i].take())
+                    .ok_or_else(|| err("face has no vertex_indices list".to_string()))?;
+                if list.len() < 3 {
+                    return Err(err(format!("face needs at least 3 vertices, found {}", list.len())));
+                }
+                if let Some(&bad) = list.iter().find(|&&i| i < 0.0 || i.fract() != 0.0) {
+                    return Err(err(format!("invalid vertex index {}", bad)));
+                }
+                faces.push(list.iter().map(|&i| i as usize).collect());
+            }
+        }
+    }
+
+    let mut triangles = Vec::new();
+    for (f, face) in faces.iter().enumerate() {
+        if let Some(&bad) = face.iter().find(|&&i| i >= positions.len()) {
+            return Err(PlyError::new(path, format!("face {}: vertex index {} out of range (have {})", f, bad, positions.len())));
+        }
+        let points: Vec<Point3> = face.iter().map(|&i| positions[i]).collect();
+        for [a, b, c] in triangulate(&points) {
+            triangles.push([face[a], face[b], face[c]]);
+        }
+    }
+
+    let mesh = TriangleMesh::with_attributes(positions, normals, uvs, triangles, mat)
+        .and_then(|mesh| mesh.with_vertex_colors(colors))
+        .map_err(|e| PlyError::new(path, e.to_string()))?;
+    Ok(HittableList::with_object(Arc::new(mesh)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hittable::{HitRecord, Hittable};
+    use crate::interval::Interval;
+    use crate::material::Lambertian;
+    use crate::ray::Ray;
+
+    const QUAD: [(f64, f64, f64); 4] = [(0.0, 0.0, 0.0), (1.0, 0.0, 0.0), (1.0, 0.0, 1.0), (0.0, 0.0, 1.0)];
+
+    fn grey() -> Arc<dyn Material + Send + Sync> {
+        Arc::new(Lambertian::from_color(Color::new(0.5, 0.5, 0.5)))
+    }
+
+    fn parse(bytes: &[u8]) -> Result<HittableList, PlyError> {
+        parse_ply(bytes, Path::new("test.ply"), grey())
+    }
+
+    fn header(format: &str, faces: usize) -> String {
+        format!(
+            "ply\nformat {} 1.0\ncomment test\nelement vertex 4\nproperty float x\nproperty float y\nproperty float z\n\
+             property uchar red\nproperty uchar green\nproperty uchar blue\nelement face {}\nproperty list uchar int vertex_indices\nend_header\n",
+            format, faces
+        )
+    }
+
+    // 四个顶点颜色为 (255, 0, 0)、单个四边形面的二进制文件
+    fn binary_quad(big_endian: bool) -> Vec<u8> {
+        let format = if big_endian { "binary_big_endian" } else { "binary_little_endian" };
+        let mut bytes = header(format, 1).into_bytes();
+        let f32_bytes = |v: f32| if big_endian { v.to_be_bytes() } else { v.to_le_bytes() };
+        let i32_bytes = |v: i32| if big_endian { v.to_be_bytes() } else { v.to_le_bytes() };
+        for (x, y, z) in QUAD {
+            for c in [x, y, z] {
+                bytes.extend_from_slice(&f32_bytes(c as f32));
+            }
+            bytes.extend_from_slice(&[255, 0, 0]);
+        }
+        bytes.push(4);
+        for i in 0..4 {
+            bytes.extend_from_slice(&i32_bytes(i));
+        }
+        bytes
+    }
+
+    fn hit_center(list: &HittableList) -> Option<HitRecord> {
+        let r = Ray::new(Point3::new(0.5, 1.0, 0.4), Vec3::new(0.0, -1.0, 0.0), 0.0);
+        let mut rec = HitRecord::default();
+        list.hit(&r, Interval::new(0.001, f64::INFINITY), &mut rec).then_some(rec)
+    }
+
+    fn error(bytes: &[u8]) -> String {
+        parse(bytes).err().expect("malformed file should fail").message
+    }
+
+    #[test]
+    fn ascii_and_binary_encodings_agree() {
+        let ascii = header("ascii", 1) + "0 0 0 255 0 0\n1 0 0 255 0 0\n1 0 1 255 0 0\n0 0 1 255 0 0\n4 0 1 2 3\n";
+        for bytes in [ascii.into_bytes(), binary_quad(false), binary_quad(true)] {
+            let rec = hit_center(&parse(&bytes).unwrap()).expect("quad should be hit");
+            assert!((rec.t - 1.0).abs() < 1e-6);
+            let color = rec.vertex_color.expect("vertex colours should be interpolated");
+            assert!((color - Color::new(1.0, 0.0, 0.0)).length() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn rejects_bad_indices_and_truncated_data() {
+        let ascii = |faces: &str| (header("ascii", 1) + "0 0 0 0 0 0\n1 0 0 0 0 0\n1 0 1 0 0 0\n0 0 1 0 0 0\n" + faces).into_bytes();
+        assert_eq!(error(&ascii("3 0 -1 2\n")), "face 0: invalid vertex index -1");
+        assert_eq!(error(&ascii("3 0 1 7\n")), "face 0: vertex index 7 out of range (have 4)");
+        assert_eq!(error(&ascii("2 0 1\n")), "face 0: face needs at least 3 vertices, found 2");
+        let mut truncated = binary_quad(false);
+        truncated.truncate(truncated.len() - 2);
+        assert_eq!(error(&truncated), "face 0: unexpected end of data");
+    }
+
+    #[test]
+    fn rejects_malformed_headers() {
+        assert_eq!(error(b"plyx\n"), "missing 'ply' magic number");
+        assert_eq!(error(b"ply\nformat binary_middle_endian 1.0\nend_header\n"), "header line 2: unsupported format Some(\"binary_middle_endian\")");
+        assert_eq!(error(b"ply\nformat ascii 1.0\nproperty float x\nend_header\n"), "header line 3: property before any element");
+        assert_eq!(error(b"ply\nformat ascii 1.0\nelement vertex 1\n"), "header is not terminated by end_header");
+    }
+
+    #[test]
+    fn loads_the_binary_blob_model() {
+        let list = load_ply("input/models/blob.ply", grey()).unwrap();
+        let r = Ray::new(Point3::new(0.0, 5.0, 0.0), Vec3::new(0.0, -1.0, 0.0), 0.0);
+        let mut rec = HitRecord::default();
+        assert!(list.hit(&r, Interval::new(0.001, f64::INFINITY), &mut rec));
+        assert!(rec.front_face && rec.normal.y > 0.9);
+        assert!(rec.vertex_color.is_some());
+    }
+}
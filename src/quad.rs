@@ -6,8 +6,20 @@ use crate::aabb::Aabb;
 use crate::ray::Ray;
 use crate::interval::Interval;
 use crate::hittable::{HitRecord, Hittable};
+use crate::onb::Onb;
+use std::f64::consts::PI;
+
+/// 平面图元的形状；(alpha, beta) 为交点在 u、v 基下的平面坐标
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PlanarShape {
+    Parallelogram,   // q + a u + b v，a, b ∈ [0, 1]
+    Triangle,        // 顶点 q、q + u、q + v
+    Ellipse,         // 以 q 为中心、u 和 v 为半轴；圆盘为其特例
+    Annulus(f64),    // 椭圆环，参数为内外半径之比
+}
 
 pub struct Quad {
+    shape: PlanarShape,
     q: Point3,
     u: Vec3,
     v: Vec3,
@@ -26,6 +38,7 @@ impl Quad {
         let area = Vec3::cross(&u, &v).length();
 
         let mut quad = Self {
+            shape: PlanarShape::Parallelogram,
             q,
             u,
             v,
@@ -39,25 +52,125 @@ impl Quad {
         quad
     }
 
+    /// 三角形：顶点 q、q + u、q + v
+    pub fn triangle(q: Point3, u: Vec3, v: Vec3, mat: Arc<dyn Material + Send + Sync>) -> Self {
+        Self::new(q, u, v, mat).with_shape(PlanarShape::Triangle)
+    }
+
+    /// 椭圆：中心 center，半轴 u、v
+    pub fn ellipse(center: Point3, u: Vec3, v: Vec3, mat: Arc<dyn Material + Send + Sync>) -> Self {
+        Self::new(center, u, v, mat).with_shape(PlanarShape::Ellipse)
+    }
+
+    /// 圆盘：法线 normal 一侧为正面
+    pub fn disk(center: Point3, normal: Vec3, radius: f64, mat: Arc<dyn Material + Send + Sync>) -> Self {
+        let (u, v) = disk_axes(&normal, radius);
+        Self::ellipse(center, u, v, mat)
+    }
+
+    /// 圆环（如行星环）：内外半径 inner < outer，u 沿环向、v 沿径向
+    pub fn annulus(center: Point3, normal: Vec3, inner: f64, outer: f64, mat: Arc<dyn Material + Send + Sync>) -> Self {
+        let (u, v) = disk_axes(&normal, outer);
+        Self::new(center, u, v, mat).with_shape(PlanarShape::Annulus((inner / outer).clamp(0.0, 1.0)))
+    }
+
+    fn with_shape(mut self, shape: PlanarShape) -> Self {
+        let parallelogram = Vec3::cross(&self.u, &self.v).length();
+        self.shape = shape;
+        self.area = match shape {
+            PlanarShape::Parallelogram => parallelogram,
+            PlanarShape::Triangle => parallelogram / 2.0,
+            PlanarShape::Ellipse => PI * parallelogram,
+            PlanarShape::Annulus(inner) => PI * parallelogram * (1.0 - inner * inner),
+        };
+        self.set_bounding_box();
+        self
+    }
+
+    pub fn area(&self) -> f64 {
+        self.area
+    }
+
     fn set_bounding_box(&mut self) {
-        // Compute the bounding box of all four vertices.
-        let bbox_diagonal1 = Aabb::from_points(self.q, self.q + self.u + self.v);
-        let bbox_diagonal2 = Aabb::from_points(self.q + self.u, self.q + self.v);
-        self.bbox = Aabb::surrounding_box(&bbox_diagonal1, &bbox_diagonal2);
+        self.bbox = match self.shape {
+            PlanarShape::Parallelogram => {
+                // Compute the bounding box of all four vertices.
+                let bbox_diagonal1 = Aabb::from_points(self.q, self.q + self.u + self.v);
+                let bbox_diagonal2 = Aabb::from_points(self.q + self.u, self.q + self.v);
+                Aabb::surrounding_box(&bbox_diagonal1, &bbox_diagonal2)
+            }
+            PlanarShape::Triangle => {
+                Aabb::surrounding_box(&Aabb::from_points(self.q, self.q + self.u), &Aabb::from_points(self.q, self.q + self.v))
+            }
+            // 椭圆在每个轴上的半宽为 sqrt(u_i² + v_i²)
+            PlanarShape::Ellipse | PlanarShape::Annulus(_) => {
+                let (u, v) = (self.u, self.v);
+                let extent = Vec3::new(
+                    (u.x * u.x + v.x * v.x).sqrt(),
+                    (u.y * u.y + v.y * v.y).sqrt(),
+                    (u.z * u.z + v.z * v.z).sqrt(),
+                );
+                Aabb::from_points(self.q - extent, self.q + extent)
+            }
+        };
     }
 
+    // 判断平面坐标 (a, b) 是否在形状内，并写入纹理坐标与切线
     fn is_interior(&self, a: f64, b: f64, rec: &mut HitRecord) -> bool {
-        let unit_interval = Interval::new(0.0, 1.0);
-        // 如果不在范围内，返回 false
-        if !unit_interval.contains(a) || !unit_interval.contains(b) {
-            return false;
+        match self.shape {
+            PlanarShape::Parallelogram => {
+                let unit_interval = Interval::new(0.0, 1.0);
+                // 如果不在范围内，返回 false
+                if !unit_interval.contains(a) || !unit_interval.contains(b) {
+                    return false;
+                }
+                rec.u = a;
+                rec.v = b;
+                rec.dpdu = self.u;
+                rec.dpdv = self.v;
+            }
+            PlanarShape::Triangle => {
+                if a < 0.0 || b < 0.0 || a + b > 1.0 {
+                    return false;
+                }
+                rec.u = a;
+                rec.v = b;
+                rec.dpdu = self.u;
+                rec.dpdv = self.v;
+            }
+            PlanarShape::Ellipse => {
+                if a * a + b * b > 1.0 {
+                    return false;
+                }
+                rec.u = (a + 1.0) / 2.0;
+                rec.v = (b + 1.0) / 2.0;
+                rec.dpdu = 2.0 * self.u;
+                rec.dpdv = 2.0 * self.v;
+            }
+            // 极坐标：u 为角度，v 从内缘 0 到外缘 1，便于贴行星环的径向纹理
+            PlanarShape::Annulus(inner) => {
+                let r = (a * a + b * b).sqrt();
+                if r > 1.0 || r < inner {
+                    return false;
+                }
+                let phi = b.atan2(a);
+                let radial = (a / r) * self.u + (b / r) * self.v;
+                rec.u = (phi + PI) / (2.0 * PI);
+                rec.v = (r - inner) / (1.0 - inner).max(1e-12);
+                rec.dpdu = 2.0 * PI * (a * self.v - b * self.u);
+                rec.dpdv = (1.0 - inner) * radial;
+            }
         }
-        rec.u = a;
-        rec.v = b;
         true
     }
 }
 
+// 垂直于 normal 的两条等长半轴，返回值的叉积与 normal 同向（Onb 的 u × v = -w，故交换次序）
+fn disk_axes(normal: &Vec3, radius: f64) -> (Vec3, Vec3) {
+    let uvw = Onb::new(normal);
+    (radius * *uvw.v(), radius * *uvw.u())
+}
+
 impl Hittable for Quad {
     fn bounding_box(&self) -> Aabb {
         self.bbox
@@ -93,6 +206,7 @@ impl Hittable for Quad {
         rec.p = intersection;
         rec.mat = Some(self.mat.clone());
         rec.set_face_normal(r, self.normal);
+        rec.vertex_color = None;
 
         true
     }
@@ -109,9 +223,25 @@ impl Hittable for Quad {
 
     fn random(&self, origin: &Point3) -> Vec3 {
         use crate::rtweekend::random::random_double;
+        let (a, b) = match self.shape {
+            PlanarShape::Parallelogram => (random_double(), random_double()),
+            // 均匀三角形采样
+            PlanarShape::Triangle => {
+                let s = random_double().sqrt();
+                let t = random_double();
+                (s * (1.0 - t), s * t)
+            }
+            // 按面积均匀：r² 在 [inner², 1] 上均匀
+            PlanarShape::Ellipse | PlanarShape::Annulus(_) => {
+                let inner = if let PlanarShape::Annulus(inner) = self.shape { inner } else { 0.0 };
+                let r = (inner * inner + (1.0 - inner * inner) * random_double()).sqrt();
+                let phi = 2.0 * PI * random_double();
+                (r * phi.cos(), r * phi.sin())
+            }
+        };
         let random_point = self.q
-            + self.u * random_double()
-            + self.v * random_double();
+            + self.u * a
+            + self.v * b;
         random_point - *origin
     }
 }
@@ -170,4 +300,102 @@ pub fn make_box(a: Point3, b: Point3, mat: Arc<dyn Material + Send + Sync>) -> A
     )));
 
     Arc::new(sides)
-}
\ No newline at end of file
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hittable::pdf_integral;
+    use crate::material::Lambertian;
+    use crate::vec3::Color;
+
+    fn grey() -> Arc<dyn Material + Send + Sync> {
+        Arc::new(Lambertian::from_color(Color::new(0.5, 0.5, 0.5)))
+    }
+
+    fn hit_from_above(shape: &Quad, x: f64, z: f64) -> Option<HitRecord> {
+        let r = Ray::new(Point3::new(x, 5.0, z), Vec3::new(0.0, -1.0, 0.0), 0.0);
+        let mut rec = HitRecord::default();
+        shape.hit(&r, Interval::new(0.001, f64::INFINITY), &mut rec).then_some(rec)
+    }
+
+    // 水平放置、法线朝上的四种形状
+    fn shapes() -> [Quad; 4] {
+        let (x, z) = (Vec3::new(2.0, 0.0, 0.0), Vec3::new(0.0, 0.0, 1.0));
+        let up = Vec3::new(0.0, 1.0, 0.0);
+        [
+            Quad::new(Point3::new(0.0, 0.0, 0.0), z, x, grey()),
+            Quad::triangle(Point3::new(0.0, 0.0, 0.0), z, x, grey()),
+            Quad::ellipse(Point3::new(0.0, 0.0, 0.0), z, x, grey()),
+            Quad::annulus(Point3::new(0.0, 0.0, 0.0), up, 0.5, 1.0, grey()),
+        ]
+    }
+
+    #[test]
+    fn areas_match_the_shape() {
+        let expected = [2.0, 1.0, 2.0 * PI, PI * 0.75];
+        for (shape, area) in shapes().iter().zip(expected) {
+            assert!((shape.area() - area).abs() < 1e-12, "{:?}", shape.shape);
+        }
+        assert!((Quad::disk(Point3::new(1.0, 2.0, 3.0), Vec3::new(1.0, 1.0, 0.0), 0.5, grey()).area() - PI * 0.25).abs() < 1e-12);
+    }
+
+    // 圆盘与圆环的正面朝向构造时给出的法线，单面光源依赖这一点
+    #[test]
+    fn disks_face_the_given_normal() {
+        for normal in [Vec3::new(0.0, 1.0, 0.0), Vec3::new(0.0, -1.0, 0.0), Vec3::new(1.0, 0.2, 0.0), Vec3::new(0.3, -0.4, 0.8)] {
+            let n = Vec3::unit_vector(normal);
+            let center = Point3::new(1.0, 2.0, 3.0);
+            // 沿法线反向射向环带上的一点
+            let r = Ray::new(center + 3.0 * n + 0.6 * *Onb::new(&n).u(), -n, 0.0);
+            for shape in [Quad::disk(center, normal, 1.0, grey()), Quad::annulus(center, normal, 0.2, 1.0, grey())] {
+                let mut rec = HitRecord::default();
+                assert!(shape.hit(&r, Interval::new(0.001, f64::INFINITY), &mut rec));
+                assert!(rec.front_face && (rec.normal - n).length() < 1e-12, "{:?} {:?}", shape.shape, normal);
+            }
+        }
+    }
+
+    #[test]
+    fn interiors_and_texture_coordinates() {
+        let [_, triangle, ellipse, annulus] = shapes();
+        let rec = hit_from_above(&triangle, 0.5, 0.25).unwrap();
+        assert!((rec.u - 0.25).abs() < 1e-12 && (rec.v - 0.25).abs() < 1e-12);
+        assert!((rec.normal - Vec3::new(0.0, 1.0, 0.0)).length() < 1e-12);
+        assert!(hit_from_above(&triangle, 1.2, 0.5).is_none());
+
+        let rec = hit_from_above(&ellipse, 1.9, 0.0).unwrap();
+        assert!((rec.u - 0.5).abs() < 1e-12 && (rec.v - 0.975).abs() < 1e-12);
+        assert!(hit_from_above(&ellipse, 1.5, 0.7).is_none());
+
+        // 圆环：内缘 v = 0，外缘 v = 1，中间的孔不命中
+        assert!(hit_from_above(&annulus, 0.3, 0.0).is_none());
+        assert!(hit_from_above(&annulus, 0.8, 0.8).is_none());
+        let rec = hit_from_above(&annulus, 0.0, -0.75).unwrap();
+        assert!((rec.v - 0.5).abs() < 1e-12 && (0.0..=1.0).contains(&rec.u));
+        assert!((rec.normal - Vec3::new(0.0, 1.0, 0.0)).length() < 1e-12);
+    }
+
+    #[test]
+    fn bounding_boxes_are_tight() {
+        let disk = Quad::disk(Point3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 1.0), 1.0, grey());
+        let bbox = disk.bounding_box();
+        let half = 0.5f64.sqrt();
+        assert!((bbox.x.max - 1.0).abs() < 1e-12 && (bbox.y.max - half).abs() < 1e-12 && (bbox.z.min + half).abs() < 1e-12);
+        // 三角形只包住三个顶点（零厚度方向按 Aabb 的最小厚度外扩）
+        let bbox = shapes()[1].bounding_box();
+        assert!(bbox.x.min.abs() < 1e-3 && (bbox.x.max - 2.0).abs() < 1e-3 && (bbox.z.max - 1.0).abs() < 1e-3 && bbox.y.size() < 1e-3);
+    }
+
+    #[test]
+    fn light_sampling_pdfs_integrate_to_one() {
+        let origin = Point3::new(0.3, -1.5, 0.2);
+        for shape in shapes() {
+            assert!((pdf_integral(&shape, &origin) - 1.0).abs() < 0.03, "{:?}", shape.shape);
+            for _ in 0..100 {
+                assert!(shape.pdf_value(&origin, &shape.random(&origin)) > 0.0, "{:?}", shape.shape);
+            }
+        }
+    }
+}
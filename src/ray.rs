@@ -1,16 +1,24 @@
 use crate::vec3::{Point3, Vec3};
+use crate::medium_stack::MediumStack;
+use crate::spectrum::Wavelengths;
 
 #[derive(Debug, Clone, Copy)]
 pub struct Ray {
     pub origin: Point3,
     pub direction: Vec3,
     pub tm: f64,
+    pub media: MediumStack, // 光线当前所处的嵌套介质
+    pub wavelengths: Option<Wavelengths>, // 光谱模式下路径携带的采样波长
+    pub channel: Option<usize>, // RGB 模式下路径已缩减到的单个颜色通道（彩色介质中按主通道采样）
 }
 
 impl Ray {
-    pub fn new(origin: Point3, direction: Vec3, time: f64) -> Self { Ray { origin, direction, tm: time }}
+    pub fn new(origin: Point3, direction: Vec3, time: f64) -> Self { Ray { origin, direction, tm: time, media: MediumStack::new(), wavelengths: None, channel: None }}
     
-    pub fn default() -> Self { Ray {origin: Point3::default(),direction: Vec3::default(),tm: 0.0,}}
+    pub fn default() -> Self { Ray {origin: Point3::default(),direction: Vec3::default(),tm: 0.0,media: MediumStack::new(),wavelengths: None,channel: None,}}
+
+    /// 由当前光线派生出新光线，沿用时间、介质栈、采样波长和颜色通道
+    pub fn spawn(&self, origin: Point3, direction: Vec3) -> Self { Ray { origin, direction, tm: self.tm, media: self.media, wavelengths: self.wavelengths, channel: self.channel }}
     
     pub fn origin(&self) -> Point3 { self.origin }
 
@@ -34,6 +42,9 @@ impl Default for Ray {
             origin: Point3::new(0.0, 0.0, 0.0),
             direction: Vec3::new(0.0, 0.0, 0.0),
             tm: 0.0,
+            media: MediumStack::new(),
+            wavelengths: None,
+            channel: None,
         }
     }
 }
\ No newline at end of file
@@ -47,8 +47,22 @@ impl RtwImage {
         img
     }
 
+    // 从内存中的编码图像（PNG、JPEG 等）加载，如 glTF 内嵌贴图；无法解码时返回 None，由调用方报告
+    pub fn from_memory(bytes: &[u8]) -> Option<Self> {
+        let mut img = Self::new();
+        img.load_from_memory(bytes).then_some(img)
+    }
+
+    pub fn load_from_memory(&mut self, bytes: &[u8]) -> bool {
+        self.set_image(image::load_from_memory(bytes))
+    }
+
     pub fn load(&mut self, filename: &str) -> bool {
-        match image::open(filename) {
+        self.set_image(image::open(filename))
+    }
+
+    fn set_image(&mut self, decoded: image::ImageResult<image::DynamicImage>) -> bool {
+        match decoded {
             Ok(img) => {
                 let img = img.to_rgb8();
                 self.image_width = img.width() as usize;
@@ -0,0 +1,400 @@
+// 有向距离场（SDF）图元：可组合的距离表达式树，用球面追踪（sphere tracing）求交
+// 适合圆角盒、平滑并、扭曲体、Mandelbulb 分形等难以网格化的形状
+use std::sync::Arc;
+use crate::aabb::Aabb;
+use crate::hittable::{HitRecord, Hittable};
+use crate::interval::Interval;
+use crate::material::Material;
+use crate::matrix::Quat;
+use crate::ray::Ray;
+use crate::sphere::get_sphere_uv;
+use crate::vec3::{Point3, Vec3};
+
+/// 距离表达式树。基本形状以原点为中心，用 translate / rotate / scale 摆放
+#[derive(Debug, Clone)]
+pub enum Sdf {
+    Sphere(f64),
+    Box(Vec3),                  // 半边长
+    Torus(f64, f64),            // 主半径、管半径，位于 xz 平面
+    Cylinder(f64, f64),         // 半径、半高，沿 y 轴
+    Mandelbulb(f64, usize),     // 幂次、迭代次数，大致位于半径 1.2 的球内
+    Union(Box<Sdf>, Box<Sdf>),
+    Intersection(Box<Sdf>, Box<Sdf>),
+    Difference(Box<Sdf>, Box<Sdf>),
+    SmoothUnion(Box<Sdf>, Box<Sdf>, f64), // 最后一个参数为过渡宽度 k
+    SmoothIntersection(Box<Sdf>, Box<Sdf>, f64),
+    SmoothDifference(Box<Sdf>, Box<Sdf>, f64),
+    Translate(Box<Sdf>, Vec3),
+    Rotate(Box<Sdf>, Quat),
+    Scale(Box<Sdf>, f64),       // 均匀缩放
+    Round(Box<Sdf>, f64),       // 向外膨胀并圆化棱角
+    Twist(Box<Sdf>, f64),       // 绕 y 轴扭曲，单位高度转过的弧度
+}
+
+impl Sdf {
+    pub fn sphere(radius: f64) -> Self { Sdf::Sphere(radius) }
+
+    pub fn cuboid(half_extent: Vec3) -> Self { Sdf::Box(half_extent) }
+
+    // 外形尺寸仍为 half_extent，棱角半径为 radius
+    pub fn round_box(half_extent: Vec3, radius: f64) -> Self {
+        let r = radius.min(half_extent.x).min(half_extent.y).min(half_extent.z);
+        Sdf::Box(half_extent - Vec3::new(r, r, r)).round(r)
+    }
+
+    pub fn torus(major: f64, minor: f64) -> Self { Sdf::Torus(major, minor) }
+
+    pub fn cylinder(radius: f64, half_height: f64) -> Self { Sdf::Cylinder(radius, half_height) }
+
+    pub fn mandelbulb(power: f64, iterations: usize) -> Self { Sdf::Mandelbulb(power, iterations) }
+
+    pub fn union(self, other: Sdf) -> Self { Sdf::Union(Box::new(self), Box::new(other)) }
+
+    pub fn intersection(self, other: Sdf) -> Self { Sdf::Intersection(Box::new(self), Box::new(other)) }
+
+    pub fn difference(self, other: Sdf) -> Self { Sdf::Difference(Box::new(self), Box::new(other)) }
+
+    pub fn smooth_union(self, other: Sdf, k: f64) -> Self { Sdf::SmoothUnion(Box::new(self), Box::new(other), k) }
+
+    pub fn smooth_intersection(self, other: Sdf, k: f64) -> Self { Sdf::SmoothIntersection(Box::new(self), Box::new(other), k) }
+
+    pub fn smooth_difference(self, other: Sdf, k: f64) -> Self { Sdf::SmoothDifference(Box::new(self), Box::new(other), k) }
+
+    pub fn translate(self, offset: Vec3) -> Self { Sdf::Translate(Box::new(self), offset) }
+
+    // 角度为度
+    pub fn rotate(self, axis: Vec3, angle: f64) -> Self { Sdf::Rotate(Box::new(self), Quat::from_axis_angle(axis, angle)) }
+
+    // 缩放系数必须为正：零会除以零，负数会翻转距离的符号
+    pub fn scale(self, s: f64) -> Self {
+        assert!(s > 0.0, "sdf scale must be positive, got {s}");
+        Sdf::Scale(Box::new(self), s)
+    }
+
+    pub fn round(self, radius: f64) -> Self { Sdf::Round(Box::new(self), radius) }
+
+    /// 扭曲不是等距变换，距离会被高估；配合 SdfHittable::with_step_scale 使用
+    pub fn twist(self, rate: f64) -> Self { Sdf::Twist(Box::new(self), rate) }
+
+    pub fn distance(&self, p: &Point3) -> f64 {
+        match self {
+            Sdf::Sphere(r) => p.length() - r,
+            Sdf::Box(b) => {
+                let q = Vec3::new(p.x.abs() - b.x, p.y.abs() - b.y, p.z.abs() - b.z);
+                let outside = Vec3::new(q.x.max(0.0), q.y.max(0.0), q.z.max(0.0)).length();
+                outside + q.x.max(q.y).max(q.z).min(0.0)
+            }
+            Sdf::Torus(major, minor) => {
+                let ring = (p.x * p.x + p.z * p.z).sqrt() - major;
+                (ring * ring + p.y * p.y).sqrt() - minor
+            }
+            Sdf::Cylinder(radius, half_height) => {
+                let dx = (p.x * p.x + p.z * p.z).sqrt() - radius;
+                let dy = p.y.abs() - half_height;
+                dx.max(dy).min(0.0) + (dx.max(0.0).powi(2) + dy.max(0.0).powi(2)).sqrt()
+            }
+            Sdf::Mandelbulb(power, iterations) => mandelbulb(p, *power, *iterations),
+            Sdf::Union(a, b) => a.distance(p).min(b.distance(p)),
+            Sdf::Intersection(a, b) => a.distance(p).max(b.distance(p)),
+            Sdf::Difference(a, b) => a.distance(p).max(-b.distance(p)),
+            // 过渡宽度不为正时退化为硬并、交、差
+            Sdf::SmoothUnion(a, b, k) if *k <= 0.0 => a.distance(p).min(b.distance(p)),
+            Sdf::SmoothIntersection(a, b, k) if *k <= 0.0 => a.distance(p).max(b.distance(p)),
+            Sdf::SmoothDifference(a, b, k) if *k <= 0.0 => a.distance(p).max(-b.distance(p)),
+            // 多项式平滑最小值
+            Sdf::SmoothUnion(a, b, k) => {
+                let (da, db) = (a.distance(p), b.distance(p));
+                let h = (0.5 + 0.5 * (db - da) / k).clamp(0.0, 1.0);
+                db + (da - db) * h - k * h * (1.0 - h)
+            }
+            Sdf::SmoothIntersection(a, b, k) => {
+                let (da, db) = (a.distance(p), b.distance(p));
+                let h = (0.5 - 0.5 * (db - da) / k).clamp(0.0, 1.0);
+                db + (da - db) * h + k * h * (1.0 - h)
+            }
+            Sdf::SmoothDifference(a, b, k) => {
+                let (da, db) = (a.distance(p), -b.distance(p));
+                let h = (0.5 - 0.5 * (db - da) / k).clamp(0.0, 1.0);
+                db + (da - db) * h + k * h * (1.0 - h)
+            }
+            Sdf::Translate(a, offset) => a.distance(&(*p - *offset)),
+            Sdf::Rotate(a, q) => a.distance(&q.conjugate().rotate(p)),
+            Sdf::Scale(a, s) => a.distance(&(*p / *s)) * s,
+            Sdf::Round(a, r) => a.distance(p) - r,
+            Sdf::Twist(a, rate) => {
+                let (sin, cos) = (rate * p.y).sin_cos();
+                a.distance(&Point3::new(cos * p.x - sin * p.z, p.y, sin * p.x + cos * p.z))
+            }
+        }
+    }
+}
+
+// Mandelbulb 距离估计：0.5 · ln r · r / |dz|
+fn mandelbulb(p: &Point3, power: f64, iterations: usize) -> f64 {
+    let mut z = *p;
+    let mut dr = 1.0;
+    let mut r = z.length();
+    for _ in 0..iterations {
+        if r > 2.0 {
+            break;
+        }
+        let theta = (z.z / r).clamp(-1.0, 1.0).acos() * power;
+        let phi = z.y.atan2(z.x) * power;
+        dr = r.powf(power - 1.0) * power * dr + 1.0;
+        let zr = r.powf(power);
+        z = zr * Vec3::new(theta.sin() * phi.cos(), theta.sin() * phi.sin(), theta.cos()) + *p;
+        r = z.length();
+        if r == 0.0 {
+            return 0.0;
+        }
+    }
+    0.5 * r.ln() * r / dr
+}
+
+pub struct SdfHittable {
+    sdf: Sdf,
+    mat: Arc<dyn Material + Send + Sync>,
+    bbox: Aabb,         // 由使用者给出，须包住整个形状
+    max_steps: usize,
+    epsilon: f64,       // 距离小于它即视为命中
+    step_scale: f64,    // 每步前进距离的系数，距离被高估（扭曲、分形）时取小于 1
+}
+
+impl SdfHittable {
+    pub fn new(sdf: Sdf, bbox: Aabb, mat: Arc<dyn Material + Send + Sync>) -> Self {
+        Self { sdf, mat, bbox, max_steps: 256, epsilon: 1e-4, step_scale: 1.0 }
+    }
+
+    pub fn with_max_steps(mut self, max_steps: usize) -> Self {
+        self.max_steps = max_steps;
+        self
+    }
+
+    pub fn with_epsilon(mut self, epsilon: f64) -> Self {
+        self.epsilon = epsilon;
+        self
+    }
+
+    pub fn with_step_scale(mut self, step_scale: f64) -> Self {
+        self.step_scale = step_scale.clamp(0.01, 1.0);
+        self
+    }
+
+    // 四面体法估计梯度，只需 4 次求值
+    fn normal(&self, p: &Point3) -> Vec3 {
+        let h = self.epsilon;
+        let k = [Vec3::new(1.0, -1.0, -1.0), Vec3::new(-1.0, -1.0, 1.0), Vec3::new(-1.0, 1.0, -1.0), Vec3::new(1.0, 1.0, 1.0)];
+        let n = k.iter().fold(Vec3::default(), |acc, k| acc + self.sdf.distance(&(*p + h * *k)) * *k);
+        if n.length_squared() > 0.0 { Vec3::unit_vector(n) } else { Vec3::new(0.0, 1.0, 0.0) }
+    }
+
+    // 光线与包围盒的参数区间（slab 法），不相交时返回 None
+    fn clip(&self, r: &Ray, ray_t: Interval) -> Option<(f64, f64)> {
+        let (mut t0, mut t1) = (ray_t.min, ray_t.max);
+        for axis in 0..3 {
+            let slab = self.bbox.axis_interval(axis);
+            let (o, d) = match axis {
+                0 => (r.origin.x, r.direction.x),
+                1 => (r.origin.y, r.direction.y),
+                _ => (r.origin.z, r.direction.z),
+            };
+            let inv = 1.0 / d;
+            let (mut near, mut far) = ((slab.min - o) * inv, (slab.max - o) * inv);
+            if near > far {
+                std::mem::swap(&mut near, &mut far);
+            }
+            if near.is_nan() || far.is_nan() {
+                continue; // 平行于该 slab 且位于边界上
+            }
+            t0 = t0.max(near);
+            t1 = t1.min(far);
+            if t1 <= t0 {
+                return None;
+            }
+        }
+        Some((t0, t1))
+    }
+}
+
+impl Hittable for SdfHittable {
+    fn hit(&self, r: &Ray, ray_t: Interval, rec: &mut HitRecord) -> bool {
+        let Some((t_enter, t_exit)) = self.clip(r, ray_t) else { return false };
+        let speed = r.direction.length();
+        if speed == 0.0 {
+            return false;
+        }
+
+        // 以世界距离步进；t = 距离 / |direction|。
+        // 起点可能就在表面上（例如次级光线），先离开表面再接受命中
+        let mut t = t_enter;
+        let mut leaving_surface = self.sdf.distance(&r.at(t)).abs() < self.epsilon;
+        let mut found = false;
+        for _ in 0..self.max_steps {
+            if t > t_exit {
+                return false;
+            }
+            let d = self.sdf.distance(&r.at(t)).abs();
+            if d < self.epsilon {
+                if !leaving_surface {
+                    found = true;
+                    break;
+                }
+            } else if d >= 2.0 * self.epsilon {
+                leaving_surface = false;
+            }
+            t += (d * self.step_scale).max(self.epsilon) / speed;
+        }
+        if !found || !ray_t.surrounds(t) {
+            return false;
+        }
+
+        rec.t = t;
+        rec.p = r.at(t);
+        let outward_normal = self.normal(&rec.p);
+        rec.set_face_normal(r, outward_normal);
+        get_sphere_uv(&outward_normal, &mut rec.u, &mut rec.v);
+        rec.dpdu = Vec3::default();
+        rec.dpdv = Vec3::default();
+        rec.vertex_color = None;
+        rec.mat = Some(self.mat.clone());
+        true
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::material::Lambertian;
+    use crate::vec3::Color;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn primitive_distances_are_exact() {
+        assert!(close(Sdf::sphere(1.0).distance(&Point3::new(0.0, 3.0, 0.0)), 2.0));
+        assert!(close(Sdf::sphere(1.0).distance(&Point3::new(0.0, 0.0, 0.0)), -1.0));
+        let cube = Sdf::cuboid(Vec3::new(1.0, 2.0, 3.0));
+        assert!(close(cube.distance(&Point3::new(4.0, 0.0, 0.0)), 3.0));
+        assert!(close(cube.distance(&Point3::new(4.0, 6.0, 0.0)), 5.0)); // 到棱的距离 (3, 4)
+        assert!(close(cube.distance(&Point3::new(0.5, 0.0, 0.0)), -0.5));
+        assert!(close(Sdf::torus(2.0, 0.5).distance(&Point3::new(0.0, 0.0, 2.0)), -0.5));
+        assert!(close(Sdf::torus(2.0, 0.5).distance(&Point3::new(0.0, 1.0, 2.0)), 0.5));
+        let can = Sdf::cylinder(1.0, 1.0);
+        assert!(close(can.distance(&Point3::new(0.0, 3.0, 0.0)), 2.0));
+        assert!(close(can.distance(&Point3::new(4.0, 5.0, 0.0)), 5.0));
+        assert!(close(Sdf::round_box(Vec3::new(1.0, 1.0, 1.0), 0.25).distance(&Point3::new(3.0, 0.0, 0.0)), 2.0));
+    }
+
+    #[test]
+    fn operators_combine_and_place_shapes() {
+        let p = Point3::new(0.9, 0.2, 0.0);
+        let (a, b) = (Sdf::sphere(1.0), Sdf::sphere(0.5).translate(Vec3::new(1.0, 0.0, 0.0)));
+        let (da, db) = (a.distance(&p), b.distance(&p));
+        assert!(close(a.clone().union(b.clone()).distance(&p), da.min(db)));
+        assert!(close(a.clone().intersection(b.clone()).distance(&p), da.max(db)));
+        assert!(close(a.clone().difference(b.clone()).distance(&p), da.max(-db)));
+        // 平滑版本在远离过渡区时与硬版本一致，在过渡区内更"胖"或更"瘦"
+        assert!(a.clone().smooth_union(b.clone(), 0.3).distance(&p) <= da.min(db));
+        assert!(a.clone().smooth_intersection(b.clone(), 0.3).distance(&p) >= da.max(db));
+        assert!(a.clone().smooth_difference(b.clone(), 0.3).distance(&p) >= da.max(-db));
+        let far = Point3::new(-5.0, 0.0, 0.0);
+        assert!(close(a.clone().smooth_union(b.clone(), 0.3).distance(&far), a.distance(&far)));
+
+        let q = Point3::new(0.3, -0.7, 1.1);
+        let cube = Sdf::cuboid(Vec3::new(0.5, 1.0, 0.25));
+        let moved = cube.clone().rotate(Vec3::new(0.0, 0.0, 1.0), 90.0).translate(Vec3::new(2.0, 0.0, 0.0));
+        // 绕 z 轴转 90° 后 (x, y) → (-y, x)，逆变换把 q 送回原形状的坐标
+        let local = Point3::new(q.y, -(q.x - 2.0), q.z);
+        assert!(close(moved.distance(&q), cube.distance(&local)));
+        assert!(close(Sdf::sphere(1.0).scale(2.0).distance(&Point3::new(5.0, 0.0, 0.0)), 3.0));
+        assert!(close(cube.clone().round(0.1).distance(&q), cube.distance(&q) - 0.1));
+        // 扭曲不移动 y 轴上的点
+        let axis_point = Point3::new(0.0, 0.8, 0.0);
+        assert!(close(cube.clone().twist(1.5).distance(&axis_point), cube.distance(&axis_point)));
+    }
+
+    // 过渡宽度为 0 或负数时平滑运算与硬运算一致，不会除以零
+    #[test]
+    fn smooth_operators_fall_back_to_hard_ones() {
+        let (a, b) = (Sdf::sphere(1.0), Sdf::sphere(0.5).translate(Vec3::new(1.0, 0.0, 0.0)));
+        for k in [0.0, -0.3] {
+            for _ in 0..100 {
+                let p = 2.0 * Vec3::random_unit_vector();
+                let (da, db) = (a.distance(&p), b.distance(&p));
+                assert_eq!(a.clone().smooth_union(b.clone(), k).distance(&p), da.min(db));
+                assert_eq!(a.clone().smooth_intersection(b.clone(), k).distance(&p), da.max(db));
+                assert_eq!(a.clone().smooth_difference(b.clone(), k).distance(&p), da.max(-db));
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "sdf scale must be positive")]
+    fn zero_scale_is_rejected() {
+        let _ = Sdf::sphere(1.0).scale(0.0);
+    }
+
+    fn grey() -> Arc<dyn Material + Send + Sync> {
+        Arc::new(Lambertian::from_color(Color::new(0.5, 0.5, 0.5)))
+    }
+
+    fn trace(shape: &SdfHittable, origin: Point3, direction: Vec3) -> Option<HitRecord> {
+        let mut rec = HitRecord::default();
+        shape.hit(&Ray::new(origin, direction, 0.0), Interval::new(0.001, f64::INFINITY), &mut rec).then_some(rec)
+    }
+
+    // 球面追踪的球与解析球在交点和法线上一致
+    #[test]
+    fn sphere_tracing_matches_the_analytic_sphere() {
+        let center = Vec3::new(1.0, 2.0, -1.0);
+        let bbox = Aabb::from_points(Point3::new(0.0, 1.0, -2.0) - Vec3::new(0.1, 0.1, 0.1), Point3::new(2.0, 3.0, 0.0) + Vec3::new(0.1, 0.1, 0.1));
+        let ball = SdfHittable::new(Sdf::sphere(1.0).translate(center), bbox, grey()).with_epsilon(1e-6);
+        for _ in 0..200 {
+            let origin = center + 4.0 * Vec3::random_unit_vector();
+            let target = center + 0.9 * Vec3::random_unit_vector();
+            let Some(rec) = trace(&ball, origin, target - origin) else { panic!("missed a ray aimed inside the ball") };
+            let expected = Vec3::unit_vector(rec.p - center);
+            assert!(((rec.p - center).length() - 1.0).abs() < 1e-5);
+            assert!((rec.normal - expected).length() < 1e-3 && rec.front_face);
+        }
+        assert!(trace(&ball, center + Vec3::new(0.0, 0.0, 4.0), Vec3::new(1.0, 1.0, 0.0)).is_none());
+
+        // 从表面出发向内的次级光线命中对面
+        let start = Point3::new(1.0, 2.0, 0.0);
+        let rec = trace(&ball, start, Vec3::new(0.0, 0.0, -1.0)).unwrap();
+        assert!((rec.p.z + 2.0).abs() < 1e-5 && !rec.front_face);
+    }
+
+    // 扭曲体的距离被高估，缩小步长后不会穿过表面
+    #[test]
+    fn step_scale_keeps_twisted_shapes_watertight() {
+        let column = Sdf::cuboid(Vec3::new(0.3, 1.0, 0.3)).twist(2.0);
+        let bbox = Aabb::from_points(Point3::new(-0.5, -1.1, -0.5), Point3::new(0.5, 1.1, 0.5));
+        let careful = SdfHittable::new(column.clone(), bbox, grey()).with_step_scale(0.5).with_max_steps(512);
+        for i in 0..100 {
+            let y = -0.9 + 1.8 * i as f64 / 99.0;
+            let rec = trace(&careful, Point3::new(3.0, y, 0.05), Vec3::new(-1.0, 0.0, 0.0)).unwrap();
+            assert!(column.distance(&rec.p).abs() < 1e-3, "y = {}", y);
+            assert!(rec.p.x > -0.5 && rec.p.x < 0.5);
+        }
+    }
+
+    #[test]
+    fn mandelbulb_is_bounded_and_traceable() {
+        let bulb = Sdf::mandelbulb(8.0, 12);
+        assert!(bulb.distance(&Point3::new(3.0, 0.0, 0.0)) > 1.0);
+        let bbox = Aabb::from_points(Point3::new(-1.3, -1.3, -1.3), Point3::new(1.3, 1.3, 1.3));
+        let fractal = SdfHittable::new(bulb, bbox, grey()).with_step_scale(0.8).with_max_steps(512).with_epsilon(1e-4);
+        let rec = trace(&fractal, Point3::new(0.0, 0.0, 5.0), Vec3::new(0.02, 0.01, -1.0)).unwrap();
+        assert!(rec.p.length() < 1.3 && rec.p.length() > 0.3);
+        assert!(Vec3::dot(&rec.normal, &Vec3::new(0.0, 0.0, 1.0)) > 0.0);
+        assert!(trace(&fractal, Point3::new(0.0, 1.6, 5.0), Vec3::new(0.0, 0.0, -1.0)).is_none());
+    }
+}
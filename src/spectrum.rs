@@ -0,0 +1,342 @@
+// 光谱渲染支持：主波长采样、RGB→光谱上采样（Smits）、CIE XYZ 与色散折射率
+use std::sync::OnceLock;
+use rand::{rngs::ThreadRng, Rng};
+use crate::vec3::{Color, Vec3};
+
+// 采样的波长范围与 Smits 基光谱的表格范围一致，720nm 以上人眼的响应已可忽略
+pub const LAMBDA_MIN: f64 = 380.0;
+pub const LAMBDA_MAX: f64 = 720.0;
+// 每条路径携带的波长数，与 Vec3 的三个分量一一对应
+pub const SPECTRUM_SAMPLES: usize = 3;
+
+// 路径上的采样波长（nm）。x 为主波长，y、z 为在可见光范围内等距旋转得到的副波长。
+// 光谱模式下路径上的 Color 各分量即为这三个波长处的光谱值。
+#[derive(Debug, Clone, Copy)]
+pub struct Wavelengths {
+    pub lambda: Vec3,
+    pub secondary_terminated: bool,
+}
+
+impl Wavelengths {
+    pub fn sample_hero(rng: &mut ThreadRng) -> Self {
+        let range = LAMBDA_MAX - LAMBDA_MIN;
+        let hero = rng.gen::<f64>() * range;
+        let step = range / SPECTRUM_SAMPLES as f64;
+        let rotate = |i: f64| LAMBDA_MIN + (hero + i * step) % range;
+        Self {
+            lambda: Vec3::new(rotate(0.0), rotate(1.0), rotate(2.0)),
+            secondary_terminated: false,
+        }
+    }
+
+    pub fn hero(&self) -> f64 {
+        self.lambda.x
+    }
+
+    // 波长相关的事件（如色散折射）只对主波长有效，副波长从此终止
+    pub fn terminate_secondary(&mut self) {
+        self.secondary_terminated = true;
+    }
+
+    // 每个波长的采样 PDF（均匀分布）
+    pub fn pdf(&self) -> f64 {
+        1.0 / (LAMBDA_MAX - LAMBDA_MIN)
+    }
+}
+
+// 副波长终止后，主波长独自承担全部样本的贡献
+pub fn termination_mask() -> Vec3 {
+    Vec3::new(SPECTRUM_SAMPLES as f64, 0.0, 0.0)
+}
+
+// CIE 1931 标准观察者配色函数的多瓣高斯拟合（Wyman, Sloan & Shirley 2013）
+pub fn cie_xyz(lambda: f64) -> Vec3 {
+    fn g(x: f64, mu: f64, sigma1: f64, sigma2: f64) -> f64 {
+        let t = (x - mu) / if x < mu { sigma1 } else { sigma2 };
+        (-0.5 * t * t).exp()
+    }
+    Vec3::new(
+        1.056 * g(lambda, 599.8, 37.9, 31.0) + 0.362 * g(lambda, 442.0, 16.0, 26.7) - 0.065 * g(lambda, 501.1, 20.4, 26.2),
+        0.821 * g(lambda, 568.8, 46.9, 40.5) + 0.286 * g(lambda, 530.9, 16.3, 31.1),
+        1.217 * g(lambda, 437.0, 11.8, 36.0) + 0.681 * g(lambda, 459.0, 26.0, 13.8),
+    )
+}
+
+// XYZ → 线性 sRGB（D65）
+pub fn xyz_to_linear_srgb(xyz: &Vec3) -> Color {
+    Color::new(
+        3.2404542 * xyz.x - 1.5371385 * xyz.y - 0.4985314 * xyz.z,
+        -0.9692660 * xyz.x + 1.8760108 * xyz.y + 0.0415560 * xyz.z,
+        0.0556434 * xyz.x - 0.2040259 * xyz.y + 1.0572252 * xyz.z,
+    )
+}
+
+// 等能白光谱（恒为 1）对应的 RGB，用于白平衡，使上采样后的白色仍还原为 (1, 1, 1)
+fn white_rgb() -> Color {
+    static WHITE: OnceLock<Color> = OnceLock::new();
+    *WHITE.get_or_init(|| {
+        let mut xyz = Vec3::default();
+        let mut lambda = LAMBDA_MIN;
+        while lambda < LAMBDA_MAX {
+            xyz += cie_xyz(lambda + 0.5);
+            lambda += 1.0;
+        }
+        xyz_to_linear_srgb(&xyz)
+    })
+}
+
+// 将路径在采样波长上的光谱辐亮度转换回线性 RGB（胶片）
+pub fn to_rgb(radiance: &Vec3, wavelengths: &Wavelengths) -> Color {
+    let scale = 1.0 / (wavelengths.pdf() * SPECTRUM_SAMPLES as f64);
+    let xyz = (cie_xyz(wavelengths.lambda.x) * radiance.x
+        + cie_xyz(wavelengths.lambda.y) * radiance.y
+        + cie_xyz(wavelengths.lambda.z) * radiance.z)
+        * scale;
+    let rgb = xyz_to_linear_srgb(&xyz);
+    let white = white_rgb();
+    Color::new(rgb.x / white.x, rgb.y / white.y, rgb.z / white.z)
+}
+
+// Smits (1999) 的 10 段基光谱，覆盖 380–720nm，即 [LAMBDA_MIN, LAMBDA_MAX) 的 10 等分
+const SMITS_BINS: usize = 10;
+const SMITS_WHITE: [f64; SMITS_BINS] = [1.0000, 1.0000, 0.9999, 0.9993, 0.9992, 0.9998, 1.0000, 1.0000, 1.0000, 1.0000];
+const SMITS_CYAN: [f64; SMITS_BINS] = [0.9710, 0.9426, 1.0007, 1.0007, 1.0007, 1.0007, 0.1564, 0.0000, 0.0000, 0.0000];
+const SMITS_MAGENTA: [f64; SMITS_BINS] = [1.0000, 1.0000, 0.9685, 0.2229, 0.0000, 0.0458, 0.8369, 1.0000, 1.0000, 0.9959];
+const SMITS_YELLOW: [f64; SMITS_BINS] = [0.0001, 0.0000, 0.1088, 0.6651, 1.0000, 1.0000, 0.9996, 0.9586, 0.9685, 0.9840];
+const SMITS_RED: [f64; SMITS_BINS] = [0.1012, 0.0515, 0.0000, 0.0000, 0.0000, 0.0000, 0.8325, 1.0149, 1.0149, 1.0149];
+const SMITS_GREEN: [f64; SMITS_BINS] = [0.0000, 0.0000, 0.0273, 0.7937, 1.0000, 0.9418, 0.1719, 0.0000, 0.0000, 0.0025];
+const SMITS_BLUE: [f64; SMITS_BINS] = [1.0000, 1.0000, 0.8916, 0.3323, 0.0000, 0.0000, 0.0003, 0.0369, 0.0483, 0.0496];
+
+// Smits 方法：把 RGB 分解为白色 + 青/品/黄 + 红/绿/蓝基光谱，返回在 lambda 处的光谱值
+pub fn rgb_to_spectrum(c: &Color, lambda: f64) -> f64 {
+    let width = (LAMBDA_MAX - LAMBDA_MIN) / SMITS_BINS as f64;
+    let bin = (((lambda - LAMBDA_MIN) / width).floor().max(0.0) as usize).min(SMITS_BINS - 1);
+    let (r, g, b) = (c.x.max(0.0), c.y.max(0.0), c.z.max(0.0));
+    if r <= g && r <= b {
+        let base = r * SMITS_WHITE[bin];
+        if g <= b {
+            base + (g - r) * SMITS_CYAN[bin] + (b - g) * SMITS_BLUE[bin]
+        } else {
+            base + (b - r) * SMITS_CYAN[bin] + (g - b) * SMITS_GREEN[bin]
+        }
+    } else if g <= r && g <= b {
+        let base = g * SMITS_WHITE[bin];
+        if r <= b {
+            base + (r - g) * SMITS_MAGENTA[bin] + (b - r) * SMITS_BLUE[bin]
+        } else {
+            base + (b - g) * SMITS_MAGENTA[bin] + (r - b) * SMITS_RED[bin]
+        }
+    } else {
+        let base = b * SMITS_WHITE[bin];
+        if r <= g {
+            base + (r - b) * SMITS_YELLOW[bin] + (g - r) * SMITS_GREEN[bin]
+        } else {
+            base + (g - b) * SMITS_YELLOW[bin] + (r - g) * SMITS_RED[bin]
+        }
+    }
+}
+
+// 将纹理、光源等给出的 RGB 上采样为路径采样波长处的光谱值
+pub fn uplift(c: &Color, wavelengths: &Wavelengths) -> Vec3 {
+    Vec3::new(
+        rgb_to_spectrum(c, wavelengths.lambda.x),
+        rgb_to_spectrum(c, wavelengths.lambda.y),
+        rgb_to_spectrum(c, wavelengths.lambda.z),
+    )
+}
+
+// 普朗克黑体辐射（λ 以 nm 计，W·sr⁻¹·m⁻³）
+pub fn planck(lambda_nm: f64, kelvin: f64) -> f64 {
+    const C: f64 = 2.99792458e8;
+    const H: f64 = 6.62607015e-34;
+    const KB: f64 = 1.380649e-23;
+    let l = lambda_nm * 1e-9;
+    2.0 * H * C * C / (l.powi(5) * ((H * C / (l * KB * kelvin)).exp() - 1.0))
+}
+
+// 黑体颜色（线性 sRGB），亮度 Y 归一化为 1；约 6500K 时接近白色
+pub fn blackbody_rgb(kelvin: f64) -> Color {
+    let mut xyz = Vec3::default();
+    let mut lambda = LAMBDA_MIN;
+    while lambda < LAMBDA_MAX {
+        xyz += cie_xyz(lambda + 0.5) * planck(lambda + 0.5, kelvin);
+        lambda += 1.0;
+    }
+    if xyz.y.is_nan() || xyz.y <= 0.0 {
+        return Color::new(0.0, 0.0, 0.0);
+    }
+    let rgb = xyz_to_linear_srgb(&(xyz / xyz.y));
+    Color::new(rgb.x.max(0.0), rgb.y.max(0.0), rgb.z.max(0.0))
+}
+
+// 普朗克轨迹色度坐标 (x, y)，Kim 等人（2002）的三次样条近似，适用于 1667K–25000K
+pub fn planckian_locus_xy(kelvin: f64) -> (f64, f64) {
+    let t = kelvin.clamp(1667.0, 25000.0);
+    let (t2, t3) = (t * t, t * t * t);
+    let x = if t <= 4000.0 {
+        -0.2661239e9 / t3 - 0.2343589e6 / t2 + 0.8776956e3 / t + 0.179910
+    } else {
+        -3.0258469e9 / t3 + 2.1070379e6 / t2 + 0.2226347e3 / t + 0.240390
+    };
+    let (x2, x3) = (x * x, x * x * x);
+    let y = if t <= 2222.0 {
+        -1.1063814 * x3 - 1.34811020 * x2 + 2.18555832 * x - 0.20219683
+    } else if t <= 4000.0 {
+        -0.9549476 * x3 - 1.37418593 * x2 + 2.09137015 * x - 0.16748867
+    } else {
+        3.0817580 * x3 - 5.87338670 * x2 + 3.75112997 * x - 0.37001483
+    };
+    (x, y)
+}
+
+// 色温对应的颜色（线性 sRGB），亮度 Y 为 1；近似范围外退回对黑体光谱积分
+pub fn color_temperature_rgb(kelvin: f64) -> Color {
+    if !(1667.0..=25000.0).contains(&kelvin) {
+        return blackbody_rgb(kelvin);
+    }
+    let (x, y) = planckian_locus_xy(kelvin);
+    let rgb = xyz_to_linear_srgb(&Vec3::new(x / y, 1.0, (1.0 - x - y) / y));
+    Color::new(rgb.x.max(0.0), rgb.y.max(0.0), rgb.z.max(0.0))
+}
+
+// 波长相关的折射率模型（λ 以 µm 代入公式）
+#[derive(Debug, Clone, Copy)]
+pub enum Dispersion {
+    None,
+    Cauchy { a: f64, b: f64 },              // n = A + B / λ²
+    Sellmeier { b: [f64; 3], c: [f64; 3] }, // n² = 1 + Σ Bᵢλ² / (λ² - Cᵢ)
+}
+
+impl Dispersion {
+    // Schott N-BK7 光学玻璃
+    pub const BK7: Dispersion = Dispersion::Sellmeier {
+        b: [1.03961212, 0.231792344, 1.01046945],
+        c: [0.00600069867, 0.0200179144, 103.560653],
+    };
+    // 钻石
+    pub const DIAMOND: Dispersion = Dispersion::Sellmeier {
+        b: [0.3306, 4.3356, 0.0],
+        c: [0.030625, 0.011236, 0.0],
+    };
+
+    // 夫琅禾费 d 线，RGB 模式下使用该波长处的折射率
+    pub const D_LINE: f64 = 587.6;
+
+    pub fn is_dispersive(&self) -> bool {
+        !matches!(self, Dispersion::None)
+    }
+
+    pub fn ior(&self, lambda_nm: f64) -> Option<f64> {
+        let l = lambda_nm * 1e-3;
+        let l2 = l * l;
+        match self {
+            Dispersion::None => None,
+            Dispersion::Cauchy { a, b } => Some(a + b / l2),
+            Dispersion::Sellmeier { b, c } => {
+                let n2 = 1.0 + (0..3).map(|i| b[i] * l2 / (l2 - c[i])).sum::<f64>();
+                Some(n2.max(1.0).sqrt())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::thread_rng;
+
+    #[test]
+    fn hero_wavelengths_are_evenly_rotated_over_the_range() {
+        let mut rng = thread_rng();
+        let step = (LAMBDA_MAX - LAMBDA_MIN) / SPECTRUM_SAMPLES as f64;
+        for _ in 0..1000 {
+            let wl = Wavelengths::sample_hero(&mut rng);
+            let lambda = [wl.lambda.x, wl.lambda.y, wl.lambda.z];
+            for (i, l) in lambda.iter().enumerate() {
+                assert!((LAMBDA_MIN..LAMBDA_MAX).contains(l));
+                let offset = (l - wl.hero() + LAMBDA_MAX - LAMBDA_MIN) % (LAMBDA_MAX - LAMBDA_MIN);
+                assert!((offset - i as f64 * step).abs() < 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn bk7_matches_catalogue_index_and_disperses_normally() {
+        let n_d = Dispersion::BK7.ior(Dispersion::D_LINE).unwrap();
+        assert!((n_d - 1.5168).abs() < 1e-4);
+        assert!(Dispersion::BK7.ior(450.0).unwrap() > Dispersion::BK7.ior(650.0).unwrap());
+        assert!(Dispersion::None.ior(500.0).is_none());
+    }
+
+    #[test]
+    fn cauchy_uses_micrometres() {
+        let n = Dispersion::Cauchy { a: 1.5, b: 0.01 }.ior(500.0).unwrap();
+        assert!((n - 1.54).abs() < 1e-12);
+    }
+
+    // 等能白光谱在多次采样下还原为 (1, 1, 1)
+    #[test]
+    fn uplifted_white_round_trips_to_white() {
+        let mut rng = thread_rng();
+        let white = Color::new(1.0, 1.0, 1.0);
+        let n = 20000;
+        let mut sum = Color::new(0.0, 0.0, 0.0);
+        for _ in 0..n {
+            let wl = Wavelengths::sample_hero(&mut rng);
+            sum += to_rgb(&uplift(&white, &wl), &wl);
+        }
+        let mean = sum / n as f64;
+        for c in [mean.x, mean.y, mean.z] {
+            assert!((c - 1.0).abs() < 0.02, "{}", mean);
+        }
+    }
+
+    // 每个采样波长都落在 Smits 表格内，最后一段只覆盖到 720nm
+    #[test]
+    fn sampled_wavelengths_stay_in_the_smits_table() {
+        let mut rng = thread_rng();
+        for _ in 0..1000 {
+            let wl = Wavelengths::sample_hero(&mut rng);
+            for l in [wl.lambda.x, wl.lambda.y, wl.lambda.z] {
+                assert!((380.0..720.0).contains(&l));
+            }
+        }
+        assert_eq!(Wavelengths::sample_hero(&mut rng).pdf(), 1.0 / 340.0);
+        let blue = Color::new(0.0, 0.0, 1.0);
+        assert_eq!(rgb_to_spectrum(&blue, 686.0), SMITS_BLUE[9]);
+        assert_eq!(rgb_to_spectrum(&blue, 685.9), SMITS_BLUE[8]);
+    }
+
+    #[test]
+    fn smits_red_is_long_wavelength() {
+        let red = Color::new(1.0, 0.0, 0.0);
+        assert!(rgb_to_spectrum(&red, 700.0) > 0.9);
+        assert!(rgb_to_spectrum(&red, 480.0) < 0.1);
+    }
+
+    fn luminance(c: &Color) -> f64 {
+        0.2126 * c.x + 0.7152 * c.y + 0.0722 * c.z
+    }
+
+    #[test]
+    fn colour_temperature_has_unit_luminance_and_follows_the_locus() {
+        for kelvin in [1000.0, 2700.0, 4000.0, 6500.0, 10000.0, 30000.0] {
+            assert!((luminance(&color_temperature_rgb(kelvin)) - 1.0).abs() < 0.02, "{}", kelvin);
+        }
+        let warm = color_temperature_rgb(2700.0);
+        assert!(warm.x > warm.y && warm.y > warm.z);
+        let cool = color_temperature_rgb(10000.0);
+        assert!(cool.z > cool.x);
+        let d65 = color_temperature_rgb(6500.0);
+        assert!((d65.x - 1.0).abs() < 0.1 && (d65.z - 1.0).abs() < 0.1);
+    }
+
+    // 轨迹近似与黑体光谱积分应给出相近的颜色
+    #[test]
+    fn planckian_locus_matches_integrated_blackbody() {
+        for kelvin in [2000.0, 3500.0, 5000.0, 8000.0] {
+            let (a, b) = (color_temperature_rgb(kelvin), blackbody_rgb(kelvin));
+            assert!((a - b).length() < 0.05, "{}: {:?} {:?}", kelvin, a, b);
+        }
+    }
+}
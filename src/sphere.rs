@@ -43,6 +43,11 @@ impl Sphere {
         let uvw = Onb::new(&direction);
         uvw.transform(&random_to_sphere(self.radius, distance_squared))
     }
+    /// 表面积，用于按物理单位换算光源亮度
+    pub fn area(&self) -> f64 {
+        4.0 * std::f64::consts::PI * self.radius * self.radius
+    }
+
     /// 静止球体
     pub fn new(static_center: Point3, radius: f64, mat: Option<Arc<dyn Material + Send + Sync>>) -> Self {
         let rvec = Vec3::new(radius, radius, radius);
@@ -69,9 +74,39 @@ impl Sphere {
             bbox,
         }
     }
+
+    fn fill_record(&self, r: &Ray, root: f64, current_center: Point3, rec: &mut HitRecord) {
+        rec.t = root;
+        rec.p = r.at(root);
+        let outward_normal = (rec.p - current_center) / self.radius;
+        rec.set_face_normal(r, outward_normal);
+        get_sphere_uv(&outward_normal, &mut rec.u, &mut rec.v);
+        (rec.dpdu, rec.dpdv) = sphere_tangents(&outward_normal, self.radius);
+        rec.vertex_color = None;
+        rec.mat = self.mat.clone();
+    }
 }
 
 impl Hittable for Sphere {
+    // 两个根都在区间内时都返回
+    fn hit_all(&self, r: &Ray, ray_t: Interval, hits: &mut Vec<HitRecord>) {
+        let current_center = self.center.at(r.time());
+        let oc = current_center - r.origin;
+        let a = r.direction.length_squared();
+        let h = Vec3::dot(&r.direction, &oc);
+        let c = oc.length_squared() - self.radius * self.radius;
+        let discriminant = h * h - a * c;
+        if discriminant < 0.0 { return; }
+        let sqrtd = discriminant.sqrt();
+        for root in [(h - sqrtd) / a, (h + sqrtd) / a] {
+            if ray_t.surrounds(root) {
+                let mut rec = HitRecord::default();
+                self.fill_record(r, root, current_center, &mut rec);
+                hits.push(rec);
+            }
+        }
+    }
+
     fn hit(&self, r: &Ray, ray_t: Interval, rec: &mut HitRecord) -> bool {
         let current_center = self.center.at(r.time());
         let oc = current_center - r.origin;
@@ -89,22 +124,32 @@ impl Hittable for Sphere {
             if !ray_t.surrounds(root) { return false;}
         }
 
-        rec.t = root;
-        rec.p = r.at(root);
-        let outward_normal = (rec.p - current_center) / self.radius;
-        rec.set_face_normal(r, outward_normal);
-
-        // 修改这里，直接传递 rec.u, rec.v 的可变引用给 get_sphere_uv
-        get_sphere_uv(&outward_normal, &mut rec.u, &mut rec.v);
-
-        rec.mat = self.mat.clone(); // 关键：赋值材质
-        
+        self.fill_record(r, root, current_center, rec);
         true
     }
 
     fn bounding_box(&self) -> Aabb {
         self.bbox
     }
+
+    fn pdf_value(&self, origin: &Point3, direction: &Vec3) -> f64 {
+        Sphere::pdf_value(self, origin, direction)
+    }
+
+    fn random(&self, origin: &Point3) -> Vec3 {
+        Sphere::random(self, origin)
+    }
+}
+
+/// get_sphere_uv 参数化下的 ∂p/∂u、∂p/∂v；两极处退化为零向量
+fn sphere_tangents(n: &Vec3, radius: f64) -> (Vec3, Vec3) {
+    let sin_theta = (1.0 - n.y * n.y).max(0.0).sqrt();
+    if sin_theta < 1e-8 {
+        return (Vec3::default(), Vec3::default());
+    }
+    let dpdu = 2.0 * std::f64::consts::PI * radius * Vec3::new(n.z, 0.0, -n.x);
+    let dpdv = std::f64::consts::PI * radius * Vec3::new(-n.x * n.y / sin_theta, sin_theta, -n.z * n.y / sin_theta);
+    (dpdu, dpdv)
 }
 
 /// 计算球面上的点的纹理坐标 (u, v)，参数为 &Point3, &mut u, &mut v
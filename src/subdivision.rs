@@ -0,0 +1,662 @@
+// 细分曲面：Catmull–Clark（任意多边形，细分后全为四边形）与 Loop（三角形）
+// 在建 BVH 之前把控制网格细化成 TriangleMesh；折痕按 DeRose 等的半锐边规则处理，
+// 开放边界按无限锐利的折痕处理；UV 为逐面角的属性，在每个面内线性细分
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use crate::material::Material;
+use crate::obj::triangulate;
+use crate::texture::Texture;
+use crate::triangle::TriangleMesh;
+use crate::vec3::{Point3, Vec3};
+
+// 屏幕空间细分时的最大层数，防止退化输入无限细分
+const MAX_LEVELS: usize = 6;
+
+// 输出顶点的去重键：(原顶点, 法线的位模式, UV 的位模式)
+type VertexKey = (usize, [u64; 3], Option<[u64; 2]>);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Scheme {
+    CatmullClark,
+    Loop,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Refinement {
+    Levels(usize),
+    // 细分到每条边对 eye 的张角都不超过 pixels 个像素
+    ScreenSpace { eye: Point3, pixel_angle: f64, pixels: f64 },
+}
+
+impl Refinement {
+    /// 由相机参数给出屏幕空间容差：vfov 为度，image_height 为像素数
+    pub fn screen_space(eye: Point3, vfov: f64, image_height: usize, pixels: f64) -> Self {
+        let pixel_angle = vfov.to_radians() / image_height.max(1) as f64;
+        Refinement::ScreenSpace { eye, pixel_angle, pixels }
+    }
+}
+
+pub struct SubdivisionMesh {
+    positions: Vec<Point3>,
+    faces: Vec<Vec<usize>>,
+    face_uvs: Vec<Vec<(f64, f64)>>, // 为空或与 faces 逐面逐角对应
+    creases: HashMap<(usize, usize), f64>, // 边的锐度，f64::INFINITY 为硬折痕
+    corners: HashSet<usize>, // 三角化前只属于一个面的顶点，三角化后仍按角点处理
+    displacement: Option<(Arc<dyn Texture + Send + Sync>, f64)>,
+}
+
+fn edge_key(a: usize, b: usize) -> (usize, usize) {
+    if a < b { (a, b) } else { (b, a) }
+}
+
+fn mid_uv(a: (f64, f64), b: (f64, f64)) -> (f64, f64) {
+    (0.5 * (a.0 + b.0), 0.5 * (a.1 + b.1))
+}
+
+fn lerp_point(t: f64, a: Point3, b: Point3) -> Point3 {
+    (1.0 - t) * a + t * b
+}
+
+// 一层细分所需的邻接关系
+struct Topology {
+    edges: HashMap<(usize, usize), usize>,
+    edge_verts: Vec<(usize, usize)>,
+    edge_faces: Vec<Vec<usize>>,
+    vertex_edges: Vec<Vec<usize>>,
+    vertex_faces: Vec<Vec<usize>>,
+}
+
+impl Topology {
+    fn new(vertex_count: usize, faces: &[Vec<usize>]) -> Self {
+        let mut topo = Topology {
+            edges: HashMap::new(),
+            edge_verts: Vec::new(),
+            edge_faces: Vec::new(),
+            vertex_edges: vec![Vec::new(); vertex_count],
+            vertex_faces: vec![Vec::new(); vertex_count],
+        };
+        for (f, face) in faces.iter().enumerate() {
+            for (i, &a) in face.iter().enumerate() {
+                let b = face[(i + 1) % face.len()];
+                topo.vertex_faces[a].push(f);
+                let key = edge_key(a, b);
+                let e = match topo.edges.get(&key) {
+                    Some(&e) => e,
+                    None => {
+                        let e = topo.edge_verts.len();
+                        topo.edges.insert(key, e);
+                        topo.edge_verts.push(key);
+                        topo.edge_faces.push(Vec::new());
+                        topo.vertex_edges[key.0].push(e);
+                        topo.vertex_edges[key.1].push(e);
+                        e
+                    }
+                };
+                topo.edge_faces[e].push(f);
+            }
+        }
+        topo
+    }
+
+    fn edge(&self, a: usize, b: usize) -> usize {
+        self.edges[&edge_key(a, b)]
+    }
+}
+
+impl SubdivisionMesh {
+    /// faces 为任意边数的多边形，按逆时针给出顶点下标
+    pub fn new(positions: Vec<Point3>, faces: Vec<Vec<usize>>) -> Self {
+        assert!(faces.iter().all(|f| f.len() >= 3), "subdivision faces need at least 3 vertices");
+        assert!(faces.iter().flatten().all(|&i| i < positions.len()), "subdivision face index out of range");
+        Self { positions, faces, face_uvs: Vec::new(), creases: HashMap::new(), corners: HashSet::new(), displacement: None }
+    }
+
+    pub fn with_face_uvs(mut self, face_uvs: Vec<Vec<(f64, f64)>>) -> Self {
+        assert!(
+            face_uvs.is_empty() || (face_uvs.len() == self.faces.len() && face_uvs.iter().zip(&self.faces).all(|(uv, f)| uv.len() == f.len())),
+            "face uvs must match face corners"
+        );
+        self.face_uvs = face_uvs;
+        self
+    }
+
+    /// 锐度每细分一层减 1：整数 k 的边在前 k 层保持锐利，之后平滑过渡；f64::INFINITY 为硬折痕
+    pub fn with_crease(mut self, a: usize, b: usize, sharpness: f64) -> Self {
+        if sharpness > 0.0 {
+            self.creases.insert(edge_key(a, b), sharpness);
+        }
+        self
+    }
+
+    /// 细分完成后沿顶点法线位移 scale × 纹理亮度
+    pub fn with_displacement(mut self, tex: Arc<dyn Texture + Send + Sync>, scale: f64) -> Self {
+        self.displacement = Some((tex, scale));
+        self
+    }
+
+    pub fn build(self, scheme: Scheme, refinement: Refinement, mat: Arc<dyn Material + Send + Sync>) -> TriangleMesh {
+        self.refine(scheme, refinement).into_triangle_mesh(mat)
+    }
+
+    pub fn refine(mut self, scheme: Scheme, refinement: Refinement) -> Self {
+        match refinement {
+            Refinement::Levels(levels) => {
+                for _ in 0..levels {
+                    self = self.step(scheme);
+                }
+            }
+            Refinement::ScreenSpace { eye, pixel_angle, pixels } => {
+                let tolerance = pixel_angle * pixels;
+                let mut level = 0;
+                while level < MAX_LEVELS && self.max_edge_angle(&eye) > tolerance {
+                    self = self.step(scheme);
+                    level += 1;
+                }
+            }
+        }
+        self
+    }
+
+    // 所有边对 eye 的最大张角（以边长 / 距离近似）
+    fn max_edge_angle(&self, eye: &Point3) -> f64 {
+        let mut max: f64 = 0.0;
+        for face in &self.faces {
+            for (i, &a) in face.iter().enumerate() {
+                let (pa, pb) = (self.positions[a], self.positions[face[(i + 1) % face.len()]]);
+                let distance = (0.5 * (pa + pb) - *eye).length().max(1e-9);
+                max = max.max((pb - pa).length() / distance);
+            }
+        }
+        max
+    }
+
+    fn step(self, scheme: Scheme) -> Self {
+        match scheme {
+            Scheme::CatmullClark => self.catmull_clark_step(),
+            Scheme::Loop => self.into_triangles().loop_step(),
+        }
+    }
+
+    // 边的锐度：边界与非流形边视为无限锐利
+    fn sharpness(&self, topo: &Topology, e: usize) -> f64 {
+        if topo.edge_faces[e].len() != 2 {
+            return f64::INFINITY;
+        }
+        self.creases.get(&topo.edge_verts[e]).copied().unwrap_or(0.0)
+    }
+
+    // 顶点规则：按相邻锐边数选择平滑 / 折痕 / 角点，平均锐度不足 1 时与平滑结果插值
+    fn vertex_point(&self, topo: &Topology, v: usize, smooth: impl Fn() -> Point3, crease: impl Fn(usize, usize) -> Point3) -> Point3 {
+        let p = self.positions[v];
+        let sharp: Vec<(usize, f64)> = topo.vertex_edges[v]
+            .iter()
+            .map(|&e| (e, self.sharpness(topo, e)))
+            .filter(|&(_, s)| s > 0.0)
+            .collect();
+        if topo.vertex_edges[v].is_empty() {
+            return p;
+        }
+        if sharp.len() < 2 {
+            return smooth();
+        }
+        let other = |e: usize| {
+            let (a, b) = topo.edge_verts[e];
+            if a == v { b } else { a }
+        };
+        // 只属于一个面的边界顶点（如平面片的角）保持为角点
+        let corner = sharp.len() > 2 || topo.vertex_faces[v].len() == 1 || self.corners.contains(&v);
+        let sharp_point = if corner { p } else { crease(other(sharp[0].0), other(sharp[1].0)) };
+        let s = sharp.iter().map(|&(_, s)| s).sum::<f64>() / sharp.len() as f64;
+        if s >= 1.0 { sharp_point } else { lerp_point(s, smooth(), sharp_point) }
+    }
+
+    // 子边继承锐度减 1
+    fn child_creases(&self, topo: &Topology, edge_vertex: impl Fn(usize) -> usize) -> HashMap<(usize, usize), f64> {
+        let mut creases = HashMap::new();
+        for (&key, &s) in &self.creases {
+            let Some(&e) = topo.edges.get(&key) else { continue };
+            let child = s - 1.0;
+            if child > 0.0 {
+                let m = edge_vertex(e);
+                creases.insert(edge_key(key.0, m), child);
+                creases.insert(edge_key(m, key.1), child);
+            }
+        }
+        creases
+    }
+
+    fn catmull_clark_step(self) -> Self {
+        let topo = Topology::new(self.positions.len(), &self.faces);
+        let (nv, ne) = (self.positions.len(), topo.edge_verts.len());
+
+        let face_points: Vec<Point3> = self
+            .faces
+            .iter()
+            .map(|f| f.iter().fold(Point3::default(), |acc, &v| acc + self.positions[v]) / f.len() as f64)
+            .collect();
+
+        let edge_points: Vec<Point3> = (0..ne)
+            .map(|e| {
+                let (a, b) = topo.edge_verts[e];
+                let mid = 0.5 * (self.positions[a] + self.positions[b]);
+                let s = self.sharpness(&topo, e);
+                if s >= 1.0 {
+                    return mid;
+                }
+                let faces = &topo.edge_faces[e];
+                let smooth = 0.25 * (self.positions[a] + self.positions[b] + face_points[faces[0]] + face_points[faces[1]]);
+                lerp_point(s, smooth, mid)
+            })
+            .collect();
+
+        let mut positions: Vec<Point3> = (0..nv)
+            .map(|v| {
+                let p = self.positions[v];
+                let smooth = || {
+                    // (Q + 2R + (n - 3) S) / n
+                    let n = topo.vertex_edges[v].len() as f64;
+                    let faces = &topo.vertex_faces[v];
+                    let q = faces.iter().fold(Point3::default(), |acc, &f| acc + face_points[f]) / faces.len() as f64;
+                    let r = topo.vertex_edges[v].iter().fold(Point3::default(), |acc, &e| {
+                        let (a, b) = topo.edge_verts[e];
+                        acc + 0.5 * (self.positions[a] + self.positions[b])
+                    }) / n;
+                    (q + 2.0 * r + (n - 3.0) * p) / n
+                };
+                let crease = |a: usize, b: usize| (self.positions[a] + 6.0 * p + self.positions[b]) / 8.0;
+                self.vertex_point(&topo, v, smooth, crease)
+            })
+            .collect();
+        positions.extend(edge_points);
+        positions.extend(face_points);
+
+        // 每个 n 边形拆成 n 个四边形：[角点, 后一条边的中点, 面中心, 前一条边的中点]
+        let mut faces = Vec::new();
+        let mut face_uvs = Vec::new();
+        for (f, face) in self.faces.iter().enumerate() {
+            let n = face.len();
+            let uvs = self.face_uvs.get(f);
+            let center_uv = uvs.map(|uv| {
+                let sum = uv.iter().fold((0.0, 0.0), |acc, t| (acc.0 + t.0, acc.1 + t.1));
+                (sum.0 / n as f64, sum.1 / n as f64)
+            });
+            for i in 0..n {
+                let (prev, cur, next) = (face[(i + n - 1) % n], face[i], face[(i + 1) % n]);
+                faces.push(vec![cur, nv + topo.edge(cur, next), nv + ne + f, nv + topo.edge(prev, cur)]);
+                if let (Some(uv), Some(center)) = (uvs, center_uv) {
+                    let (up, uc, un) = (uv[(i + n - 1) % n], uv[i], uv[(i + 1) % n]);
+                    face_uvs.push(vec![uc, mid_uv(uc, un), center, mid_uv(up, uc)]);
+                }
+            }
+        }
+
+        let creases = self.child_creases(&topo, |e| nv + e);
+        Self { positions, faces, face_uvs, creases, corners: self.corners, displacement: self.displacement }
+    }
+
+    // Loop 细分只接受三角形，先把多边形三角化；对角线会让多边形的角变成多个三角形的公共顶点，
+    // 先记下原来只属于一个面的顶点
+    fn into_triangles(mut self) -> Self {
+        if self.faces.iter().all(|f| f.len() == 3) {
+            return self;
+        }
+        let mut face_counts = vec![0; self.positions.len()];
+        for &v in self.faces.iter().flatten() {
+            face_counts[v] += 1;
+        }
+        self.corners.extend((0..self.positions.len()).filter(|&v| face_counts[v] == 1));
+        let mut faces = Vec::new();
+        let mut face_uvs = Vec::new();
+        for (f, face) in self.faces.iter().enumerate() {
+            let points: Vec<Point3> = face.iter().map(|&v| self.positions[v]).collect();
+            for [a, b, c] in triangulate(&points) {
+                faces.push(vec![face[a], face[b], face[c]]);
+                if let Some(uv) = self.face_uvs.get(f) {
+                    face_uvs.push(vec![uv[a], uv[b], uv[c]]);
+                }
+            }
+        }
+        self.faces = faces;
+        self.face_uvs = face_uvs;
+        self
+    }
+
+    fn loop_step(self) -> Self {
+        let topo = Topology::new(self.positions.len(), &self.faces);
+        let (nv, ne) = (self.positions.len(), topo.edge_verts.len());
+
+        let opposite = |f: usize, a: usize, b: usize| *self.faces[f].iter().find(|&&v| v != a && v != b).unwrap_or(&a);
+        let edge_points: Vec<Point3> = (0..ne)
+            .map(|e| {
+                let (a, b) = topo.edge_verts[e];
+                let mid = 0.5 * (self.positions[a] + self.positions[b]);
+                let s = self.sharpness(&topo, e);
+                if s >= 1.0 {
+                    return mid;
+                }
+                let faces = &topo.edge_faces[e];
+                let (c, d) = (opposite(faces[0], a, b), opposite(faces[1], a, b));
+                let smooth = 0.375 * (self.positions[a] + self.positions[b]) + 0.125 * (self.positions[c] + self.positions[d]);
+                lerp_point(s, smooth, mid)
+            })
+            .collect();
+
+        let mut positions: Vec<Point3> = (0..nv)
+            .map(|v| {
+                let p = self.positions[v];
+                let smooth = || {
+                    let edges = &topo.vertex_edges[v];
+                    let n = edges.len() as f64;
+                    let beta = (0.625 - (0.375 + 0.25 * (2.0 * std::f64::consts::PI / n).cos()).powi(2)) / n;
+                    let sum = edges.iter().fold(Point3::default(), |acc, &e| {
+                        let (a, b) = topo.edge_verts[e];
+                        acc + self.positions[if a == v { b } else { a }]
+                    });
+                    (1.0 - n * beta) * p + beta * sum
+                };
+                let crease = |a: usize, b: usize| 0.75 * p + 0.125 * (self.positions[a] + self.positions[b]);
+                self.vertex_point(&topo, v, smooth, crease)
+            })
+            .collect();
+        positions.extend(edge_points);
+
+        let mut faces = Vec::with_capacity(4 * self.faces.len());
+        let mut face_uvs = Vec::new();
+        for (f, face) in self.faces.iter().enumerate() {
+            let (a, b, c) = (face[0], face[1], face[2]);
+            let (ab, bc, ca) = (nv + topo.edge(a, b), nv + topo.edge(b, c), nv + topo.edge(c, a));
+            faces.extend([vec![a, ab, ca], vec![ab, b, bc], vec![ca, bc, c], vec![ab, bc, ca]]);
+            if let Some(uv) = self.face_uvs.get(f) {
+                let (ua, ub, uc) = (uv[0], uv[1], uv[2]);
+                let (uab, ubc, uca) = (mid_uv(ua, ub), mid_uv(ub, uc), mid_uv(uc, ua));
+                face_uvs.extend([vec![ua, uab, uca], vec![uab, ub, ubc], vec![uca, ubc, uc], vec![uab, ubc, uca]]);
+            }
+        }
+
+        let creases = self.child_creases(&topo, |e| nv + e);
+        Self { positions, faces, face_uvs, creases, corners: self.corners, displacement: self.displacement }
+    }
+
+    // 多边形的面积向量（Newell 法）
+    fn face_normal(&self, f: usize) -> Vec3 {
+        let face = &self.faces[f];
+        let mut n = Vec3::default();
+        for (i, &a) in face.iter().enumerate() {
+            n += Vec3::cross(&self.positions[a], &self.positions[face[(i + 1) % face.len()]]);
+        }
+        0.5 * n
+    }
+
+    // 每个面角的法线：绕顶点相邻且不隔着锐边的面归为一组，组内按面积加权平均
+    fn corner_normals(&self, topo: &Topology) -> Vec<Vec<Vec3>> {
+        let face_normals: Vec<Vec3> = (0..self.faces.len()).map(|f| self.face_normal(f)).collect();
+        let mut corners: Vec<Vec<Vec3>> = self.faces.iter().map(|f| vec![Vec3::default(); f.len()]).collect();
+        for v in 0..self.positions.len() {
+            let faces = &topo.vertex_faces[v];
+            if faces.is_empty() {
+                continue;
+            }
+            // 小规模并查集，下标为 faces 中的位置
+            let mut parent: Vec<usize> = (0..faces.len()).collect();
+            fn find(parent: &mut [usize], mut i: usize) -> usize {
+                while parent[i] != i {
+                    parent[i] = parent[parent[i]];
+                    i = parent[i];
+                }
+                i
+            }
+            for &e in &topo.vertex_edges[v] {
+                let adjacent = &topo.edge_faces[e];
+                if adjacent.len() == 2 && self.sharpness(topo, e) <= 0.0 {
+                    let i = faces.iter().position(|&f| f == adjacent[0]);
+                    let j = faces.iter().position(|&f| f == adjacent[1]);
+                    if let (Some(i), Some(j)) = (i, j) {
+                        let (ri, rj) = (find(&mut parent, i), find(&mut parent, j));
+                        parent[ri] = rj;
+                    }
+                }
+            }
+            let mut sums = vec![Vec3::default(); faces.len()];
+            for (i, &f) in faces.iter().enumerate() {
+                let root = find(&mut parent, i);
+                sums[root] += face_normals[f];
+            }
+            for (i, &f) in faces.iter().enumerate() {
+                let root = find(&mut parent, i);
+                let n = if sums[root].length_squared() > 0.0 { Vec3::unit_vector(sums[root]) } else { Vec3::default() };
+                for (k, &fv) in self.faces[f].iter().enumerate() {
+                    if fv == v {
+                        corners[f][k] = n;
+                    }
+                }
+            }
+        }
+        corners
+    }
+
+    // 沿平滑顶点法线位移；同一位置的多个 UV 取第一个面角
+    fn displace(&mut self, tex: &Arc<dyn Texture + Send + Sync>, scale: f64) {
+        let mut normals = vec![Vec3::default(); self.positions.len()];
+        let mut uvs: Vec<Option<(f64, f64)>> = vec![None; self.positions.len()];
+        for (f, face) in self.faces.iter().enumerate() {
+            let n = self.face_normal(f);
+            for (k, &v) in face.iter().enumerate() {
+                normals[v] += n;
+                if uvs[v].is_none() {
+                    uvs[v] = self.face_uvs.get(f).map(|uv| uv[k]);
+                }
+            }
+        }
+        for (v, p) in self.positions.iter_mut().enumerate() {
+            if normals[v].length_squared() == 0.0 {
+                continue;
+            }
+            let (u, w) = uvs[v].unwrap_or((0.0, 0.0));
+            let c = tex.value(u, w, p);
+            let height = 0.2126 * c.x + 0.7152 * c.y + 0.0722 * c.z;
+            *p += scale * height * Vec3::unit_vector(normals[v]);
+        }
+    }
+
+    /// 不再细分，直接三角化输出；位移在此时施加
+    pub fn into_triangle_mesh(mut self, mat: Arc<dyn Material + Send + Sync>) -> TriangleMesh {
+        if let Some((tex, scale)) = self.displacement.take() {
+            self.displace(&tex, scale);
+        }
+        let topo = Topology::new(self.positions.len(), &self.faces);
+        let corner_normals = self.corner_normals(&topo);
+
+        // 输出顶点按 (位置, 法线, UV) 去重，锐边与 UV 接缝处会拆开
+        let mut remap: HashMap<VertexKey, usize> = HashMap::new();
+        let mut positions = Vec::new();
+        let mut normals = Vec::new();
+        let mut uvs = Vec::new();
+        let mut indices = Vec::new();
+        let has_uvs = !self.face_uvs.is_empty();
+        for (f, face) in self.faces.iter().enumerate() {
+            let ids: Vec<usize> = face
+                .iter()
+                .enumerate()
+                .map(|(k, &v)| {
+                    let n = corner_normals[f][k];
+                    let uv = self.face_uvs.get(f).map(|uv| uv[k]);
+                    let key = (v, [n.x.to_bits(), n.y.to_bits(), n.z.to_bits()], uv.map(|t| [t.0.to_bits(), t.1.to_bits()]));
+                    *remap.entry(key).or_insert_with(|| {
+                        positions.push(self.positions[v]);
+                        normals.push(n);
+                        uvs.push(uv.unwrap_or((0.0, 0.0)));
+                        positions.len() - 1
+                    })
+                })
+                .collect();
+            let points: Vec<Point3> = face.iter().map(|&v| self.positions[v]).collect();
+            for [a, b, c] in triangulate(&points) {
+                indices.push([ids[a], ids[b], ids[c]]);
+            }
+        }
+        let uvs = if has_uvs { uvs } else { Vec::new() };
+        TriangleMesh::with_attributes(positions, normals, uvs, indices, mat).expect("subdivided vertices carry one normal and uv each")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::material::Lambertian;
+    use crate::texture::SolidColor;
+    use crate::vec3::Color;
+
+    fn grey() -> Arc<dyn Material + Send + Sync> {
+        Arc::new(Lambertian::from_color(Color::new(0.5, 0.5, 0.5)))
+    }
+
+    // [-1, 1]³ 立方体，顶点下标的三个二进制位对应 x、y、z 的正负
+    fn cube() -> SubdivisionMesh {
+        let positions = (0..8)
+            .map(|i| Point3::new(if i & 1 == 0 { -1.0 } else { 1.0 }, if i & 2 == 0 { -1.0 } else { 1.0 }, if i & 4 == 0 { -1.0 } else { 1.0 }))
+            .collect();
+        let faces = vec![vec![0, 2, 3, 1], vec![4, 5, 7, 6], vec![0, 1, 5, 4], vec![2, 6, 7, 3], vec![0, 4, 6, 2], vec![1, 3, 7, 5]];
+        SubdivisionMesh::new(positions, faces)
+    }
+
+    fn octahedron() -> SubdivisionMesh {
+        let positions = vec![
+            Point3::new(1.0, 0.0, 0.0),
+            Point3::new(-1.0, 0.0, 0.0),
+            Point3::new(0.0, 1.0, 0.0),
+            Point3::new(0.0, -1.0, 0.0),
+            Point3::new(0.0, 0.0, 1.0),
+            Point3::new(0.0, 0.0, -1.0),
+        ];
+        let faces = vec![
+            vec![0, 2, 4], vec![2, 1, 4], vec![1, 3, 4], vec![3, 0, 4],
+            vec![2, 0, 5], vec![1, 2, 5], vec![3, 1, 5], vec![0, 3, 5],
+        ];
+        SubdivisionMesh::new(positions, faces)
+    }
+
+    fn hard_edges(mut mesh: SubdivisionMesh, sharpness: f64) -> SubdivisionMesh {
+        let faces = mesh.faces.clone();
+        for face in &faces {
+            for (i, &a) in face.iter().enumerate() {
+                mesh = mesh.with_crease(a, face[(i + 1) % face.len()], sharpness);
+            }
+        }
+        mesh
+    }
+
+    fn close(a: Point3, b: Point3) -> bool {
+        (a - b).length() < 1e-12
+    }
+
+    // 角点 (Q + 2R + (n - 3)S) / n：三个面心均值 1/3、三条边中点均值 2/3，得 5/9
+    #[test]
+    fn catmull_clark_follows_the_smooth_rules() {
+        let once = cube().refine(Scheme::CatmullClark, Refinement::Levels(1));
+        assert_eq!(once.faces.len(), 24);
+        assert!(once.faces.iter().all(|f| f.len() == 4));
+        assert!(close(once.positions[7], Point3::new(5.0, 5.0, 5.0) / 9.0));
+        let twice = once.refine(Scheme::CatmullClark, Refinement::Levels(1));
+        assert_eq!(twice.faces.len(), 96);
+        // 平滑后整体收缩到立方体内部，且保持关于原点对称
+        assert!(twice.positions.iter().all(|p| p.x.abs() < 1.0 && p.y.abs() < 1.0 && p.z.abs() < 1.0));
+        let centroid = twice.positions.iter().fold(Point3::default(), |acc, p| acc + *p) / twice.positions.len() as f64;
+        assert!(centroid.length() < 1e-12);
+    }
+
+    // 价为 4 的顶点 β = (5/8 - (3/8 + cos(π/2)/4)²) / 4，八面体的邻点之和为零
+    #[test]
+    fn loop_follows_the_smooth_rules() {
+        let once = octahedron().refine(Scheme::Loop, Refinement::Levels(1));
+        assert_eq!(once.faces.len(), 32);
+        let beta = (0.625 - 0.375f64.powi(2)) / 4.0;
+        assert!(close(once.positions[0], Point3::new(1.0 - 4.0 * beta, 0.0, 0.0)));
+        // 边点 3/8 (a + b) + 1/8 (c + d)，两个对顶点关于该边对称
+        assert!(once.positions.iter().any(|p| close(*p, Point3::new(0.375, 0.375, 0.0))));
+        let sphere = once.refine(Scheme::Loop, Refinement::Levels(2));
+        assert_eq!(sphere.faces.len(), 512);
+        assert!(sphere.positions.iter().all(|p| p.length() < 1.0 && p.length() > 0.4));
+        // 四边形先三角化再细分
+        assert_eq!(cube().refine(Scheme::Loop, Refinement::Levels(1)).faces.len(), 48);
+    }
+
+    // 全部边为硬折痕时细分不改变形状，平直着色的网格面积仍为 24
+    #[test]
+    fn hard_creases_keep_the_control_shape() {
+        let sharp = hard_edges(cube(), f64::INFINITY).refine(Scheme::CatmullClark, Refinement::Levels(3));
+        assert!(sharp.positions.iter().all(|p| (p.x.abs().max(p.y.abs()).max(p.z.abs()) - 1.0).abs() < 1e-12));
+        let mesh = hard_edges(cube(), f64::INFINITY).build(Scheme::CatmullClark, Refinement::Levels(2), grey());
+        assert_eq!(mesh.triangle_count(), 6 * 16 * 2);
+        assert!((mesh.area() - 24.0).abs() < 1e-9);
+        let smooth = cube().build(Scheme::CatmullClark, Refinement::Levels(2), grey());
+        assert!(smooth.area() < 20.0);
+    }
+
+    // 半锐边：锐度 0.5 的结果恰为平滑与硬折痕的中点；锐度每层减 1，耗尽后不再传递
+    #[test]
+    fn semi_sharp_creases_blend_and_decay() {
+        let step = |s: f64| cube().with_crease(7, 3, s).refine(Scheme::CatmullClark, Refinement::Levels(1)).positions;
+        let (smooth, half, hard) = (step(0.0), step(0.5), step(f64::INFINITY));
+        let mut moved = 0;
+        for i in 0..smooth.len() {
+            assert!(close(half[i], 0.5 * (smooth[i] + hard[i])));
+            if !close(smooth[i], hard[i]) {
+                moved += 1;
+            }
+        }
+        assert_eq!(moved, 1); // 只有该边的边点不同，单条锐边的端点仍按平滑规则
+
+        let once = cube().with_crease(7, 3, 2.5).refine(Scheme::CatmullClark, Refinement::Levels(1));
+        assert_eq!(once.creases.len(), 2);
+        assert!(once.creases.values().all(|&s| s == 1.5));
+        let twice = once.refine(Scheme::CatmullClark, Refinement::Levels(1));
+        assert_eq!(twice.creases.len(), 4);
+        assert!(twice.refine(Scheme::CatmullClark, Refinement::Levels(1)).creases.is_empty());
+    }
+
+    // 开放边界按硬折痕处理：平面片保持平面，四个角点与边界线不动
+    #[test]
+    fn boundaries_stay_put() {
+        let positions: Vec<Point3> = (0..9).map(|i| Point3::new((i % 3) as f64, (i / 3) as f64, 0.0)).collect();
+        let faces = vec![vec![0, 1, 4, 3], vec![1, 2, 5, 4], vec![3, 4, 7, 6], vec![4, 5, 8, 7]];
+        for scheme in [Scheme::CatmullClark, Scheme::Loop] {
+            let patch = SubdivisionMesh::new(positions.clone(), faces.clone()).refine(scheme, Refinement::Levels(2));
+            assert!(patch.positions.iter().all(|p| p.z == 0.0 && (0.0..=2.0).contains(&p.x) && (0.0..=2.0).contains(&p.y)));
+            for corner in [Point3::new(0.0, 0.0, 0.0), Point3::new(2.0, 0.0, 0.0), Point3::new(0.0, 2.0, 0.0), Point3::new(2.0, 2.0, 0.0)] {
+                assert!(patch.positions.iter().any(|p| close(*p, corner)));
+            }
+            let on_edge = patch.positions.iter().filter(|p| p.x == 0.0).count();
+            assert_eq!(on_edge, 9);
+        }
+    }
+
+    // 屏幕空间容差：离得越近细分越多，结果中每条边的张角都不超过容差
+    #[test]
+    fn screen_space_refinement_meets_the_tolerance() {
+        let refine = |distance: f64| {
+            let refinement = Refinement::screen_space(Point3::new(0.0, 0.0, distance), 40.0, 400, 8.0);
+            let Refinement::ScreenSpace { eye, pixel_angle, pixels } = refinement else { unreachable!() };
+            let mesh = cube().refine(Scheme::CatmullClark, refinement);
+            assert!(mesh.max_edge_angle(&eye) <= pixel_angle * pixels);
+            mesh.faces.len()
+        };
+        let (near, far) = (refine(4.0), refine(40.0));
+        assert!(near > far && far > 6);
+    }
+
+    // 常量位移纹理把每个顶点沿法线推出 scale
+    #[test]
+    fn displacement_moves_vertices_along_normals() {
+        let mut sphere = octahedron().refine(Scheme::Loop, Refinement::Levels(3));
+        let before = sphere.positions.clone();
+        let white: Arc<dyn Texture + Send + Sync> = Arc::new(SolidColor::new(Color::new(1.0, 1.0, 1.0)));
+        sphere.displace(&white, 0.1);
+        for (p, q) in before.iter().zip(&sphere.positions) {
+            assert!(((*q - *p).length() - 0.1).abs() < 1e-12);
+            assert!(q.length() - p.length() > 0.09);
+        }
+        let bumpy = octahedron().with_displacement(white, 0.1).build(Scheme::Loop, Refinement::Levels(3), grey());
+        let plain = octahedron().build(Scheme::Loop, Refinement::Levels(3), grey());
+        assert!(bumpy.area() > plain.area());
+    }
+}
@@ -0,0 +1,261 @@
+use std::sync::Arc;
+use rand::{rngs::ThreadRng, Rng};
+use crate::constant_medium::sample_free_flight;
+use crate::aabb::Aabb;
+use crate::hittable::{HitRecord, Hittable};
+use crate::interval::Interval;
+use crate::material::{Material, ScatterRecord};
+use crate::ray::Ray;
+use crate::rtweekend::random::random_unit_vector_with_rng;
+use crate::rtweekend::INFINITY;
+use crate::vec3::{Color, Point3, Vec3};
+
+// 次表面散射材质：光线折射进入物体后，在物体的闭合边界内做体积随机游走，
+// 直到从边界折射离开或被吸收。适用于大理石、皮肤、蜡等半透明材质。
+//
+// boundary 是游走时寻找出射点的闭合边界，必须与使用该材质的物体几何一致；
+// 用 into_object 把它本身作为物体加入场景，不必再构造一份几何副本。
+pub struct SubsurfaceMaterial {
+    boundary: Arc<dyn Hittable + Send + Sync>,
+    albedo: Color,         // 单次散射反照率 σs / σt（每通道）
+    sigma_t: Color,        // 消光系数，即平均自由程的倒数（每通道）
+    ior: f64,
+    max_bounces: usize,
+}
+
+impl SubsurfaceMaterial {
+    pub fn new(boundary: Arc<dyn Hittable + Send + Sync>, albedo: Color, mean_free_path: Color, ior: f64) -> Self {
+        let sigma_t = Color::new(
+            1.0 / mean_free_path.x.max(1e-8),
+            1.0 / mean_free_path.y.max(1e-8),
+            1.0 / mean_free_path.z.max(1e-8),
+        );
+        Self { boundary, albedo, sigma_t, ior, max_bounces: 256 }
+    }
+
+    // 游走的最大散射次数，超过后视为被吸收
+    pub fn with_max_bounces(mut self, max_bounces: usize) -> Self {
+        self.max_bounces = max_bounces;
+        self
+    }
+
+    /// 以边界本身为几何的物体，命中时使用该材质；边界自带的材质被忽略
+    pub fn into_object(self) -> SubsurfaceObject {
+        SubsurfaceObject { shape: self.boundary.clone(), material: Arc::new(self) }
+    }
+
+    fn channel(c: &Color, i: usize) -> f64 {
+        match i {
+            0 => c.x,
+            1 => c.y,
+            _ => c.z,
+        }
+    }
+
+    // 每通道透射率 exp(-σt d)
+    fn transmittance(&self, d: f64) -> Color {
+        Color::new((-self.sigma_t.x * d).exp(), (-self.sigma_t.y * d).exp(), (-self.sigma_t.z * d).exp())
+    }
+
+    fn reflectance(cosine: f64, ref_idx: f64) -> f64 {
+        let r0 = ((1.0 - ref_idx) / (1.0 + ref_idx)).powi(2);
+        r0 + (1.0 - r0) * (1.0 - cosine).powi(5)
+    }
+
+    // 在界面处按菲涅尔项选择反射或折射，返回新的方向以及是否发生了折射
+    fn interface(dir: &Vec3, normal: &Vec3, ri: f64, rng: &mut ThreadRng) -> (Vec3, bool) {
+        let cos_theta = Vec3::dot(&-*dir, normal).min(1.0);
+        let sin_theta = (1.0 - cos_theta * cos_theta).sqrt();
+        if ri * sin_theta > 1.0 || Self::reflectance(cos_theta, ri) > rng.gen::<f64>() {
+            (Vec3::reflect(dir, normal), false)
+        } else {
+            (Vec3::unit_vector(Vec3::refract(dir, normal, ri)), true)
+        }
+    }
+}
+
+impl Material for SubsurfaceMaterial {
+    fn scatter(
+        &self,
+        r_in: &Ray,
+        rec: &HitRecord,
+        srec: &mut ScatterRecord,
+        rng: &mut ThreadRng,
+    ) -> bool {
+        srec.pdf_ptr = None;
+        srec.skip_pdf = true;
+
+        let unit_direction = Vec3::unit_vector(r_in.direction);
+        if !rec.front_face {
+            // 起点已在物体内部（例如相机在物体里），直接穿出
+            srec.attenuation = Color::new(1.0, 1.0, 1.0);
+            srec.skip_pdf_ray = Some(r_in.spawn(rec.p, unit_direction));
+            return true;
+        }
+
+        let (mut dir, entered) = Self::interface(&unit_direction, &rec.normal, 1.0 / self.ior, rng);
+        if !entered {
+            srec.attenuation = Color::new(1.0, 1.0, 1.0);
+            srec.skip_pdf_ray = Some(r_in.spawn(rec.p, dir));
+            return true;
+        }
+
+        // 随机选择一个通道按其 σt 采样距离，权重使用三个通道 PDF 的平均值（单样本 MIS），保证各通道无偏
+        let mut p = rec.p;
+        // 起点在边界上时跳过自身交点；体内散射点可能离边界比这更近，不能跳过
+        let mut t_min = 0.0001;
+        let mut throughput = Color::new(1.0, 1.0, 1.0);
+        for _ in 0..self.max_bounces {
+            let channel = rng.gen_range(0..3);
+            let distance = sample_free_flight(-1.0 / Self::channel(&self.sigma_t, channel));
+
+            let walk = Ray::new(p, dir, r_in.time());
+            let mut exit = HitRecord::default();
+            if !self.boundary.hit(&walk, Interval::new(t_min, INFINITY), &mut exit) {
+                // 边界不闭合，路径丢失
+                return false;
+            }
+
+            if exit.t <= distance {
+                // 到达边界：权重为 T(L) / avg(T(L))
+                let tr = self.transmittance(exit.t);
+                throughput = throughput * tr / ((tr.x + tr.y + tr.z) / 3.0);
+
+                let (out_dir, refracted) = Self::interface(&dir, &exit.normal, self.ior, rng);
+                if refracted {
+                    srec.attenuation = throughput;
+                    srec.skip_pdf_ray = Some(r_in.spawn(exit.p, out_dir));
+                    return true;
+                }
+                // 全反射或菲涅尔反射，留在内部继续游走
+                p = exit.p;
+                dir = out_dir;
+                t_min = 0.0001;
+                continue;
+            }
+
+            // 体内散射：权重为 σs T(d) / avg(σt T(d))
+            let tr = self.transmittance(distance);
+            let pdf = (self.sigma_t.x * tr.x + self.sigma_t.y * tr.y + self.sigma_t.z * tr.z) / 3.0;
+            throughput = throughput * self.albedo * self.sigma_t * tr / pdf;
+            p = walk.at(distance);
+            dir = random_unit_vector_with_rng(rng);
+            t_min = 0.0;
+        }
+
+        false
+    }
+}
+
+// 次表面物体：几何与材质的游走边界是同一个形状
+pub struct SubsurfaceObject {
+    shape: Arc<dyn Hittable + Send + Sync>,
+    material: Arc<dyn Material + Send + Sync>,
+}
+
+impl Hittable for SubsurfaceObject {
+    fn hit(&self, r: &Ray, ray_t: Interval, rec: &mut HitRecord) -> bool {
+        if !self.shape.hit(r, ray_t, rec) {
+            return false;
+        }
+        rec.mat = Some(self.material.clone());
+        true
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.shape.bounding_box()
+    }
+
+    fn pdf_value(&self, origin: &Point3, direction: &Vec3) -> f64 {
+        self.shape.pdf_value(origin, direction)
+    }
+
+    fn random(&self, origin: &Point3) -> Vec3 {
+        self.shape.random(origin)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sphere::Sphere;
+    use rand::thread_rng;
+
+    // 从正上方射入单位球顶点时游走的平均出射权重
+    fn mean_exit_weight(material: &SubsurfaceMaterial, n: usize) -> Color {
+        let r = Ray::new(Point3::new(0.0, 2.0, 0.0), Vec3::new(0.0, -1.0, 0.0), 0.0);
+        let mut rec = HitRecord::default();
+        rec.p = Point3::new(0.0, 1.0, 0.0);
+        rec.normal = Vec3::new(0.0, 1.0, 0.0);
+        rec.front_face = true;
+        let mut rng = thread_rng();
+        let mut sum = Color::new(0.0, 0.0, 0.0);
+        for _ in 0..n {
+            let mut srec = ScatterRecord::new();
+            if material.scatter(&r, &rec, &mut srec, &mut rng) {
+                sum += srec.attenuation;
+            }
+        }
+        sum / n as f64
+    }
+
+    #[test]
+    fn free_flight_has_mean_one_over_density() {
+        let n = 100000;
+        let mean = (0..n).map(|_| sample_free_flight(-1.0 / 4.0)).sum::<f64>() / n as f64;
+        assert!((mean - 0.25).abs() < 0.005);
+    }
+
+    // 无吸收的灰色介质：每条游走都带着权重 1 离开
+    #[test]
+    fn lossless_grey_walk_always_exits_with_unit_weight() {
+        let boundary = Arc::new(Sphere::new(Point3::new(0.0, 0.0, 0.0), 1.0, None));
+        let material = SubsurfaceMaterial::new(boundary, Color::new(1.0, 1.0, 1.0), Color::new(0.3, 0.3, 0.3), 1.5)
+            .with_max_bounces(100000);
+        let mean = mean_exit_weight(&material, 2000);
+        for c in [mean.x, mean.y, mean.z] {
+            assert!((c - 1.0).abs() < 1e-9, "{}", mean);
+        }
+    }
+
+    // 三个通道的平均自由程不同时，单样本 MIS 下各通道的平均出射权重仍为 1
+    #[test]
+    fn lossless_chromatic_walk_conserves_energy_per_channel() {
+        let boundary = Arc::new(Sphere::new(Point3::new(0.0, 0.0, 0.0), 1.0, None));
+        let material = SubsurfaceMaterial::new(boundary, Color::new(1.0, 1.0, 1.0), Color::new(0.6, 0.8, 1.0), 1.0)
+            .with_max_bounces(100000);
+        let mean = mean_exit_weight(&material, 20000);
+        for c in [mean.x, mean.y, mean.z] {
+            assert!((c - 1.0).abs() < 0.05, "{}", mean);
+        }
+    }
+
+    #[test]
+    fn absorbing_walk_loses_energy() {
+        let boundary = Arc::new(Sphere::new(Point3::new(0.0, 0.0, 0.0), 1.0, None));
+        let material = SubsurfaceMaterial::new(boundary, Color::new(0.5, 0.5, 0.5), Color::new(0.2, 0.2, 0.2), 1.0);
+        let mean = mean_exit_weight(&material, 5000);
+        assert!(mean.x < 0.5 && (mean.x - mean.z).abs() < 1e-12);
+    }
+
+    // 物体与游走边界共用同一个球：命中点使用次表面材质，游走在同一个球内进行
+    #[test]
+    fn object_shares_its_shape_with_the_walk() {
+        let object = SubsurfaceMaterial::new(Arc::new(Sphere::new(Point3::new(0.0, 0.0, 0.0), 1.0, None)), Color::new(1.0, 1.0, 1.0), Color::new(0.3, 0.3, 0.3), 1.5)
+            .with_max_bounces(100000)
+            .into_object();
+        let r = Ray::new(Point3::new(0.0, 2.0, 0.0), Vec3::new(0.0, -1.0, 0.0), 0.0);
+        let mut rec = HitRecord::default();
+        assert!(object.hit(&r, Interval::new(0.001, INFINITY), &mut rec));
+        assert!((rec.p.y - 1.0).abs() < 1e-12 && rec.front_face);
+        let mut rng = thread_rng();
+        for _ in 0..200 {
+            let mut srec = ScatterRecord::new();
+            assert!(rec.mat.as_ref().unwrap().scatter(&r, &rec, &mut srec, &mut rng));
+            let exit = srec.skip_pdf_ray.unwrap();
+            assert!((exit.origin.length() - 1.0).abs() < 1e-9);
+            assert!((srec.attenuation.x - 1.0).abs() < 1e-9);
+        }
+        assert!((object.bounding_box().x.max - 1.0).abs() < 1e-12);
+    }
+}
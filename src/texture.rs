@@ -3,9 +3,36 @@ use crate::color::*;
 use crate::rtw_stb_image::*;
 use std::sync::Arc;
 use crate::perlin::Perlin;
+use crate::hittable::HitRecord;
 
 pub trait Texture {
     fn value(&self, u: f64, v: f64, p: &Point3) -> Color;
+
+    // 可访问完整命中信息的取值（如顶点颜色），默认按 (u, v, p) 取值
+    fn value_at(&self, rec: &HitRecord) -> Color {
+        self.value(rec.u, rec.v, &rec.p)
+    }
+}
+
+// 网格顶点颜色；命中点没有顶点颜色时使用 fallback
+pub struct VertexColorTexture {
+    fallback: Color,
+}
+
+impl VertexColorTexture {
+    pub fn new(fallback: Color) -> Self {
+        Self { fallback }
+    }
+}
+
+impl Texture for VertexColorTexture {
+    fn value(&self, _u: f64, _v: f64, _p: &Point3) -> Color {
+        self.fallback
+    }
+
+    fn value_at(&self, rec: &HitRecord) -> Color {
+        rec.vertex_color.unwrap_or(self.fallback)
+    }
 }
 
 pub struct SolidColor {
@@ -76,6 +103,10 @@ impl ImageTexture {
             image: RtwImage::from_file(filename),
         }
     }
+
+    pub fn from_memory(bytes: &[u8]) -> Option<Self> {
+        RtwImage::from_memory(bytes).map(|image| Self { image })
+    }
 }
 
 impl Texture for ImageTexture {
@@ -0,0 +1,148 @@
+// 薄膜干涉涂层（肥皂泡、油膜、阳极氧化金属）
+// 使用 Airy 公式计算单层薄膜的反射率，s/p 偏振取平均
+use std::f64::consts::PI;
+use std::sync::Arc;
+use crate::hittable::HitRecord;
+use crate::spectrum::Wavelengths;
+use crate::texture::Texture;
+use crate::vec3::Color;
+
+// RGB 模式下每个通道在其波段内取三个波长平均，近似对波段积分
+const RGB_BANDS: [[f64; 3]; 3] = [
+    [610.0, 640.0, 670.0],
+    [510.0, 535.0, 560.0],
+    [440.0, 460.0, 480.0],
+];
+
+#[derive(Clone)]
+pub struct ThinFilm {
+    pub thickness: f64, // 薄膜厚度（nm）
+    pub ior: f64,       // 薄膜折射率
+    pub thickness_map: Option<Arc<dyn Texture + Send + Sync>>, // 纹理通道均值缩放厚度
+}
+
+impl ThinFilm {
+    pub fn new(thickness: f64, ior: f64) -> Self {
+        ThinFilm { thickness: thickness.max(0.0), ior, thickness_map: None }
+    }
+
+    pub fn with_thickness_map(mut self, map: Arc<dyn Texture + Send + Sync>) -> Self {
+        self.thickness_map = Some(map);
+        self
+    }
+
+    pub fn thickness_at(&self, rec: &HitRecord) -> f64 {
+        match self.thickness_map {
+            Some(ref map) => {
+                let c = map.value_at(rec);
+                self.thickness * ((c.x + c.y + c.z) / 3.0).max(0.0)
+            }
+            None => self.thickness,
+        }
+    }
+
+    // 电介质基底：入射侧折射率 n1、入射角余弦 cos1，基底折射率 n3
+    pub fn reflectance(&self, thickness: f64, n1: f64, cos1: f64, n3: f64, lambda: f64) -> f64 {
+        let sin1_sq = (1.0 - cos1 * cos1).max(0.0);
+        let Some(cos_f) = refracted_cos(n1, self.ior, sin1_sq) else { return 1.0 };
+        let Some(cos3) = refracted_cos(n1, n3, sin1_sq) else { return 1.0 };
+        let nf = self.ior;
+        let phase = self.phase(thickness, cos_f, lambda);
+
+        let rs12 = (n1 * cos1 - nf * cos_f) / (n1 * cos1 + nf * cos_f);
+        let rs23 = (nf * cos_f - n3 * cos3) / (nf * cos_f + n3 * cos3);
+        let rp12 = (nf * cos1 - n1 * cos_f) / (nf * cos1 + n1 * cos_f);
+        let rp23 = (n3 * cos_f - nf * cos3) / (n3 * cos_f + nf * cos3);
+        0.5 * (airy(rs12, rs23, phase) + airy(rp12, rp23, phase))
+    }
+
+    // 导体基底：基底反射率用 Schlick（法向反射率 r0）近似，界面相移取 π
+    pub fn reflectance_over_conductor(&self, thickness: f64, n1: f64, cos1: f64, r0: f64, lambda: f64) -> f64 {
+        let sin1_sq = (1.0 - cos1 * cos1).max(0.0);
+        let Some(cos_f) = refracted_cos(n1, self.ior, sin1_sq) else { return 1.0 };
+        let nf = self.ior;
+        let phase = self.phase(thickness, cos_f, lambda);
+
+        let r0 = r0.clamp(0.0, 1.0);
+        let r23 = -(r0 + (1.0 - r0) * (1.0 - cos_f).powi(5)).sqrt();
+        let rs12 = (n1 * cos1 - nf * cos_f) / (n1 * cos1 + nf * cos_f);
+        let rp12 = (nf * cos1 - n1 * cos_f) / (nf * cos1 + n1 * cos_f);
+        0.5 * (airy(rs12, r23, phase) + airy(rp12, r23, phase))
+    }
+
+    // 在路径波长（光谱模式）或 RGB 代表波长处求值；f(lambda, 通道) 给出单波长反射率
+    pub fn eval<F: Fn(f64, usize) -> f64>(wavelengths: Option<&Wavelengths>, f: F) -> Color {
+        match wavelengths {
+            Some(wl) => Color::new(f(wl.lambda.x, 0), f(wl.lambda.y, 1), f(wl.lambda.z, 2)),
+            None => {
+                let band = |i: usize| RGB_BANDS[i].iter().map(|&l| f(l, i)).sum::<f64>() / 3.0;
+                Color::new(band(0), band(1), band(2))
+            }
+        }
+    }
+
+    // 薄膜内往返一次的相位差
+    fn phase(&self, thickness: f64, cos_f: f64, lambda: f64) -> f64 {
+        4.0 * PI * self.ior * thickness * cos_f / lambda
+    }
+}
+
+// Snell 定律：由入射侧 sin²θ 求折射侧 cosθ，全反射时返回 None
+fn refracted_cos(n1: f64, n2: f64, sin1_sq: f64) -> Option<f64> {
+    let sin2_sq = (n1 / n2) * (n1 / n2) * sin1_sq;
+    if sin2_sq >= 1.0 {
+        None
+    } else {
+        Some((1.0 - sin2_sq).sqrt())
+    }
+}
+
+// 单层薄膜的 Airy 反射率（实振幅反射系数）
+fn airy(r12: f64, r23: f64, phase: f64) -> f64 {
+    let cross = 2.0 * r12 * r23 * phase.cos();
+    ((r12 * r12 + r23 * r23 + cross) / (1.0 + r12 * r12 * r23 * r23 + cross)).clamp(0.0, 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::texture::SolidColor;
+
+    fn bare(n1: f64, n3: f64) -> f64 {
+        ((n1 - n3) / (n1 + n3)).powi(2)
+    }
+
+    #[test]
+    fn zero_thickness_matches_bare_interface() {
+        let film = ThinFilm::new(0.0, 1.8);
+        assert!((film.reflectance(0.0, 1.0, 1.0, 1.5, 550.0) - bare(1.0, 1.5)).abs() < 1e-12);
+    }
+
+    // 折射率为 √n3、厚度为四分之一波长的膜在该波长处完全消反射；半波长膜等同于没有膜
+    #[test]
+    fn quarter_wave_cancels_and_half_wave_is_absent() {
+        let nf = 1.5f64.sqrt();
+        let film = ThinFilm::new(0.0, nf);
+        let quarter = 550.0 / (4.0 * nf);
+        assert!(film.reflectance(quarter, 1.0, 1.0, 1.5, 550.0) < 1e-12);
+        assert!((film.reflectance(2.0 * quarter, 1.0, 1.0, 1.5, 550.0) - bare(1.0, 1.5)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn total_internal_reflection_reflects_everything() {
+        let film = ThinFilm::new(300.0, 1.33);
+        assert_eq!(film.reflectance(300.0, 1.5, 0.1, 1.0, 550.0), 1.0);
+    }
+
+    #[test]
+    fn thickness_map_scales_thickness() {
+        let film = ThinFilm::new(400.0, 1.33).with_thickness_map(Arc::new(SolidColor::new(Color::new(0.5, 0.5, 0.5))));
+        assert!((film.thickness_at(&HitRecord::default()) - 200.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn rgb_bands_average_three_wavelengths() {
+        let c = ThinFilm::eval(None, |lambda, _| lambda);
+        assert!((c.x - 640.0).abs() < 1e-12 && (c.y - 535.0).abs() < 1e-12 && (c.z - 460.0).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,336 @@
+// 圆环面：中心 center，对称轴 axis，主半径 major（管中心圆），副半径 minor（管半径）
+// 求交化为关于 t 的四次方程，用 Ferrari 法求根后牛顿迭代精修
+use std::f64::consts::PI;
+use std::sync::Arc;
+use crate::aabb::Aabb;
+use crate::hittable::{area_pdf_value, HitRecord, Hittable};
+use crate::interval::Interval;
+use crate::material::Material;
+use crate::onb::Onb;
+use crate::ray::Ray;
+use crate::rtweekend::random::random_double;
+use crate::vec3::{Point3, Vec3};
+
+pub struct Torus {
+    center: Point3,
+    uvw: Onb, // w 为对称轴
+    major: f64,
+    minor: f64,
+    mat: Arc<dyn Material + Send + Sync>,
+    bbox: Aabb,
+}
+
+impl Torus {
+    pub fn new(center: Point3, axis: Vec3, major: f64, minor: f64, mat: Arc<dyn Material + Send + Sync>) -> Self {
+        let uvw = Onb::new(&axis);
+        // 局部包围盒 [-(R+r), R+r]² × [-r, r] 的 8 个角点转到世界坐标
+        let extent = Vec3::new(major + minor, major + minor, minor);
+        let mut bbox = Aabb::new_empty();
+        for i in 0..8 {
+            let corner = Vec3::new(
+                if i & 1 == 0 { -extent.x } else { extent.x },
+                if i & 2 == 0 { -extent.y } else { extent.y },
+                if i & 4 == 0 { -extent.z } else { extent.z },
+            );
+            let p = center + uvw.transform(&corner);
+            bbox = Aabb::surrounding_box(&bbox, &Aabb::from_points(p, p));
+        }
+        Self { center, uvw, major, minor, mat, bbox }
+    }
+
+    pub fn area(&self) -> f64 {
+        4.0 * PI * PI * self.major * self.minor
+    }
+}
+
+impl Hittable for Torus {
+    fn hit(&self, r: &Ray, ray_t: Interval, rec: &mut HitRecord) -> bool {
+        let o = self.uvw.to_local(&(r.origin - self.center));
+        let d = self.uvw.to_local(&r.direction);
+        let len = d.length();
+        if len == 0.0 {
+            return false;
+        }
+        let d = d / len;
+
+        // 把原点移到离中心最近处，减小系数量级、缓解远处光线的相消误差
+        let t0 = -Vec3::dot(&o, &d);
+        let o = o + t0 * d;
+        let outer = self.major + self.minor;
+        if o.length_squared() > outer * outer {
+            return false;
+        }
+
+        // (|p|² + R² - r²)² = 4R² (x² + y²)，p = o + s d，|d| = 1
+        let r2 = self.major * self.major;
+        let g = Vec3::dot(&o, &d);
+        let e = o.length_squared() + r2 - self.minor * self.minor;
+        let a3 = 4.0 * g;
+        let a2 = 4.0 * g * g + 2.0 * e - 4.0 * r2 * (d.x * d.x + d.y * d.y);
+        let a1 = 4.0 * g * e - 8.0 * r2 * (o.x * d.x + o.y * d.y);
+        let a0 = e * e - 4.0 * r2 * (o.x * o.x + o.y * o.y);
+
+        let (roots, count) = solve_quartic(a3, a2, a1, a0);
+        let Some(t) = roots[..count]
+            .iter()
+            .map(|s| (t0 + s) / len)
+            .filter(|t| ray_t.surrounds(*t))
+            .min_by(|a, b| a.total_cmp(b))
+        else {
+            return false;
+        };
+
+        let p = r.at(t);
+        let lp = self.uvw.to_local(&(p - self.center));
+        let rho = (lp.x * lp.x + lp.y * lp.y).sqrt();
+        let (cos_phi, sin_phi) = if rho > 0.0 { (lp.x / rho, lp.y / rho) } else { (1.0, 0.0) };
+        // 管截面内的角度 θ，从外赤道起绕管一周
+        let theta = lp.z.atan2(rho - self.major);
+        let (sin_theta, cos_theta) = theta.sin_cos();
+        let normal = Vec3::new(cos_theta * cos_phi, cos_theta * sin_phi, sin_theta);
+
+        rec.t = t;
+        rec.p = p;
+        rec.set_face_normal(r, self.uvw.transform(&normal));
+        rec.u = (sin_phi.atan2(cos_phi) + PI) / (2.0 * PI);
+        rec.v = (theta + PI) / (2.0 * PI);
+        rec.dpdu = self.uvw.transform(&(2.0 * PI * Vec3::new(-lp.y, lp.x, 0.0)));
+        rec.dpdv = self.uvw.transform(&(2.0 * PI * self.minor * Vec3::new(-sin_theta * cos_phi, -sin_theta * sin_phi, cos_theta)));
+        rec.vertex_color = None;
+        rec.mat = Some(self.mat.clone());
+        true
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+
+    fn pdf_value(&self, origin: &Point3, direction: &Vec3) -> f64 {
+        area_pdf_value(self, self.area(), origin, direction)
+    }
+
+    // 面积元正比于 R + r cosθ，对 θ 用拒绝采样
+    fn random(&self, origin: &Point3) -> Vec3 {
+        let phi = 2.0 * PI * random_double();
+        let theta = loop {
+            let theta = 2.0 * PI * random_double();
+            if random_double() * (self.major + self.minor) <= self.major + self.minor * theta.cos() {
+                break theta;
+            }
+        };
+        let rho = self.major + self.minor * theta.cos();
+        let local = Vec3::new(rho * phi.cos(), rho * phi.sin(), self.minor * theta.sin());
+        self.center + self.uvw.transform(&local) - *origin
+    }
+}
+
+// 实系数一元二次方程 x² + b x + c = 0 的实根，写入 out 并返回个数
+fn solve_quadratic(b: f64, c: f64, out: &mut [f64]) -> usize {
+    let discriminant = b * b - 4.0 * c;
+    if discriminant < 0.0 {
+        return 0;
+    }
+    // 避免相近数相减：先求绝对值较大的根，再由韦达定理求另一个
+    let q = -0.5 * (b + b.signum() * discriminant.sqrt());
+    if q == 0.0 {
+        out[0] = 0.0;
+        return 1;
+    }
+    out[0] = q;
+    out[1] = c / q;
+    2
+}
+
+// x³ + b x² + c x + d = 0 的最大实根（至少存在一个）
+fn largest_cubic_root(b: f64, c: f64, d: f64) -> f64 {
+    let p = c - b * b / 3.0;
+    let q = 2.0 * b * b * b / 27.0 - b * c / 3.0 + d;
+    let discriminant = q * q / 4.0 + p * p * p / 27.0;
+    let mut x = if discriminant > 0.0 {
+        let s = discriminant.sqrt();
+        (-q / 2.0 + s).cbrt() + (-q / 2.0 - s).cbrt()
+    } else if p < 0.0 {
+        // 三个实根，三角解法取最大者
+        let m = 2.0 * (-p / 3.0).sqrt();
+        let theta = (3.0 * q / (p * m)).clamp(-1.0, 1.0).acos() / 3.0;
+        m * theta.cos()
+    } else {
+        0.0
+    } - b / 3.0;
+    // 牛顿迭代精修
+    for _ in 0..2 {
+        let f = ((x + b) * x + c) * x + d;
+        let df = (3.0 * x + 2.0 * b) * x + c;
+        if df.abs() > 1e-14 {
+            x -= f / df;
+        }
+    }
+    x
+}
+
+/// 首一四次方程 x⁴ + a3 x³ + a2 x² + a1 x + a0 = 0 的实根（Ferrari 法 + 牛顿精修）
+pub(crate) fn solve_quartic(a3: f64, a2: f64, a1: f64, a0: f64) -> ([f64; 4], usize) {
+    // 换元 x = y - a3/4 得到缺三次项的 y⁴ + p y² + q y + r = 0
+    let shift = a3 / 4.0;
+    let a3_sq = a3 * a3;
+    let p = a2 - 3.0 * a3_sq / 8.0;
+    let q = a1 - a3 * a2 / 2.0 + a3_sq * a3 / 8.0;
+    let r = a0 - a3 * a1 / 4.0 + a3_sq * a2 / 16.0 - 3.0 * a3_sq * a3_sq / 256.0;
+
+    let mut roots = [0.0; 4];
+    let mut count = 0;
+    if q.abs() < 1e-12 {
+        // 双二次方程：z = y²
+        let mut z = [0.0; 2];
+        let n = solve_quadratic(p, r, &mut z);
+        for &z in &z[..n] {
+            if z >= 0.0 {
+                let y = z.sqrt();
+                roots[count] = y;
+                roots[count + 1] = -y;
+                count += 2;
+            }
+        }
+    } else {
+        // 预解三次方程 m³ + p m² + (p²/4 - r) m - q²/8 = 0 必有正根
+        let m = largest_cubic_root(p, p * p / 4.0 - r, -q * q / 8.0).max(1e-14);
+        let s = (2.0 * m).sqrt();
+        let k = q / (2.0 * s);
+        count += solve_quadratic(s, p / 2.0 + m - k, &mut roots[count..]);
+        count += solve_quadratic(-s, p / 2.0 + m + k, &mut roots[count..]);
+    }
+
+    // 回代并在原方程上牛顿精修
+    for x in roots[..count].iter_mut() {
+        *x -= shift;
+        for _ in 0..2 {
+            let f = (((*x + a3) * *x + a2) * *x + a1) * *x + a0;
+            let df = ((4.0 * *x + 3.0 * a3) * *x + 2.0 * a2) * *x + a1;
+            if df.abs() > 1e-14 {
+                *x -= f / df;
+            }
+        }
+    }
+    (roots, count)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hittable::pdf_integral;
+    use crate::material::Lambertian;
+    use crate::vec3::Color;
+
+    // 由四个根展开首一多项式的系数 (a3, a2, a1, a0)
+    fn monic_from_roots(r: [f64; 4]) -> (f64, f64, f64, f64) {
+        let [a, b, c, d] = r;
+        (
+            -(a + b + c + d),
+            a * b + a * c + a * d + b * c + b * d + c * d,
+            -(a * b * c + a * b * d + a * c * d + b * c * d),
+            a * b * c * d,
+        )
+    }
+
+    fn sorted_roots(a3: f64, a2: f64, a1: f64, a0: f64) -> Vec<f64> {
+        let (roots, count) = solve_quartic(a3, a2, a1, a0);
+        let mut roots = roots[..count].to_vec();
+        roots.sort_by(|a, b| a.total_cmp(b));
+        roots
+    }
+
+    fn assert_roots(found: &[f64], expected: &[f64], eps: f64) {
+        assert_eq!(found.len(), expected.len(), "{:?} vs {:?}", found, expected);
+        for (x, y) in found.iter().zip(expected) {
+            assert!((x - y).abs() <= eps * y.abs().max(1.0), "{:?} vs {:?}", found, expected);
+        }
+    }
+
+    #[test]
+    fn quartic_solver_finds_known_roots() {
+        for expected in [[-3.0, 0.5, 1.0, 2.0], [-1.0, -0.25, 0.125, 4.0], [-40.0, -0.01, 0.02, 25.0], [1.0, 2.0, 3.0, 4.0]] {
+            let (a3, a2, a1, a0) = monic_from_roots(expected);
+            assert_roots(&sorted_roots(a3, a2, a1, a0), &expected, 1e-9);
+        }
+        // 双二次方程 x⁴ - 5x² + 4 = 0
+        assert_roots(&sorted_roots(0.0, -5.0, 0.0, 4.0), &[-2.0, -1.0, 1.0, 2.0], 1e-12);
+        // (x² + 1)(x - 1)(x - 3)：只有两个实根
+        assert_roots(&sorted_roots(-4.0, 4.0, -4.0, 3.0), &[1.0, 3.0], 1e-9);
+        // x⁴ + 1 与 (x² + 1)(x² + 4) 没有实根
+        assert!(sorted_roots(0.0, 0.0, 0.0, 1.0).is_empty());
+        assert!(sorted_roots(0.0, 5.0, 0.0, 4.0).is_empty());
+        // 重根 (x - 1)²(x + 2)²：数值上可能算成一对近似根，但都应贴近真实根
+        let (a3, a2, a1, a0) = monic_from_roots([-2.0, -2.0, 1.0, 1.0]);
+        let found = sorted_roots(a3, a2, a1, a0);
+        assert!(!found.is_empty());
+        assert!(found.iter().all(|x| (x + 2.0).abs() < 1e-6 || (x - 1.0).abs() < 1e-6), "{:?}", found);
+    }
+
+    fn torus() -> Torus {
+        Torus::new(Point3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0), 2.0, 0.5, Arc::new(Lambertian::from_color(Color::new(0.5, 0.5, 0.5))))
+    }
+
+    fn implicit(p: &Point3) -> f64 {
+        let e = p.length_squared() + 4.0 - 0.25;
+        e * e - 16.0 * (p.x * p.x + p.z * p.z)
+    }
+
+    fn hit(torus: &Torus, origin: Point3, direction: Vec3) -> Option<HitRecord> {
+        let mut rec = HitRecord::default();
+        torus.hit(&Ray::new(origin, direction, 0.0), Interval::new(0.001, f64::INFINITY), &mut rec).then_some(rec)
+    }
+
+    #[test]
+    fn hits_the_tube_and_misses_the_hole() {
+        let torus = torus();
+        let rec = hit(&torus, Point3::new(10.0, 0.0, 0.0), Vec3::new(-2.0, 0.0, 0.0)).unwrap();
+        assert!((rec.t - 3.75).abs() < 1e-9);
+        assert!((rec.normal - Vec3::new(1.0, 0.0, 0.0)).length() < 1e-9 && rec.front_face);
+
+        let top = hit(&torus, Point3::new(0.0, 5.0, 2.0), Vec3::new(0.0, -1.0, 0.0)).unwrap();
+        assert!((top.p.y - 0.5).abs() < 1e-9);
+        assert!((top.normal - Vec3::new(0.0, 1.0, 0.0)).length() < 1e-9);
+        assert!((0.0..=1.0).contains(&top.u) && (0.0..=1.0).contains(&top.v));
+
+        assert!(hit(&torus, Point3::new(0.0, 5.0, 0.0), Vec3::new(0.0, -1.0, 0.0)).is_none());
+        assert!(hit(&torus, Point3::new(0.0, 0.6, 10.0), Vec3::new(0.0, 0.0, -1.0)).is_none());
+        // 从管内部射出的光线命中背面
+        let inside = hit(&torus, Point3::new(2.0, 0.0, 0.0), Vec3::new(0.0, 0.0, 1.0)).unwrap();
+        assert!(!inside.front_face);
+    }
+
+    // 远处与掠射光线的交点仍落在隐式曲面上
+    #[test]
+    fn far_and_grazing_hits_lie_on_the_surface() {
+        let torus = torus();
+        let far = hit(&torus, Point3::new(1.0e5, 0.1, 0.0), Vec3::new(-1.0, 0.0, 0.0)).unwrap();
+        assert!((far.p.x - (2.0 + 0.24f64.sqrt())).abs() < 1e-7);
+
+        let mut hits = 0;
+        for i in 0..2000 {
+            let origin = 8.0 * Vec3::random_unit_vector();
+            let target = Point3::new(0.0, 0.0, 0.0) + 2.5 * Vec3::random_unit_vector();
+            let direction = target - origin;
+            if let Some(rec) = hit(&torus, origin, direction) {
+                hits += 1;
+                assert!(implicit(&rec.p).abs() < 1e-7, "ray {}: residual {}", i, implicit(&rec.p));
+                assert!(torus.bounding_box().x.contains(rec.p.x) && torus.bounding_box().y.contains(rec.p.y));
+            }
+        }
+        assert!(hits > 500);
+    }
+
+    #[test]
+    fn light_sampling_pdf_integrates_to_one() {
+        let torus = torus();
+        assert!((torus.area() - 4.0 * PI * PI).abs() < 1e-12);
+        let origin = Point3::new(1.0, 3.0, 4.0);
+        assert!((pdf_integral(&torus, &origin) - 1.0).abs() < 0.03);
+        for _ in 0..100 {
+            let direction = torus.random(&origin);
+            assert!(implicit(&(origin + direction)).abs() < 1e-9);
+            assert!(torus.pdf_value(&origin, &direction) > 0.0);
+        }
+    }
+}
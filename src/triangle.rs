@@ -0,0 +1,626 @@
+// 三角形与索引三角网格
+use std::fmt;
+use std::sync::Arc;
+use crate::aabb::Aabb;
+use crate::hittable::{area_pdf_value_with, next_t, HitRecord, Hittable};
+use crate::interval::Interval;
+use crate::material::Material;
+use crate::ray::Ray;
+use crate::rtweekend::random::random_double;
+use crate::vec3::{Color, Point3, Vec3};
+
+// 叶子节点最多包含的三角形数
+const MESH_LEAF_SIZE: usize = 4;
+
+fn component(v: &Vec3, axis: usize) -> f64 {
+    match axis {
+        0 => v.x,
+        1 => v.y,
+        _ => v.z,
+    }
+}
+
+fn permute(v: &Vec3, kx: usize, ky: usize, kz: usize) -> Vec3 {
+    Vec3::new(component(v, kx), component(v, ky), component(v, kz))
+}
+
+// 水密的射线-三角形求交（Woop, Benthin & Wald 2013）：共享边上的命中不会漏判也不会重复。
+// 命中时返回 (t, b0, b1, b2)，交点为 b0*p0 + b1*p1 + b2*p2
+pub fn intersect_watertight(r: &Ray, ray_t: Interval, p0: &Point3, p1: &Point3, p2: &Point3) -> Option<(f64, f64, f64, f64)> {
+    // 变换到以射线原点为中心、射线方向为 +z 的坐标系
+    let d = r.direction();
+    let abs_d = Vec3::new(d.x.abs(), d.y.abs(), d.z.abs());
+    let kz = if abs_d.x > abs_d.y { if abs_d.x > abs_d.z { 0 } else { 2 } } else if abs_d.y > abs_d.z { 1 } else { 2 };
+    let kx = (kz + 1) % 3;
+    let ky = (kx + 1) % 3;
+    let d = permute(&d, kx, ky, kz);
+    if d.z == 0.0 {
+        return None;
+    }
+    let o = r.origin();
+    let mut p0t = permute(&(*p0 - o), kx, ky, kz);
+    let mut p1t = permute(&(*p1 - o), kx, ky, kz);
+    let mut p2t = permute(&(*p2 - o), kx, ky, kz);
+
+    let sx = -d.x / d.z;
+    let sy = -d.y / d.z;
+    let sz = 1.0 / d.z;
+    for p in [&mut p0t, &mut p1t, &mut p2t] {
+        p.x += sx * p.z;
+        p.y += sy * p.z;
+    }
+
+    // 边函数
+    let e0 = p1t.x * p2t.y - p1t.y * p2t.x;
+    let e1 = p2t.x * p0t.y - p2t.y * p0t.x;
+    let e2 = p0t.x * p1t.y - p0t.y * p1t.x;
+    if (e0 < 0.0 || e1 < 0.0 || e2 < 0.0) && (e0 > 0.0 || e1 > 0.0 || e2 > 0.0) {
+        return None;
+    }
+    let det = e0 + e1 + e2;
+    if det == 0.0 {
+        return None;
+    }
+
+    let t_scaled = (e0 * p0t.z + e1 * p1t.z + e2 * p2t.z) * sz;
+    let t = t_scaled / det;
+    if !ray_t.surrounds(t) {
+        return None;
+    }
+    Some((t, e0 / det, e1 / det, e2 / det))
+}
+
+// 三角形上按面积均匀采样一点
+fn sample_triangle(p0: &Point3, p1: &Point3, p2: &Point3) -> Point3 {
+    let su = random_double().sqrt();
+    let b0 = 1.0 - su;
+    let b1 = random_double() * su;
+    b0 * *p0 + b1 * *p1 + (1.0 - b0 - b1) * *p2
+}
+
+// 面光源的立体角 PDF：distance² / (|cosθ| · 面积)
+fn area_light_pdf(direction: &Vec3, t: f64, normal: &Vec3, area: f64) -> f64 {
+    let distance_squared = t * t * direction.length_squared();
+    let cosine = Vec3::dot(direction, normal).abs() / direction.length();
+    if cosine <= 0.0 || area <= 0.0 {
+        return 0.0;
+    }
+    distance_squared / (cosine * area)
+}
+
+pub struct Triangle {
+    v: [Point3; 3],
+    normal: Vec3,
+    mat: Arc<dyn Material + Send + Sync>,
+    bbox: Aabb,
+    area: f64,
+}
+
+impl Triangle {
+    pub fn new(a: Point3, b: Point3, c: Point3, mat: Arc<dyn Material + Send + Sync>) -> Self {
+        let n = Vec3::cross(&(b - a), &(c - a));
+        let bbox = Aabb::surrounding_box(&Aabb::from_points(a, b), &Aabb::from_points(a, c));
+        Self {
+            v: [a, b, c],
+            normal: Vec3::unit_vector(n),
+            mat,
+            bbox,
+            area: 0.5 * n.length(),
+        }
+    }
+
+    pub fn area(&self) -> f64 {
+        self.area
+    }
+}
+
+impl Hittable for Triangle {
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+
+    fn hit(&self, r: &Ray, ray_t: Interval, rec: &mut HitRecord) -> bool {
+        let Some((t, b0, b1, b2)) = intersect_watertight(r, ray_t, &self.v[0], &self.v[1], &self.v[2]) else {
+            return false;
+        };
+        rec.t = t;
+        rec.p = b0 * self.v[0] + b1 * self.v[1] + b2 * self.v[2];
+        rec.u = b1;
+        rec.v = b2;
+        rec.dpdu = self.v[1] - self.v[0];
+        rec.dpdv = self.v[2] - self.v[0];
+        rec.vertex_color = None;
+        rec.mat = Some(self.mat.clone());
+        rec.set_face_normal(r, self.normal);
+        true
+    }
+
+    fn pdf_value(&self, origin: &Point3, direction: &Vec3) -> f64 {
+        let ray = Ray::new(*origin, *direction, 0.0);
+        match intersect_watertight(&ray, Interval::new(0.001, f64::INFINITY), &self.v[0], &self.v[1], &self.v[2]) {
+            Some((t, ..)) => area_light_pdf(direction, t, &self.normal, self.area),
+            None => 0.0,
+        }
+    }
+
+    fn random(&self, origin: &Point3) -> Vec3 {
+        sample_triangle(&self.v[0], &self.v[1], &self.v[2]) - *origin
+    }
+}
+
+// 网格内部 BVH 节点：count > 0 为叶子，引用 face_order[start..start + count]；
+// 否则左子节点紧随其后，右子节点下标为 right
+struct MeshBvhNode {
+    bbox: Aabb,
+    start: usize,
+    count: usize,
+    right: usize,
+}
+
+/// 网格的顶点属性或索引与顶点数不符
+#[derive(Debug, Clone)]
+pub struct MeshError {
+    pub message: String,
+}
+
+impl MeshError {
+    fn new(message: impl Into<String>) -> Self {
+        MeshError { message: message.into() }
+    }
+}
+
+impl fmt::Display for MeshError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for MeshError {}
+
+// 索引三角网格：共享顶点、法线与 UV 缓冲区，内部自带 BVH
+pub struct TriangleMesh {
+    positions: Vec<Point3>,
+    normals: Vec<Vec3>,     // 每顶点着色法线，为空时使用几何法线
+    uvs: Vec<(f64, f64)>,   // 每顶点 UV，为空时使用重心坐标
+    colors: Vec<Color>,     // 每顶点颜色，可为空
+    indices: Vec<[usize; 3]>,
+    mat: Arc<dyn Material + Send + Sync>,
+    nodes: Vec<MeshBvhNode>,
+    face_order: Vec<usize>,
+    area_cdf: Vec<f64>,     // 按面积的累积分布，用于在网格上均匀采样
+    area: f64,
+    bbox: Aabb,
+}
+
+impl TriangleMesh {
+    pub fn new(positions: Vec<Point3>, indices: Vec<[usize; 3]>, mat: Arc<dyn Material + Send + Sync>) -> Result<Self, MeshError> {
+        Self::with_attributes(positions, Vec::new(), Vec::new(), indices, mat)
+    }
+
+    // normals、uvs 为空或与 positions 等长；面积为 0 的三角形没有法线，构建时丢弃
+    pub fn with_attributes(
+        positions: Vec<Point3>,
+        normals: Vec<Vec3>,
+        uvs: Vec<(f64, f64)>,
+        indices: Vec<[usize; 3]>,
+        mat: Arc<dyn Material + Send + Sync>,
+    ) -> Result<Self, MeshError> {
+        if !normals.is_empty() && normals.len() != positions.len() {
+            return Err(MeshError::new(format!("mesh has {} normals for {} vertices", normals.len(), positions.len())));
+        }
+        if !uvs.is_empty() && uvs.len() != positions.len() {
+            return Err(MeshError::new(format!("mesh has {} uvs for {} vertices", uvs.len(), positions.len())));
+        }
+        if let Some(&bad) = indices.iter().flatten().find(|&&i| i >= positions.len()) {
+            return Err(MeshError::new(format!("mesh index {} out of range (have {} vertices)", bad, positions.len())));
+        }
+        let indices = indices
+            .into_iter()
+            .filter(|&[i0, i1, i2]| {
+                let (p0, p1, p2) = (positions[i0], positions[i1], positions[i2]);
+                Vec3::cross(&(p1 - p0), &(p2 - p0)).length_squared() > 0.0
+            })
+            .collect();
+
+        let mut mesh = Self {
+            positions,
+            normals,
+            uvs,
+            colors: Vec::new(),
+            indices,
+            mat,
+            nodes: Vec::new(),
+            face_order: Vec::new(),
+            area_cdf: Vec::new(),
+            area: 0.0,
+            bbox: Aabb::new_empty(),
+        };
+
+        let mut total = 0.0;
+        mesh.area_cdf = (0..mesh.indices.len())
+            .map(|f| {
+                total += mesh.face_area(f);
+                total
+            })
+            .collect();
+        mesh.area = total;
+
+        mesh.face_order = (0..mesh.indices.len()).collect();
+        if !mesh.indices.is_empty() {
+            let mut order = std::mem::take(&mut mesh.face_order);
+            mesh.build(&mut order, 0);
+            mesh.face_order = order;
+            mesh.bbox = mesh.nodes[0].bbox;
+        }
+        Ok(mesh)
+    }
+
+    pub fn with_vertex_colors(mut self, colors: Vec<Color>) -> Result<Self, MeshError> {
+        if !colors.is_empty() && colors.len() != self.positions.len() {
+            return Err(MeshError::new(format!("mesh has {} colors for {} vertices", colors.len(), self.positions.len())));
+        }
+        self.colors = colors;
+        Ok(self)
+    }
+
+    pub fn triangle_count(&self) -> usize {
+        self.indices.len()
+    }
+
+    pub fn area(&self) -> f64 {
+        self.area
+    }
+
+    fn vertices(&self, face: usize) -> (&Point3, &Point3, &Point3) {
+        let [i0, i1, i2] = self.indices[face];
+        (&self.positions[i0], &self.positions[i1], &self.positions[i2])
+    }
+
+    fn face_area(&self, face: usize) -> f64 {
+        let (p0, p1, p2) = self.vertices(face);
+        0.5 * Vec3::cross(&(*p1 - *p0), &(*p2 - *p0)).length()
+    }
+
+    fn geometric_normal(&self, face: usize) -> Vec3 {
+        let (p0, p1, p2) = self.vertices(face);
+        Vec3::unit_vector(Vec3::cross(&(*p1 - *p0), &(*p2 - *p0)))
+    }
+
+    // 由 UV 求 ∂p/∂u、∂p/∂v；无 UV 时 (u, v) 即重心坐标 (b1, b2)
+    fn tangents(&self, face: usize) -> (Vec3, Vec3) {
+        let (p0, p1, p2) = self.vertices(face);
+        if self.uvs.is_empty() {
+            return (*p1 - *p0, *p2 - *p0);
+        }
+        let [uv0, uv1, uv2] = self.indices[face].map(|i| self.uvs[i]);
+        let (du02, dv02) = (uv0.0 - uv2.0, uv0.1 - uv2.1);
+        let (du12, dv12) = (uv1.0 - uv2.0, uv1.1 - uv2.1);
+        let (dp02, dp12) = (*p0 - *p2, *p1 - *p2);
+        let det = du02 * dv12 - dv02 * du12;
+        if det.abs() < 1e-12 {
+            // UV 退化：回退到重心坐标的切线
+            return (*p1 - *p0, *p2 - *p0);
+        }
+        (
+            (dv12 * dp02 - dv02 * dp12) / det,
+            (du02 * dp12 - du12 * dp02) / det,
+        )
+    }
+
+    fn face_bbox(&self, face: usize) -> Aabb {
+        let (p0, p1, p2) = self.vertices(face);
+        Aabb::surrounding_box(&Aabb::from_points(*p0, *p1), &Aabb::from_points(*p0, *p2))
+    }
+
+    fn centroid(&self, face: usize) -> Point3 {
+        let (p0, p1, p2) = self.vertices(face);
+        (*p0 + *p1 + *p2) / 3.0
+    }
+
+    // 按质心在最长轴上取中位数划分，返回节点下标
+    fn build(&mut self, faces: &mut [usize], start: usize) -> usize {
+        let bbox = faces
+            .iter()
+            .fold(Aabb::new_empty(), |b, &f| Aabb::surrounding_box(&b, &self.face_bbox(f)));
+        let index = self.nodes.len();
+        self.nodes.push(MeshBvhNode { bbox, start, count: faces.len(), right: 0 });
+        if faces.len() <= MESH_LEAF_SIZE {
+            return index;
+        }
+
+        let centroid_bounds = faces.iter().fold(Aabb::new_empty(), |b, &f| {
+            let c = self.centroid(f);
+            Aabb::surrounding_box(&b, &Aabb::from_points(c, c))
+        });
+        let axis = centroid_bounds.longest_axis();
+        faces.sort_by(|&a, &b| {
+            component(&self.centroid(a), axis).total_cmp(&component(&self.centroid(b), axis))
+        });
+        let mid = faces.len() / 2;
+        let (left, right) = faces.split_at_mut(mid);
+        self.build(left, start);
+        let right_index = self.build(right, start + mid);
+        self.nodes[index].count = 0;
+        self.nodes[index].right = right_index;
+        index
+    }
+
+    // 最近交点：(t, 三角形下标, b0, b1, b2)
+    fn hit_face(&self, r: &Ray, ray_t: Interval) -> Option<(f64, usize, f64, f64, f64)> {
+        if self.nodes.is_empty() {
+            return None;
+        }
+        let mut closest: Option<(f64, usize, f64, f64, f64)> = None;
+        let mut t_max = ray_t.max;
+        let mut stack = vec![0usize];
+        while let Some(n) = stack.pop() {
+            let node = &self.nodes[n];
+            if !node.bbox.hit(r, Interval::new(ray_t.min, t_max)) {
+                continue;
+            }
+            if node.count == 0 {
+                stack.push(node.right);
+                stack.push(n + 1);
+                continue;
+            }
+            for &face in &self.face_order[node.start..node.start + node.count] {
+                let (p0, p1, p2) = self.vertices(face);
+                if let Some((t, b0, b1, b2)) = intersect_watertight(r, Interval::new(ray_t.min, t_max), p0, p1, p2) {
+                    t_max = t;
+                    closest = Some((t, face, b0, b1, b2));
+                }
+            }
+        }
+        closest
+    }
+
+    fn fill_record(&self, r: &Ray, t: f64, face: usize, b: [f64; 3], rec: &mut HitRecord) {
+        let idx = self.indices[face];
+        let (p0, p1, p2) = self.vertices(face);
+        rec.t = t;
+        rec.p = b[0] * *p0 + b[1] * *p1 + b[2] * *p2;
+        (rec.u, rec.v) = if self.uvs.is_empty() {
+            (b[1], b[2])
+        } else {
+            let uv = idx.map(|i| self.uvs[i]);
+            (
+                b[0] * uv[0].0 + b[1] * uv[1].0 + b[2] * uv[2].0,
+                b[0] * uv[0].1 + b[1] * uv[1].1 + b[2] * uv[2].1,
+            )
+        };
+        (rec.dpdu, rec.dpdv) = self.tangents(face);
+        rec.vertex_color = if self.colors.is_empty() {
+            None
+        } else {
+            Some(b[0] * self.colors[idx[0]] + b[1] * self.colors[idx[1]] + b[2] * self.colors[idx[2]])
+        };
+        rec.mat = Some(self.mat.clone());
+        // front_face 由几何法线决定，着色法线翻到同一侧
+        rec.set_face_normal(r, self.geometric_normal(face));
+        if !self.normals.is_empty() {
+            let n = b[0] * self.normals[idx[0]] + b[1] * self.normals[idx[1]] + b[2] * self.normals[idx[2]];
+            if n.length_squared() > 0.0 {
+                let n = Vec3::unit_vector(n);
+                rec.normal = if Vec3::dot(&n, &rec.normal) < 0.0 { -n } else { n };
+            }
+        }
+    }
+}
+
+impl Hittable for TriangleMesh {
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+
+    // 一次遍历收集所有三角形交点；光线恰好穿过公共边时两侧三角形都会报告，
+    // 相同 t 且同向的交点只保留一个
+    fn hit_all(&self, r: &Ray, ray_t: Interval, hits: &mut Vec<HitRecord>) {
+        if self.nodes.is_empty() {
+            return;
+        }
+        let mut found: Vec<(f64, usize, f64, f64, f64)> = Vec::new();
+        let mut stack = vec![0usize];
+        while let Some(n) = stack.pop() {
+            let node = &self.nodes[n];
+            if !node.bbox.hit(r, ray_t) {
+                continue;
+            }
+            if node.count == 0 {
+                stack.push(node.right);
+                stack.push(n + 1);
+                continue;
+            }
+            for &face in &self.face_order[node.start..node.start + node.count] {
+                let (p0, p1, p2) = self.vertices(face);
+                if let Some((t, b0, b1, b2)) = intersect_watertight(r, ray_t, p0, p1, p2) {
+                    found.push((t, face, b0, b1, b2));
+                }
+            }
+        }
+        found.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let start = hits.len();
+        for (t, face, b0, b1, b2) in found {
+            let mut rec = HitRecord::default();
+            self.fill_record(r, t, face, [b0, b1, b2], &mut rec);
+            if let Some(last) = hits[start..].last() {
+                if next_t(last.t) >= t && last.front_face == rec.front_face {
+                    continue;
+                }
+            }
+            hits.push(rec);
+        }
+    }
+
+    fn hit(&self, r: &Ray, ray_t: Interval, rec: &mut HitRecord) -> bool {
+        match self.hit_face(r, ray_t) {
+            Some((t, face, b0, b1, b2)) => {
+                self.fill_record(r, t, face, [b0, b1, b2], rec);
+                true
+            }
+            None => false,
+        }
+    }
+
+    // 按面积均匀采样整个网格，因此面积密度为 1 / 总面积；该方向上的每个交点都可能是采样点，逐个求和
+    fn pdf_value(&self, origin: &Point3, direction: &Vec3) -> f64 {
+        area_pdf_value_with(self.area, origin, direction, |ray, ray_t| {
+            self.hit_face(ray, ray_t).map(|(t, face, ..)| (t, self.geometric_normal(face)))
+        })
+    }
+
+    fn random(&self, origin: &Point3) -> Vec3 {
+        if self.area_cdf.is_empty() || self.area <= 0.0 {
+            return Vec3::new(1.0, 0.0, 0.0);
+        }
+        let target = random_double() * self.area;
+        let face = self.area_cdf.partition_point(|&c| c <= target).min(self.area_cdf.len() - 1);
+        let (p0, p1, p2) = self.vertices(face);
+        sample_triangle(p0, p1, p2) - *origin
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hittable::pdf_integral;
+    use crate::material::Lambertian;
+    use crate::rtweekend::random::random_double_range;
+
+    fn grey() -> Arc<dyn Material + Send + Sync> {
+        Arc::new(Lambertian::from_color(Color::new(0.5, 0.5, 0.5)))
+    }
+
+    fn down(x: f64, z: f64) -> Ray {
+        Ray::new(Point3::new(x, 1.0, z), Vec3::new(0.0, -1.0, 0.0), 0.0)
+    }
+
+    // 沿公共对角线射入的光线至少命中一侧，且 hit_all 只报告一次
+    #[test]
+    fn shared_edge_is_hit_exactly_once() {
+        let positions = vec![
+            Point3::new(0.0, 0.0, 0.0),
+            Point3::new(1.0, 0.0, 0.0),
+            Point3::new(1.0, 0.0, 1.0),
+            Point3::new(0.0, 0.0, 1.0),
+        ];
+        let mesh = TriangleMesh::new(positions.clone(), vec![[0, 1, 2], [0, 2, 3]], grey()).unwrap();
+        let ray_t = Interval::new(0.001, f64::INFINITY);
+        for k in 1..10 {
+            let r = down(k as f64 / 10.0, k as f64 / 10.0);
+            let a = intersect_watertight(&r, ray_t, &positions[0], &positions[1], &positions[2]);
+            let b = intersect_watertight(&r, ray_t, &positions[0], &positions[2], &positions[3]);
+            assert!(a.is_some() || b.is_some());
+            let mut hits = Vec::new();
+            mesh.hit_all(&r, ray_t, &mut hits);
+            assert_eq!(hits.len(), 1);
+        }
+    }
+
+    #[test]
+    fn hit_interpolates_normals_and_uvs() {
+        let mesh = TriangleMesh::with_attributes(
+            vec![Point3::new(0.0, 0.0, 0.0), Point3::new(0.0, 0.0, 3.0), Point3::new(3.0, 0.0, 0.0)],
+            vec![Vec3::new(1.0, 1.0, 0.0), Vec3::new(0.0, 1.0, 0.0), Vec3::new(0.0, 1.0, 1.0)],
+            vec![(0.0, 0.0), (1.0, 0.0), (0.0, 1.0)],
+            vec![[0, 1, 2]],
+            grey(),
+        )
+        .unwrap();
+        let mut rec = HitRecord::default();
+        assert!(mesh.hit(&down(1.0, 1.0), Interval::new(0.001, f64::INFINITY), &mut rec));
+        assert!((rec.t - 1.0).abs() < 1e-12);
+        assert!((rec.u - 1.0 / 3.0).abs() < 1e-12 && (rec.v - 1.0 / 3.0).abs() < 1e-12);
+        let expected = Vec3::unit_vector(Vec3::new(1.0, 3.0, 1.0));
+        assert!((rec.normal - expected).length() < 1e-12);
+        assert!(rec.front_face);
+    }
+
+    // 起伏网格：内部 BVH 的最近交点应与逐个三角形求交一致
+    #[test]
+    fn mesh_bvh_matches_brute_force() {
+        let n = 12;
+        let height = |i: usize, j: usize| (i as f64 * 0.7).sin() * (j as f64 * 0.5).cos() * 0.3;
+        let positions: Vec<Point3> = (0..=n)
+            .flat_map(|i| (0..=n).map(move |j| Point3::new(i as f64 / n as f64, height(i, j), j as f64 / n as f64)))
+            .collect();
+        let mut indices = Vec::new();
+        for i in 0..n {
+            for j in 0..n {
+                let a = i * (n + 1) + j;
+                indices.push([a, a + n + 1, a + 1]);
+                indices.push([a + 1, a + n + 1, a + n + 2]);
+            }
+        }
+        let triangles: Vec<Triangle> =
+            indices.iter().map(|f| Triangle::new(positions[f[0]], positions[f[1]], positions[f[2]], grey())).collect();
+        let mesh = TriangleMesh::new(positions, indices, grey()).unwrap();
+        assert_eq!(mesh.triangle_count(), 2 * n * n);
+        let ray_t = Interval::new(0.001, f64::INFINITY);
+        for _ in 0..500 {
+            let origin = Point3::new(random_double_range(-0.5, 1.5), 2.0, random_double_range(-0.5, 1.5));
+            let target = Point3::new(random_double_range(0.0, 1.0), 0.0, random_double_range(0.0, 1.0));
+            let r = Ray::new(origin, target - origin, 0.0);
+            let mut rec = HitRecord::default();
+            let brute = triangles
+                .iter()
+                .filter_map(|tri| {
+                    let mut rec = HitRecord::default();
+                    tri.hit(&r, ray_t, &mut rec).then_some(rec.t)
+                })
+                .min_by(|a, b| a.total_cmp(b));
+            let found = mesh.hit(&r, ray_t, &mut rec).then_some(rec.t);
+            assert_eq!(found.is_some(), brute.is_some());
+            if let (Some(a), Some(b)) = (found, brute) {
+                assert!((a - b).abs() < 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn light_sampling_pdfs_integrate_to_one() {
+        let (a, b, c) = (Point3::new(-3.0, 1.0, -3.0), Point3::new(3.0, 1.0, -3.0), Point3::new(0.0, 1.0, 3.0));
+        let origin = Point3::new(0.0, 0.0, 0.0);
+        let triangle = Triangle::new(a, b, c, grey());
+        assert!((pdf_integral(&triangle, &origin) - 1.0).abs() < 0.03);
+        let mesh = TriangleMesh::new(vec![a, b, c, Point3::new(0.0, 2.0, 0.0)], vec![[0, 1, 2], [0, 1, 3]], grey()).unwrap();
+        assert!((mesh.area() - triangle.area() - 0.5 * Vec3::cross(&(b - a), &(Point3::new(0.0, 2.0, 0.0) - a)).length()).abs() < 1e-12);
+        assert!((pdf_integral(&mesh, &origin) - 1.0).abs() < 0.03);
+        for _ in 0..100 {
+            assert!(mesh.pdf_value(&origin, &mesh.random(&origin)) > 0.0);
+        }
+    }
+
+    // 属性个数或索引不符时返回错误而不是 panic
+    #[test]
+    fn mismatched_attributes_are_errors() {
+        let positions = vec![Point3::new(0.0, 0.0, 0.0), Point3::new(1.0, 0.0, 0.0), Point3::new(0.0, 0.0, 1.0)];
+        let normals = vec![Vec3::new(0.0, 1.0, 0.0)];
+        assert!(TriangleMesh::with_attributes(positions.clone(), normals, Vec::new(), vec![[0, 1, 2]], grey()).is_err());
+        assert!(TriangleMesh::with_attributes(positions.clone(), Vec::new(), vec![(0.0, 0.0)], vec![[0, 1, 2]], grey()).is_err());
+        assert!(TriangleMesh::new(positions.clone(), vec![[0, 1, 3]], grey()).is_err());
+        let mesh = TriangleMesh::new(positions, vec![[0, 1, 2]], grey()).unwrap();
+        assert!(mesh.with_vertex_colors(vec![Color::new(1.0, 0.0, 0.0)]).is_err());
+    }
+
+    // 共线或重复顶点的三角形面积为 0，构建时丢弃，不会产生 NaN 法线
+    #[test]
+    fn degenerate_triangles_are_skipped() {
+        let positions = vec![
+            Point3::new(0.0, 0.0, 0.0),
+            Point3::new(1.0, 0.0, 0.0),
+            Point3::new(0.0, 0.0, 1.0),
+            Point3::new(2.0, 0.0, 0.0),
+        ];
+        let mesh = TriangleMesh::new(positions.clone(), vec![[0, 1, 3], [0, 0, 2], [0, 2, 1]], grey()).unwrap();
+        assert_eq!(mesh.triangle_count(), 1);
+        assert!((mesh.area() - 0.5).abs() < 1e-12);
+        let mut rec = HitRecord::default();
+        assert!(mesh.hit(&down(0.2, 0.2), Interval::new(0.001, f64::INFINITY), &mut rec));
+        assert!(rec.normal.x.is_finite() && rec.normal.y.abs() == 1.0);
+        assert!(!mesh.hit(&down(1.5, 0.0), Interval::new(0.001, f64::INFINITY), &mut rec));
+
+        let empty = TriangleMesh::new(positions, vec![[0, 1, 3]], grey()).unwrap();
+        assert_eq!(empty.triangle_count(), 0);
+        assert!(!empty.hit(&down(0.5, 0.0), Interval::new(0.001, f64::INFINITY), &mut rec));
+    }
+}
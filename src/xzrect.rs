@@ -42,6 +42,9 @@ impl Hittable for XZRect {
         rec.set_face_normal(r, outward_normal);
         rec.u = (x - self.x0) / (self.x1 - self.x0);
         rec.v = (z - self.z0) / (self.z1 - self.z0);
+        rec.dpdu = Vec3::new(self.x1 - self.x0, 0.0, 0.0);
+        rec.dpdv = Vec3::new(0.0, 0.0, self.z1 - self.z0);
+        rec.vertex_color = None;
         rec.mat = Some(self.mat.clone());
         true
     }